@@ -3,6 +3,7 @@
 //! [Advent of Code]: https://adventofcode.com/
 
 use std::collections::HashMap;
+use std::collections::hash_map::Entry;
 
 use serde::{Deserialize, Serialize};
 use serde_with::DisplayFromStr;
@@ -45,6 +46,114 @@ pub struct Leaderboard {
     pub members: HashMap<u64, LeaderboardMember>,
 }
 
+impl Leaderboard {
+    /// Merges several leaderboards into a single, virtual leaderboard.
+    ///
+    /// This can be used to produce a single ranking for members spread across several private
+    /// leaderboards (for example, because of the limit on the number of members of a private
+    /// leaderboard). Members are identified by their [`id`]; members appearing in more than one
+    /// leaderboard are only included once. The [local scores] of all members are then
+    /// [recomputed] over the combined membership.
+    ///
+    /// The merged leaderboard's [`owner_id`] and [`day1_ts`] are those of the first leaderboard.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::NoLeaderboardsToMerge`]: if `leaderboards` is empty
+    /// - [`Error::YearMismatch`]: if the leaderboards are not all for the same year
+    ///
+    /// [`id`]: LeaderboardMember::id
+    /// [local scores]: LeaderboardMember::local_score
+    /// [recomputed]: Self::compute_local_scores
+    /// [`owner_id`]: Self::owner_id
+    /// [`day1_ts`]: Self::day1_ts
+    /// [`Error::NoLeaderboardsToMerge`]: crate::Error::NoLeaderboardsToMerge
+    /// [`Error::YearMismatch`]: crate::Error::YearMismatch
+    pub fn merge<'a, I>(leaderboards: I) -> crate::Result<Self>
+    where
+        I: IntoIterator<Item = &'a Leaderboard>,
+    {
+        let mut leaderboards = leaderboards.into_iter();
+        let mut merged = leaderboards
+            .next()
+            .ok_or(crate::Error::NoLeaderboardsToMerge)?
+            .clone();
+
+        for leaderboard in leaderboards {
+            if leaderboard.year != merged.year {
+                return Err(crate::Error::YearMismatch {
+                    expected: merged.year,
+                    actual: leaderboard.year,
+                });
+            }
+
+            for (id, member) in &leaderboard.members {
+                match merged.members.entry(*id) {
+                    // Leaderboards might have been fetched at slightly different times,
+                    // so keep the most up-to-date version of each member.
+                    Entry::Occupied(mut entry) => {
+                        if (member.stars, member.last_star_ts)
+                            > (entry.get().stars, entry.get().last_star_ts)
+                        {
+                            entry.insert(member.clone());
+                        }
+                    },
+                    Entry::Vacant(entry) => {
+                        entry.insert(member.clone());
+                    },
+                }
+            }
+        }
+
+        merged.compute_local_scores();
+        Ok(merged)
+    }
+
+    /// Recomputes the [local score] of every member of this leaderboard.
+    ///
+    /// For each star, the first member to obtain it gets as many points as there are members in
+    /// the leaderboard, the second one gets one less point, and so on.
+    ///
+    /// Note that this does not take into account the rare puzzles for which the [Advent of Code]
+    /// website did not award points (for example, because of an outage).
+    ///
+    /// [local score]: LeaderboardMember::local_score
+    /// [Advent of Code]: https://adventofcode.com/
+    pub fn compute_local_scores(&mut self) {
+        // Members who obtained each star, as `(get_star_ts, star_index, member_id)`.
+        type Solvers = Vec<(i64, u64, u64)>;
+
+        let mut stars: HashMap<(u32, u8), Solvers> = HashMap::new();
+        for member in self.members.values() {
+            for (day, completion) in &member.completion_day_level {
+                let parts = [Some(completion.part_1), completion.part_2];
+                for (part, info) in (1..).zip(parts) {
+                    if let Some(info) = info {
+                        stars.entry((*day, part)).or_default().push((
+                            info.get_star_ts,
+                            info.star_index,
+                            member.id,
+                        ));
+                    }
+                }
+            }
+        }
+
+        let member_count = self.members.len() as u64;
+        let mut scores: HashMap<u64, u64> = HashMap::new();
+        for mut solvers in stars.into_values() {
+            solvers.sort_unstable();
+            for (rank, (_, _, id)) in (0..).zip(solvers) {
+                *scores.entry(id).or_default() += member_count - rank;
+            }
+        }
+
+        for member in self.members.values_mut() {
+            member.local_score = scores.get(&member.id).copied().unwrap_or_default();
+        }
+    }
+}
+
 #[cfg(feature = "http")]
 impl Leaderboard {
     /// Fetches this leaderboard's data from the [Advent of Code] website.
@@ -88,6 +197,33 @@ impl Leaderboard {
         id: u64,
         credentials: &LeaderboardCredentials,
     ) -> crate::Result<Self>
+    where
+        B: AsRef<str> + std::fmt::Debug,
+    {
+        Self::get_with_status_from(http_client, base, year, id, credentials)
+            .await
+            .map(|(leaderboard, _)| leaderboard)
+    }
+
+    /// Fetches this leaderboard's data from the [Advent of Code] website
+    /// using the provided http client and base website URL, returning the
+    /// HTTP status of the response along with the leaderboard.
+    ///
+    /// Works like [`get_from`]; see that method's documentation for more details.
+    ///
+    /// [Advent of Code]: https://adventofcode.com/
+    /// [`get_from`]: Self::get_from
+    #[cfg_attr(
+        not(coverage),
+        tracing::instrument(skip(http_client), level = "debug", ret(level = "trace"), err)
+    )]
+    pub async fn get_with_status_from<B>(
+        http_client: reqwest::Client,
+        base: B,
+        year: i32,
+        id: u64,
+        credentials: &LeaderboardCredentials,
+    ) -> crate::Result<(Self, reqwest::StatusCode)>
     where
         B: AsRef<str> + std::fmt::Debug,
     {
@@ -99,13 +235,20 @@ impl Leaderboard {
         if let Some(cookie_header) = credentials.session_cookie_header_value() {
             request = request.header(reqwest::header::COOKIE, cookie_header);
         }
+        #[cfg(feature = "opentelemetry")]
+        {
+            request = crate::telemetry::inject_trace_context(request);
+        }
 
         let response = request
             .send()
             .await
             .and_then(reqwest::Response::error_for_status);
         match response {
-            Ok(response) => Ok(response.json().await?),
+            Ok(response) => {
+                let status = response.status();
+                Ok((response.json().await?, status))
+            },
             // Note: since 2025, the AoC website actually returns an error when trying to access
             // a leaderboard you don't have access to... but it's a `400 Bad Request` 😭
             Err(err)
@@ -393,6 +536,33 @@ mod tests {
                 });
             }
 
+            #[rstest]
+            #[awt]
+            #[test_log::test(tokio::test)]
+            async fn with_status(
+                #[from(test_leaderboard)] expected: Leaderboard,
+                #[from(test_leaderboard_credentials)]
+                #[with(LeaderboardCredentialsKind::SessionCookie)]
+                credentials: LeaderboardCredentials,
+                #[future]
+                #[from(mock_server_with_leaderboard)]
+                #[with(expected.clone(), credentials.clone())]
+                mock_server: MockServer,
+            ) {
+                let actual = Leaderboard::get_with_status_from(
+                    Leaderboard::http_client().unwrap(),
+                    mock_server.uri(),
+                    TEST_YEAR,
+                    TEST_LEADERBOARD_ID,
+                    &credentials,
+                )
+                .await;
+                assert_matches!(actual, Ok((actual, status)) => {
+                    assert_eq!(actual, expected);
+                    assert_eq!(status, StatusCode::OK);
+                });
+            }
+
             mod errors {
                 use super::*;
 
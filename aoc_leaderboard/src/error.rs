@@ -34,6 +34,26 @@ pub enum Error {
     /// [Advent of Code]: https://adventofcode.com/
     #[error("session does not have access to this leaderboard")]
     NoAccess,
+
+    /// Error occurring when trying to [merge] an empty list of [`Leaderboard`]s.
+    ///
+    /// [merge]: crate::aoc::Leaderboard::merge
+    /// [`Leaderboard`]: crate::aoc::Leaderboard
+    #[error("no leaderboards to merge")]
+    NoLeaderboardsToMerge,
+
+    /// Error occurring when trying to [merge] [`Leaderboard`]s of different years.
+    ///
+    /// [merge]: crate::aoc::Leaderboard::merge
+    /// [`Leaderboard`]: crate::aoc::Leaderboard
+    #[error("cannot merge leaderboard for year {actual} with leaderboards for year {expected}")]
+    YearMismatch {
+        /// Year of the first leaderboard.
+        expected: i32,
+
+        /// Year of the leaderboard that could not be merged.
+        actual: i32,
+    },
 }
 
 impl Error {
@@ -3,10 +3,15 @@
 //!
 //! This crate's API consists essentially of the [`Leaderboard`] type and its
 //! related subcomponents. If the `http` feature is enabled, a helper to fetch
-//! a leaderboard's data from the Advent of Code website is also provided.
+//! a leaderboard's data from the Advent of Code website is also provided. With the
+//! `opentelemetry` feature, these requests also propagate the current trace context.
+//!
+//! Several leaderboards of the same year can also be [merged] into a single one,
+//! with local scores recomputed over the combined membership.
 //!
 //! [Advent of Code]: https://adventofcode.com/
 //! [`Leaderboard`]: aoc::Leaderboard
+//! [merged]: aoc::Leaderboard::merge
 
 #![deny(missing_docs)]
 #![deny(rustdoc::missing_crate_level_docs)]
@@ -17,6 +22,8 @@
 
 pub mod aoc;
 pub mod error;
+#[cfg(feature = "opentelemetry")]
+pub mod telemetry;
 #[cfg(feature = "__test_helpers")]
 #[doc(hidden)]
 pub mod test_helpers;
@@ -0,0 +1,69 @@
+//! [OpenTelemetry] support for HTTP requests.
+//!
+//! When the `opentelemetry` feature is enabled, requests sent to the Advent of Code website
+//! include the trace context of the current [`tracing`] span (as provided by
+//! [`tracing-opentelemetry`]), so that they can be linked to the trace of the caller. Trace context
+//! is injected using the global [text map propagator]; if none has been configured, nothing is
+//! injected.
+//!
+//! [OpenTelemetry]: https://opentelemetry.io/
+//! [`tracing-opentelemetry`]: https://docs.rs/tracing-opentelemetry
+//! [text map propagator]: opentelemetry::global::set_text_map_propagator
+
+use opentelemetry::global;
+use opentelemetry_http::HeaderInjector;
+use reqwest::RequestBuilder;
+use reqwest::header::HeaderMap;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// Adds the trace context of the current [`tracing`] span to the headers of the given request.
+///
+/// See [module documentation](self) for details.
+pub fn inject_trace_context(request: RequestBuilder) -> RequestBuilder {
+    let context = tracing::Span::current().context();
+
+    let mut headers = HeaderMap::new();
+    global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&context, &mut HeaderInjector(&mut headers));
+    });
+
+    request.headers(headers)
+}
+
+#[cfg(all(test, feature = "__test_helpers"))]
+mod tests {
+    use opentelemetry::trace::TracerProvider;
+    use opentelemetry_sdk::propagation::TraceContextPropagator;
+    use opentelemetry_sdk::trace::SdkTracerProvider;
+    use tracing::Instrument;
+    use tracing_subscriber::layer::SubscriberExt;
+    use wiremock::matchers::{header_exists, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn propagates_trace_context() {
+        global::set_text_map_propagator(TraceContextPropagator::new());
+        let tracer_provider = SdkTracerProvider::builder().build();
+        let subscriber = tracing_subscriber::registry().with(
+            tracing_opentelemetry::layer().with_tracer(tracer_provider.tracer("aoc_leaderboard")),
+        );
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(header_exists("traceparent"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let request = reqwest::Client::new().get(mock_server.uri());
+        let response = async { inject_trace_context(request).send().await }
+            .instrument(tracing::info_span!("request"))
+            .await
+            .unwrap();
+        assert!(response.status().is_success());
+    }
+}
@@ -71,3 +71,145 @@ mod real_endpoint {
         }
     }
 }
+
+mod leaderboard {
+    use std::collections::HashMap;
+
+    use aoc_leaderboard::aoc::{
+        CompletionDayLevel, Leaderboard, LeaderboardMember, PuzzleCompletionInfo,
+    };
+
+    const YEAR: i32 = 2024;
+
+    // Creates a member who solved the given puzzles, as `(day, part 1 ts, part 2 ts)`.
+    fn member(id: u64, solved: &[(u32, i64, Option<i64>)]) -> LeaderboardMember {
+        let info = |get_star_ts| PuzzleCompletionInfo { get_star_ts, star_index: 0 };
+        let completion_day_level: HashMap<_, _> = solved
+            .iter()
+            .map(|&(day, part_1, part_2)| {
+                (day, CompletionDayLevel { part_1: info(part_1), part_2: part_2.map(info) })
+            })
+            .collect();
+
+        LeaderboardMember {
+            name: Some(format!("member {id}")),
+            id,
+            stars: solved
+                .iter()
+                .map(|(_, _, part_2)| if part_2.is_some() { 2 } else { 1 })
+                .sum(),
+            local_score: 0,
+            global_score: 0,
+            last_star_ts: solved
+                .iter()
+                .map(|&(_, part_1, part_2)| part_2.unwrap_or(part_1))
+                .max()
+                .unwrap_or_default(),
+            completion_day_level,
+        }
+    }
+
+    fn leaderboard(year: i32, owner_id: u64, members: Vec<LeaderboardMember>) -> Leaderboard {
+        Leaderboard {
+            year,
+            owner_id,
+            day1_ts: 0,
+            members: members
+                .into_iter()
+                .map(|member| (member.id, member))
+                .collect(),
+        }
+    }
+
+    fn local_scores(leaderboard: &Leaderboard) -> Vec<(u64, u64)> {
+        let mut scores: Vec<_> = leaderboard
+            .members
+            .values()
+            .map(|member| (member.id, member.local_score))
+            .collect();
+        scores.sort_unstable();
+        scores
+    }
+
+    mod compute_local_scores {
+        use super::*;
+
+        #[test_log::test]
+        fn test_compute() {
+            let mut leaderboard = leaderboard(
+                YEAR,
+                1,
+                vec![
+                    member(1, &[(1, 10, Some(20)), (2, 100, None)]),
+                    member(2, &[(1, 5, Some(30))]),
+                    member(3, &[]),
+                ],
+            );
+
+            leaderboard.compute_local_scores();
+
+            // Day 1 part 1: 2 (3 points), 1 (2 points)
+            // Day 1 part 2: 1 (3 points), 2 (2 points)
+            // Day 2 part 1: 1 (3 points)
+            assert_eq!(local_scores(&leaderboard), [(1, 8), (2, 5), (3, 0)]);
+        }
+    }
+
+    mod merge {
+        use assert_matches::assert_matches;
+
+        use super::*;
+
+        #[test_log::test]
+        fn test_merge() {
+            let first = leaderboard(
+                YEAR,
+                1,
+                vec![member(1, &[(1, 10, Some(20))]), member(2, &[(1, 5, None)])],
+            );
+            let second = leaderboard(
+                YEAR,
+                3,
+                vec![member(2, &[(1, 5, Some(30))]), member(3, &[(1, 15, Some(25))])],
+            );
+
+            let merged = Leaderboard::merge([&first, &second]).unwrap();
+
+            assert_eq!(merged.year, YEAR);
+            assert_eq!(merged.owner_id, 1);
+            assert_eq!(merged.members[&2].stars, 2);
+            // Day 1 part 1: 2 (3 points), 1 (2 points), 3 (1 point)
+            // Day 1 part 2: 1 (3 points), 3 (2 points), 2 (1 point)
+            assert_eq!(local_scores(&merged), [(1, 5), (2, 4), (3, 3)]);
+        }
+
+        #[test_log::test]
+        fn test_single() {
+            let mut expected = leaderboard(YEAR, 1, vec![member(1, &[(1, 10, None)])]);
+
+            let merged = Leaderboard::merge([&expected]).unwrap();
+
+            expected.compute_local_scores();
+            assert_eq!(merged, expected);
+        }
+
+        #[test_log::test]
+        fn test_empty() {
+            assert_matches!(
+                Leaderboard::merge([]),
+                Err(aoc_leaderboard::Error::NoLeaderboardsToMerge)
+            );
+        }
+
+        #[test_log::test]
+        fn test_year_mismatch() {
+            let first = leaderboard(YEAR, 1, vec![]);
+            let second = leaderboard(YEAR - 1, 2, vec![]);
+
+            assert_matches!(
+                Leaderboard::merge([&first, &second]),
+                Err(aoc_leaderboard::Error::YearMismatch { expected: YEAR, actual }) if actual == YEAR - 1
+            );
+        }
+    }
+}
@@ -0,0 +1,116 @@
+#![deny(rustdoc::broken_intra_doc_links)]
+#![deny(rustdoc::private_intra_doc_links)]
+#![cfg_attr(coverage_nightly, feature(coverage_attribute))]
+
+use aoc_leaderbot_aws_lib::leaderbot::storage::aws::dynamodb::DynamoDbStorage;
+use aoc_leaderbot_lib::leaderbot::{StorageAdmin, StorageEntry};
+use aws_config::BehaviorVersion;
+use clap::Parser;
+use dotenvy::dotenv;
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let _ = dotenv();
+
+    let cli = Cli::parse();
+
+    let mut storage = get_storage(&cli).await;
+    match cli.command {
+        cli::Command::List => {
+            let mut entries = storage.list().await?;
+            entries.sort_by_key(|entry| (entry.leaderboard_id, entry.year));
+
+            for entry in entries {
+                println!("{}", entry_text(&entry));
+            }
+        },
+        cli::Command::Delete(target) => {
+            storage.delete(target.year, target.leaderboard_id).await?;
+        },
+        cli::Command::ClearError(target) => {
+            storage
+                .clear_error(target.year, target.leaderboard_id)
+                .await?;
+        },
+    }
+
+    Ok(())
+}
+
+async fn get_storage(cli: &Cli) -> DynamoDbStorage {
+    if cli.test_endpoint_url.is_empty() {
+        DynamoDbStorage::new(&cli.table_name).await
+    } else {
+        let config = aws_config::defaults(BehaviorVersion::latest())
+            .region("ca-central-1")
+            .test_credentials()
+            .endpoint_url(&cli.test_endpoint_url)
+            .load()
+            .await;
+        DynamoDbStorage::with_config(&config, &cli.table_name).await
+    }
+}
+
+fn entry_text(entry: &StorageEntry) -> String {
+    let members = entry
+        .leaderboard
+        .as_ref()
+        .map(|leaderboard| format!("{} members", leaderboard.members.len()))
+        .unwrap_or_else(|| "no leaderboard data".into());
+    let last_error = entry
+        .last_error
+        .map(|error_kind| format!("last error: {error_kind:?}"))
+        .unwrap_or_else(|| "no error".into());
+
+    format!("{}\t{}\t{members}\t{last_error}", entry.leaderboard_id, entry.year)
+}
+
+use cli::Cli;
+
+// It seems some code generated by `clap` is flagged as not covered by tests,
+// but I don't know how to cover them, so I'll move the struct to a "no coverage" module.
+#[cfg_attr(coverage_nightly, coverage(off))]
+mod cli {
+    use aoc_leaderbot_aws_lambda_impl::leaderbot::DEFAULT_DYNAMODB_TABLE_NAME;
+    use clap::{Args, Subcommand};
+
+    use super::*;
+
+    #[derive(Debug, Parser)]
+    #[command(version, about = "Inspect and administer aoc_leaderbot data stored in DynamoDB", long_about = None)]
+    pub struct Cli {
+        /// Name of DynamoDB table used for leaderboard data
+        #[arg(short, long, global = true, default_value_t = DEFAULT_DYNAMODB_TABLE_NAME.into())]
+        pub table_name: String,
+
+        /// Test endpoint URL. Used by tests only, not shown in help
+        #[arg(long, global = true, hide = true, default_value_t)]
+        pub test_endpoint_url: String,
+
+        #[command(subcommand)]
+        pub command: Command,
+    }
+
+    #[derive(Debug, Subcommand)]
+    pub enum Command {
+        /// List all leaderboards stored in the table
+        List,
+
+        /// Delete all data stored for a leaderboard; its next bot run will be a first run
+        Delete(Target),
+
+        /// Clear the last error stored for a leaderboard
+        ClearError(Target),
+    }
+
+    #[derive(Debug, Args)]
+    pub struct Target {
+        /// Year of leaderboard
+        #[arg(short, long)]
+        pub year: i32,
+
+        /// ID of leaderboard
+        #[arg(short, long)]
+        pub leaderboard_id: u64,
+    }
+}
@@ -4,19 +4,30 @@
 //! [`aoc_leaderbot`]: https://github.com/clechasseur/aoc_leaderbot
 
 use std::borrow::Cow;
+use std::env;
 use std::fmt::Debug;
 
-use aoc_leaderboard::aoc::LeaderboardCredentials;
+use aoc_leaderboard::aoc::{LeaderboardCredentials, LeaderboardCredentialsKind};
+use aoc_leaderbot_aws_lib::leaderbot::credentials::aws::secrets::{
+    AwsSecretCredentialsProvider, AwsSecretSource,
+};
 use aoc_leaderbot_aws_lib::leaderbot::storage::aws::dynamodb::DynamoDbStorage;
-use aoc_leaderbot_lib::leaderbot::config::env::get_env_config;
+use aoc_leaderbot_lib::ErrorKind;
+use aoc_leaderbot_lib::error::RunError;
+use aoc_leaderbot_lib::leaderbot::clock::SharedClock;
+use aoc_leaderbot_lib::leaderbot::config::env::{get_env_config, get_env_config_with_credentials};
 use aoc_leaderbot_lib::leaderbot::config::mem::MemoryConfig;
-use aoc_leaderbot_lib::leaderbot::{BotOutput, Config, Reporter, run_bot_from};
+use aoc_leaderbot_lib::leaderbot::diagnostics::RunDiagnostics;
+use aoc_leaderbot_lib::leaderbot::dynamic::BackendRegistry;
+use aoc_leaderbot_lib::leaderbot::outbox::run_bot_outbox_diagnosed_from;
+use aoc_leaderbot_lib::leaderbot::team::Team;
+use aoc_leaderbot_lib::leaderbot::{BotOutput, Config, Reporter, provide_credentials};
 use aoc_leaderbot_slack_lib::leaderbot::reporter::slack::webhook::{
     LeaderboardSortOrder, SlackWebhookReporter,
 };
 use lambda_runtime::{Error, LambdaEvent};
 use serde::{Deserialize, Serialize};
-use tracing::{debug, info, trace};
+use tracing::{Span, debug, error, info, trace};
 use veil::Redact;
 
 /// Struct used to deserialize the incoming message passed
@@ -46,6 +57,15 @@ pub struct IncomingMessage {
     #[serde(default)]
     pub credentials: Option<LeaderboardCredentials>,
 
+    /// Whether to replace member names with pseudonyms.
+    ///
+    /// If set, overrides [`MemberSettings::pseudonymize`]. Pseudonyms are used in the
+    /// Slack reports as well as in the [`OutgoingMessage`].
+    ///
+    /// [`MemberSettings::pseudonymize`]: aoc_leaderbot_lib::leaderbot::members::MemberSettings::pseudonymize
+    #[serde(default)]
+    pub pseudonymize_members: Option<bool>,
+
     /// Set to `true` to do a test run.
     ///
     /// A test run will report changes even if there are none.
@@ -60,6 +80,20 @@ pub struct IncomingMessage {
     #[serde(default)]
     pub aoc_base_url: Option<String>,
 
+    /// Name of the storage backend to use (see [`backend_registry`]).
+    ///
+    /// If not set, the backend is read from the [`STORAGE_ENV_VAR`] environment variable,
+    /// defaulting to [`DYNAMODB_STORAGE_NAME`].
+    #[serde(default)]
+    pub storage: Option<String>,
+
+    /// Name of the reporter backend to use (see [`backend_registry`]).
+    ///
+    /// If not set, the backend is read from the [`REPORTER_ENV_VAR`] environment variable,
+    /// defaulting to [`SLACK_REPORTER_NAME`].
+    #[serde(default)]
+    pub reporter: Option<String>,
+
     /// AWS DynamoDB storage-specific input parameters.
     #[serde(flatten)]
     pub dynamodb_storage_input: IncomingDynamoDbStorageInput,
@@ -72,7 +106,7 @@ pub struct IncomingMessage {
 /// AWS DynamoDB storage-specific part of the lambda's [`IncomingMessage`].
 ///
 /// Allows caller to override the storage's table name.
-#[derive(Debug, Default, Clone, Deserialize)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct IncomingDynamoDbStorageInput {
     /// Name of DynamoDB table to use to store leaderboard data.
@@ -100,7 +134,7 @@ pub struct IncomingDynamoDbStorageInput {
 /// Slack webhook reporter-specific part of the lambda's [`IncomingMessage`].
 ///
 /// Allows caller to override fields in the [`SlackWebhookReporter`].
-#[derive(Redact, Default, Clone, Deserialize)]
+#[derive(Redact, Default, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct IncomingSlackWebhookReporterInput {
     /// Slack webhook URL where to report changes.
@@ -132,16 +166,110 @@ pub struct IncomingSlackWebhookReporterInput {
 
 /// Struct used to serialize the output of our [AWS Lambda] function.
 ///
+/// The bot's output includes [diagnostics] about the bot run (fetch latency, storage operations,
+/// reporter calls, etc.); since the function's output is logged, they can be used to debug a bot
+/// run from CloudWatch without enabling trace logs.
+///
+/// If the bot run fails, the error is returned in the message along with the diagnostics
+/// collected before the failure, instead of failing the function. The error has already been
+/// reported via Slack at that point, so the invocation should not be retried.
+///
 /// [AWS Lambda]: https://aws.amazon.com/lambda/
+/// [diagnostics]: BotOutput::diagnostics
 #[derive(Debug, Clone, Serialize)]
 pub struct OutgoingMessage {
     /// [Output](BotOutput) of the bot's run.
-    pub output: BotOutput,
+    ///
+    /// Set to `None` if the bot run was skipped because it happened outside the
+    /// [event window](Config::event_window), or if it failed (see [`error`](Self::error)).
+    pub output: Option<BotOutput>,
+
+    /// Error that occurred during the bot's run, if any.
+    pub error: Option<ErrorOutput>,
+}
+
+/// Error that occurred during the bot's run, as returned in the [`OutgoingMessage`].
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorOutput {
+    /// Kind of error that occurred.
+    pub kind: ErrorKind,
+
+    /// Error message.
+    pub message: String,
+
+    /// [Diagnostics](RunDiagnostics) about the bot run, up to the point where it failed.
+    pub diagnostics: RunDiagnostics,
+}
+
+impl From<RunError> for ErrorOutput {
+    fn from(value: RunError) -> Self {
+        Self {
+            kind: ErrorKind::from(&value.error),
+            message: value.error.to_string(),
+            diagnostics: value.diagnostics,
+        }
+    }
 }
 
 /// Prefix of environment variables used for the bot [`Config`] (see [`get_env_config`]).
 pub const CONFIG_ENV_VAR_PREFIX: &str = "AOC_LEADERBOT_AWS_";
 
+/// Name of environment variable containing the ID of an [AWS Secrets Manager] secret storing
+/// the leaderboard credentials.
+///
+/// If set, credentials are fetched from the secret instead of the environment (see
+/// [`AwsSecretCredentialsProvider`]). The secret can contain either the credentials only, or a
+/// JSON object (see [`CREDENTIALS_SECRET_JSON_KEY_ENV_VAR`]).
+///
+/// [AWS Secrets Manager]: https://aws.amazon.com/secrets-manager/
+pub const CREDENTIALS_SECRET_ID_ENV_VAR: &str = "AOC_LEADERBOT_AWS_CREDENTIALS_SECRET_ID";
+
+/// Name of environment variable containing the key of the credentials in the secret
+/// identified by [`CREDENTIALS_SECRET_ID_ENV_VAR`], if it is stored as a JSON object.
+pub const CREDENTIALS_SECRET_JSON_KEY_ENV_VAR: &str =
+    "AOC_LEADERBOT_AWS_CREDENTIALS_SECRET_JSON_KEY";
+
+/// Name of environment variable containing the name of an [AWS Systems Manager Parameter Store]
+/// parameter storing the leaderboard credentials.
+///
+/// If set (and [`CREDENTIALS_SECRET_ID_ENV_VAR`] is not), credentials are fetched from the
+/// parameter instead of the environment (see [`AwsSecretCredentialsProvider`]).
+///
+/// [AWS Systems Manager Parameter Store]: https://docs.aws.amazon.com/systems-manager/latest/userguide/systems-manager-parameter-store.html
+pub const CREDENTIALS_PARAMETER_NAME_ENV_VAR: &str = "AOC_LEADERBOT_AWS_CREDENTIALS_PARAMETER_NAME";
+
+/// Name of environment variable containing the kind of credentials stored in AWS: either
+/// `session_cookie` (the default) or `view_key`.
+pub const CREDENTIALS_KIND_ENV_VAR: &str = "AOC_LEADERBOT_AWS_CREDENTIALS_KIND";
+
+/// Name of environment variable that can be set to `true` to fetch credentials through the
+/// [AWS Parameters and Secrets Lambda Extension] instead of calling AWS directly.
+///
+/// The extension caches secrets, but must be added to the Lambda function as a layer
+/// (see [`AwsSecretCredentialsProvider::lambda_extension`]).
+///
+/// [AWS Parameters and Secrets Lambda Extension]: https://docs.aws.amazon.com/secretsmanager/latest/userguide/retrieving-secrets_lambda.html
+pub const CREDENTIALS_USE_LAMBDA_EXTENSION_ENV_VAR: &str =
+    "AOC_LEADERBOT_AWS_CREDENTIALS_USE_LAMBDA_EXTENSION";
+
+/// Name of environment variable containing the name of the storage backend to use
+/// (see [`backend_registry`]).
+///
+/// Can be overridden via [`IncomingMessage::storage`].
+pub const STORAGE_ENV_VAR: &str = "AOC_LEADERBOT_AWS_STORAGE";
+
+/// Name of environment variable containing the name of the reporter backend to use
+/// (see [`backend_registry`]).
+///
+/// Can be overridden via [`IncomingMessage::reporter`].
+pub const REPORTER_ENV_VAR: &str = "AOC_LEADERBOT_AWS_REPORTER";
+
+/// Name of the [`DynamoDbStorage`] backend in the [`backend_registry`]. Used by default.
+pub const DYNAMODB_STORAGE_NAME: &str = "dynamodb";
+
+/// Name of the [`SlackWebhookReporter`] backend in the [`backend_registry`]. Used by default.
+pub const SLACK_REPORTER_NAME: &str = "slack";
+
 /// Default name of DynamoDB table used for the bot [`Storage`] (see [`DynamoDbStorage`]).
 ///
 /// [`Storage`]: aoc_leaderbot_lib::leaderbot::Storage
@@ -149,24 +277,57 @@ pub const DEFAULT_DYNAMODB_TABLE_NAME: &str = "aoc_leaderbot";
 
 /// [AWS Lambda] function handler that will be called to monitor an AoC leaderboard.
 ///
-/// The handler will call the [`run_bot`] function using the following parameters:
+/// The handler will call the [`run_bot_outbox_diagnosed`] function using the following
+/// parameters:
 ///
 /// - [`Config`] loaded from the environment (see [`get_env_config`]), possibly
-///   overridden via the [input](IncomingMessage)
-/// - [`DynamoDbStorage`]
-/// - [`SlackWebhookReporter`]
+///   overridden via the [input](IncomingMessage); credentials can also be fetched from
+///   AWS Secrets Manager or Parameter Store (see [`CREDENTIALS_SECRET_ID_ENV_VAR`] and
+///   [`CREDENTIALS_PARAMETER_NAME_ENV_VAR`])
+/// - Storage built via the [`backend_registry`] ([`DynamoDbStorage`] by default; see
+///   [`STORAGE_ENV_VAR`])
+/// - Reporter built via the [`backend_registry`] ([`SlackWebhookReporter`] by default; see
+///   [`REPORTER_ENV_VAR`])
+///
+/// Using an outbox ensures that changes are only reported once, even if saving
+/// leaderboard data to DynamoDB fails (for example, because of throttling). If the bot run
+/// fails, the error and the diagnostics collected before the failure are returned in the
+/// [`OutgoingMessage`] (see [`ErrorOutput`]).
+///
+/// The handler's span records the year and ID of the monitored leaderboard, so that they
+/// are included in exported traces when the `opentelemetry` feature is enabled.
 ///
 /// [AWS Lambda]: https://aws.amazon.com/lambda/
-/// [`run_bot`]: aoc_leaderbot_lib::leaderbot::run_bot
-#[cfg_attr(not(coverage), tracing::instrument(ret, err))]
+/// [`run_bot_outbox_diagnosed`]: aoc_leaderbot_lib::leaderbot::outbox::run_bot_outbox_diagnosed
+#[cfg_attr(
+    not(coverage),
+    tracing::instrument(ret, err, fields(year = tracing::field::Empty, leaderboard_id = tracing::field::Empty))
+)]
 pub async fn bot_lambda_handler(
     event: LambdaEvent<IncomingMessage>,
 ) -> Result<OutgoingMessage, Error> {
     let input = event.payload;
 
-    let config = get_config(&input)?;
-    let mut storage = get_storage(&input).await;
-    let mut reporter = get_reporter(&input)?;
+    let config = get_config(&input).await?;
+    Span::current()
+        .record("year", config.year())
+        .record("leaderboard_id", config.leaderboard_id());
+    let registry = backend_registry();
+    let mut storage = registry
+        .build_outbox_storage(
+            &backend_name(input.storage.as_deref(), STORAGE_ENV_VAR, DYNAMODB_STORAGE_NAME),
+            serde_json::to_value(&input.dynamodb_storage_input)?,
+        )
+        .await?;
+    let mut reporter = registry
+        .build_reporter(
+            &backend_name(input.reporter.as_deref(), REPORTER_ENV_VAR, SLACK_REPORTER_NAME),
+            serde_json::to_value(SlackWebhookReporterSettings {
+                input: input.slack_webhook_reporter_input.clone(),
+                teams: config.teams(),
+            })?,
+        )
+        .await?;
 
     #[cfg(feature = "__testing")]
     let advent_of_code_base = input.aoc_base_url;
@@ -174,11 +335,25 @@ pub async fn bot_lambda_handler(
     let advent_of_code_base: Option<String> = None;
 
     trace!("Running bot (test run: {})", input.test_run);
-    let output =
-        run_bot_from(advent_of_code_base, &config, &mut storage, &mut reporter, input.test_run)
-            .await?;
-
-    if input.test_run {
+    let mut output = match run_bot_outbox_diagnosed_from(
+        advent_of_code_base,
+        &config,
+        &mut storage,
+        &mut reporter,
+        input.test_run,
+    )
+    .await
+    {
+        Ok(output) => output,
+        Err(err) => {
+            error!("Bot run failed: {err}");
+            return Ok(OutgoingMessage { output: None, error: Some(err.into()) });
+        },
+    };
+
+    if let Some(output) = output.as_mut()
+        && input.test_run
+    {
         let previous_leaderboard = output
             .previous_leaderboard
             .as_ref()
@@ -191,58 +366,160 @@ pub async fn bot_lambda_handler(
 
         info!("Test run: reporting changes");
         debug!(?previous_leaderboard, ?changes);
-        reporter
-            .report_changes(
-                output.year,
-                output.leaderboard_id,
-                config.credentials().view_key(),
-                previous_leaderboard,
-                &output.leaderboard,
-                &changes,
+        output
+            .diagnostics
+            .reporter_call(
+                "report_changes",
+                reporter.report_changes(
+                    output.year,
+                    output.leaderboard_id,
+                    config.credentials().view_key(),
+                    previous_leaderboard,
+                    &output.leaderboard,
+                    &changes,
+                ),
             )
             .await?;
     }
 
-    Ok(OutgoingMessage { output })
+    Ok(OutgoingMessage { output, error: None })
+}
+
+/// Returns a [`BackendRegistry`] containing the backends that can be used by the
+/// [`bot_lambda_handler`].
+///
+/// | Name                      | Backend                  | Settings                               |
+/// |---------------------------|--------------------------|----------------------------------------|
+/// | [`DYNAMODB_STORAGE_NAME`] | [`DynamoDbStorage`]      | [`IncomingDynamoDbStorageInput`]       |
+/// | [`SLACK_REPORTER_NAME`]   | [`SlackWebhookReporter`] | [`IncomingSlackWebhookReporterInput`]  |
+///
+/// The [`DynamoDbStorage`] supports an [outbox](aoc_leaderbot_lib::leaderbot::OutboxStorage).
+pub fn backend_registry() -> BackendRegistry {
+    BackendRegistry::new()
+        .with_outbox_storage(DYNAMODB_STORAGE_NAME, |settings| async move {
+            let input: IncomingDynamoDbStorageInput = serde_json::from_value(settings)?;
+            Ok::<_, anyhow::Error>(get_storage(&input).await)
+        })
+        .with_reporter(SLACK_REPORTER_NAME, |settings| async move {
+            let settings: SlackWebhookReporterSettings = serde_json::from_value(settings)?;
+            Ok::<_, anyhow::Error>(get_reporter(&settings.input, settings.teams)?)
+        })
+}
+
+#[derive(Serialize, Deserialize)]
+struct SlackWebhookReporterSettings {
+    #[serde(flatten)]
+    input: IncomingSlackWebhookReporterInput,
+    #[serde(default)]
+    teams: Vec<Team>,
+}
+
+fn backend_name(input: Option<&str>, env_var_name: &str, default: &str) -> String {
+    input
+        .map(ToString::to_string)
+        .or_else(|| env::var(env_var_name).ok())
+        .unwrap_or_else(|| default.into())
 }
 
 #[cfg_attr(not(coverage), tracing::instrument(err))]
-fn get_config(input: &IncomingMessage) -> Result<MemoryConfig, Error> {
-    let (year, leaderboard_id, credentials) =
-        match (input.year, input.leaderboard_id, input.credentials.clone()) {
-            (Some(year), Some(leaderboard_id), Some(credentials)) => {
-                (year, leaderboard_id, credentials)
-            },
-            (year, leaderboard_id, credentials) => {
-                let env_config = get_env_config(CONFIG_ENV_VAR_PREFIX)?;
-                (
-                    year.unwrap_or_else(|| env_config.year()),
-                    leaderboard_id.unwrap_or_else(|| env_config.leaderboard_id()),
-                    credentials.unwrap_or_else(|| env_config.credentials()),
-                )
-            },
-        };
-    debug!(year, leaderboard_id, ?credentials);
-
-    Ok(MemoryConfig::builder()
-        .year(year)
-        .leaderboard_id(leaderboard_id)
-        .credentials(credentials)
-        .build()
-        .expect("all fields should have been specified"))
+async fn get_config(input: &IncomingMessage) -> Result<MemoryConfig, Error> {
+    let mut config = match (input.year, input.leaderboard_id, input.credentials.clone()) {
+        (Some(year), Some(leaderboard_id), Some(credentials)) => {
+            MemoryConfig::new(year, leaderboard_id, credentials)
+        },
+        (year, leaderboard_id, credentials) => {
+            let credentials = match (credentials, get_credentials_provider().await?) {
+                (Some(credentials), _) => Some(credentials),
+                (None, Some(provider)) => Some(provide_credentials(&provider).await?),
+                (None, None) => None,
+            };
+            let mut config = match credentials {
+                Some(credentials) => memory_config(&get_env_config_with_credentials(
+                    CONFIG_ENV_VAR_PREFIX,
+                    credentials,
+                )?),
+                None => memory_config(&get_env_config(CONFIG_ENV_VAR_PREFIX)?),
+            };
+            if let Some(year) = year {
+                config.year = Some(year);
+            }
+            if let Some(leaderboard_id) = leaderboard_id {
+                config.leaderboard_id = leaderboard_id;
+            }
+            config
+        },
+    };
+    if let Some(pseudonymize) = input.pseudonymize_members {
+        config.member_settings.pseudonymize = pseudonymize;
+    }
+    debug!(?config);
+
+    Ok(config)
+}
+
+fn memory_config<C>(config: &C) -> MemoryConfig
+where
+    C: Config,
+{
+    MemoryConfig {
+        year: Some(config.year()),
+        leaderboard_id: config.leaderboard_id(),
+        credentials: config.credentials(),
+        renotify_policy: config.renotify_policy(),
+        event_window: config.event_window(),
+        heartbeat_policy: config.heartbeat_policy(),
+        merged_leaderboards: config.merged_leaderboards(),
+        teams: config.teams(),
+        member_settings: config.member_settings(),
+        clock: SharedClock::default(),
+    }
+}
+
+#[cfg_attr(not(coverage), tracing::instrument(err))]
+async fn get_credentials_provider() -> Result<Option<AwsSecretCredentialsProvider>, Error> {
+    let source = match (
+        env::var(CREDENTIALS_SECRET_ID_ENV_VAR),
+        env::var(CREDENTIALS_PARAMETER_NAME_ENV_VAR),
+    ) {
+        (Ok(secret_id), _) => AwsSecretSource::SecretsManager {
+            secret_id,
+            json_key: env::var(CREDENTIALS_SECRET_JSON_KEY_ENV_VAR).ok(),
+        },
+        (Err(_), Ok(name)) => AwsSecretSource::ParameterStore { name },
+        (Err(_), Err(_)) => return Ok(None),
+    };
+    let kind = match env::var(CREDENTIALS_KIND_ENV_VAR).as_deref() {
+        Ok("view_key") => LeaderboardCredentialsKind::ViewKey,
+        Ok("session_cookie") | Err(_) => LeaderboardCredentialsKind::SessionCookie,
+        Ok(kind) => {
+            return Err(format!(
+                "invalid value for {CREDENTIALS_KIND_ENV_VAR}: {kind} (expected view_key or session_cookie)"
+            )
+            .into());
+        },
+    };
+
+    let provider = match env::var(CREDENTIALS_USE_LAMBDA_EXTENSION_ENV_VAR).as_deref() {
+        Ok("true") => AwsSecretCredentialsProvider::lambda_extension(source, kind),
+        _ => AwsSecretCredentialsProvider::new(source, kind).await,
+    };
+
+    Ok(Some(provider))
 }
 
 #[cfg_attr(not(coverage), tracing::instrument)]
-async fn get_storage(input: &IncomingMessage) -> DynamoDbStorage {
+async fn get_storage(input: &IncomingDynamoDbStorageInput) -> DynamoDbStorage {
     #[cfg(feature = "__testing")]
     #[cfg_attr(coverage_nightly, coverage(off))]
-    async fn internal_get_storage(input: &IncomingMessage, table_name: String) -> DynamoDbStorage {
-        match input.dynamodb_storage_input.test_endpoint_url.as_ref() {
+    async fn internal_get_storage(
+        input: &IncomingDynamoDbStorageInput,
+        table_name: String,
+    ) -> DynamoDbStorage {
+        match input.test_endpoint_url.as_ref() {
             Some(endpoint_url) => {
                 let config = aws_config::defaults(aws_config::BehaviorVersion::latest())
                     .region(aws_config::Region::new(
                         input
-                            .dynamodb_storage_input
                             .test_region
                             .as_ref()
                             .map(|region| Cow::Owned(region.clone()))
@@ -259,37 +536,43 @@ async fn get_storage(input: &IncomingMessage) -> DynamoDbStorage {
     }
 
     #[cfg(not(feature = "__testing"))]
-    async fn internal_get_storage(_input: &IncomingMessage, table_name: String) -> DynamoDbStorage {
+    async fn internal_get_storage(
+        _input: &IncomingDynamoDbStorageInput,
+        table_name: String,
+    ) -> DynamoDbStorage {
         DynamoDbStorage::new(table_name).await
     }
 
     let table_name = input
-        .dynamodb_storage_input
         .table_name
         .clone()
         .unwrap_or_else(|| DEFAULT_DYNAMODB_TABLE_NAME.into());
     internal_get_storage(input, table_name).await
 }
 
-#[cfg_attr(not(coverage), tracing::instrument(err))]
-fn get_reporter(input: &IncomingMessage) -> Result<SlackWebhookReporter, Error> {
+#[cfg_attr(not(coverage), tracing::instrument(skip(teams), err))]
+fn get_reporter(
+    input: &IncomingSlackWebhookReporterInput,
+    teams: Vec<Team>,
+) -> aoc_leaderbot_slack_lib::Result<SlackWebhookReporter> {
     let mut builder = SlackWebhookReporter::builder();
+    builder.teams(teams);
 
-    if let Some(webhook_url) = input.slack_webhook_reporter_input.webhook_url.clone() {
+    if let Some(webhook_url) = input.webhook_url.clone() {
         builder.webhook_url(webhook_url);
     }
-    if let Some(channel) = input.slack_webhook_reporter_input.channel.clone() {
+    if let Some(channel) = input.channel.clone() {
         builder.channel(channel);
     }
-    if let Some(username) = input.slack_webhook_reporter_input.username.clone() {
+    if let Some(username) = input.username.clone() {
         builder.username(username);
     }
-    if let Some(icon_url) = input.slack_webhook_reporter_input.icon_url.clone() {
+    if let Some(icon_url) = input.icon_url.clone() {
         builder.icon_url(icon_url);
     }
-    if let Some(sort_order) = input.slack_webhook_reporter_input.sort_order {
+    if let Some(sort_order) = input.sort_order {
         builder.sort_order(sort_order);
     }
 
-    Ok(builder.build()?)
+    builder.build()
 }
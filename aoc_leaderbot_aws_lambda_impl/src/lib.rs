@@ -2,6 +2,9 @@
 //! an [Advent of Code] private leaderboard for changes and report them to various channels
 //! like Slack.
 //!
+//! If the `opentelemetry` feature is enabled, the function can also export traces of each
+//! bot run to an OpenTelemetry collector (see the `telemetry` module).
+//!
 //! [AWS Lambda]: https://aws.amazon.com/lambda/
 //! [`aoc_leaderbot`]: https://github.com/clechasseur/aoc_leaderbot
 //! [Advent of Code]: https://adventofcode.com/
@@ -12,5 +15,8 @@
 #![deny(rustdoc::private_intra_doc_links)]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![cfg_attr(coverage_nightly, feature(coverage_attribute))]
+#![recursion_limit = "256"]
 
 pub mod leaderbot;
+#[cfg(feature = "opentelemetry")]
+pub mod telemetry;
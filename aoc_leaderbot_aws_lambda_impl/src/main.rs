@@ -9,15 +9,34 @@
 #![cfg_attr(coverage_nightly, feature(coverage_attribute))]
 
 use aoc_leaderbot_aws_lambda_impl::leaderbot::bot_lambda_handler;
+#[cfg(feature = "opentelemetry")]
+use aoc_leaderbot_aws_lambda_impl::telemetry;
 use dotenvy::dotenv;
-use lambda_runtime::{Error, run, service_fn, tracing};
+use lambda_runtime::{Error, run, service_fn};
 
 #[tokio::main]
 #[cfg_attr(coverage_nightly, coverage(off))]
 async fn main() -> Result<(), Error> {
     let _ = dotenv();
 
-    tracing::init_default_subscriber();
+    #[cfg(feature = "opentelemetry")]
+    if let Some(tracer_provider) = telemetry::init_subscriber()? {
+        // The handler's span is the root of the trace, so we do not use `run` here since
+        // it wraps every invocation in its own span.
+        return lambda_runtime::Runtime::new(service_fn(move |event| {
+            let tracer_provider = tracer_provider.clone();
+            async move {
+                let result = bot_lambda_handler(event).await;
+                telemetry::flush(&tracer_provider);
+                result
+            }
+        }))
+        .run()
+        .await;
+    }
+    // Without an OTLP endpoint, `init_subscriber` installs the default subscriber.
+    #[cfg(not(feature = "opentelemetry"))]
+    lambda_runtime::tracing::init_default_subscriber();
 
     run(service_fn(bot_lambda_handler)).await
 }
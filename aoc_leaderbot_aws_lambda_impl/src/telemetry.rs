@@ -0,0 +1,156 @@
+//! [OpenTelemetry] tracing support for the [AWS Lambda] function.
+//!
+//! By default, the function only logs to [CloudWatch] (see [`init_default_subscriber`]). If an
+//! [OTLP] endpoint is configured through the standard environment variables (see
+//! [`otlp_configured`]), spans are also exported to that endpoint over HTTP, so that a whole bot
+//! run can be traced end to end: the handler's span includes the [year] and [leaderboard ID] being
+//! monitored, and trace context is propagated (using the [W3C Trace Context] format) into the
+//! requests sent to the Advent of Code website and to Slack.
+//!
+//! Other standard environment variables, like `OTEL_EXPORTER_OTLP_HEADERS`,
+//! `OTEL_EXPORTER_OTLP_TIMEOUT` or `OTEL_SERVICE_NAME`, are also supported. If no service name is
+//! configured, the name of the Lambda function is used.
+//!
+//! [OpenTelemetry]: https://opentelemetry.io/
+//! [AWS Lambda]: https://aws.amazon.com/lambda/
+//! [CloudWatch]: https://aws.amazon.com/cloudwatch/
+//! [`init_default_subscriber`]: lambda_runtime::tracing::init_default_subscriber
+//! [OTLP]: https://opentelemetry.io/docs/specs/otlp/
+//! [year]: aoc_leaderbot_lib::leaderbot::Config::year
+//! [leaderboard ID]: aoc_leaderbot_lib::leaderbot::Config::leaderboard_id
+//! [W3C Trace Context]: https://www.w3.org/TR/trace-context/
+
+use std::env;
+use std::str::FromStr;
+
+use lambda_runtime::Error;
+use opentelemetry::global;
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_otlp::{
+    OTEL_EXPORTER_OTLP_ENDPOINT, OTEL_EXPORTER_OTLP_TRACES_ENDPOINT, SpanExporter,
+};
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::{SdkTracer, SdkTracerProvider};
+use tracing::Subscriber;
+use tracing::level_filters::LevelFilter;
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
+
+/// Name of environment variable containing the name of the service reported in traces.
+pub const SERVICE_NAME_ENV_VAR: &str = "OTEL_SERVICE_NAME";
+
+/// Name of the [OpenTelemetry] tracer used to export spans.
+///
+/// [OpenTelemetry]: https://opentelemetry.io/
+pub const TRACER_NAME: &str = env!("CARGO_PKG_NAME");
+
+/// Returns `true` if an [OTLP] endpoint where to export spans has been configured via
+/// `OTEL_EXPORTER_OTLP_ENDPOINT` or `OTEL_EXPORTER_OTLP_TRACES_ENDPOINT`.
+///
+/// [OTLP]: https://opentelemetry.io/docs/specs/otlp/
+pub fn otlp_configured() -> bool {
+    env::var_os(OTEL_EXPORTER_OTLP_ENDPOINT).is_some()
+        || env::var_os(OTEL_EXPORTER_OTLP_TRACES_ENDPOINT).is_some()
+}
+
+/// Creates a tracer provider exporting spans to the [OTLP] endpoint configured in the
+/// environment.
+///
+/// Spans are exported in batches; call [`flush`] to make sure they have all been exported
+/// before the Lambda function is frozen.
+///
+/// [OTLP]: https://opentelemetry.io/docs/specs/otlp/
+pub fn tracer_provider() -> Result<SdkTracerProvider, Error> {
+    let exporter = SpanExporter::builder().with_http().build()?;
+
+    let mut resource = Resource::builder();
+    if env::var_os(SERVICE_NAME_ENV_VAR).is_none() {
+        let service_name =
+            env::var("AWS_LAMBDA_FUNCTION_NAME").unwrap_or_else(|_| TRACER_NAME.into());
+        resource = resource.with_service_name(service_name);
+    }
+
+    Ok(SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(resource.build())
+        .build())
+}
+
+/// Returns a [`tracing_subscriber`] layer that records spans using the given tracer provider.
+///
+/// Spans emitted by the bot's crates are recorded from the `DEBUG` level; other spans (like
+/// those emitted by the AWS SDK) are recorded from the `INFO` level.
+pub fn layer<S>(tracer_provider: &SdkTracerProvider) -> impl Layer<S>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    let targets = Targets::new().with_default(LevelFilter::INFO).with_targets(
+        [
+            "aoc_leaderboard",
+            "aoc_leaderbot_lib",
+            "aoc_leaderbot_aws_lib",
+            "aoc_leaderbot_slack_lib",
+            "aoc_leaderbot_aws_lambda_impl",
+        ]
+        .map(|target| (target, LevelFilter::DEBUG)),
+    );
+
+    let layer: OpenTelemetryLayer<S, SdkTracer> =
+        tracing_opentelemetry::layer().with_tracer(tracer_provider.tracer(TRACER_NAME));
+    layer.with_filter(targets)
+}
+
+/// Initializes the global [`tracing`] subscriber, exporting spans to an [OTLP] endpoint
+/// if one has been configured (see [module documentation](self)).
+///
+/// Logs are written like [`init_default_subscriber`] does. If spans are exported,
+/// the tracer provider is returned; it must be [flushed](flush) after each invocation.
+///
+/// [OTLP]: https://opentelemetry.io/docs/specs/otlp/
+/// [`init_default_subscriber`]: lambda_runtime::tracing::init_default_subscriber
+pub fn init_subscriber() -> Result<Option<SdkTracerProvider>, Error> {
+    if !otlp_configured() {
+        lambda_runtime::tracing::init_default_subscriber();
+        return Ok(None);
+    }
+
+    let tracer_provider = tracer_provider()?;
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
+    // Logging options are the same as those used by `init_default_subscriber`.
+    let log_level = env::var("AWS_LAMBDA_LOG_LEVEL").or_else(|_| env::var("RUST_LOG"));
+    let log_level =
+        LevelFilter::from_str(log_level.as_deref().unwrap_or("INFO")).unwrap_or(LevelFilter::INFO);
+    let log_filter = EnvFilter::builder()
+        .with_default_directive(log_level.into())
+        .from_env_lossy();
+    let log_layer = tracing_subscriber::fmt::layer()
+        .with_target(false)
+        .without_time();
+    let log_layer = match env::var("AWS_LAMBDA_LOG_FORMAT") {
+        Ok(format) if format.eq_ignore_ascii_case("json") => log_layer.json().boxed(),
+        _ => log_layer.boxed(),
+    };
+
+    tracing_subscriber::registry()
+        .with(log_layer.with_filter(log_filter))
+        .with(layer(&tracer_provider))
+        .try_init()?;
+
+    Ok(Some(tracer_provider))
+}
+
+/// Exports all spans that have not been exported yet.
+///
+/// Should be called at the end of each invocation, since the Lambda function can be frozen
+/// (or terminated) afterwards.
+pub fn flush(tracer_provider: &SdkTracerProvider) {
+    if let Err(err) = tracer_provider.force_flush() {
+        tracing::warn!("error exporting spans: {err}");
+    }
+}
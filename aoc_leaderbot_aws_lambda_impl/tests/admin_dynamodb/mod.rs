@@ -0,0 +1,94 @@
+use aoc_leaderboard::aoc::Leaderboard;
+use aoc_leaderboard::test_helpers::{TEST_LEADERBOARD_ID, TEST_YEAR, test_leaderboard};
+use aoc_leaderbot_aws_lib::leaderbot::storage::aws::dynamodb::test_helpers::LocalTable;
+use aoc_leaderbot_lib::ErrorKind;
+use assert_cmd::{Command, cargo_bin};
+use assert_matches::assert_matches;
+use rstest::rstest;
+use serial_test::file_serial;
+
+fn admin_command(table: &LocalTable, subcommand: &str) -> Command {
+    let mut command = Command::new(cargo_bin!("admin_dynamodb"));
+    command
+        .arg(subcommand)
+        .arg("--table-name")
+        .arg(table.name())
+        .arg("--test-endpoint-url")
+        .arg(table.dynamodb_endpoint_url());
+    command
+}
+
+fn with_target(mut command: Command) -> Command {
+    command
+        .arg("--year")
+        .arg(TEST_YEAR.to_string())
+        .arg("--leaderboard-id")
+        .arg(TEST_LEADERBOARD_ID.to_string());
+    command
+}
+
+#[rstest]
+#[test_log::test]
+#[file_serial(testcontainers_dynamodb)]
+fn list(#[from(test_leaderboard)] leaderboard: Leaderboard) {
+    LocalTable::builder()
+        .containerized(true)
+        .run_test(move |table| async move {
+            admin_command(&table, "list")
+                .assert()
+                .success()
+                .stdout("");
+
+            table.save_leaderboard(&leaderboard).await;
+            table
+                .save_last_error(ErrorKind::Leaderboard(aoc_leaderboard::ErrorKind::NoAccess))
+                .await;
+
+            admin_command(&table, "list")
+                .assert()
+                .success()
+                .stdout(format!(
+                    "{TEST_LEADERBOARD_ID}\t{TEST_YEAR}\t{} members\tlast error: Leaderboard(NoAccess)\n",
+                    leaderboard.members.len(),
+                ));
+        });
+}
+
+#[rstest]
+#[test_log::test]
+#[file_serial(testcontainers_dynamodb)]
+fn delete(#[from(test_leaderboard)] leaderboard: Leaderboard) {
+    LocalTable::builder()
+        .containerized(true)
+        .run_test(move |table| async move {
+            table.save_leaderboard(&leaderboard).await;
+
+            with_target(admin_command(&table, "delete"))
+                .assert()
+                .success();
+
+            assert_matches!(table.load_leaderboard_and_last_error().await, (None, None));
+        });
+}
+
+#[rstest]
+#[test_log::test]
+#[file_serial(testcontainers_dynamodb)]
+fn clear_error(#[from(test_leaderboard)] leaderboard: Leaderboard) {
+    LocalTable::builder()
+        .containerized(true)
+        .run_test(move |table| async move {
+            table.save_leaderboard(&leaderboard).await;
+            table
+                .save_last_error(ErrorKind::Leaderboard(aoc_leaderboard::ErrorKind::NoAccess))
+                .await;
+
+            with_target(admin_command(&table, "clear-error"))
+                .assert()
+                .success();
+
+            assert_matches!(table.load_leaderboard_and_last_error().await, (Some(actual), None) => {
+                assert_eq!(actual, leaderboard);
+            });
+        });
+}
@@ -3,3 +3,9 @@
     feature = "__prepare_dynamodb"     // These tests only work if you compile with the internal `__prepare_dynamodb` feature. 
 ))]
 mod prepare_dynamodb;
+
+#[cfg(all(
+    any(not(ci), target_os = "linux"), // These tests require Docker, which only seems to work reliably on Linux in GitHub workflows.
+    feature = "__admin_dynamodb"       // These tests only work if you compile with the internal `__admin_dynamodb` feature.
+))]
+mod admin_dynamodb;
@@ -15,7 +15,8 @@ mod bot_lambda_handler {
     use aoc_leaderboard::reqwest::Method;
     use aoc_leaderboard::test_helpers::{
         TEST_AOC_SESSION, TEST_DAY_1_TS, TEST_DAY_2_TS, TEST_LEADERBOARD_ID, TEST_YEAR,
-        mock_server_with_leaderboard, test_leaderboard_credentials,
+        mock_server_with_inaccessible_leaderboard, mock_server_with_leaderboard,
+        test_leaderboard_credentials,
     };
     use aoc_leaderboard::wiremock::matchers::{header, method, path};
     use aoc_leaderboard::wiremock::{Mock, MockServer, ResponseTemplate};
@@ -27,6 +28,7 @@ mod bot_lambda_handler {
         LOCAL_ENDPOINT_URL, LocalTable,
     };
     use aoc_leaderbot_lib::ErrorKind;
+    use aoc_leaderbot_lib::leaderbot::members::pseudonym;
     use aoc_leaderbot_slack_lib::leaderbot::reporter::slack::webhook::LeaderboardSortOrder;
     use assert_matches::assert_matches;
     use lambda_runtime::{Context, LambdaEvent};
@@ -173,8 +175,11 @@ mod bot_lambda_handler {
                 year: Some(TEST_YEAR),
                 leaderboard_id: Some(TEST_LEADERBOARD_ID),
                 credentials: Some(LeaderboardCredentials::SessionCookie(TEST_AOC_SESSION.into())),
+                pseudonymize_members: None,
                 test_run,
                 aoc_base_url: Some(mock_server.uri()),
+                storage: None,
+                reporter: None,
                 dynamodb_storage_input: IncomingDynamoDbStorageInput {
                     table_name: Some(table.name().into()),
                     test_endpoint_url: Some(LOCAL_ENDPOINT_URL.into()),
@@ -201,12 +206,14 @@ mod bot_lambda_handler {
                     let event = LambdaEvent::new(incoming_message, Context::default());
                     let result = bot_lambda_handler(event).await;
 
-                    assert_matches!(result, Ok(OutgoingMessage { output }) => {
+                    assert_matches!(result, Ok(OutgoingMessage { output: Some(output), error: None }) => {
                         assert_eq!(output.year, TEST_YEAR);
                         assert_eq!(output.leaderboard_id, TEST_LEADERBOARD_ID);
                         assert!(output.previous_leaderboard.is_none());
                         assert_eq!(output.leaderboard, current_leaderboard);
                         assert!(output.changes.is_none());
+                        assert_eq!(output.diagnostics.fetches.len(), 1);
+                        assert_eq!(output.diagnostics.reporter_call_names(), ["report_first_run"]);
                     });
 
                     let actual = table.load_leaderboard_and_last_error().await;
@@ -232,12 +239,13 @@ mod bot_lambda_handler {
                             let event = LambdaEvent::new(incoming_message, Context::default());
                             let result = bot_lambda_handler(event).await;
 
-                            assert_matches!(result, Ok(OutgoingMessage { output }) => {
+                            assert_matches!(result, Ok(OutgoingMessage { output: Some(output), error: None }) => {
                                 assert_eq!(output.year, TEST_YEAR);
                                 assert_eq!(output.leaderboard_id, TEST_LEADERBOARD_ID);
                                 assert!(output.previous_leaderboard.is_none());
                                 assert_eq!(output.leaderboard, current_leaderboard);
                                 assert!(output.changes.is_none());
+                                assert_eq!(output.diagnostics.reporter_call_names(), ["report_changes"]);
                             });
 
                             let actual = table.load_leaderboard_and_last_error().await;
@@ -269,7 +277,7 @@ mod bot_lambda_handler {
                             let event = LambdaEvent::new(incoming_message, Context::default());
                             let result = bot_lambda_handler(event).await;
 
-                            assert_matches!(result, Ok(OutgoingMessage { output }) => {
+                            assert_matches!(result, Ok(OutgoingMessage { output: Some(output), error: None }) => {
                                 assert_eq!(output.year, TEST_YEAR);
                                 assert_eq!(output.leaderboard_id, TEST_LEADERBOARD_ID);
                                 assert_matches!(output.previous_leaderboard, Some(leaderboard) => {
@@ -305,7 +313,7 @@ mod bot_lambda_handler {
                                 let event = LambdaEvent::new(incoming_message, Context::default());
                                 let result = bot_lambda_handler(event).await;
 
-                                assert_matches!(result, Ok(OutgoingMessage { output }) => {
+                                assert_matches!(result, Ok(OutgoingMessage { output: Some(output), error: None }) => {
                                     assert_eq!(output.year, TEST_YEAR);
                                     assert_eq!(output.leaderboard_id, TEST_LEADERBOARD_ID);
                                     assert_matches!(output.previous_leaderboard, Some(leaderboard) => {
@@ -344,7 +352,7 @@ mod bot_lambda_handler {
                             let event = LambdaEvent::new(incoming_message, Context::default());
                             let result = bot_lambda_handler(event).await;
 
-                            assert_matches!(result, Ok(OutgoingMessage { output }) => {
+                            assert_matches!(result, Ok(OutgoingMessage { output: Some(output), error: None }) => {
                                 assert_eq!(output.year, TEST_YEAR);
                                 assert_eq!(output.leaderboard_id, TEST_LEADERBOARD_ID);
                                 assert_matches!(output.previous_leaderboard, Some(leaderboard) => {
@@ -365,6 +373,41 @@ mod bot_lambda_handler {
                     );
                 }
 
+                #[rstest]
+                #[test_log::test]
+                fn pseudonymizes_members(
+                    #[from(base_leaderboard)] previous_leaderboard: Leaderboard,
+                    #[from(leaderboard_with_new_member)] current_leaderboard: Leaderboard,
+                ) {
+                    run_bot_test(
+                        current_leaderboard.clone(),
+                        true,
+                        |mock_server, table| async move {
+                            table.save_leaderboard(&previous_leaderboard).await;
+
+                            let incoming_message = IncomingMessage {
+                                pseudonymize_members: Some(true),
+                                ..incoming_message(false, &mock_server, &table)
+                            };
+                            let event = LambdaEvent::new(incoming_message, Context::default());
+                            let result = bot_lambda_handler(event).await;
+
+                            assert_matches!(result, Ok(OutgoingMessage { output: Some(output), error: None }) => {
+                                for member in output.leaderboard.members.values() {
+                                    assert_eq!(member.name, Some(pseudonym(member.id)));
+
+                                    let current_member = &current_leaderboard.members[&member.id];
+                                    assert_eq!(member.stars, current_member.stars);
+                                    assert_eq!(member.local_score, current_member.local_score);
+                                }
+                                assert_matches!(output.changes, Some(changes) => {
+                                    assert_eq!(changes.new_members, [MEMBER_2].into());
+                                });
+                            });
+                        },
+                    );
+                }
+
                 mod test_run {
                     use super::*;
 
@@ -384,7 +427,7 @@ mod bot_lambda_handler {
                                 let event = LambdaEvent::new(incoming_message, Context::default());
                                 let result = bot_lambda_handler(event).await;
 
-                                assert_matches!(result, Ok(OutgoingMessage { output }) => {
+                                assert_matches!(result, Ok(OutgoingMessage { output: Some(output), error: None }) => {
                                     assert_eq!(output.year, TEST_YEAR);
                                     assert_eq!(output.leaderboard_id, TEST_LEADERBOARD_ID);
                                     assert_matches!(output.previous_leaderboard, Some(leaderboard) => {
@@ -408,6 +451,70 @@ mod bot_lambda_handler {
             }
         }
 
+        mod with_inaccessible_leaderboard {
+            use super::*;
+
+            #[rstest]
+            #[test_log::test]
+            fn returns_error_with_diagnostics() {
+                LocalTable::run_test(None, |table| async move {
+                    let mock_server = mock_server_with_inaccessible_leaderboard().await;
+                    mount_slack_webhook_handler(&mock_server, true).await;
+
+                    let incoming_message = incoming_message(false, &mock_server, &table);
+                    let event = LambdaEvent::new(incoming_message, Context::default());
+                    let result = bot_lambda_handler(event).await;
+
+                    assert_matches!(result, Ok(OutgoingMessage { output: None, error: Some(error) }) => {
+                        assert_eq!(
+                            error.kind,
+                            ErrorKind::Leaderboard(aoc_leaderboard::ErrorKind::NoAccess)
+                        );
+                        assert_eq!(
+                            error.diagnostics.storage_operation_names(),
+                            ["load_previous", "load_outbox", "save_error_state"]
+                        );
+                        assert_eq!(error.diagnostics.reporter_call_names(), ["report_error"]);
+                    });
+
+                    let actual = table.load_leaderboard_and_last_error().await;
+                    assert_matches!(
+                        actual,
+                        (None, Some(ErrorKind::Leaderboard(aoc_leaderboard::ErrorKind::NoAccess)))
+                    );
+                });
+            }
+        }
+
+        mod with_unknown_backend {
+            use aoc_leaderbot_lib::error::BackendKind;
+
+            use super::*;
+
+            #[rstest]
+            #[test_log::test]
+            fn returns_error() {
+                LocalTable::run_test(None, |table| async move {
+                    let mock_server = MockServer::start().await;
+
+                    let incoming_message = IncomingMessage {
+                        storage: Some("s3".into()),
+                        ..incoming_message(false, &mock_server, &table)
+                    };
+                    let event = LambdaEvent::new(incoming_message, Context::default());
+                    let result = bot_lambda_handler(event).await;
+
+                    assert_matches!(result, Err(lambda_err) => {
+                        assert_matches!(lambda_err.downcast::<aoc_leaderbot_lib::Error>(), Ok(err) => {
+                            assert_matches!(*err, aoc_leaderbot_lib::Error::UnknownBackend { kind: BackendKind::Storage, name } => {
+                                assert_eq!(name, "s3");
+                            });
+                        });
+                    });
+                });
+            }
+        }
+
         mod with_previous_last_error {
             use super::*;
 
@@ -425,7 +532,7 @@ mod bot_lambda_handler {
                     let event = LambdaEvent::new(incoming_message, Context::default());
                     let result = bot_lambda_handler(event).await;
 
-                    assert_matches!(result, Ok(OutgoingMessage { output }) => {
+                    assert_matches!(result, Ok(OutgoingMessage { output: Some(output), error: None }) => {
                         assert_eq!(output.year, TEST_YEAR);
                         assert_eq!(output.leaderboard_id, TEST_LEADERBOARD_ID);
                         assert!(output.previous_leaderboard.is_none());
@@ -462,7 +569,7 @@ mod bot_lambda_handler {
                             let event = LambdaEvent::new(incoming_message, Context::default());
                             let result = bot_lambda_handler(event).await;
 
-                            assert_matches!(result, Ok(OutgoingMessage { output }) => {
+                            assert_matches!(result, Ok(OutgoingMessage { output: Some(output), error: None }) => {
                                 assert_eq!(output.year, TEST_YEAR);
                                 assert_eq!(output.leaderboard_id, TEST_LEADERBOARD_ID);
                                 assert!(output.previous_leaderboard.is_none());
@@ -525,8 +632,11 @@ mod bot_lambda_handler {
                 year: None,
                 leaderboard_id: None,
                 credentials: None,
+                pseudonymize_members: None,
                 test_run: false,
                 aoc_base_url: Some(mock_server.uri()),
+                storage: None,
+                reporter: None,
                 dynamodb_storage_input: IncomingDynamoDbStorageInput {
                     table_name: Some(table.name().into()),
                     test_endpoint_url: Some(LOCAL_ENDPOINT_URL.into()),
@@ -554,7 +664,7 @@ mod bot_lambda_handler {
                     let event = LambdaEvent::new(incoming_message, Context::default());
                     let result = bot_lambda_handler(event).await;
 
-                    assert_matches!(result, Ok(OutgoingMessage { output }) => {
+                    assert_matches!(result, Ok(OutgoingMessage { output: Some(output), error: None }) => {
                         assert_eq!(output.year, TEST_YEAR);
                         assert_eq!(output.leaderboard_id, TEST_LEADERBOARD_ID);
                         assert_matches!(
@@ -615,6 +725,8 @@ mod bot_lambda_handler {
             }
 
             mod reporter {
+                use aoc_leaderbot_aws_lambda_impl::leaderbot::SLACK_REPORTER_NAME;
+                use aoc_leaderbot_lib::error::BackendKind;
                 use aoc_leaderbot_slack_lib::error::WebhookError;
                 use aoc_leaderbot_slack_lib::leaderbot::reporter::slack::webhook::SlackWebhookReporterBuilderError;
 
@@ -643,10 +755,15 @@ mod bot_lambda_handler {
                     let result = bot_lambda_handler(event).await;
 
                     assert_matches!(result, Err(lambda_err) => {
-                        assert_matches!(lambda_err.downcast::<aoc_leaderbot_slack_lib::Error>(), Ok(err) => {
-                            assert_matches!(*err, aoc_leaderbot_slack_lib::Error::Webhook(
-                                WebhookError::ReporterBuilder(SlackWebhookReporterBuilderError::ValidationError(_))
-                            ));
+                        assert_matches!(lambda_err.downcast::<aoc_leaderbot_lib::Error>(), Ok(err) => {
+                            assert_matches!(*err, aoc_leaderbot_lib::Error::Backend { kind: BackendKind::Reporter, name, source } => {
+                                assert_eq!(name, SLACK_REPORTER_NAME);
+                                assert_matches!(source.downcast::<aoc_leaderbot_slack_lib::Error>(), Ok(err) => {
+                                    assert_matches!(err, aoc_leaderbot_slack_lib::Error::Webhook(
+                                        WebhookError::ReporterBuilder(SlackWebhookReporterBuilderError::ValidationError(_))
+                                    ));
+                                });
+                            });
                         });
                     });
                 }
@@ -0,0 +1,87 @@
+#![cfg(feature = "opentelemetry")]
+
+mod telemetry {
+    use std::env;
+
+    use aoc_leaderboard::aoc::Leaderboard;
+    use aoc_leaderboard::test_helpers::{
+        TEST_LEADERBOARD_ID, TEST_YEAR, mock_server_with_leaderboard, test_leaderboard_credentials,
+    };
+    use aoc_leaderboard::wiremock::matchers::{method, path};
+    use aoc_leaderboard::wiremock::{Mock, MockServer, ResponseTemplate};
+    use aoc_leaderbot_aws_lambda_impl::telemetry::{
+        flush, layer, otlp_configured, tracer_provider,
+    };
+    use opentelemetry::global;
+    use opentelemetry_otlp::{OTEL_EXPORTER_OTLP_ENDPOINT, OTEL_EXPORTER_OTLP_TRACES_ENDPOINT};
+    use opentelemetry_sdk::propagation::TraceContextPropagator;
+    use rstest::rstest;
+    use serial_test::serial;
+    use tracing::Instrument;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    fn clear_environment() {
+        unsafe {
+            env::remove_var(OTEL_EXPORTER_OTLP_ENDPOINT);
+            env::remove_var(OTEL_EXPORTER_OTLP_TRACES_ENDPOINT);
+        }
+    }
+
+    #[test_log::test]
+    #[serial(otel_env)]
+    fn not_configured() {
+        clear_environment();
+
+        assert!(!otlp_configured());
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    #[serial(otel_env)]
+    async fn exports_spans_and_propagates_context(
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        aoc_server: MockServer,
+    ) {
+        let collector = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/traces"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1..)
+            .mount(&collector)
+            .await;
+
+        clear_environment();
+        unsafe {
+            env::set_var(OTEL_EXPORTER_OTLP_ENDPOINT, collector.uri());
+        }
+        assert!(otlp_configured());
+
+        let tracer_provider = tracer_provider().unwrap();
+        global::set_text_map_propagator(TraceContextPropagator::new());
+        let subscriber = tracing_subscriber::registry().with(layer(&tracer_provider));
+        {
+            let _guard = tracing::subscriber::set_default(subscriber);
+
+            Leaderboard::get_from(
+                Leaderboard::http_client().unwrap(),
+                aoc_server.uri(),
+                TEST_YEAR,
+                TEST_LEADERBOARD_ID,
+                &test_leaderboard_credentials::default(),
+            )
+            .instrument(tracing::info_span!("bot_run", year = TEST_YEAR))
+            .await
+            .unwrap();
+
+            flush(&tracer_provider);
+        }
+        clear_environment();
+
+        let requests = aoc_server.received_requests().await.unwrap();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].headers.contains_key("traceparent"));
+        collector.verify().await;
+    }
+}
@@ -10,6 +10,85 @@ pub enum Error {
     #[cfg(feature = "dynamodb-base")]
     #[error(transparent)]
     Dynamo(#[from] DynamoDbError),
+
+    /// AWS Secrets Manager or Parameter Store error.
+    #[cfg(feature = "credentials-secrets")]
+    #[error(transparent)]
+    Secrets(#[from] SecretsError),
+}
+
+/// Errors pertaining to fetching secrets from [AWS Secrets Manager] or [AWS Systems Manager
+/// Parameter Store].
+///
+/// [AWS Secrets Manager]: https://aws.amazon.com/secrets-manager/
+/// [AWS Systems Manager Parameter Store]: https://docs.aws.amazon.com/systems-manager/latest/userguide/systems-manager-parameter-store.html
+#[cfg(feature = "credentials-secrets")]
+#[derive(Debug, thiserror::Error)]
+pub enum SecretsError {
+    /// Error occurred while fetching the secret.
+    #[error("failed to fetch secret {name}: {source}")]
+    Fetch {
+        /// Name of requested secret.
+        name: String,
+
+        /// The error that occurred while trying to fetch the secret.
+        source: FetchSecretError,
+    },
+
+    /// The response containing the secret could not be parsed.
+    #[error("failed to parse secret {name}: {source}")]
+    Parse {
+        /// Name of requested secret.
+        name: String,
+
+        /// The error that occurred while trying to parse the response.
+        source: serde_json::Error,
+    },
+
+    /// The secret did not contain the expected value.
+    #[error("secret {name} does not contain {field}")]
+    MissingValue {
+        /// Name of requested secret.
+        name: String,
+
+        /// Field of the secret that was expected.
+        field: String,
+    },
+}
+
+/// Error that occurred while fetching a secret (see [`SecretsError::Fetch`]).
+#[cfg(feature = "credentials-secrets")]
+#[derive(Debug, thiserror::Error)]
+pub enum FetchSecretError {
+    /// Error returned by AWS Secrets Manager.
+    #[error("error getting secret value: {0}")]
+    SecretsManager(
+        #[from]
+        Box<
+            aws_sdk_secretsmanager::error::SdkError<
+                aws_sdk_secretsmanager::operation::get_secret_value::GetSecretValueError,
+                aws_sdk_secretsmanager::config::http::HttpResponse,
+            >,
+        >,
+    ),
+
+    /// Error returned by AWS Systems Manager Parameter Store.
+    #[error("error getting parameter: {0}")]
+    ParameterStore(
+        #[from]
+        Box<
+            aws_sdk_ssm::error::SdkError<
+                aws_sdk_ssm::operation::get_parameter::GetParameterError,
+                aws_sdk_ssm::config::http::HttpResponse,
+            >,
+        >,
+    ),
+
+    /// Error returned by the [AWS Parameters and Secrets Lambda Extension].
+    ///
+    /// [AWS Parameters and Secrets Lambda Extension]: https://docs.aws.amazon.com/secretsmanager/latest/userguide/retrieving-secrets_lambda.html
+    #[error("error calling Lambda extension: {0}")]
+    LambdaExtension(#[from] reqwest::Error),
 }
 
 /// Errors pertaining to the [AWS DynamoDB] service.
@@ -61,6 +140,164 @@ pub enum DynamoDbError {
         source: SaveDynamoDbError,
     },
 
+    /// Error occurred while listing leaderboards stored in DynamoDB table.
+    #[error("failed to list leaderboards in table {table_name}: {source}")]
+    ListLeaderboards {
+        /// Name of table that was scanned.
+        table_name: String,
+
+        /// The error that occurred while trying to list leaderboards.
+        source: ListDynamoDbError,
+    },
+
+    /// Error occurred while deleting leaderboard from DynamoDB table.
+    #[error("failed to delete leaderboard with id {leaderboard_id} for year {year}: {source}")]
+    DeleteLeaderboard {
+        /// ID of leaderboard to delete.
+        leaderboard_id: u64,
+
+        /// Year to delete.
+        year: i32,
+
+        /// The error that occurred while trying to delete leaderboard.
+        source: DeleteDynamoDbError,
+    },
+
+    /// Error occurred while clearing last error information in DynamoDB table.
+    #[error(
+        "failed to clear last error information for leaderboard with id {leaderboard_id} for year {year}: {source}"
+    )]
+    ClearLastError {
+        /// ID of leaderboard to update.
+        leaderboard_id: u64,
+
+        /// Year to update.
+        year: i32,
+
+        /// The error that occurred while trying to clear last error information.
+        source: SaveDynamoDbError,
+    },
+
+    /// Error occurred while loading pending changes from DynamoDB table.
+    #[error(
+        "failed to load pending changes for leaderboard with id {leaderboard_id} for year {year}: {source}"
+    )]
+    LoadPendingChanges {
+        /// ID of requested leaderboard.
+        leaderboard_id: u64,
+
+        /// Requested year.
+        year: i32,
+
+        /// The error that occurred while trying to load pending changes.
+        source: LoadPreviousDynamoDbError,
+    },
+
+    /// Error occurred while saving pending changes in DynamoDB table.
+    #[error(
+        "failed to save pending changes for leaderboard with id {leaderboard_id} for year {year}: {source}"
+    )]
+    SavePendingChanges {
+        /// ID of leaderboard to update.
+        leaderboard_id: u64,
+
+        /// Year to update.
+        year: i32,
+
+        /// The error that occurred while trying to save pending changes.
+        source: SaveDynamoDbError,
+    },
+
+    /// Error occurred while clearing pending changes in DynamoDB table.
+    #[error(
+        "failed to clear pending changes for leaderboard with id {leaderboard_id} for year {year}: {source}"
+    )]
+    ClearPendingChanges {
+        /// ID of leaderboard to update.
+        leaderboard_id: u64,
+
+        /// Year to update.
+        year: i32,
+
+        /// The error that occurred while trying to clear pending changes.
+        source: SaveDynamoDbError,
+    },
+
+    /// Error occurred while loading the outbox from DynamoDB table.
+    #[error(
+        "failed to load outbox for leaderboard with id {leaderboard_id} for year {year}: {source}"
+    )]
+    LoadOutbox {
+        /// ID of requested leaderboard.
+        leaderboard_id: u64,
+
+        /// Requested year.
+        year: i32,
+
+        /// The error that occurred while trying to load the outbox.
+        source: LoadPreviousDynamoDbError,
+    },
+
+    /// Error occurred while marking the report in the outbox as delivered in DynamoDB table.
+    #[error(
+        "failed to mark outbox report as delivered for leaderboard with id {leaderboard_id} for year {year}: {source}"
+    )]
+    MarkDelivered {
+        /// ID of leaderboard to update.
+        leaderboard_id: u64,
+
+        /// Year to update.
+        year: i32,
+
+        /// The error that occurred while trying to mark the report as delivered.
+        source: SaveDynamoDbError,
+    },
+
+    /// Error occurred while loading last error state from DynamoDB table.
+    #[error(
+        "failed to load last error state for leaderboard with id {leaderboard_id} for year {year}: {source}"
+    )]
+    LoadErrorState {
+        /// ID of requested leaderboard.
+        leaderboard_id: u64,
+
+        /// Requested year.
+        year: i32,
+
+        /// The error that occurred while trying to load the last error state.
+        source: LoadPreviousDynamoDbError,
+    },
+
+    /// Error occurred while loading heartbeat state from DynamoDB table.
+    #[error(
+        "failed to load heartbeat state for leaderboard with id {leaderboard_id} for year {year}: {source}"
+    )]
+    LoadHeartbeat {
+        /// ID of requested leaderboard.
+        leaderboard_id: u64,
+
+        /// Requested year.
+        year: i32,
+
+        /// The error that occurred while trying to load the heartbeat state.
+        source: LoadPreviousDynamoDbError,
+    },
+
+    /// Error occurred while saving heartbeat state in DynamoDB table.
+    #[error(
+        "failed to save heartbeat state for leaderboard with id {leaderboard_id} for year {year}: {source}"
+    )]
+    SaveHeartbeat {
+        /// ID of leaderboard to update.
+        leaderboard_id: u64,
+
+        /// Year to update.
+        year: i32,
+
+        /// The error that occurred while trying to save the heartbeat state.
+        source: SaveDynamoDbError,
+    },
+
     /// Error occurred while creating a table to store leaderboard data
     #[error("failed to create table {table_name}: {source}")]
     CreateTable {
@@ -110,7 +347,7 @@ pub enum SaveDynamoDbError {
     ),
 
     /// Error that occurred while trying to upsert data in DynamoDB.
-    #[error("error upserting last error information: {0}")]
+    #[error("error upserting leaderboard data: {0}")]
     UpdateItem(
         #[from]
         Box<
@@ -126,6 +363,44 @@ pub enum SaveDynamoDbError {
     Serialize(#[from] serde_dynamo::Error),
 }
 
+/// Error pertaining to listing data stored in DynamoDB.
+#[cfg(feature = "dynamodb-base")]
+#[derive(Debug, thiserror::Error)]
+pub enum ListDynamoDbError {
+    /// Error that occurred while trying to scan leaderboard data in DynamoDB.
+    #[error("error scanning leaderboard data: {0}")]
+    Scan(
+        #[from]
+        Box<
+            aws_sdk_dynamodb::error::SdkError<
+                aws_sdk_dynamodb::operation::scan::ScanError,
+                aws_sdk_dynamodb::config::http::HttpResponse,
+            >,
+        >,
+    ),
+
+    /// Failed to deserialize leaderboard data.
+    #[error("failed to deserialize leaderboard data: {0}")]
+    Deserialize(#[from] serde_dynamo::Error),
+}
+
+/// Error pertaining to deleting data from DynamoDB.
+#[cfg(feature = "dynamodb-base")]
+#[derive(Debug, thiserror::Error)]
+pub enum DeleteDynamoDbError {
+    /// Error that occurred while trying to delete leaderboard data from DynamoDB.
+    #[error("error deleting leaderboard data: {0}")]
+    DeleteItem(
+        #[from]
+        Box<
+            aws_sdk_dynamodb::error::SdkError<
+                aws_sdk_dynamodb::operation::delete_item::DeleteItemError,
+                aws_sdk_dynamodb::config::http::HttpResponse,
+            >,
+        >,
+    ),
+}
+
 /// Error pertaining to creating a DynamoDB table to store leaderboard data.
 #[cfg(feature = "dynamodb-base")]
 #[derive(Debug, thiserror::Error)]
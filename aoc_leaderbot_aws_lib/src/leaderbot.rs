@@ -2,4 +2,5 @@
 //!
 //! [`aoc_leaderbot`]: https://github.com/clechasseur/aoc_leaderbot
 
+pub mod credentials;
 pub mod storage;
@@ -0,0 +1,3 @@
+//! Implementations of [`leaderbot::CredentialsProvider`](aoc_leaderbot_lib::leaderbot::CredentialsProvider).
+
+pub mod aws;
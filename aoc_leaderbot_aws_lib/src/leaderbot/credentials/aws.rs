@@ -0,0 +1,4 @@
+//! Implementations of [`leaderbot::CredentialsProvider`](aoc_leaderbot_lib::leaderbot::CredentialsProvider) using AWS services.
+
+#[cfg(feature = "credentials-secrets")]
+pub mod secrets;
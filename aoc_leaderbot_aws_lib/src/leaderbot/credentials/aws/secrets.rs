@@ -0,0 +1,337 @@
+//! Implementation of [`leaderbot::CredentialsProvider`](CredentialsProvider) fetching
+//! credentials from [AWS Secrets Manager] or [AWS Systems Manager Parameter Store].
+//!
+//! [AWS Secrets Manager]: https://aws.amazon.com/secrets-manager/
+//! [AWS Systems Manager Parameter Store]: https://docs.aws.amazon.com/systems-manager/latest/userguide/systems-manager-parameter-store.html
+
+use std::env;
+
+use aoc_leaderboard::aoc::{LeaderboardCredentials, LeaderboardCredentialsKind};
+use aoc_leaderbot_lib::leaderbot::CredentialsProvider;
+use aoc_leaderbot_lib::leaderbot::credentials::credentials_from_secret;
+use aws_config::SdkConfig;
+use serde::Deserialize;
+
+use crate::error::{FetchSecretError, SecretsError};
+
+/// Default port of the [AWS Parameters and Secrets Lambda Extension].
+///
+/// [AWS Parameters and Secrets Lambda Extension]: https://docs.aws.amazon.com/secretsmanager/latest/userguide/retrieving-secrets_lambda.html
+pub const DEFAULT_EXTENSION_HTTP_PORT: u16 = 2773;
+
+/// Environment variable used to configure the port of the
+/// [AWS Parameters and Secrets Lambda Extension].
+///
+/// [AWS Parameters and Secrets Lambda Extension]: https://docs.aws.amazon.com/secretsmanager/latest/userguide/retrieving-secrets_lambda.html
+pub const EXTENSION_HTTP_PORT_ENV_VAR: &str = "PARAMETERS_SECRETS_EXTENSION_HTTP_PORT";
+
+/// Environment variable containing the session token used to authenticate requests
+/// to the [AWS Parameters and Secrets Lambda Extension].
+///
+/// [AWS Parameters and Secrets Lambda Extension]: https://docs.aws.amazon.com/secretsmanager/latest/userguide/retrieving-secrets_lambda.html
+pub const SESSION_TOKEN_ENV_VAR: &str = "AWS_SESSION_TOKEN";
+
+const SESSION_TOKEN_HEADER: &str = "X-Aws-Parameters-Secrets-Token";
+
+/// Source of a secret stored in AWS.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AwsSecretSource {
+    /// Secret stored in [AWS Secrets Manager].
+    ///
+    /// [AWS Secrets Manager]: https://aws.amazon.com/secrets-manager/
+    SecretsManager {
+        /// Name or ARN of the secret.
+        secret_id: String,
+
+        /// Key of the value to use if the secret is stored as a JSON object.
+        ///
+        /// If `None`, the whole secret string is used.
+        json_key: Option<String>,
+    },
+
+    /// Parameter stored in [AWS Systems Manager Parameter Store].
+    ///
+    /// `SecureString` parameters are decrypted.
+    ///
+    /// [AWS Systems Manager Parameter Store]: https://docs.aws.amazon.com/systems-manager/latest/userguide/systems-manager-parameter-store.html
+    ParameterStore {
+        /// Name or ARN of the parameter.
+        name: String,
+    },
+}
+
+impl AwsSecretSource {
+    /// Returns a source for a secret stored in [AWS Secrets Manager], using the whole
+    /// secret string.
+    ///
+    /// [AWS Secrets Manager]: https://aws.amazon.com/secrets-manager/
+    pub fn secrets_manager<S>(secret_id: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::SecretsManager { secret_id: secret_id.into(), json_key: None }
+    }
+
+    /// Returns a source for a parameter stored in [AWS Systems Manager Parameter Store].
+    ///
+    /// [AWS Systems Manager Parameter Store]: https://docs.aws.amazon.com/systems-manager/latest/userguide/systems-manager-parameter-store.html
+    pub fn parameter_store<S>(name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::ParameterStore { name: name.into() }
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            Self::SecretsManager { secret_id, .. } => secret_id,
+            Self::ParameterStore { name } => name,
+        }
+    }
+}
+
+/// [`CredentialsProvider`] fetching credentials from [AWS Secrets Manager] or
+/// [AWS Systems Manager Parameter Store].
+///
+/// Secrets are fetched using the AWS SDK, with an [`SdkConfig`] loaded from the environment
+/// (see [`new`]) or provided by the caller (see [`with_config`]). The caller needs the permission
+/// to read the secret (`secretsmanager:GetSecretValue` or `ssm:GetParameter`, plus `kms:Decrypt`
+/// if the secret is encrypted using a customer-managed key).
+///
+/// When running in AWS Lambda, secrets can instead be fetched through the
+/// [AWS Parameters and Secrets Lambda Extension] (see [`lambda_extension`]), which must be added
+/// as a layer to the function. The extension caches secrets, so credentials can be fetched on
+/// every bot run without calling AWS every time.
+///
+/// The secret must contain only the view key or the session cookie, unless a
+/// [JSON key](AwsSecretSource::SecretsManager::json_key) is specified.
+///
+/// [AWS Secrets Manager]: https://aws.amazon.com/secrets-manager/
+/// [AWS Systems Manager Parameter Store]: https://docs.aws.amazon.com/systems-manager/latest/userguide/systems-manager-parameter-store.html
+/// [`new`]: Self::new
+/// [`with_config`]: Self::with_config
+/// [AWS Parameters and Secrets Lambda Extension]: https://docs.aws.amazon.com/secretsmanager/latest/userguide/retrieving-secrets_lambda.html
+/// [`lambda_extension`]: Self::lambda_extension
+#[derive(Debug, Clone)]
+pub struct AwsSecretCredentialsProvider {
+    source: AwsSecretSource,
+    kind: LeaderboardCredentialsKind,
+    client: SecretsClient,
+}
+
+#[derive(Debug, Clone)]
+enum SecretsClient {
+    Sdk { secrets_manager: aws_sdk_secretsmanager::Client, ssm: aws_sdk_ssm::Client },
+    LambdaExtension { endpoint: String, http_client: reqwest::Client },
+}
+
+impl AwsSecretCredentialsProvider {
+    /// Creates a provider fetching credentials of the given kind from the given source.
+    ///
+    /// AWS SDK config will be loaded from the environment.
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    pub async fn new(source: AwsSecretSource, kind: LeaderboardCredentialsKind) -> Self {
+        let config = aws_config::load_from_env().await;
+        Self::with_config(&config, source, kind).await
+    }
+
+    /// Creates a provider fetching credentials of the given kind from the given source,
+    /// using the provided AWS SDK config.
+    pub async fn with_config(
+        config: &SdkConfig,
+        source: AwsSecretSource,
+        kind: LeaderboardCredentialsKind,
+    ) -> Self {
+        let client = SecretsClient::Sdk {
+            secrets_manager: aws_sdk_secretsmanager::Client::new(config),
+            ssm: aws_sdk_ssm::Client::new(config),
+        };
+
+        Self { source, kind, client }
+    }
+
+    /// Creates a provider fetching credentials of the given kind from the given source through
+    /// the [AWS Parameters and Secrets Lambda Extension].
+    ///
+    /// The extension's endpoint is determined using the [`EXTENSION_HTTP_PORT_ENV_VAR`]
+    /// environment variable, defaulting to [`DEFAULT_EXTENSION_HTTP_PORT`].
+    ///
+    /// [AWS Parameters and Secrets Lambda Extension]: https://docs.aws.amazon.com/secretsmanager/latest/userguide/retrieving-secrets_lambda.html
+    pub fn lambda_extension(source: AwsSecretSource, kind: LeaderboardCredentialsKind) -> Self {
+        let port = env::var(EXTENSION_HTTP_PORT_ENV_VAR)
+            .ok()
+            .and_then(|port| port.parse().ok())
+            .unwrap_or(DEFAULT_EXTENSION_HTTP_PORT);
+
+        Self {
+            source,
+            kind,
+            client: SecretsClient::LambdaExtension {
+                endpoint: format!("http://localhost:{port}"),
+                http_client: reqwest::Client::new(),
+            },
+        }
+    }
+
+    /// Returns this provider's secret source.
+    pub fn source(&self) -> &AwsSecretSource {
+        &self.source
+    }
+
+    /// Returns the kind of credentials stored in the secret.
+    pub fn kind(&self) -> LeaderboardCredentialsKind {
+        self.kind
+    }
+
+    /// Returns the endpoint of the Lambda extension used to fetch secrets, if secrets are
+    /// fetched [through the extension](Self::lambda_extension).
+    pub fn endpoint(&self) -> Option<&str> {
+        match &self.client {
+            SecretsClient::Sdk { .. } => None,
+            SecretsClient::LambdaExtension { endpoint, .. } => Some(endpoint),
+        }
+    }
+
+    /// Fetches secrets through the Lambda extension listening at the given endpoint.
+    ///
+    /// Should only be needed for testing purposes.
+    pub fn with_endpoint<S>(self, endpoint: S) -> Self
+    where
+        S: Into<String>,
+    {
+        let http_client = match self.client {
+            SecretsClient::LambdaExtension { http_client, .. } => http_client,
+            SecretsClient::Sdk { .. } => reqwest::Client::new(),
+        };
+
+        Self {
+            client: SecretsClient::LambdaExtension { endpoint: endpoint.into(), http_client },
+            ..self
+        }
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self), fields(source = ?self.source), level = "debug", err))]
+    async fn fetch(&self) -> crate::Result<LeaderboardCredentials> {
+        let name = self.source.name();
+
+        let missing_value =
+            |field: &str| SecretsError::MissingValue { name: name.into(), field: field.into() };
+        let secret = match &self.client {
+            SecretsClient::Sdk { secrets_manager, ssm } => match &self.source {
+                AwsSecretSource::SecretsManager { secret_id, .. } => secrets_manager
+                    .get_secret_value()
+                    .secret_id(secret_id)
+                    .send()
+                    .await
+                    .map_err(|err| fetch_error(name, Box::new(err).into()))?
+                    .secret_string
+                    .ok_or_else(|| missing_value("SecretString"))?,
+                AwsSecretSource::ParameterStore { name: parameter_name } => ssm
+                    .get_parameter()
+                    .name(parameter_name)
+                    .with_decryption(true)
+                    .send()
+                    .await
+                    .map_err(|err| fetch_error(name, Box::new(err).into()))?
+                    .parameter
+                    .and_then(|parameter| parameter.value)
+                    .ok_or_else(|| missing_value("Value"))?,
+            },
+            SecretsClient::LambdaExtension { endpoint, http_client } => {
+                self.fetch_from_extension(endpoint, http_client).await?
+            },
+        };
+
+        let secret = match &self.source {
+            AwsSecretSource::SecretsManager { json_key: Some(key), .. } => {
+                serde_json::from_str::<serde_json::Value>(&secret)
+                    .map_err(|source| SecretsError::Parse { name: name.into(), source })?
+                    .get(key)
+                    .and_then(serde_json::Value::as_str)
+                    .map(ToString::to_string)
+                    .ok_or_else(|| missing_value(key))?
+            },
+            _ => secret,
+        };
+
+        Ok(credentials_from_secret(self.kind, secret).ok_or_else(|| missing_value("a value"))?)
+    }
+
+    async fn fetch_from_extension(
+        &self,
+        endpoint: &str,
+        http_client: &reqwest::Client,
+    ) -> crate::Result<String> {
+        let name = self.source.name();
+
+        let request = match &self.source {
+            AwsSecretSource::SecretsManager { secret_id, .. } => http_client
+                .get(format!("{endpoint}/secretsmanager/get"))
+                .query(&[("secretId", secret_id.as_str())]),
+            AwsSecretSource::ParameterStore { name } => http_client
+                .get(format!("{endpoint}/systemsmanager/parameters/get"))
+                .query(&[("name", name.as_str()), ("withDecryption", "true")]),
+        };
+        let request = match env::var(SESSION_TOKEN_ENV_VAR) {
+            Ok(token) => request.header(SESSION_TOKEN_HEADER, token),
+            Err(_) => request,
+        };
+
+        let body = request
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|err| fetch_error(name, err.into()))?
+            .text()
+            .await
+            .map_err(|err| fetch_error(name, err.into()))?;
+
+        let parse_err = |source| SecretsError::Parse { name: name.into(), source };
+        let missing_value =
+            |field: &str| SecretsError::MissingValue { name: name.into(), field: field.into() };
+        Ok(match &self.source {
+            AwsSecretSource::SecretsManager { .. } => {
+                serde_json::from_str::<SecretsManagerResponse>(&body)
+                    .map_err(parse_err)?
+                    .secret_string
+                    .ok_or_else(|| missing_value("SecretString"))?
+            },
+            AwsSecretSource::ParameterStore { .. } => {
+                serde_json::from_str::<ParameterStoreResponse>(&body)
+                    .map_err(parse_err)?
+                    .parameter
+                    .value
+            },
+        })
+    }
+}
+
+impl CredentialsProvider for AwsSecretCredentialsProvider {
+    type Err = crate::Error;
+
+    async fn get_credentials(&self) -> Result<LeaderboardCredentials, Self::Err> {
+        self.fetch().await
+    }
+}
+
+fn fetch_error(name: &str, source: FetchSecretError) -> SecretsError {
+    SecretsError::Fetch { name: name.into(), source }
+}
+
+#[derive(Debug, Deserialize)]
+struct SecretsManagerResponse {
+    #[serde(rename = "SecretString")]
+    secret_string: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ParameterStoreResponse {
+    #[serde(rename = "Parameter")]
+    parameter: Parameter,
+}
+
+#[derive(Debug, Deserialize)]
+struct Parameter {
+    #[serde(rename = "Value")]
+    value: String,
+}
@@ -7,21 +7,32 @@ pub mod config;
 #[doc(hidden)]
 pub mod test_helpers;
 
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, Mutex, PoisonError};
 use std::time::Duration;
 
 use aoc_leaderboard::aoc::Leaderboard;
 use aoc_leaderbot_lib::ErrorKind;
-use aoc_leaderbot_lib::leaderbot::Storage;
+use aoc_leaderbot_lib::leaderbot::heartbeat::HeartbeatState;
+use aoc_leaderbot_lib::leaderbot::{
+    DigestStorage, ErrorState, OutboxReport, OutboxStorage, PendingChanges, Storage, StorageAdmin,
+    StorageEntry,
+};
 use aws_config::SdkConfig;
+use aws_sdk_dynamodb::error::SdkError;
 use aws_sdk_dynamodb::operation::create_table::CreateTableOutput;
+use aws_sdk_dynamodb::operation::update_item::builders::UpdateItemFluentBuilder;
 use aws_sdk_dynamodb::types::{
     AttributeDefinition, AttributeValue, KeySchemaElement, KeyType, ScalarAttributeType,
     TableDescription, TableStatus,
 };
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use tokio::time::sleep;
 
-use crate::error::DynamoDbError;
+use crate::error::{DynamoDbError, LoadPreviousDynamoDbError};
 use crate::leaderbot::storage::aws::dynamodb::config::table::{CreateTableBuilderExt, TableConfig};
 
 /// The hash key (aka partition key) used by [`DynamoDbStorage`].
@@ -40,6 +51,37 @@ pub const LEADERBOARD_DATA: &str = "leaderboard_data";
 /// The column storing last error information in the [`DynamoDbStorage`].
 pub const LAST_ERROR: &str = "last_error";
 
+/// The column storing the time at which the first error of the current outage
+/// occurred in the [`DynamoDbStorage`].
+pub const LAST_ERROR_SINCE: &str = "last_error_since";
+
+/// The column storing the time at which the last error of the current outage
+/// occurred in the [`DynamoDbStorage`].
+pub const LAST_ERROR_SEEN: &str = "last_error_seen";
+
+/// The column storing the number of errors that occurred during the current outage
+/// in the [`DynamoDbStorage`].
+pub const LAST_ERROR_COUNT: &str = "last_error_count";
+
+/// The column storing the message of the last error in the [`DynamoDbStorage`].
+pub const LAST_ERROR_MESSAGE: &str = "last_error_message";
+
+/// The column storing the time at which the last error was reported in the [`DynamoDbStorage`].
+pub const LAST_ERROR_NOTIFIED: &str = "last_error_notified";
+
+// Placeholders for all the columns storing error information, for use in `REMOVE` clauses.
+const ERROR_STATE_PLACEHOLDERS: &str = "#last_error, #last_error_since, #last_error_seen, \
+    #last_error_count, #last_error_message, #last_error_notified";
+
+/// The column storing changes pending in digest mode in the [`DynamoDbStorage`].
+pub const PENDING_CHANGES: &str = "pending_changes";
+
+/// The column storing the report in the outbox in the [`DynamoDbStorage`].
+pub const OUTBOX: &str = "outbox";
+
+/// The column storing the bot's heartbeat state in the [`DynamoDbStorage`].
+pub const HEARTBEAT: &str = "heartbeat";
+
 /// Struct used to persist [`Leaderboard`] data into a DynamoDB
 /// table. Used by [`DynamoDbStorage`].
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -57,22 +99,147 @@ pub struct DynamoDbLeaderboardData {
     /// Information about last execution error, if any. Stored in the [`LAST_ERROR`] column.
     #[serde(default)]
     pub last_error: Option<ErrorKind>,
+
+    /// Time at which the first error of the current outage occurred, if any.
+    /// Stored in the [`LAST_ERROR_SINCE`] column.
+    #[serde(default)]
+    pub last_error_since: Option<DateTime<Utc>>,
+
+    /// Time at which the last error of the current outage occurred, if any.
+    /// Stored in the [`LAST_ERROR_SEEN`] column.
+    #[serde(default)]
+    pub last_error_seen: Option<DateTime<Utc>>,
+
+    /// Number of errors that occurred during the current outage, if any.
+    /// Stored in the [`LAST_ERROR_COUNT`] column.
+    #[serde(default)]
+    pub last_error_count: Option<u64>,
+
+    /// Message of the last error, if known. Stored in the [`LAST_ERROR_MESSAGE`] column.
+    #[serde(default)]
+    pub last_error_message: Option<String>,
+
+    /// Time at which the last error was reported, if ever.
+    /// Stored in the [`LAST_ERROR_NOTIFIED`] column.
+    #[serde(default)]
+    pub last_error_notified: Option<DateTime<Utc>>,
+
+    /// Changes accumulated in digest mode that have not been reported yet, if any.
+    /// Stored in the [`PENDING_CHANGES`] column.
+    #[serde(default)]
+    pub pending_changes: Option<PendingChanges>,
+
+    /// Report in the outbox that has not been delivered yet, if any.
+    /// Stored in the [`OUTBOX`] column.
+    #[serde(default)]
+    pub outbox: Option<OutboxReport>,
+
+    /// Heartbeat state of the bot, if recorded.
+    /// Stored in the [`HEARTBEAT`] column.
+    #[serde(default)]
+    pub heartbeat: Option<HeartbeatState>,
 }
 
 impl DynamoDbLeaderboardData {
     /// Creates a [`DynamoDbLeaderboardData`] to store the result of a successful bot run.
     pub fn for_success(year: i32, leaderboard_id: u64, leaderboard: Leaderboard) -> Self {
-        Self { leaderboard_id, year, leaderboard_data: Some(leaderboard), last_error: None }
+        Self {
+            leaderboard_id,
+            year,
+            leaderboard_data: Some(leaderboard),
+            last_error: None,
+            last_error_since: None,
+            last_error_seen: None,
+            last_error_count: None,
+            last_error_message: None,
+            last_error_notified: None,
+            pending_changes: None,
+            outbox: None,
+            heartbeat: None,
+        }
+    }
+}
+
+impl DynamoDbLeaderboardData {
+    /// Returns information about the last error, if any.
+    pub fn error_state(&self) -> Option<ErrorState> {
+        let (error_kind, first_seen) = self.last_error.zip(self.last_error_since)?;
+
+        Some(ErrorState {
+            last_seen: self.last_error_seen.unwrap_or(first_seen),
+            occurrences: self.last_error_count.unwrap_or(1),
+            last_message: self.last_error_message.clone(),
+            last_notified: self.last_error_notified,
+            ..ErrorState::new(error_kind, first_seen)
+        })
+    }
+}
+
+impl From<DynamoDbLeaderboardData> for StorageEntry {
+    fn from(value: DynamoDbLeaderboardData) -> Self {
+        Self {
+            year: value.year,
+            leaderboard_id: value.leaderboard_id,
+            leaderboard: value.leaderboard_data,
+            last_error: value.last_error,
+        }
     }
 }
 
 /// Bot storage that keeps data in an [AWS DynamoDB] table.
 ///
+/// All data for a leaderboard is stored in a single item. To avoid reading that item
+/// multiple times per bot run, the item read by [`load_previous`] is cached and reused by
+/// the other `load_*` methods (like [`load_error_state`] or [`load_outbox`]) until it is written
+/// through this storage (or one of its clones), or until [`load_previous`] is called again.
+///
 /// [AWS DynamoDB]: https://aws.amazon.com/dynamodb/
+/// [`load_previous`]: Storage::load_previous
+/// [`load_error_state`]: Storage::load_error_state
+/// [`load_outbox`]: OutboxStorage::load_outbox
 #[derive(Debug, Clone)]
 pub struct DynamoDbStorage {
     client: aws_sdk_dynamodb::Client,
     table_name: String,
+    items: ItemCache,
+}
+
+// Items read by `load_previous`, keyed by year and leaderboard ID. A `None` value means
+// that there was no item for the leaderboard.
+#[derive(Default, Clone)]
+struct ItemCache(Arc<Mutex<HashMap<ItemKey, Option<DynamoDbLeaderboardData>>>>);
+
+type ItemKey = (i32, u64);
+
+impl ItemCache {
+    fn get(&self, year: i32, leaderboard_id: u64) -> Option<Option<DynamoDbLeaderboardData>> {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&(year, leaderboard_id))
+            .cloned()
+    }
+
+    fn insert(&self, year: i32, leaderboard_id: u64, item: Option<DynamoDbLeaderboardData>) {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert((year, leaderboard_id), item);
+    }
+
+    fn invalidate(&self, year: i32, leaderboard_id: u64) {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&(year, leaderboard_id));
+    }
+}
+
+impl Debug for ItemCache {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let len = self.0.lock().unwrap_or_else(PoisonError::into_inner).len();
+        f.debug_struct("ItemCache").field("len", &len).finish()
+    }
 }
 
 impl DynamoDbStorage {
@@ -94,7 +261,11 @@ impl DynamoDbStorage {
     where
         T: Into<String>,
     {
-        Self { client: aws_sdk_dynamodb::Client::new(config), table_name: table_name.into() }
+        Self {
+            client: aws_sdk_dynamodb::Client::new(config),
+            table_name: table_name.into(),
+            items: ItemCache::default(),
+        }
     }
 
     /// Creates a DynamoDB table suitable for storing leaderboard data.
@@ -184,6 +355,44 @@ impl DynamoDbStorage {
 
         Ok(())
     }
+
+    // Loads the item storing data for the given leaderboard.
+    //
+    // If `refresh` is `true`, the item is always read from the table and cached; otherwise, the
+    // cached item is returned if there is one.
+    async fn load_item<E>(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+        refresh: bool,
+        load_error: E,
+    ) -> crate::Result<Option<DynamoDbLeaderboardData>>
+    where
+        E: Fn(LoadPreviousDynamoDbError) -> DynamoDbError,
+    {
+        if !refresh && let Some(item) = self.items.get(year, leaderboard_id) {
+            return Ok(item);
+        }
+
+        let item: Option<DynamoDbLeaderboardData> = self
+            .client
+            .get_item()
+            .table_name(self.table_name.clone())
+            .key(HASH_KEY, AttributeValue::N(leaderboard_id.to_string()))
+            .key(RANGE_KEY, AttributeValue::N(year.to_string()))
+            .send()
+            .await
+            .map_err(|err| load_error(Box::new(err).into()))?
+            .item
+            .map(serde_dynamo::from_item)
+            .transpose()
+            .map_err(|err| load_error(err.into()))?;
+
+        if refresh {
+            self.items.insert(year, leaderboard_id, item.clone());
+        }
+        Ok(item)
+    }
 }
 
 impl Storage for DynamoDbStorage {
@@ -199,22 +408,10 @@ impl Storage for DynamoDbStorage {
             |source| DynamoDbError::LoadPreviousLeaderboard { leaderboard_id, year, source };
 
         Ok(self
-            .client
-            .get_item()
-            .table_name(self.table_name.clone())
-            .key(HASH_KEY, AttributeValue::N(leaderboard_id.to_string()))
-            .key(RANGE_KEY, AttributeValue::N(year.to_string()))
-            .send()
-            .await
-            .map_err(|err| load_previous_error(Box::new(err).into()))?
-            .item
-            .map(|item| {
-                let data: Result<DynamoDbLeaderboardData, _> = serde_dynamo::from_item(item);
-                data.map(|data| (data.leaderboard_data, data.last_error))
-            })
-            .transpose()
-            .map(Option::unwrap_or_default)
-            .map_err(|err| load_previous_error(err.into()))?)
+            .load_item(year, leaderboard_id, true, load_previous_error)
+            .await?
+            .map(|data| (data.leaderboard_data, data.last_error))
+            .unwrap_or_default())
     }
 
     #[cfg_attr(not(coverage), tracing::instrument(skip(self), ret, err))]
@@ -224,16 +421,25 @@ impl Storage for DynamoDbStorage {
         leaderboard_id: u64,
         leaderboard: &Leaderboard,
     ) -> Result<(), Self::Err> {
+        self.items.invalidate(year, leaderboard_id);
+
         let save_error = |source| DynamoDbError::SaveLeaderboard { leaderboard_id, year, source };
 
-        let leaderboard_data =
-            DynamoDbLeaderboardData::for_success(year, leaderboard_id, leaderboard.clone());
-        let item = serde_dynamo::to_item(leaderboard_data).map_err(|err| save_error(err.into()))?;
+        // Update the item instead of replacing it so that pending changes are kept.
+        let attribute_value =
+            serde_dynamo::to_attribute_value(leaderboard).map_err(|err| save_error(err.into()))?;
 
         self.client
-            .put_item()
+            .update_item()
             .table_name(self.table_name.clone())
-            .set_item(Some(item))
+            .key(HASH_KEY, AttributeValue::N(leaderboard_id.to_string()))
+            .key(RANGE_KEY, AttributeValue::N(year.to_string()))
+            .update_expression(format!(
+                "SET #leaderboard_data = :leaderboard_data REMOVE {ERROR_STATE_PLACEHOLDERS}"
+            ))
+            .expression_attribute_names("#leaderboard_data", LEADERBOARD_DATA)
+            .error_state_attribute_names()
+            .expression_attribute_values(":leaderboard_data", attribute_value)
             .send()
             .await
             .map_err(|err| save_error(Box::new(err).into()))?;
@@ -248,23 +454,459 @@ impl Storage for DynamoDbStorage {
         leaderboard_id: u64,
         error_kind: ErrorKind,
     ) -> Result<(), Self::Err> {
+        self.items.invalidate(year, leaderboard_id);
+
         let save_error = |source| DynamoDbError::SaveLastError { leaderboard_id, year, source };
 
         let attribute_value =
             serde_dynamo::to_attribute_value(error_kind).map_err(|err| save_error(err.into()))?;
+        let now_value =
+            serde_dynamo::to_attribute_value(Utc::now()).map_err(|err| save_error(err.into()))?;
 
+        // The time of the first error is only set if it isn't already, so that it
+        // is kept until the outage ends. The message of the previous error (if any)
+        // is removed, since we don't know the message of this one.
         self.client
             .update_item()
             .table_name(self.table_name.clone())
             .key(HASH_KEY, AttributeValue::N(leaderboard_id.to_string()))
             .key(RANGE_KEY, AttributeValue::N(year.to_string()))
-            .update_expression("SET #last_error = :last_error")
+            .update_expression(
+                "SET #last_error = :last_error, \
+                #last_error_since = if_not_exists(#last_error_since, :now), \
+                #last_error_seen = :now \
+                REMOVE #last_error_message \
+                ADD #last_error_count :one",
+            )
             .expression_attribute_names("#last_error", LAST_ERROR)
+            .expression_attribute_names("#last_error_since", LAST_ERROR_SINCE)
+            .expression_attribute_names("#last_error_seen", LAST_ERROR_SEEN)
+            .expression_attribute_names("#last_error_message", LAST_ERROR_MESSAGE)
+            .expression_attribute_names("#last_error_count", LAST_ERROR_COUNT)
             .expression_attribute_values(":last_error", attribute_value)
+            .expression_attribute_values(":now", now_value)
+            .expression_attribute_values(":one", AttributeValue::N("1".into()))
             .send()
             .await
             .map_err(|err| save_error(Box::new(err).into()))?;
 
         Ok(())
     }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self), ret, err))]
+    async fn save_error_state(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        error_state: &ErrorState,
+    ) -> Result<(), Self::Err> {
+        self.items.invalidate(year, leaderboard_id);
+
+        let save_error = |source| DynamoDbError::SaveLastError { leaderboard_id, year, source };
+
+        let mut set_clauses = vec![
+            "#last_error = :last_error",
+            "#last_error_since = :last_error_since",
+            "#last_error_seen = :last_error_seen",
+            "#last_error_count = :last_error_count",
+        ];
+        let mut remove_clauses = vec![];
+        let mut update = self
+            .client
+            .update_item()
+            .table_name(self.table_name.clone())
+            .key(HASH_KEY, AttributeValue::N(leaderboard_id.to_string()))
+            .key(RANGE_KEY, AttributeValue::N(year.to_string()))
+            .error_state_attribute_names()
+            .expression_attribute_values(
+                ":last_error",
+                serde_dynamo::to_attribute_value(error_state.error_kind)
+                    .map_err(|err| save_error(err.into()))?,
+            )
+            .expression_attribute_values(
+                ":last_error_since",
+                serde_dynamo::to_attribute_value(error_state.first_seen)
+                    .map_err(|err| save_error(err.into()))?,
+            )
+            .expression_attribute_values(
+                ":last_error_seen",
+                serde_dynamo::to_attribute_value(error_state.last_seen)
+                    .map_err(|err| save_error(err.into()))?,
+            )
+            .expression_attribute_values(
+                ":last_error_count",
+                AttributeValue::N(error_state.occurrences.to_string()),
+            );
+
+        match &error_state.last_message {
+            Some(message) => {
+                set_clauses.push("#last_error_message = :last_error_message");
+                update = update.expression_attribute_values(
+                    ":last_error_message",
+                    AttributeValue::S(message.clone()),
+                );
+            },
+            None => remove_clauses.push("#last_error_message"),
+        }
+        match error_state.last_notified {
+            Some(last_notified) => {
+                set_clauses.push("#last_error_notified = :last_error_notified");
+                update = update.expression_attribute_values(
+                    ":last_error_notified",
+                    serde_dynamo::to_attribute_value(last_notified)
+                        .map_err(|err| save_error(err.into()))?,
+                );
+            },
+            None => remove_clauses.push("#last_error_notified"),
+        }
+
+        let mut update_expression = format!("SET {}", set_clauses.join(", "));
+        if !remove_clauses.is_empty() {
+            update_expression = format!("{update_expression} REMOVE {}", remove_clauses.join(", "));
+        }
+
+        update
+            .update_expression(update_expression)
+            .send()
+            .await
+            .map_err(|err| save_error(Box::new(err).into()))?;
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self), ret, err))]
+    async fn load_error_state(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> Result<Option<ErrorState>, Self::Err> {
+        let load_error = |source| DynamoDbError::LoadErrorState { leaderboard_id, year, source };
+
+        Ok(self
+            .load_item(year, leaderboard_id, false, load_error)
+            .await?
+            .and_then(|data| data.error_state()))
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self), ret, err))]
+    async fn load_heartbeat(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> Result<Option<HeartbeatState>, Self::Err> {
+        let load_error = |source| DynamoDbError::LoadHeartbeat { leaderboard_id, year, source };
+
+        Ok(self
+            .load_item(year, leaderboard_id, false, load_error)
+            .await?
+            .and_then(|data| data.heartbeat))
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self), ret, err))]
+    async fn save_heartbeat(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        heartbeat: &HeartbeatState,
+    ) -> Result<(), Self::Err> {
+        self.items.invalidate(year, leaderboard_id);
+
+        let save_error = |source| DynamoDbError::SaveHeartbeat { leaderboard_id, year, source };
+
+        let attribute_value =
+            serde_dynamo::to_attribute_value(heartbeat).map_err(|err| save_error(err.into()))?;
+
+        self.client
+            .update_item()
+            .table_name(self.table_name.clone())
+            .key(HASH_KEY, AttributeValue::N(leaderboard_id.to_string()))
+            .key(RANGE_KEY, AttributeValue::N(year.to_string()))
+            .update_expression("SET #heartbeat = :heartbeat")
+            .expression_attribute_names("#heartbeat", HEARTBEAT)
+            .expression_attribute_values(":heartbeat", attribute_value)
+            .send()
+            .await
+            .map_err(|err| save_error(Box::new(err).into()))?;
+
+        Ok(())
+    }
+}
+
+impl StorageAdmin for DynamoDbStorage {
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self), err))]
+    async fn list(&self) -> Result<Vec<StorageEntry>, Self::Err> {
+        let list_error = |source| DynamoDbError::ListLeaderboards {
+            table_name: self.table_name.clone(),
+            source,
+        };
+
+        let items: Vec<_> = self
+            .client
+            .scan()
+            .table_name(self.table_name.clone())
+            .into_paginator()
+            .items()
+            .send()
+            .collect::<Result<_, _>>()
+            .await
+            .map_err(|err| list_error(Box::new(err).into()))?;
+
+        Ok(items
+            .into_iter()
+            .map(|item| {
+                let data: Result<DynamoDbLeaderboardData, _> = serde_dynamo::from_item(item);
+                data.map(Into::<StorageEntry>::into)
+            })
+            // Items can also hold data that is not returned here (like pending changes
+            // or heartbeats); skip those that have neither a leaderboard nor an error.
+            .filter(|entry| {
+                !matches!(entry, Ok(StorageEntry { leaderboard: None, last_error: None, .. }))
+            })
+            .collect::<Result<_, _>>()
+            .map_err(|err| list_error(err.into()))?)
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self), ret, err))]
+    async fn delete(&mut self, year: i32, leaderboard_id: u64) -> Result<(), Self::Err> {
+        self.items.invalidate(year, leaderboard_id);
+
+        self.client
+            .delete_item()
+            .table_name(self.table_name.clone())
+            .key(HASH_KEY, AttributeValue::N(leaderboard_id.to_string()))
+            .key(RANGE_KEY, AttributeValue::N(year.to_string()))
+            .send()
+            .await
+            .map_err(|err| DynamoDbError::DeleteLeaderboard {
+                leaderboard_id,
+                year,
+                source: Box::new(err).into(),
+            })?;
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self), ret, err))]
+    async fn clear_error(&mut self, year: i32, leaderboard_id: u64) -> Result<(), Self::Err> {
+        self.items.invalidate(year, leaderboard_id);
+
+        let update_result = self
+            .client
+            .update_item()
+            .table_name(self.table_name.clone())
+            .key(HASH_KEY, AttributeValue::N(leaderboard_id.to_string()))
+            .key(RANGE_KEY, AttributeValue::N(year.to_string()))
+            .update_expression(format!("REMOVE {ERROR_STATE_PLACEHOLDERS}"))
+            .condition_expression("attribute_exists(#hash_key)")
+            .error_state_attribute_names()
+            .expression_attribute_names("#hash_key", HASH_KEY)
+            .send()
+            .await;
+
+        match update_result {
+            Ok(_) => Ok(()),
+            Err(SdkError::ServiceError(service_err))
+                if service_err.err().is_conditional_check_failed_exception() =>
+            {
+                // There's no item for this leaderboard, so there's no error to clear.
+                Ok(())
+            },
+            Err(err) => Err(DynamoDbError::ClearLastError {
+                leaderboard_id,
+                year,
+                source: Box::new(err).into(),
+            }
+            .into()),
+        }
+    }
+}
+
+impl DigestStorage for DynamoDbStorage {
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self), ret, err))]
+    async fn load_pending_changes(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> Result<Option<PendingChanges>, Self::Err> {
+        let load_error =
+            |source| DynamoDbError::LoadPendingChanges { leaderboard_id, year, source };
+
+        Ok(self
+            .load_item(year, leaderboard_id, false, load_error)
+            .await?
+            .and_then(|data| data.pending_changes))
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self, pending_changes), ret, err))]
+    async fn save_pending_changes(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        pending_changes: &PendingChanges,
+    ) -> Result<(), Self::Err> {
+        self.items.invalidate(year, leaderboard_id);
+
+        let save_error =
+            |source| DynamoDbError::SavePendingChanges { leaderboard_id, year, source };
+
+        let attribute_value = serde_dynamo::to_attribute_value(pending_changes)
+            .map_err(|err| save_error(err.into()))?;
+
+        self.client
+            .update_item()
+            .table_name(self.table_name.clone())
+            .key(HASH_KEY, AttributeValue::N(leaderboard_id.to_string()))
+            .key(RANGE_KEY, AttributeValue::N(year.to_string()))
+            .update_expression("SET #pending_changes = :pending_changes")
+            .expression_attribute_names("#pending_changes", PENDING_CHANGES)
+            .expression_attribute_values(":pending_changes", attribute_value)
+            .send()
+            .await
+            .map_err(|err| save_error(Box::new(err).into()))?;
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self), ret, err))]
+    async fn clear_pending_changes(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> Result<(), Self::Err> {
+        self.items.invalidate(year, leaderboard_id);
+
+        let update_result = self
+            .client
+            .update_item()
+            .table_name(self.table_name.clone())
+            .key(HASH_KEY, AttributeValue::N(leaderboard_id.to_string()))
+            .key(RANGE_KEY, AttributeValue::N(year.to_string()))
+            .update_expression("REMOVE #pending_changes")
+            .condition_expression("attribute_exists(#hash_key)")
+            .expression_attribute_names("#pending_changes", PENDING_CHANGES)
+            .expression_attribute_names("#hash_key", HASH_KEY)
+            .send()
+            .await;
+
+        match update_result {
+            Ok(_) => Ok(()),
+            Err(SdkError::ServiceError(service_err))
+                if service_err.err().is_conditional_check_failed_exception() =>
+            {
+                // There's no item for this leaderboard, so there's nothing to clear.
+                Ok(())
+            },
+            Err(err) => Err(DynamoDbError::ClearPendingChanges {
+                leaderboard_id,
+                year,
+                source: Box::new(err).into(),
+            }
+            .into()),
+        }
+    }
+}
+
+impl OutboxStorage for DynamoDbStorage {
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self), ret, err))]
+    async fn load_outbox(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> Result<Option<OutboxReport>, Self::Err> {
+        let load_error = |source| DynamoDbError::LoadOutbox { leaderboard_id, year, source };
+
+        Ok(self
+            .load_item(year, leaderboard_id, false, load_error)
+            .await?
+            .and_then(|data| data.outbox))
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self, leaderboard, report), ret, err))]
+    async fn save_success_with_outbox(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        leaderboard: &Leaderboard,
+        report: &OutboxReport,
+    ) -> Result<(), Self::Err> {
+        self.items.invalidate(year, leaderboard_id);
+
+        let save_error = |source| DynamoDbError::SaveLeaderboard { leaderboard_id, year, source };
+
+        let leaderboard_value =
+            serde_dynamo::to_attribute_value(leaderboard).map_err(|err| save_error(err.into()))?;
+        let outbox_value =
+            serde_dynamo::to_attribute_value(report).map_err(|err| save_error(err.into()))?;
+
+        // Both values are saved in a single update so that they are persisted atomically.
+        self.client
+            .update_item()
+            .table_name(self.table_name.clone())
+            .key(HASH_KEY, AttributeValue::N(leaderboard_id.to_string()))
+            .key(RANGE_KEY, AttributeValue::N(year.to_string()))
+            .update_expression(format!(
+                "SET #leaderboard_data = :leaderboard_data, #outbox = :outbox \
+                REMOVE {ERROR_STATE_PLACEHOLDERS}"
+            ))
+            .expression_attribute_names("#leaderboard_data", LEADERBOARD_DATA)
+            .expression_attribute_names("#outbox", OUTBOX)
+            .error_state_attribute_names()
+            .expression_attribute_values(":leaderboard_data", leaderboard_value)
+            .expression_attribute_values(":outbox", outbox_value)
+            .send()
+            .await
+            .map_err(|err| save_error(Box::new(err).into()))?;
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self), ret, err))]
+    async fn mark_delivered(&mut self, year: i32, leaderboard_id: u64) -> Result<(), Self::Err> {
+        self.items.invalidate(year, leaderboard_id);
+
+        let update_result = self
+            .client
+            .update_item()
+            .table_name(self.table_name.clone())
+            .key(HASH_KEY, AttributeValue::N(leaderboard_id.to_string()))
+            .key(RANGE_KEY, AttributeValue::N(year.to_string()))
+            .update_expression("REMOVE #outbox")
+            .condition_expression("attribute_exists(#hash_key)")
+            .expression_attribute_names("#outbox", OUTBOX)
+            .expression_attribute_names("#hash_key", HASH_KEY)
+            .send()
+            .await;
+
+        match update_result {
+            Ok(_) => Ok(()),
+            Err(SdkError::ServiceError(service_err))
+                if service_err.err().is_conditional_check_failed_exception() =>
+            {
+                // There's no item for this leaderboard, so the outbox is already empty.
+                Ok(())
+            },
+            Err(err) => Err(DynamoDbError::MarkDelivered {
+                leaderboard_id,
+                year,
+                source: Box::new(err).into(),
+            }
+            .into()),
+        }
+    }
+}
+
+trait UpdateItemFluentBuilderExt {
+    // Adds the names of all the columns storing error information as expression attribute names.
+    fn error_state_attribute_names(self) -> Self;
+}
+
+impl UpdateItemFluentBuilderExt for UpdateItemFluentBuilder {
+    fn error_state_attribute_names(self) -> Self {
+        self.expression_attribute_names("#last_error", LAST_ERROR)
+            .expression_attribute_names("#last_error_since", LAST_ERROR_SINCE)
+            .expression_attribute_names("#last_error_seen", LAST_ERROR_SEEN)
+            .expression_attribute_names("#last_error_count", LAST_ERROR_COUNT)
+            .expression_attribute_names("#last_error_message", LAST_ERROR_MESSAGE)
+            .expression_attribute_names("#last_error_notified", LAST_ERROR_NOTIFIED)
+    }
 }
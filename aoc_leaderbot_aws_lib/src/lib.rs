@@ -15,12 +15,27 @@
 //! The only thing that the storage needs is the name of the table where to store data.
 //! If that table does not yet exist, it's possible to create it via the [`create_table`].
 //!
+//! ### [`AwsSecretCredentialsProvider`]
+//!
+//! Required feature: `credentials-secrets`
+//!
+//! An implementation of the [`CredentialsProvider`] trait that fetches AoC credentials from
+//! [AWS Secrets Manager] or [AWS Systems Manager Parameter Store], so that they do not need to be
+//! stored in a Lambda function's environment variables. Secrets are fetched using the AWS SDK,
+//! or optionally through the [AWS Parameters and Secrets Lambda Extension] when it is added to
+//! the function as a layer.
+//!
 //! [`aoc_leaderbot`]: https://github.com/clechasseur/aoc_leaderbot
 //! [Advent of Code]: https://adventofcode.com/
 //! [`DynamoDbStorage`]: leaderbot::storage::aws::dynamodb::DynamoDbStorage
 //! [`Storage`]: aoc_leaderbot_lib::leaderbot::Storage
 //! [AWS DynamoDB]: https://aws.amazon.com/dynamodb/
 //! [`create_table`]: leaderbot::storage::aws::dynamodb::DynamoDbStorage::create_table
+//! [`AwsSecretCredentialsProvider`]: leaderbot::credentials::aws::secrets::AwsSecretCredentialsProvider
+//! [`CredentialsProvider`]: aoc_leaderbot_lib::leaderbot::CredentialsProvider
+//! [AWS Secrets Manager]: https://aws.amazon.com/secrets-manager/
+//! [AWS Systems Manager Parameter Store]: https://docs.aws.amazon.com/systems-manager/latest/userguide/systems-manager-parameter-store.html
+//! [AWS Parameters and Secrets Lambda Extension]: https://docs.aws.amazon.com/secretsmanager/latest/userguide/retrieving-secrets_lambda.html
 
 #![deny(missing_docs)]
 #![deny(rustdoc::missing_crate_level_docs)]
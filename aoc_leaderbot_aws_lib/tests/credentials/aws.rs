@@ -0,0 +1,2 @@
+#[cfg(feature = "credentials-secrets")]
+mod secrets;
@@ -0,0 +1,352 @@
+mod aws_secret_credentials_provider {
+    use aoc_leaderboard::aoc::{LeaderboardCredentials, LeaderboardCredentialsKind};
+    use aoc_leaderboard::test_helpers::{TEST_AOC_SESSION, TEST_AOC_VIEW_KEY};
+    use aoc_leaderbot_aws_lib::Error;
+    use aoc_leaderbot_aws_lib::error::{FetchSecretError, SecretsError};
+    use aoc_leaderbot_aws_lib::leaderbot::credentials::aws::secrets::{
+        AwsSecretCredentialsProvider, AwsSecretSource,
+    };
+    use aoc_leaderbot_lib::leaderbot::CredentialsProvider;
+    use assert_matches::assert_matches;
+    use serde_json::json;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const SECRET_ID: &str = "aoc/session";
+    const PARAMETER_NAME: &str = "/aoc/view_key";
+
+    async fn mock_secret(mock_server: &MockServer, response: ResponseTemplate) {
+        Mock::given(method("GET"))
+            .and(path("/secretsmanager/get"))
+            .and(query_param("secretId", SECRET_ID))
+            .respond_with(response)
+            .mount(mock_server)
+            .await;
+    }
+
+    mod lambda_extension {
+        use super::*;
+
+        mod secrets_manager {
+            use super::*;
+
+            #[test_log::test(tokio::test)]
+            async fn plain_secret() {
+                let mock_server = MockServer::start().await;
+                mock_secret(
+                    &mock_server,
+                    ResponseTemplate::new(200).set_body_json(
+                        json!({ "Name": SECRET_ID, "SecretString": TEST_AOC_SESSION }),
+                    ),
+                )
+                .await;
+
+                let provider = AwsSecretCredentialsProvider::lambda_extension(
+                    AwsSecretSource::secrets_manager(SECRET_ID),
+                    LeaderboardCredentialsKind::SessionCookie,
+                )
+                .with_endpoint(mock_server.uri());
+                let actual = provider.get_credentials().await.unwrap();
+
+                assert_eq!(actual, LeaderboardCredentials::SessionCookie(TEST_AOC_SESSION.into()));
+            }
+
+            #[test_log::test(tokio::test)]
+            async fn json_secret() {
+                let mock_server = MockServer::start().await;
+                let secret_string = json!({ "session": TEST_AOC_SESSION }).to_string();
+                mock_secret(
+                    &mock_server,
+                    ResponseTemplate::new(200)
+                        .set_body_json(json!({ "SecretString": secret_string })),
+                )
+                .await;
+
+                let provider = AwsSecretCredentialsProvider::lambda_extension(
+                    AwsSecretSource::SecretsManager {
+                        secret_id: SECRET_ID.into(),
+                        json_key: Some("session".into()),
+                    },
+                    LeaderboardCredentialsKind::SessionCookie,
+                )
+                .with_endpoint(mock_server.uri());
+                let actual = provider.get_credentials().await.unwrap();
+
+                assert_eq!(actual, LeaderboardCredentials::SessionCookie(TEST_AOC_SESSION.into()));
+            }
+
+            #[test_log::test(tokio::test)]
+            async fn missing_json_key() {
+                let mock_server = MockServer::start().await;
+                let secret_string = json!({ "cookie": TEST_AOC_SESSION }).to_string();
+                mock_secret(
+                    &mock_server,
+                    ResponseTemplate::new(200)
+                        .set_body_json(json!({ "SecretString": secret_string })),
+                )
+                .await;
+
+                let provider = AwsSecretCredentialsProvider::lambda_extension(
+                    AwsSecretSource::SecretsManager {
+                        secret_id: SECRET_ID.into(),
+                        json_key: Some("session".into()),
+                    },
+                    LeaderboardCredentialsKind::SessionCookie,
+                )
+                .with_endpoint(mock_server.uri());
+                let actual = provider.get_credentials().await;
+
+                assert_matches!(actual, Err(Error::Secrets(SecretsError::MissingValue { name, field })) => {
+                    assert_eq!(name, SECRET_ID);
+                    assert_eq!(field, "session");
+                });
+            }
+
+            #[test_log::test(tokio::test)]
+            async fn binary_secret() {
+                let mock_server = MockServer::start().await;
+                mock_secret(
+                    &mock_server,
+                    ResponseTemplate::new(200).set_body_json(json!({ "SecretBinary": "Zm9v" })),
+                )
+                .await;
+
+                let provider = AwsSecretCredentialsProvider::lambda_extension(
+                    AwsSecretSource::secrets_manager(SECRET_ID),
+                    LeaderboardCredentialsKind::SessionCookie,
+                )
+                .with_endpoint(mock_server.uri());
+                let actual = provider.get_credentials().await;
+
+                assert_matches!(actual, Err(Error::Secrets(SecretsError::MissingValue { field, .. })) => {
+                    assert_eq!(field, "SecretString");
+                });
+            }
+
+            #[test_log::test(tokio::test)]
+            async fn not_found() {
+                let mock_server = MockServer::start().await;
+                mock_secret(&mock_server, ResponseTemplate::new(404)).await;
+
+                let provider = AwsSecretCredentialsProvider::lambda_extension(
+                    AwsSecretSource::secrets_manager(SECRET_ID),
+                    LeaderboardCredentialsKind::SessionCookie,
+                )
+                .with_endpoint(mock_server.uri());
+                let actual = provider.get_credentials().await;
+
+                assert_matches!(actual, Err(Error::Secrets(SecretsError::Fetch { name, source })) => {
+                    assert_eq!(name, SECRET_ID);
+                    assert_matches!(source, FetchSecretError::LambdaExtension(source) => {
+                        assert_eq!(source.status(), Some(reqwest::StatusCode::NOT_FOUND));
+                    });
+                });
+            }
+
+            #[test_log::test(tokio::test)]
+            async fn invalid_response() {
+                let mock_server = MockServer::start().await;
+                mock_secret(&mock_server, ResponseTemplate::new(200).set_body_string("not json"))
+                    .await;
+
+                let provider = AwsSecretCredentialsProvider::lambda_extension(
+                    AwsSecretSource::secrets_manager(SECRET_ID),
+                    LeaderboardCredentialsKind::SessionCookie,
+                )
+                .with_endpoint(mock_server.uri());
+                let actual = provider.get_credentials().await;
+
+                assert_matches!(actual, Err(Error::Secrets(SecretsError::Parse { .. })));
+            }
+        }
+
+        mod parameter_store {
+            use super::*;
+
+            #[test_log::test(tokio::test)]
+            async fn secure_string() {
+                let mock_server = MockServer::start().await;
+                Mock::given(method("GET"))
+                    .and(path("/systemsmanager/parameters/get"))
+                    .and(query_param("name", PARAMETER_NAME))
+                    .and(query_param("withDecryption", "true"))
+                    .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                        "Parameter": {
+                            "Name": PARAMETER_NAME,
+                            "Type": "SecureString",
+                            "Value": TEST_AOC_VIEW_KEY,
+                        },
+                    })))
+                    .mount(&mock_server)
+                    .await;
+
+                let provider = AwsSecretCredentialsProvider::lambda_extension(
+                    AwsSecretSource::parameter_store(PARAMETER_NAME),
+                    LeaderboardCredentialsKind::ViewKey,
+                )
+                .with_endpoint(mock_server.uri());
+                let actual = provider.get_credentials().await.unwrap();
+
+                assert_eq!(actual, LeaderboardCredentials::ViewKey(TEST_AOC_VIEW_KEY.into()));
+            }
+        }
+    }
+
+    // These tests require Docker, which only seems to work reliably on Linux in GitHub workflows.
+    #[cfg(any(not(ci), target_os = "linux"))]
+    mod sdk {
+        use std::future::Future;
+
+        use aws_config::{BehaviorVersion, SdkConfig};
+        use serial_test::file_serial;
+        use testcontainers_modules::localstack::LocalStack;
+        use testcontainers_modules::testcontainers::runners::AsyncRunner;
+
+        use super::*;
+
+        async fn with_localstack<TF, TFR>(test_f: TF)
+        where
+            TF: FnOnce(SdkConfig) -> TFR,
+            TFR: Future<Output = ()>,
+        {
+            let container = LocalStack::default()
+                .start()
+                .await
+                .expect("should be able to create localstack container");
+            let host = container
+                .get_host()
+                .await
+                .expect("should be able to get localstack container host");
+            let port = container
+                .get_host_port_ipv4(4566)
+                .await
+                .expect("should be able to get localstack container port");
+
+            let config = aws_config::defaults(BehaviorVersion::latest())
+                .region("ca-central-1")
+                .test_credentials()
+                .endpoint_url(format!("http://{host}:{port}"))
+                .load()
+                .await;
+
+            test_f(config).await;
+        }
+
+        async fn create_secret(config: &SdkConfig, secret_string: &str) {
+            aws_sdk_secretsmanager::Client::new(config)
+                .create_secret()
+                .name(SECRET_ID)
+                .secret_string(secret_string)
+                .send()
+                .await
+                .expect("secret should be creatable in localstack");
+        }
+
+        mod secrets_manager {
+            use super::*;
+
+            #[test_log::test(tokio::test)]
+            #[file_serial(testcontainers_localstack)]
+            async fn plain_secret() {
+                with_localstack(|config| async move {
+                    create_secret(&config, TEST_AOC_SESSION).await;
+
+                    let provider = AwsSecretCredentialsProvider::with_config(
+                        &config,
+                        AwsSecretSource::secrets_manager(SECRET_ID),
+                        LeaderboardCredentialsKind::SessionCookie,
+                    )
+                    .await;
+                    assert!(provider.endpoint().is_none());
+                    let actual = provider.get_credentials().await.unwrap();
+
+                    assert_eq!(
+                        actual,
+                        LeaderboardCredentials::SessionCookie(TEST_AOC_SESSION.into())
+                    );
+                })
+                .await;
+            }
+
+            #[test_log::test(tokio::test)]
+            #[file_serial(testcontainers_localstack)]
+            async fn json_secret() {
+                with_localstack(|config| async move {
+                    let secret_string = json!({ "session": TEST_AOC_SESSION }).to_string();
+                    create_secret(&config, &secret_string).await;
+
+                    let provider = AwsSecretCredentialsProvider::with_config(
+                        &config,
+                        AwsSecretSource::SecretsManager {
+                            secret_id: SECRET_ID.into(),
+                            json_key: Some("session".into()),
+                        },
+                        LeaderboardCredentialsKind::SessionCookie,
+                    )
+                    .await;
+                    let actual = provider.get_credentials().await.unwrap();
+
+                    assert_eq!(
+                        actual,
+                        LeaderboardCredentials::SessionCookie(TEST_AOC_SESSION.into())
+                    );
+                })
+                .await;
+            }
+
+            #[test_log::test(tokio::test)]
+            #[file_serial(testcontainers_localstack)]
+            async fn not_found() {
+                with_localstack(|config| async move {
+                    let provider = AwsSecretCredentialsProvider::with_config(
+                        &config,
+                        AwsSecretSource::secrets_manager(SECRET_ID),
+                        LeaderboardCredentialsKind::SessionCookie,
+                    )
+                    .await;
+                    let actual = provider.get_credentials().await;
+
+                    assert_matches!(actual, Err(Error::Secrets(SecretsError::Fetch { name, source })) => {
+                        assert_eq!(name, SECRET_ID);
+                        assert_matches!(source, FetchSecretError::SecretsManager(source) => {
+                            assert!(source.into_service_error().is_resource_not_found_exception());
+                        });
+                    });
+                })
+                .await;
+            }
+        }
+
+        mod parameter_store {
+            use aws_sdk_ssm::types::ParameterType;
+
+            use super::*;
+
+            #[test_log::test(tokio::test)]
+            #[file_serial(testcontainers_localstack)]
+            async fn secure_string() {
+                with_localstack(|config| async move {
+                    aws_sdk_ssm::Client::new(&config)
+                        .put_parameter()
+                        .name(PARAMETER_NAME)
+                        .r#type(ParameterType::SecureString)
+                        .value(TEST_AOC_VIEW_KEY)
+                        .send()
+                        .await
+                        .expect("parameter should be creatable in localstack");
+
+                    let provider = AwsSecretCredentialsProvider::with_config(
+                        &config,
+                        AwsSecretSource::parameter_store(PARAMETER_NAME),
+                        LeaderboardCredentialsKind::ViewKey,
+                    )
+                    .await;
+                    let actual = provider.get_credentials().await.unwrap();
+
+                    assert_eq!(actual, LeaderboardCredentials::ViewKey(TEST_AOC_VIEW_KEY.into()));
+                })
+                .await;
+            }
+        }
+    }
+}
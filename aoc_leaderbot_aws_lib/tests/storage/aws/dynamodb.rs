@@ -4,14 +4,19 @@ mod dynamo_storage {
     use aoc_leaderboard::aoc::Leaderboard;
     use aoc_leaderboard::test_helpers::{TEST_LEADERBOARD_ID, TEST_YEAR, test_leaderboard};
     use aoc_leaderbot_aws_lib::error::{
-        CreateDynamoDbTableError, DynamoDbError, LoadPreviousDynamoDbError, SaveDynamoDbError,
+        CreateDynamoDbTableError, DeleteDynamoDbError, DynamoDbError, ListDynamoDbError,
+        LoadPreviousDynamoDbError, SaveDynamoDbError,
     };
     use aoc_leaderbot_aws_lib::leaderbot::storage::aws::dynamodb::test_helpers::LocalTable;
     use aoc_leaderbot_aws_lib::leaderbot::storage::aws::dynamodb::{
         HASH_KEY, LEADERBOARD_DATA, RANGE_KEY,
     };
     use aoc_leaderbot_lib::ErrorKind;
-    use aoc_leaderbot_lib::leaderbot::Storage;
+    use aoc_leaderbot_lib::leaderbot::storage::conformance::{
+        check_digest_storage, check_error_state, check_heartbeat, check_outbox_storage,
+        check_storage, check_storage_admin,
+    };
+    use aoc_leaderbot_lib::leaderbot::{Storage, StorageAdmin};
     use assert_matches::assert_matches;
     use aws_sdk_dynamodb::error::SdkError;
     use aws_sdk_dynamodb::operation::create_table::CreateTableError;
@@ -55,17 +60,6 @@ mod dynamo_storage {
         pub mod load_previous {
             use super::*;
 
-            #[test_log::test]
-            fn without_existing() {
-                LocalTable::run_test(None, |mut table| async move {
-                    let previous_leaderboard = table
-                        .storage()
-                        .load_previous(TEST_YEAR, TEST_LEADERBOARD_ID)
-                        .await;
-                    assert_matches!(previous_leaderboard, Ok((None, None)));
-                });
-            }
-
             #[rstest]
             #[test_log::test]
             fn with_existing_leaderboard(
@@ -234,114 +228,106 @@ mod dynamo_storage {
             }
         }
 
-        pub mod save_success {
+        mod item_cache {
+            use aoc_leaderbot_lib::leaderbot::heartbeat::HeartbeatState;
+            use chrono::{TimeZone, Utc};
+
             use super::*;
 
-            #[rstest]
             #[test_log::test]
-            fn without_existing(#[from(test_leaderboard)] expected_leaderboard: Leaderboard) {
+            fn reuses_item_read_by_load_previous() {
                 LocalTable::run_test(None, |mut table| async move {
+                    let heartbeat = HeartbeatState::new(
+                        Utc.with_ymd_and_hms(TEST_YEAR, 12, 15, 12, 0, 0).unwrap(),
+                    );
                     table
                         .storage()
-                        .save_success(TEST_YEAR, TEST_LEADERBOARD_ID, &expected_leaderboard)
+                        .save_heartbeat(TEST_YEAR, TEST_LEADERBOARD_ID, &heartbeat)
                         .await
                         .unwrap();
+                    assert_matches!(
+                        table
+                            .storage()
+                            .load_previous(TEST_YEAR, TEST_LEADERBOARD_ID)
+                            .await,
+                        Ok((None, None))
+                    );
 
-                    let (actual_leaderboard, actual_last_error) =
-                        table.load_leaderboard_and_last_error().await;
-                    assert_matches!(actual_leaderboard, Some(actual_leaderboard) => {
-                        assert_eq!(expected_leaderboard, actual_leaderboard);
-                    });
-                    assert!(actual_last_error.is_none());
-                });
-            }
-
-            #[rstest]
-            #[test_log::test]
-            fn with_existing_leaderboard(
-                #[from(test_leaderboard)] previous_leaderboard: Leaderboard,
-            ) {
-                LocalTable::run_test(None, |mut table| async move {
-                    table.save_leaderboard(&previous_leaderboard).await;
-
-                    let expected_leaderboard = Leaderboard {
-                        day1_ts: previous_leaderboard.day1_ts + 1,
-                        ..previous_leaderboard
-                    };
+                    // Delete the item behind the storage's back: the cached item is still used.
                     table
-                        .storage()
-                        .save_success(TEST_YEAR, TEST_LEADERBOARD_ID, &expected_leaderboard)
+                        .client()
+                        .delete_item()
+                        .table_name(table.name())
+                        .key(HASH_KEY, AttributeValue::N(TEST_LEADERBOARD_ID.to_string()))
+                        .key(RANGE_KEY, AttributeValue::N(TEST_YEAR.to_string()))
+                        .send()
                         .await
                         .unwrap();
+                    assert_matches!(
+                        table
+                            .storage()
+                            .load_heartbeat(TEST_YEAR, TEST_LEADERBOARD_ID)
+                            .await,
+                        Ok(Some(actual)) => assert_eq!(heartbeat, actual)
+                    );
 
-                    let actual = table.load_leaderboard_and_last_error().await;
-                    assert_matches!(actual, (Some(actual_leaderboard), None) => {
-                        assert_eq!(expected_leaderboard, actual_leaderboard);
-                    });
+                    // Loading previous data again refreshes the cached item.
+                    assert_matches!(
+                        table
+                            .storage()
+                            .load_previous(TEST_YEAR, TEST_LEADERBOARD_ID)
+                            .await,
+                        Ok((None, None))
+                    );
+                    assert_matches!(
+                        table
+                            .storage()
+                            .load_heartbeat(TEST_YEAR, TEST_LEADERBOARD_ID)
+                            .await,
+                        Ok(None)
+                    );
                 });
             }
 
-            #[rstest]
             #[test_log::test]
-            fn with_existing_last_error(
-                #[from(test_leaderboard)] expected_leaderboard: Leaderboard,
-            ) {
+            fn invalidated_on_write() {
                 LocalTable::run_test(None, |mut table| async move {
-                    table
-                        .save_last_error(ErrorKind::Leaderboard(
-                            aoc_leaderboard::ErrorKind::NoAccess,
-                        ))
-                        .await;
+                    let heartbeat = HeartbeatState::new(
+                        Utc.with_ymd_and_hms(TEST_YEAR, 12, 15, 12, 0, 0).unwrap(),
+                    );
+                    assert_matches!(
+                        table
+                            .storage()
+                            .load_previous(TEST_YEAR, TEST_LEADERBOARD_ID)
+                            .await,
+                        Ok((None, None))
+                    );
 
                     table
                         .storage()
-                        .save_success(TEST_YEAR, TEST_LEADERBOARD_ID, &expected_leaderboard)
+                        .save_heartbeat(TEST_YEAR, TEST_LEADERBOARD_ID, &heartbeat)
                         .await
                         .unwrap();
-
-                    let actual = table.load_leaderboard_and_last_error().await;
-                    assert_matches!(actual, (Some(actual_leaderboard), None) => {
-                        assert_eq!(expected_leaderboard, actual_leaderboard);
-                    });
+                    assert_matches!(
+                        table
+                            .storage()
+                            .load_heartbeat(TEST_YEAR, TEST_LEADERBOARD_ID)
+                            .await,
+                        Ok(Some(actual)) => assert_eq!(heartbeat, actual)
+                    );
                 });
             }
+        }
 
-            #[rstest]
-            #[test_log::test]
-            fn with_existing_leaderboard_and_last_error(
-                #[from(test_leaderboard)] previous_leaderboard: Leaderboard,
-            ) {
-                LocalTable::run_test(None, |mut table| async move {
-                    table.save_leaderboard(&previous_leaderboard).await;
-                    table
-                        .save_last_error(ErrorKind::Leaderboard(
-                            aoc_leaderboard::ErrorKind::NoAccess,
-                        ))
-                        .await;
-
-                    let expected_leaderboard = Leaderboard {
-                        day1_ts: previous_leaderboard.day1_ts + 1,
-                        ..previous_leaderboard
-                    };
-                    table
-                        .storage()
-                        .save_success(TEST_YEAR, TEST_LEADERBOARD_ID, &expected_leaderboard)
-                        .await
-                        .unwrap();
-
-                    let actual = table.load_leaderboard_and_last_error().await;
-                    assert_matches!(actual, (Some(actual_leaderboard), None) => {
-                        assert_eq!(expected_leaderboard, actual_leaderboard);
-                    });
-                });
-            }
+        pub mod save_success {
+            use super::*;
 
             pub mod errors {
                 use super::*;
 
                 #[rstest]
                 #[test_log::test]
-                fn put_item(#[from(test_leaderboard)] leaderboard: Leaderboard) {
+                fn update_item(#[from(test_leaderboard)] leaderboard: Leaderboard) {
                     LocalTable::builder()
                         .pre_create(false)
                         .run_test(|mut table| async move {
@@ -355,7 +341,7 @@ mod dynamo_storage {
                                     DynamoDbError::SaveLeaderboard {
                                         leaderboard_id,
                                         year,
-                                        source: SaveDynamoDbError::PutItem(_),
+                                        source: SaveDynamoDbError::UpdateItem(_),
                                     }
                                 )) => {
                                     assert_eq!(TEST_LEADERBOARD_ID, leaderboard_id);
@@ -370,39 +356,163 @@ mod dynamo_storage {
         pub mod save_error {
             use super::*;
 
-            #[test_log::test]
-            fn without_existing() {
-                LocalTable::run_test(None, |mut table| async move {
-                    table
-                        .storage()
-                        .save_error(
-                            TEST_YEAR,
-                            TEST_LEADERBOARD_ID,
-                            ErrorKind::Leaderboard(aoc_leaderboard::ErrorKind::NoAccess),
-                        )
-                        .await
-                        .unwrap();
+            pub mod errors {
+                use super::*;
 
-                    let actual = table.load_leaderboard_and_last_error().await;
-                    assert_matches!(actual, (None, Some(actual_last_error)) => {
-                        assert_eq!(
-                            ErrorKind::Leaderboard(aoc_leaderboard::ErrorKind::NoAccess),
-                            actual_last_error,
+                #[test_log::test]
+                fn update_item() {
+                    LocalTable::builder()
+                        .pre_create(false)
+                        .run_test(|mut table| async move {
+                            let save_result = table
+                                .storage()
+                                .save_error(
+                                    TEST_YEAR,
+                                    TEST_LEADERBOARD_ID,
+                                    ErrorKind::Leaderboard(aoc_leaderboard::ErrorKind::NoAccess),
+                                )
+                                .await;
+                            assert_matches!(
+                                save_result,
+                                Err(aoc_leaderbot_aws_lib::Error::Dynamo(
+                                    DynamoDbError::SaveLastError {
+                                        leaderboard_id,
+                                        year,
+                                        source: SaveDynamoDbError::UpdateItem(_),
+                                    }
+                                )) => {
+                                    assert_eq!(TEST_LEADERBOARD_ID, leaderboard_id);
+                                    assert_eq!(TEST_YEAR, year);
+                                }
+                            );
+                        });
+                }
+            }
+        }
+    }
+
+    mod storage_admin_impl {
+        use super::*;
+
+        pub mod list {
+            use super::*;
+
+            pub mod errors {
+                use super::*;
+
+                #[test_log::test]
+                fn scan() {
+                    LocalTable::builder()
+                        .pre_create(false)
+                        .run_test(|mut table| async move {
+                            let entries = table.storage().list().await;
+                            assert_matches!(
+                                entries,
+                                Err(aoc_leaderbot_aws_lib::Error::Dynamo(
+                                    DynamoDbError::ListLeaderboards {
+                                        table_name,
+                                        source: ListDynamoDbError::Scan(_),
+                                    }
+                                )) => {
+                                    assert_eq!(table.name(), table_name);
+                                }
+                            );
+                        });
+                }
+
+                #[test_log::test]
+                fn invalid_leaderboard_data_type() {
+                    LocalTable::run_test(None, |mut table| async move {
+                        table
+                            .client()
+                            .put_item()
+                            .table_name(table.name())
+                            .item(HASH_KEY, AttributeValue::N(TEST_LEADERBOARD_ID.to_string()))
+                            .item(RANGE_KEY, AttributeValue::N(TEST_YEAR.to_string()))
+                            .item(LEADERBOARD_DATA, AttributeValue::N(42.to_string()))
+                            .send()
+                            .await
+                            .unwrap();
+
+                        let entries = table.storage().list().await;
+                        assert_matches!(
+                            entries,
+                            Err(aoc_leaderbot_aws_lib::Error::Dynamo(
+                                DynamoDbError::ListLeaderboards {
+                                    source: ListDynamoDbError::Deserialize(_),
+                                    ..
+                                }
+                            ))
                         );
                     });
-                });
+                }
+            }
+        }
+
+        pub mod delete {
+            use super::*;
+
+            pub mod errors {
+                use super::*;
+
+                #[test_log::test]
+                fn delete_item() {
+                    LocalTable::builder()
+                        .pre_create(false)
+                        .run_test(|mut table| async move {
+                            let delete_result =
+                                table.storage().delete(TEST_YEAR, TEST_LEADERBOARD_ID).await;
+                            assert_matches!(
+                                delete_result,
+                                Err(aoc_leaderbot_aws_lib::Error::Dynamo(
+                                    DynamoDbError::DeleteLeaderboard {
+                                        leaderboard_id,
+                                        year,
+                                        source: DeleteDynamoDbError::DeleteItem(_),
+                                    }
+                                )) => {
+                                    assert_eq!(TEST_LEADERBOARD_ID, leaderboard_id);
+                                    assert_eq!(TEST_YEAR, year);
+                                }
+                            );
+                        });
+                }
             }
+        }
+
+        pub mod clear_error {
+            use aoc_leaderbot_lib::leaderbot::heartbeat::HeartbeatState;
+            use aoc_leaderbot_lib::leaderbot::{
+                Changes, DigestStorage, OutboxReport, OutboxStorage, PendingChanges,
+            };
+            use chrono::{TimeZone, Utc};
+
+            use super::*;
 
             #[rstest]
             #[test_log::test]
-            fn with_existing_leaderboard(
-                #[from(test_leaderboard)] expected_leaderboard: Leaderboard,
-            ) {
+            fn keeps_other_data(#[from(test_leaderboard)] leaderboard: Leaderboard) {
                 LocalTable::run_test(None, |mut table| async move {
-                    table.save_leaderboard(&expected_leaderboard).await;
+                    let now = Utc.with_ymd_and_hms(TEST_YEAR, 12, 15, 12, 0, 0).unwrap();
+                    let pending_changes = PendingChanges {
+                        previous_leaderboard: leaderboard.clone(),
+                        changes: Changes::default(),
+                        since: now,
+                    };
+                    let heartbeat = HeartbeatState::new(now);
+                    let storage = table.storage();
 
-                    table
-                        .storage()
+                    // Without leaderboard data: the item only holds the error, pending changes
+                    // and heartbeat.
+                    storage
+                        .save_pending_changes(TEST_YEAR, TEST_LEADERBOARD_ID, &pending_changes)
+                        .await
+                        .unwrap();
+                    storage
+                        .save_heartbeat(TEST_YEAR, TEST_LEADERBOARD_ID, &heartbeat)
+                        .await
+                        .unwrap();
+                    storage
                         .save_error(
                             TEST_YEAR,
                             TEST_LEADERBOARD_ID,
@@ -411,53 +521,36 @@ mod dynamo_storage {
                         .await
                         .unwrap();
 
-                    let actual = table.load_leaderboard_and_last_error().await;
-                    assert_matches!(actual, (Some(actual_leaderboard), Some(actual_last_error)) => {
-                        assert_eq!(expected_leaderboard, actual_leaderboard);
-                        assert_eq!(
-                            ErrorKind::Leaderboard(aoc_leaderboard::ErrorKind::NoAccess),
-                            actual_last_error,
-                        );
-                    });
-                });
-            }
+                    storage
+                        .clear_error(TEST_YEAR, TEST_LEADERBOARD_ID)
+                        .await
+                        .unwrap();
 
-            #[test_log::test]
-            fn with_existing_last_error() {
-                LocalTable::run_test(None, |mut table| async move {
-                    table.save_last_error(ErrorKind::MissingField).await;
+                    assert_matches!(
+                        storage.load_previous(TEST_YEAR, TEST_LEADERBOARD_ID).await,
+                        Ok((None, None))
+                    );
+                    assert_matches!(
+                        storage.load_pending_changes(TEST_YEAR, TEST_LEADERBOARD_ID).await,
+                        Ok(Some(actual)) => assert_eq!(pending_changes, actual)
+                    );
+                    assert_matches!(
+                        storage.load_heartbeat(TEST_YEAR, TEST_LEADERBOARD_ID).await,
+                        Ok(Some(actual)) => assert_eq!(heartbeat, actual)
+                    );
+                    assert!(storage.list().await.unwrap().is_empty());
 
-                    table
-                        .storage()
-                        .save_error(
+                    // With leaderboard data and a report in the outbox.
+                    storage
+                        .save_success_with_outbox(
                             TEST_YEAR,
                             TEST_LEADERBOARD_ID,
-                            ErrorKind::Leaderboard(aoc_leaderboard::ErrorKind::NoAccess),
+                            &leaderboard,
+                            &OutboxReport::FirstRun,
                         )
                         .await
                         .unwrap();
-
-                    let actual = table.load_leaderboard_and_last_error().await;
-                    assert_matches!(actual, (None, Some(actual_last_error)) => {
-                        assert_eq!(
-                            ErrorKind::Leaderboard(aoc_leaderboard::ErrorKind::NoAccess),
-                            actual_last_error,
-                        );
-                    });
-                });
-            }
-
-            #[rstest]
-            #[test_log::test]
-            fn with_existing_leaderboard_and_last_error(
-                #[from(test_leaderboard)] expected_leaderboard: Leaderboard,
-            ) {
-                LocalTable::run_test(None, |mut table| async move {
-                    table.save_leaderboard(&expected_leaderboard).await;
-                    table.save_last_error(ErrorKind::MissingField).await;
-
-                    table
-                        .storage()
+                    storage
                         .save_error(
                             TEST_YEAR,
                             TEST_LEADERBOARD_ID,
@@ -466,14 +559,27 @@ mod dynamo_storage {
                         .await
                         .unwrap();
 
-                    let actual = table.load_leaderboard_and_last_error().await;
-                    assert_matches!(actual, (Some(actual_leaderboard), Some(actual_last_error)) => {
-                        assert_eq!(expected_leaderboard, actual_leaderboard);
-                        assert_eq!(
-                            ErrorKind::Leaderboard(aoc_leaderboard::ErrorKind::NoAccess),
-                            actual_last_error,
-                        );
-                    });
+                    storage
+                        .clear_error(TEST_YEAR, TEST_LEADERBOARD_ID)
+                        .await
+                        .unwrap();
+
+                    assert_matches!(
+                        storage.load_previous(TEST_YEAR, TEST_LEADERBOARD_ID).await,
+                        Ok((Some(actual), None)) => assert_eq!(leaderboard, actual)
+                    );
+                    assert_matches!(
+                        storage.load_outbox(TEST_YEAR, TEST_LEADERBOARD_ID).await,
+                        Ok(Some(OutboxReport::FirstRun))
+                    );
+                    assert_matches!(
+                        storage.load_pending_changes(TEST_YEAR, TEST_LEADERBOARD_ID).await,
+                        Ok(Some(actual)) => assert_eq!(pending_changes, actual)
+                    );
+                    assert_matches!(
+                        storage.load_heartbeat(TEST_YEAR, TEST_LEADERBOARD_ID).await,
+                        Ok(Some(actual)) => assert_eq!(heartbeat, actual)
+                    );
                 });
             }
 
@@ -485,18 +591,14 @@ mod dynamo_storage {
                     LocalTable::builder()
                         .pre_create(false)
                         .run_test(|mut table| async move {
-                            let save_result = table
+                            let clear_result = table
                                 .storage()
-                                .save_error(
-                                    TEST_YEAR,
-                                    TEST_LEADERBOARD_ID,
-                                    ErrorKind::Leaderboard(aoc_leaderboard::ErrorKind::NoAccess),
-                                )
+                                .clear_error(TEST_YEAR, TEST_LEADERBOARD_ID)
                                 .await;
                             assert_matches!(
-                                save_result,
+                                clear_result,
                                 Err(aoc_leaderbot_aws_lib::Error::Dynamo(
-                                    DynamoDbError::SaveLastError {
+                                    DynamoDbError::ClearLastError {
                                         leaderboard_id,
                                         year,
                                         source: SaveDynamoDbError::UpdateItem(_),
@@ -511,4 +613,49 @@ mod dynamo_storage {
             }
         }
     }
+    mod conformance {
+        use super::*;
+
+        #[test_log::test]
+        fn storage() {
+            LocalTable::run_test(None, |mut table| async move {
+                check_storage(table.storage()).await;
+            });
+        }
+
+        #[test_log::test]
+        fn storage_admin() {
+            LocalTable::run_test(None, |mut table| async move {
+                check_storage_admin(table.storage()).await;
+            });
+        }
+
+        #[test_log::test]
+        fn digest_storage() {
+            LocalTable::run_test(None, |mut table| async move {
+                check_digest_storage(table.storage()).await;
+            });
+        }
+
+        #[test_log::test]
+        fn outbox_storage() {
+            LocalTable::run_test(None, |mut table| async move {
+                check_outbox_storage(table.storage()).await;
+            });
+        }
+
+        #[test_log::test]
+        fn error_state() {
+            LocalTable::run_test(None, |mut table| async move {
+                check_error_state(table.storage()).await;
+            });
+        }
+
+        #[test_log::test]
+        fn heartbeat() {
+            LocalTable::run_test(None, |mut table| async move {
+                check_heartbeat(table.storage()).await;
+            });
+        }
+    }
 }
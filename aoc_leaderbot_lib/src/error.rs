@@ -3,10 +3,13 @@
 use std::env;
 use std::ffi::{OsStr, OsString};
 use std::num::ParseIntError;
+use std::path::PathBuf;
 
 use gratte::{EnumDiscriminants, EnumIs, IntoDiscriminant};
 use serde::{Deserialize, Serialize};
 
+use crate::leaderbot::diagnostics::RunDiagnostics;
+
 /// Custom [`Result`](std::result::Result) type that defaults to this crate's [`Error`] type.
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
@@ -35,6 +38,65 @@ pub enum Error {
         source: EnvVarError,
     },
 
+    /// Error while loading a [config file].
+    ///
+    /// [config file]: crate::leaderbot::config::file
+    #[error(transparent)]
+    ConfigFile(#[from] ConfigFileError),
+
+    /// Error while fetching leaderboard credentials from a [`CredentialsProvider`].
+    ///
+    /// [`CredentialsProvider`]: crate::leaderbot::CredentialsProvider
+    #[error("failed to fetch leaderboard credentials: {0}")]
+    Credentials(anyhow::Error),
+
+    /// No backend with the given name was registered in a [`BackendRegistry`].
+    ///
+    /// [`BackendRegistry`]: crate::leaderbot::dynamic::BackendRegistry
+    #[error("unknown {kind} backend: {name}")]
+    UnknownBackend {
+        /// Kind of backend that was requested.
+        kind: BackendKind,
+
+        /// Name of the requested backend.
+        name: String,
+    },
+
+    /// Error while building a backend via a [`BackendRegistry`].
+    ///
+    /// [`BackendRegistry`]: crate::leaderbot::dynamic::BackendRegistry
+    #[error("failed to build {kind} backend {name}: {source}")]
+    Backend {
+        /// Kind of backend that was built.
+        kind: BackendKind,
+
+        /// Name of the backend that was built.
+        name: String,
+
+        /// Error returned by the backend's factory.
+        source: anyhow::Error,
+    },
+
+    /// Error while exporting [metrics].
+    ///
+    /// [metrics]: crate::leaderbot::metrics
+    #[error("metrics error: {0}")]
+    Metrics(anyhow::Error),
+
+    /// Attempted to add a [`Target`] to a [`MultiTargetRunner`], but another target already
+    /// monitors the same leaderboard for the same year.
+    ///
+    /// [`Target`]: crate::leaderbot::multi_target::Target
+    /// [`MultiTargetRunner`]: crate::leaderbot::multi_target::MultiTargetRunner
+    #[error("leaderboard {leaderboard_id} is already monitored for year {year}")]
+    DuplicateTarget {
+        /// Year monitored by both targets.
+        year: i32,
+
+        /// ID of the leaderboard monitored by both targets.
+        leaderboard_id: u64,
+    },
+
     /// Error while fetching leaderboard data from the AoC website.
     #[error(transparent)]
     Leaderboard(#[from] aoc_leaderboard::Error),
@@ -113,6 +175,18 @@ impl Error {
         }
     }
 
+    /// Returns `true` if the enum is [`Error::ConfigFile`] and the internal
+    /// [`ConfigFileError`] matches the given predicate.
+    pub fn is_config_file_and<P>(&self, predicate: P) -> bool
+    where
+        P: FnOnce(&ConfigFileError) -> bool,
+    {
+        match self {
+            Self::ConfigFile(source) => predicate(source),
+            _ => false,
+        }
+    }
+
     /// Returns `true` if the enum is [`Error::Leaderboard`] and the internal
     /// [`aoc_leaderboard::Error`] matches the given predicate.
     pub fn is_leaderboard_and<P>(&self, predicate: P) -> bool
@@ -162,6 +236,38 @@ pub enum ErrorKind {
     /// Error while getting the value of an environment variable.
     Env(EnvVarErrorKind),
 
+    /// Error while loading a [config file].
+    ///
+    /// [config file]: crate::leaderbot::config::file
+    ConfigFile(ConfigFileErrorKind),
+
+    /// Error while fetching leaderboard credentials from a [`CredentialsProvider`].
+    ///
+    /// [`CredentialsProvider`]: crate::leaderbot::CredentialsProvider
+    Credentials,
+
+    /// No backend with the given name was registered in a [`BackendRegistry`].
+    ///
+    /// [`BackendRegistry`]: crate::leaderbot::dynamic::BackendRegistry
+    UnknownBackend,
+
+    /// Error while building a backend via a [`BackendRegistry`].
+    ///
+    /// [`BackendRegistry`]: crate::leaderbot::dynamic::BackendRegistry
+    Backend,
+
+    /// Error while exporting [metrics].
+    ///
+    /// [metrics]: crate::leaderbot::metrics
+    Metrics,
+
+    /// Attempted to add a [`Target`] to a [`MultiTargetRunner`], but another target already
+    /// monitors the same leaderboard for the same year.
+    ///
+    /// [`Target`]: crate::leaderbot::multi_target::Target
+    /// [`MultiTargetRunner`]: crate::leaderbot::multi_target::MultiTargetRunner
+    DuplicateTarget,
+
     /// Error while fetching leaderboard data from the AoC website.
     Leaderboard(aoc_leaderboard::ErrorKind),
 
@@ -211,6 +317,12 @@ impl ErrorKind {
         *self == ErrorKind::Env(env_var_error_kind)
     }
 
+    /// Returns `true` if the enum is [`ErrorKind::ConfigFile`] of the given
+    /// [`ConfigFileErrorKind`].
+    pub fn is_config_file_of_kind(&self, config_file_error_kind: ConfigFileErrorKind) -> bool {
+        *self == ErrorKind::ConfigFile(config_file_error_kind)
+    }
+
     /// Returns `true` if the enum is [`ErrorKind::Leaderboard`] of the given
     /// [`aoc_leaderboard::ErrorKind`].
     pub fn is_leaderboard_of_kind(
@@ -254,6 +366,12 @@ impl From<&Error> for ErrorKind {
         match value {
             Error::MissingField { .. } => ErrorKind::MissingField,
             Error::Env { source, .. } => ErrorKind::Env(source.into()),
+            Error::ConfigFile(source) => ErrorKind::ConfigFile(source.into()),
+            Error::Credentials(_) => ErrorKind::Credentials,
+            Error::UnknownBackend { .. } => ErrorKind::UnknownBackend,
+            Error::Backend { .. } => ErrorKind::Backend,
+            Error::Metrics(_) => ErrorKind::Metrics,
+            Error::DuplicateTarget { .. } => ErrorKind::DuplicateTarget,
             Error::Leaderboard(source) => ErrorKind::Leaderboard(source.into()),
             Error::Storage(source) => ErrorKind::Storage(source.into()),
             Error::Reporter(source) => ErrorKind::Reporter(source.into()),
@@ -308,6 +426,16 @@ pub enum EnvVarError {
         /// The error that occurred while parsing the environment variable's content.
         source: ParseIntError,
     },
+
+    /// Environment variable contains a value that does not have the expected format.
+    #[error("invalid value {actual}: expected {expected}")]
+    InvalidFormat {
+        /// The actual content of the environment variable.
+        actual: String,
+
+        /// Description of the expected format.
+        expected: &'static str,
+    },
 }
 
 impl EnvVarError {
@@ -484,6 +612,183 @@ impl From<&aoc_leaderboard::Error> for ErrorKind {
     }
 }
 
+/// Kind of backend that can be registered in a [`BackendRegistry`].
+///
+/// [`BackendRegistry`]: crate::leaderbot::dynamic::BackendRegistry
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, EnumIs, gratte::Display,
+)]
+#[strum(serialize_all = "lowercase")]
+#[non_exhaustive]
+pub enum BackendKind {
+    /// Backend implementing [`Storage`](crate::leaderbot::Storage).
+    Storage,
+
+    /// Backend implementing [`Reporter`](crate::leaderbot::Reporter).
+    Reporter,
+}
+
+/// Error returned by [`Storage`] and [`Reporter`] trait objects.
+///
+/// Wraps the error returned by the underlying implementation; both [`Display`] and
+/// [`source`] are forwarded to it.
+///
+/// [`Storage`]: crate::leaderbot::Storage
+/// [`Reporter`]: crate::leaderbot::Reporter
+/// [`Display`]: std::fmt::Display
+/// [`source`]: std::error::Error::source
+#[derive(Debug, thiserror::Error)]
+#[error(transparent)]
+pub struct DynError(#[from] pub anyhow::Error);
+
+/// Error returned by a failed bot run, along with the [diagnostics] collected before it failed.
+///
+/// Returned by the `*_diagnosed` variants of the bot's run functions (like
+/// [`run_bot_diagnosed_from`]); can be converted into the underlying [`Error`].
+///
+/// [diagnostics]: crate::leaderbot::diagnostics
+/// [`run_bot_diagnosed_from`]: crate::leaderbot::run_bot_diagnosed_from
+#[derive(Debug, thiserror::Error)]
+#[error("{error}")]
+pub struct RunError {
+    /// Error that caused the bot run to fail.
+    #[source]
+    pub error: Error,
+
+    /// Diagnostics about the bot run, up to the point where it failed.
+    ///
+    /// Includes the operations performed while handling the error (like
+    /// [reporting](crate::leaderbot::Reporter::report_error) it).
+    pub diagnostics: RunDiagnostics,
+}
+
+impl From<RunError> for Error {
+    fn from(value: RunError) -> Self {
+        value.error
+    }
+}
+
+/// Error type used for errors related to [config files].
+///
+/// [config files]: crate::leaderbot::config::file
+#[derive(Debug, thiserror::Error, EnumDiscriminants, EnumIs)]
+#[non_exhaustive]
+#[strum_discriminants(
+    name(ConfigFileErrorKind),
+    derive(Serialize, Deserialize, EnumIs),
+    non_exhaustive
+)]
+pub enum ConfigFileError {
+    /// Error while reading the config file.
+    #[error("failed to read config file {}: {source}", path.display())]
+    Io {
+        /// Path of the config file.
+        path: PathBuf,
+
+        /// I/O error that occurred.
+        source: std::io::Error,
+    },
+
+    /// Format of the config file could not be determined from its extension.
+    #[error("unsupported config file format for {}: expected .toml, .yaml or .yml", path.display())]
+    UnsupportedFormat {
+        /// Path of the config file.
+        path: PathBuf,
+    },
+
+    /// The content of the config file could not be parsed.
+    #[error("{}{message}", location_prefix(*line, *column))]
+    Syntax {
+        /// Line where the error occurred (1-based), if known.
+        line: Option<usize>,
+
+        /// Column where the error occurred (1-based), if known.
+        column: Option<usize>,
+
+        /// Description of the error.
+        message: String,
+    },
+
+    /// An environment variable referenced in the config file could not be interpolated.
+    #[error("failed to interpolate environment variable {var_name} in {field}: {source}")]
+    Interpolation {
+        /// Path of the field referencing the environment variable (e.g. `leaderboards[0].view_key`).
+        field: String,
+
+        /// Name of the environment variable.
+        var_name: String,
+
+        /// Error that occurred while trying to get environment variable's value.
+        source: EnvVarError,
+    },
+
+    /// A field of the config file contains an invalid value.
+    #[error("invalid value for {field}: {message}")]
+    Invalid {
+        /// Path of the invalid field (e.g. `leaderboards[0].view_key`).
+        field: String,
+
+        /// Description of the error.
+        message: String,
+    },
+}
+
+fn location_prefix(line: Option<usize>, column: Option<usize>) -> String {
+    match (line, column) {
+        (Some(line), Some(column)) => format!("line {line}, column {column}: "),
+        (Some(line), None) => format!("line {line}: "),
+        _ => String::new(),
+    }
+}
+
+impl PartialEq<ConfigFileErrorKind> for ConfigFileError {
+    fn eq(&self, other: &ConfigFileErrorKind) -> bool {
+        ConfigFileErrorKind::from(self) == *other
+    }
+}
+
+impl PartialEq<ConfigFileError> for ConfigFileErrorKind {
+    fn eq(&self, other: &ConfigFileError) -> bool {
+        *self == Self::from(other)
+    }
+}
+
+impl PartialEq<ConfigFileErrorKind> for ErrorKind {
+    fn eq(&self, other: &ConfigFileErrorKind) -> bool {
+        *self == ErrorKind::ConfigFile(*other)
+    }
+}
+
+impl PartialEq<ErrorKind> for ConfigFileErrorKind {
+    fn eq(&self, other: &ErrorKind) -> bool {
+        ErrorKind::ConfigFile(*self) == *other
+    }
+}
+
+impl From<ConfigFileErrorKind> for ErrorKind {
+    fn from(value: ConfigFileErrorKind) -> Self {
+        ErrorKind::ConfigFile(value)
+    }
+}
+
+impl From<&ConfigFileErrorKind> for ErrorKind {
+    fn from(value: &ConfigFileErrorKind) -> Self {
+        (*value).into()
+    }
+}
+
+impl From<ConfigFileError> for ErrorKind {
+    fn from(value: ConfigFileError) -> Self {
+        ConfigFileErrorKind::from(value).into()
+    }
+}
+
+impl From<&ConfigFileError> for ErrorKind {
+    fn from(value: &ConfigFileError) -> Self {
+        ConfigFileErrorKind::from(value).into()
+    }
+}
+
 /// Error type used for errors related to [`Storage`].
 ///
 /// [`Storage`]: crate::leaderbot::Storage
@@ -506,6 +811,34 @@ pub enum StorageError {
     /// Error while trying to save previous error.
     #[error("failed to save previous error: {0}")]
     SaveError(anyhow::Error),
+
+    /// Error while trying to load pending changes in digest mode.
+    #[error("failed to load pending changes: {0}")]
+    LoadPendingChanges(anyhow::Error),
+
+    /// Error while trying to save pending changes in digest mode.
+    #[error("failed to save pending changes: {0}")]
+    SavePendingChanges(anyhow::Error),
+
+    /// Error while trying to clear pending changes in digest mode, after they were reported.
+    #[error("failed to clear pending changes: {0}")]
+    ClearPendingChanges(anyhow::Error),
+
+    /// Error while trying to load the report in the outbox.
+    #[error("failed to load outbox: {0}")]
+    LoadOutbox(anyhow::Error),
+
+    /// Error while trying to mark the report in the outbox as delivered.
+    #[error("failed to mark outbox report as delivered: {0}")]
+    MarkDelivered(anyhow::Error),
+
+    /// Error while trying to load the bot's heartbeat state.
+    #[error("failed to load heartbeat state: {0}")]
+    LoadHeartbeat(anyhow::Error),
+
+    /// Error while trying to save the bot's heartbeat state.
+    #[error("failed to save heartbeat state: {0}")]
+    SaveHeartbeat(anyhow::Error),
 }
 
 impl StorageError {
@@ -544,6 +877,90 @@ impl StorageError {
             _ => false,
         }
     }
+
+    /// Returns `true` if the enum is [`StorageError::LoadPendingChanges`] and the internal
+    /// [`anyhow::Error`] matches the given predicate.
+    pub fn is_load_pending_changes_and<P>(&self, predicate: P) -> bool
+    where
+        P: FnOnce(&anyhow::Error) -> bool,
+    {
+        match self {
+            Self::LoadPendingChanges(source) => predicate(source),
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the enum is [`StorageError::SavePendingChanges`] and the internal
+    /// [`anyhow::Error`] matches the given predicate.
+    pub fn is_save_pending_changes_and<P>(&self, predicate: P) -> bool
+    where
+        P: FnOnce(&anyhow::Error) -> bool,
+    {
+        match self {
+            Self::SavePendingChanges(source) => predicate(source),
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the enum is [`StorageError::ClearPendingChanges`] and the internal
+    /// [`anyhow::Error`] matches the given predicate.
+    pub fn is_clear_pending_changes_and<P>(&self, predicate: P) -> bool
+    where
+        P: FnOnce(&anyhow::Error) -> bool,
+    {
+        match self {
+            Self::ClearPendingChanges(source) => predicate(source),
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the enum is [`StorageError::LoadOutbox`] and the internal
+    /// [`anyhow::Error`] matches the given predicate.
+    pub fn is_load_outbox_and<P>(&self, predicate: P) -> bool
+    where
+        P: FnOnce(&anyhow::Error) -> bool,
+    {
+        match self {
+            Self::LoadOutbox(source) => predicate(source),
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the enum is [`StorageError::MarkDelivered`] and the internal
+    /// [`anyhow::Error`] matches the given predicate.
+    pub fn is_mark_delivered_and<P>(&self, predicate: P) -> bool
+    where
+        P: FnOnce(&anyhow::Error) -> bool,
+    {
+        match self {
+            Self::MarkDelivered(source) => predicate(source),
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the enum is [`StorageError::LoadHeartbeat`] and the internal
+    /// [`anyhow::Error`] matches the given predicate.
+    pub fn is_load_heartbeat_and<P>(&self, predicate: P) -> bool
+    where
+        P: FnOnce(&anyhow::Error) -> bool,
+    {
+        match self {
+            Self::LoadHeartbeat(source) => predicate(source),
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the enum is [`StorageError::SaveHeartbeat`] and the internal
+    /// [`anyhow::Error`] matches the given predicate.
+    pub fn is_save_heartbeat_and<P>(&self, predicate: P) -> bool
+    where
+        P: FnOnce(&anyhow::Error) -> bool,
+    {
+        match self {
+            Self::SaveHeartbeat(source) => predicate(source),
+            _ => false,
+        }
+    }
 }
 
 impl PartialEq<StorageErrorKind> for StorageError {
@@ -624,6 +1041,11 @@ pub enum ReporterError {
     /// Error while trying to report the first bot run.
     #[error("failed to report first run: {0}")]
     ReportFirstRun(anyhow::Error),
+
+    /// One or more child reporters failed while a composite reporter
+    /// was forwarding a call to them.
+    #[error("{}", children_error_message(.0))]
+    Children(Vec<ChildReporterError>),
 }
 
 impl ReporterError {
@@ -652,6 +1074,24 @@ impl ReporterError {
     }
 }
 
+fn children_error_message(errors: &[ChildReporterError]) -> String {
+    let errors: Vec<_> = errors.iter().map(ToString::to_string).collect();
+    format!("{} child reporter(s) failed: {}", errors.len(), errors.join("; "))
+}
+
+/// Error returned by a child reporter of a composite reporter.
+///
+/// Stored in [`ReporterError::Children`].
+#[derive(Debug, thiserror::Error)]
+#[error("reporter {name} failed: {source}")]
+pub struct ChildReporterError {
+    /// Name of the child reporter that failed.
+    pub name: String,
+
+    /// Error returned by the child reporter.
+    pub source: anyhow::Error,
+}
+
 impl PartialEq<ReporterErrorKind> for ReporterError {
     fn eq(&self, other: &ReporterErrorKind) -> bool {
         ReporterErrorKind::from(self) == *other
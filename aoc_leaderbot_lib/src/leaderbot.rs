@@ -2,21 +2,49 @@
 //!
 //! [`aoc_leaderbot`]: https://github.com/clechasseur/aoc_leaderbot
 
+pub mod clock;
 pub mod config;
+pub mod credentials;
+#[cfg(feature = "daemon")]
+pub mod daemon;
+pub mod diagnostics;
+pub mod digest;
+#[cfg(feature = "dynamic")]
+pub mod dynamic;
+pub mod event;
+pub mod heartbeat;
+pub mod members;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+#[cfg(feature = "multi-target")]
+pub mod multi_target;
+pub mod outbox;
+pub mod reporter;
 pub mod storage;
+pub mod team;
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::convert::Infallible;
 use std::error::Error;
 use std::fmt::Debug;
 use std::future::{Future, ready};
+use std::time::Instant;
 
 use anyhow::anyhow;
 use aoc_leaderboard::aoc::{Leaderboard, LeaderboardCredentials};
-use chrono::{Datelike, Local};
+use chrono::{DateTime, TimeDelta, Utc};
 use gratte::IntoDiscriminant;
 use serde::{Deserialize, Serialize};
 
-use crate::error::{ReporterError, StorageError};
+use crate::error::{ReporterError, RunError, StorageError};
+use crate::leaderbot::clock::{Clock, SystemClock};
+use crate::leaderbot::diagnostics::RunDiagnostics;
+use crate::leaderbot::event::{EventWindow, current_event_year};
+use crate::leaderbot::heartbeat::{HeartbeatPolicy, HeartbeatState};
+use crate::leaderbot::members::MemberSettings;
+use crate::leaderbot::team::Team;
+
+const DEFAULT_ADVENT_OF_CODE_BASE: &str = "https://adventofcode.com";
 
 /// Trait that must be implemented to provide the parameters required by the
 /// bot to monitor an [Advent of Code] leaderboard.
@@ -25,10 +53,13 @@ use crate::error::{ReporterError, StorageError};
 pub trait Config {
     /// Year for which we want to monitor the leaderboard.
     ///
-    /// Defaults to the current year.
+    /// Defaults to the [year of the current Advent of Code event], which switches to the
+    /// new year on December 1st, according to the config's [`clock`](Self::clock).
+    ///
+    /// [year of the current Advent of Code event]: current_event_year
     #[cfg_attr(not(coverage), tracing::instrument(skip(self), level = "trace", ret))]
     fn year(&self) -> i32 {
-        Local::now().year()
+        current_event_year(self.clock().now())
     }
 
     /// ID of the leaderboard to monitor.
@@ -48,6 +79,121 @@ pub trait Config {
     /// [view key]: LeaderboardCredentials::ViewKey
     /// [session cookie]: LeaderboardCredentials::SessionCookie
     fn credentials(&self) -> LeaderboardCredentials;
+
+    /// Policy determining when an error that keeps occurring should be reported again.
+    ///
+    /// Defaults to [`RenotifyPolicy::never`], meaning that an error is reported only once,
+    /// no matter how long it persists.
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self), level = "trace", ret))]
+    fn renotify_policy(&self) -> RenotifyPolicy {
+        RenotifyPolicy::never()
+    }
+
+    /// Window during which the bot fetches leaderboard data normally.
+    ///
+    /// Defaults to `None`, meaning that the bot fetches leaderboard data every time it runs.
+    /// See the [`event`] module for details.
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self), level = "trace", ret))]
+    fn event_window(&self) -> Option<EventWindow> {
+        None
+    }
+
+    /// Policy determining how the bot's heartbeat is monitored.
+    ///
+    /// Defaults to a policy that does not monitor the heartbeat. See the [`heartbeat`] module
+    /// for details.
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self), level = "trace", ret))]
+    fn heartbeat_policy(&self) -> HeartbeatPolicy {
+        HeartbeatPolicy::default()
+    }
+
+    /// Other leaderboards to merge with the monitored leaderboard.
+    ///
+    /// If not empty, the bot fetches the data of every leaderboard and [merges] them into a
+    /// single leaderboard, which is then stored and reported on as if it was the leaderboard
+    /// with the ID returned by [`leaderboard_id`](Self::leaderboard_id).
+    ///
+    /// Defaults to an empty list, meaning that only the monitored leaderboard is fetched.
+    ///
+    /// [merges]: Leaderboard::merge
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self), level = "trace", ret))]
+    fn merged_leaderboards(&self) -> Vec<MergedLeaderboard> {
+        Vec::new()
+    }
+
+    /// Teams of leaderboard members, used to compute [team standings].
+    ///
+    /// Defaults to an empty list.
+    ///
+    /// [team standings]: team::team_standings
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self), level = "trace", ret))]
+    fn teams(&self) -> Vec<Team> {
+        Vec::new()
+    }
+
+    /// Aliases, ignored members and opted-out members of the monitored leaderboard.
+    ///
+    /// The [settings] are applied to leaderboards before detecting changes, so they affect
+    /// storage and reporting.
+    ///
+    /// Defaults to empty settings, meaning that leaderboards are left untouched.
+    ///
+    /// [settings]: MemberSettings
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self), level = "trace", ret))]
+    fn member_settings(&self) -> MemberSettings {
+        MemberSettings::default()
+    }
+
+    /// [`Clock`] used to determine the current time.
+    ///
+    /// Used for the default [`year`](Self::year), to check the [`event_window`](Self::event_window)
+    /// and to timestamp error states. Defaults to the [`SystemClock`]; see the [`clock`] module
+    /// for details.
+    fn clock(&self) -> &dyn Clock {
+        &SystemClock
+    }
+}
+
+/// Trait that can be implemented to fetch [`LeaderboardCredentials`] from an external source,
+/// so that secrets do not need to be stored in the bot's [`Config`].
+///
+/// Use [`provide_credentials`] to fetch credentials from a provider.
+pub trait CredentialsProvider {
+    /// Type of error used by this provider.
+    type Err: Error + Send + Sync + 'static;
+
+    /// Fetches the leaderboard credentials.
+    fn get_credentials(
+        &self,
+    ) -> impl Future<Output = Result<LeaderboardCredentials, Self::Err>> + Send;
+}
+
+impl CredentialsProvider for LeaderboardCredentials {
+    type Err = Infallible;
+
+    fn get_credentials(
+        &self,
+    ) -> impl Future<Output = Result<LeaderboardCredentials, Self::Err>> + Send {
+        ready(Ok(self.clone()))
+    }
+}
+
+/// Fetches leaderboard credentials from the given [`CredentialsProvider`].
+///
+/// # Errors
+///
+/// - [`Error::Credentials`]: if the provider fails to fetch the credentials
+///
+/// [`Error::Credentials`]: crate::Error::Credentials
+#[cfg_attr(not(coverage), tracing::instrument(skip(provider), level = "debug", err))]
+pub async fn provide_credentials<P>(provider: &P) -> crate::Result<LeaderboardCredentials>
+where
+    P: CredentialsProvider + ?Sized,
+{
+    provider
+        .get_credentials()
+        .await
+        .map_err(|err| crate::Error::Credentials(anyhow!(err)))
 }
 
 /// Trait that must be implemented to persist the data required by the bot
@@ -101,173 +247,934 @@ pub trait Storage {
         leaderboard_id: u64,
         error_kind: crate::ErrorKind,
     ) -> impl Future<Output = Result<(), Self::Err>> + Send;
-}
-
-/// Changes to a leaderboard detected by the bot.
-#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct Changes {
-    /// IDs of new members added to the leaderboard since last run.
-    pub new_members: HashSet<u64>,
-
-    /// IDs of members who got new stars since last run.
-    pub members_with_new_stars: HashSet<u64>,
-}
 
-impl Changes {
-    /// Returns a [`Changes`] with the given new/updated members.
-    #[cfg_attr(not(coverage), tracing::instrument(level = "trace"))]
-    pub fn new(new_members: HashSet<u64>, members_with_new_stars: HashSet<u64>) -> Self {
-        Self { new_members, members_with_new_stars }
+    /// Saves the result of a failed bot run, along with detailed information about the error.
+    ///
+    /// The given [`ErrorState`] should be returned by the next call to [`load_error_state`].
+    /// The same rules apply as for [`save_error`]; storages that do not keep track of this
+    /// information can rely on the default implementation, which calls [`save_error`].
+    ///
+    /// This is the method called by the bot when a run fails. The times stored in the
+    /// [`ErrorState`] come from the [`Config`]'s [clock](Config::clock), so storages that
+    /// timestamp errors should override this method and use them instead of the system time.
+    ///
+    /// [`load_error_state`]: Self::load_error_state
+    /// [`save_error`]: Self::save_error
+    fn save_error_state(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        error_state: &ErrorState,
+    ) -> impl Future<Output = Result<(), Self::Err>> + Send {
+        self.save_error(year, leaderboard_id, error_state.error_kind)
     }
 
-    /// Returns a [`Changes`] if there are new members and/or members
-    /// with new stars, otherwise returns `None`.
-    #[cfg_attr(not(coverage), tracing::instrument(level = "trace", ret))]
-    pub fn if_needed(
-        new_members: HashSet<u64>,
-        members_with_new_stars: HashSet<u64>,
-    ) -> Option<Self> {
-        if !new_members.is_empty() || !members_with_new_stars.is_empty() {
-            Some(Self::new(new_members, members_with_new_stars))
-        } else {
-            None
-        }
-    }
-}
+    /// Loads information about the error that occurred during the last bot run, if any.
+    ///
+    /// The returned [`ErrorState`] should reflect the error kind returned by [`load_previous`],
+    /// along with information about the errors saved since the first one of the current outage.
+    /// Errors saved via [`save_error`] should be counted as occurrences, while errors saved via
+    /// [`save_error_state`] should be returned as is. Storages that do not keep track of this
+    /// information can rely on the default implementation, which returns `None`.
+    ///
+    /// [`load_previous`]: Self::load_previous
+    /// [`save_error`]: Self::save_error
+    /// [`save_error_state`]: Self::save_error_state
+    fn load_error_state(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> impl Future<Output = Result<Option<ErrorState>, Self::Err>> + Send {
+        let (_, _) = (year, leaderboard_id);
 
-/// Trait that must be implemented to report changes to the leaderboard.
-pub trait Reporter {
-    /// Type of error used by this reporter.
-    type Err: Error + Send;
+        ready(Ok(None))
+    }
 
-    /// Report changes to the leaderboard.
-    ///
-    /// The method receives references to both the previous version of the leaderboard,
-    /// the current version of the leaderboard, and the lists of changes detected.
+    /// Loads the [`HeartbeatState`] saved by the last call to [`save_heartbeat`], if any.
     ///
-    /// IDs stored in the [`Changes`] point to [leaderboard members] found
-    /// in the current version of the leaderboard.
+    /// Storages that do not keep track of this information can rely on the default
+    /// implementation, which returns `None`; [`check_heartbeat`] will then never report
+    /// the bot as stale.
     ///
-    /// [leaderboard members]: Leaderboard::members
-    fn report_changes(
-        &mut self,
+    /// [`save_heartbeat`]: Self::save_heartbeat
+    /// [`check_heartbeat`]: heartbeat::check_heartbeat
+    fn load_heartbeat(
+        &self,
         year: i32,
         leaderboard_id: u64,
-        view_key: Option<&str>,
-        previous_leaderboard: &Leaderboard,
-        leaderboard: &Leaderboard,
-        changes: &Changes,
-    ) -> impl Future<Output = Result<(), Self::Err>> + Send;
+    ) -> impl Future<Output = Result<Option<HeartbeatState>, Self::Err>> + Send {
+        let (_, _) = (year, leaderboard_id);
 
-    /// Report the first run of the bot for this leaderboard.
-    /// No changes are included, simply the initial leaderboard state.
+        ready(Ok(None))
+    }
+
+    /// Saves information about the bot's heartbeat.
     ///
-    /// This function does nothing by default so that implementing it
-    /// is optional for reporters.
-    #[cfg_attr(not(coverage), tracing::instrument(skip(self, view_key)))]
-    fn report_first_run(
+    /// The given [`HeartbeatState`] should be returned by the next call to [`load_heartbeat`].
+    /// It must be kept independently of the other data persisted for the leaderboard (e.g. it
+    /// must not be cleared by [`save_success`] or [`save_error`]). The default implementation
+    /// does nothing.
+    ///
+    /// [`load_heartbeat`]: Self::load_heartbeat
+    /// [`save_success`]: Self::save_success
+    /// [`save_error`]: Self::save_error
+    fn save_heartbeat(
         &mut self,
         year: i32,
         leaderboard_id: u64,
-        view_key: Option<&str>,
-        leaderboard: &Leaderboard,
+        heartbeat: &HeartbeatState,
     ) -> impl Future<Output = Result<(), Self::Err>> + Send {
-        let (_, _, _, _) = (year, leaderboard_id, view_key, leaderboard);
+        let (_, _, _) = (year, leaderboard_id, heartbeat);
 
         ready(Ok(()))
     }
+}
 
-    /// Report an error that occurred while the bot was running.
+/// Information about the error that occurred during the last bot run.
+///
+/// Returned by [`Storage::load_error_state`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorState {
+    /// Kind of the last error that occurred, as passed to [`Storage::save_error`].
+    pub error_kind: crate::ErrorKind,
+
+    /// Time at which the first error of the current outage was saved.
     ///
-    /// This can be useful to report things to the same channel as
-    /// the one where we send the leaderboard changes, so that the
-    /// bot owner can fix the issue.
+    /// When errors occur in several consecutive bot runs, this is the time at which
+    /// the first one was saved, even if the kind of error has changed since.
+    pub first_seen: DateTime<Utc>,
+
+    /// Time at which the last error of the current outage was saved.
+    pub last_seen: DateTime<Utc>,
+
+    /// Number of consecutive bot runs that resulted in an error during the current outage.
+    pub occurrences: u64,
+
+    /// Message of the last error that occurred, if known.
+    pub last_message: Option<String>,
+
+    /// Time at which an error of the current outage was last reported, if ever.
+    pub last_notified: Option<DateTime<Utc>>,
+}
+
+impl ErrorState {
+    /// Creates a new error state for an error first seen at the given time.
+    pub fn new(error_kind: crate::ErrorKind, first_seen: DateTime<Utc>) -> Self {
+        Self {
+            error_kind,
+            first_seen,
+            last_seen: first_seen,
+            occurrences: 1,
+            last_message: None,
+            last_notified: None,
+        }
+    }
+
+    /// Returns this error state updated for another error seen at the given time.
     ///
-    /// The default implementation prints the error to `stderr`.
+    /// The last message is cleared, since it belonged to the previous error.
+    pub fn recur(self, error_kind: crate::ErrorKind, last_seen: DateTime<Utc>) -> Self {
+        Self {
+            error_kind,
+            last_seen,
+            occurrences: self.occurrences + 1,
+            last_message: None,
+            ..self
+        }
+    }
+}
+
+/// Policy determining when an error that keeps occurring should be reported again.
+///
+/// By default, when the bot encounters the same kind of error during consecutive bot runs,
+/// it is only reported once, to avoid spamming the [`Reporter`]. A policy can instead remind
+/// about an ongoing error after some time has elapsed and/or every few failed bot runs.
+///
+/// Re-notification requires a [`Storage`] that keeps track of [`ErrorState`]s.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RenotifyPolicy {
+    /// Report an ongoing error again once this much time has elapsed since it was last reported.
+    pub interval: Option<TimeDelta>,
+
+    /// Report an ongoing error again every time this many more consecutive bot runs have failed.
+    pub failures: Option<u64>,
+}
+
+impl RenotifyPolicy {
+    /// Returns a policy that never reports an ongoing error again.
+    pub fn never() -> Self {
+        Self::default()
+    }
+
+    /// Returns a policy that reports an ongoing error again after the given interval.
+    pub fn every(interval: TimeDelta) -> Self {
+        Self { interval: Some(interval), failures: None }
+    }
+
+    /// Returns a policy that reports an ongoing error again every `failures` failed bot runs.
+    pub fn after_failures(failures: u64) -> Self {
+        Self { interval: None, failures: Some(failures) }
+    }
+
+    /// Checks if an ongoing error should be reported again, given its updated state.
+    pub fn is_due(&self, error_state: &ErrorState, now: DateTime<Utc>) -> bool {
+        let last_notified = error_state.last_notified.unwrap_or(error_state.first_seen);
+        let interval_elapsed = self
+            .interval
+            .is_some_and(|interval| now - last_notified >= interval);
+        let failures_reached = self.failures.is_some_and(|failures| {
+            failures > 0
+                && error_state.occurrences > 1
+                && (error_state.occurrences - 1).is_multiple_of(failures)
+        });
+
+        interval_elapsed || failures_reached
+    }
+}
+
+/// Leaderboard merged with the one monitored by the bot.
+///
+/// See [`Config::merged_leaderboards`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MergedLeaderboard {
+    /// ID of the leaderboard.
+    pub leaderboard_id: u64,
+
+    /// Credentials to use to fetch the leaderboard's data.
     ///
-    /// # Notes
+    /// If `None`, the [credentials] of the monitored leaderboard are used. This only works if
+    /// they are a [session cookie] giving access to both leaderboards.
     ///
-    /// This method doesn't allow returning an error, because it
-    /// will only be called while processing another error.
-    /// If an error occurs while sending the error report,
-    /// it should simply be ignored internally.
-    #[cfg_attr(not(coverage), tracing::instrument(skip(self, view_key)))]
-    fn report_error(
-        &mut self,
-        year: i32,
-        leaderboard_id: u64,
-        view_key: Option<&str>,
-        error: &crate::Error,
-    ) -> impl Future<Output = ()> + Send {
-        let _ = view_key;
+    /// [credentials]: Config::credentials
+    /// [session cookie]: LeaderboardCredentials::SessionCookie
+    #[serde(default)]
+    pub credentials: Option<LeaderboardCredentials>,
+}
 
-        eprintln!(
-            "Error while looking for changes to leaderboard {leaderboard_id} for year {year}: {error}"
-        );
-        ready(())
+impl MergedLeaderboard {
+    /// Creates a new merged leaderboard fetched using the monitored leaderboard's credentials.
+    pub fn new(leaderboard_id: u64) -> Self {
+        Self { leaderboard_id, credentials: None }
+    }
+
+    /// Sets the credentials to use to fetch the leaderboard's data.
+    pub fn with_credentials(mut self, credentials: LeaderboardCredentials) -> Self {
+        self.credentials = Some(credentials);
+        self
     }
 }
 
-/// Output returned by the [`run_bot`] function.
+/// Information about a leaderboard persisted in a [`Storage`].
+///
+/// Returned by [`StorageAdmin::list`].
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct BotOutput {
-    /// Year for which the bot was run.
+pub struct StorageEntry {
+    /// Year of the leaderboard.
     pub year: i32,
 
-    /// ID of leaderboard checked by the bot.
+    /// ID of the leaderboard.
     pub leaderboard_id: u64,
 
-    /// Leaderboard data from previous run, if any.
+    /// The last leaderboard saved via a call to [`Storage::save_success`], if any.
+    pub leaderboard: Option<Leaderboard>,
+
+    /// The kind of error that occurred during the last bot run, if any,
+    /// as passed to [`Storage::save_error`].
+    pub last_error: Option<crate::ErrorKind>,
+}
+
+/// Trait that can be implemented by a [`Storage`] to allow bot owners
+/// to inspect and administer the data it persists.
+///
+/// None of these methods are used by [`run_bot`]; they are meant to be used by
+/// administrative tools.
+pub trait StorageAdmin: Storage {
+    /// Lists all leaderboards currently persisted in storage.
     ///
-    /// If this was the first bot run, will be set to `None`.
-    pub previous_leaderboard: Option<Leaderboard>,
+    /// The order of the returned entries is unspecified.
+    fn list(&self) -> impl Future<Output = Result<Vec<StorageEntry>, Self::Err>> + Send;
 
-    /// Current leaderboard data.
-    pub leaderboard: Leaderboard,
+    /// Deletes all data persisted for the given leaderboard, including any last error.
+    ///
+    /// The next bot run for this leaderboard will thus be considered a first run.
+    /// Deleting a leaderboard that is not in storage is not an error.
+    fn delete(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> impl Future<Output = Result<(), Self::Err>> + Send;
 
-    /// Changes detected, if any.
-    pub changes: Option<Changes>,
+    /// Clears the last error persisted for the given leaderboard via [`Storage::save_error`].
+    ///
+    /// Any leaderboard data persisted via [`Storage::save_success`] must **not** be cleared.
+    /// Clearing the error of a leaderboard that is not in storage is not an error.
+    fn clear_error(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> impl Future<Output = Result<(), Self::Err>> + Send;
 }
 
-/// Runs the bot's core functionality.
-///
-/// Reads the [`config`], fetches the previous leaderboard data from [`storage`], then
-/// fetches current leaderboard data via [`Leaderboard::get`]. If there was no previous
-/// leaderboard (e.g. this is the first run), saves the current leaderboard to storage
-/// and exits; otherwise, computes if the leaderboard has new members and/or members who
-/// got new stars and calls the [`reporter`] if some diff is found.
+/// Changes detected by the bot that have not been reported yet.
 ///
-/// If the `dry_run` parameter is set to `true`, then the bot will fetch data and compute
-/// changes but will not persist or report them.
+/// Used by [digest mode] to accumulate changes across bot runs.
 ///
-/// [`config`]: Config
-/// [`storage`]: Storage
-/// [`reporter`]: Reporter
-#[cfg_attr(coverage_nightly, coverage(off))]
-#[tracing::instrument(skip(config, storage, reporter), ret, err)]
-pub async fn run_bot<C, S, R>(
-    config: &C,
-    storage: &mut S,
-    reporter: &mut R,
-    dry_run: bool,
-) -> crate::Result<BotOutput>
-where
-    C: Config,
-    S: Storage,
-    <S as Storage>::Err: Sync + 'static,
-    R: Reporter,
-    <R as Reporter>::Err: Sync + 'static,
-{
-    run_bot_from(None::<String>, config, storage, reporter, dry_run).await
+/// [digest mode]: digest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingChanges {
+    /// Version of the leaderboard before the first pending changes were detected.
+    ///
+    /// When pending changes are reported, this is passed to the [`Reporter`] as
+    /// the previous version of the leaderboard.
+    pub previous_leaderboard: Leaderboard,
+
+    /// Changes accumulated since the last report.
+    pub changes: Changes,
+
+    /// Time at which changes started to accumulate.
+    pub since: DateTime<Utc>,
 }
 
-/// Runs the bot's core functionality, using the given base Advent of Code URL
-/// (or the default, `https://adventofcode.com`, if not provided).
+impl PendingChanges {
+    /// Returns new [`PendingChanges`] starting to accumulate at the given time.
+    #[cfg_attr(not(coverage), tracing::instrument(skip(previous_leaderboard), level = "trace"))]
+    pub fn new(previous_leaderboard: Leaderboard, changes: Changes, since: DateTime<Utc>) -> Self {
+        Self { previous_leaderboard, changes, since }
+    }
+}
+
+/// Trait that can be implemented by a [`Storage`] to persist changes that have
+/// not been reported yet, as required by [digest mode].
 ///
-/// This function is mostly exposed for testing; you should use [`run_bot`] instead.
-#[cfg_attr(
-    not(coverage),
-    tracing::instrument(skip(config, storage, reporter), level = "debug", ret, err)
+/// Pending changes must be kept independently of the data saved via [`Storage::save_success`]
+/// and [`Storage::save_error`]; calling those methods must **not** clear pending changes.
+///
+/// [digest mode]: digest
+pub trait DigestStorage: Storage {
+    /// Loads the changes accumulated for the given leaderboard, if any.
+    fn load_pending_changes(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> impl Future<Output = Result<Option<PendingChanges>, Self::Err>> + Send;
+
+    /// Saves the changes accumulated for the given leaderboard, replacing any
+    /// pending changes previously saved.
+    fn save_pending_changes(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        pending_changes: &PendingChanges,
+    ) -> impl Future<Output = Result<(), Self::Err>> + Send;
+
+    /// Clears the changes accumulated for the given leaderboard, usually after they
+    /// have been reported.
+    ///
+    /// Clearing pending changes for a leaderboard that has none is not an error.
+    fn clear_pending_changes(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> impl Future<Output = Result<(), Self::Err>> + Send;
+}
+
+/// Report persisted in an [outbox] until it is delivered to the [`Reporter`].
+///
+/// [outbox]: OutboxStorage
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OutboxReport {
+    /// Changes to report via [`Reporter::report_changes`].
+    ///
+    /// The current version of the leaderboard is the one saved alongside the report.
+    Changes {
+        /// Previous version of the leaderboard.
+        previous_leaderboard: Leaderboard,
+
+        /// Changes detected by the bot.
+        changes: Changes,
+    },
+
+    /// First bot run to report via [`Reporter::report_first_run`].
+    FirstRun,
+}
+
+/// Trait that can be implemented by a [`Storage`] to persist reports in an outbox,
+/// allowing the bot to report changes exactly once (see [`outbox`]).
+///
+/// The outbox must be kept independently of the data saved via [`Storage::save_success`]
+/// and [`Storage::save_error`]; calling those methods must **not** clear the outbox.
+pub trait OutboxStorage: Storage {
+    /// Loads the report in the outbox for the given leaderboard, if any.
+    ///
+    /// A report is only returned if it has not been [marked as delivered] yet.
+    ///
+    /// [marked as delivered]: Self::mark_delivered
+    fn load_outbox(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> impl Future<Output = Result<Option<OutboxReport>, Self::Err>> + Send;
+
+    /// Saves the result of a successful bot run like [`Storage::save_success`], and puts the
+    /// given report in the outbox, replacing any report already there.
+    ///
+    /// Both must be saved in a **single** write: either both are persisted, or neither is.
+    fn save_success_with_outbox(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        leaderboard: &Leaderboard,
+        report: &OutboxReport,
+    ) -> impl Future<Output = Result<(), Self::Err>> + Send;
+
+    /// Marks the report in the outbox for the given leaderboard as delivered, removing it.
+    ///
+    /// Marking a report as delivered when the outbox is empty is not an error.
+    fn mark_delivered(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> impl Future<Output = Result<(), Self::Err>> + Send;
+}
+
+/// Changes to a leaderboard detected by the bot.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Changes {
+    /// IDs of new members added to the leaderboard since last run.
+    pub new_members: HashSet<u64>,
+
+    /// IDs of members who got new stars since last run.
+    pub members_with_new_stars: HashSet<u64>,
+}
+
+impl Changes {
+    /// Returns a [`Changes`] with the given new/updated members.
+    #[cfg_attr(not(coverage), tracing::instrument(level = "trace"))]
+    pub fn new(new_members: HashSet<u64>, members_with_new_stars: HashSet<u64>) -> Self {
+        Self { new_members, members_with_new_stars }
+    }
+
+    /// Returns a [`Changes`] if there are new members and/or members
+    /// with new stars, otherwise returns `None`.
+    #[cfg_attr(not(coverage), tracing::instrument(level = "trace", ret))]
+    pub fn if_needed(
+        new_members: HashSet<u64>,
+        members_with_new_stars: HashSet<u64>,
+    ) -> Option<Self> {
+        if !new_members.is_empty() || !members_with_new_stars.is_empty() {
+            Some(Self::new(new_members, members_with_new_stars))
+        } else {
+            None
+        }
+    }
+
+    /// Merges the given [`Changes`] into this one.
+    ///
+    /// Members who are new in either set of changes are considered new; they are
+    /// never included in [`members_with_new_stars`](Self::members_with_new_stars).
+    #[cfg_attr(not(coverage), tracing::instrument(level = "trace"))]
+    pub fn merge(&mut self, other: &Changes) {
+        self.new_members.extend(&other.new_members);
+        self.members_with_new_stars
+            .extend(&other.members_with_new_stars);
+        self.members_with_new_stars
+            .retain(|id| !self.new_members.contains(id));
+    }
+}
+
+/// Changes to a single leaderboard, as passed to [`Reporter::report_changes`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LeaderboardChanges {
+    /// Year of the leaderboard.
+    pub year: i32,
+
+    /// ID of the leaderboard.
+    pub leaderboard_id: u64,
+
+    /// View key of the leaderboard, if any.
+    pub view_key: Option<String>,
+
+    /// Previous version of the leaderboard.
+    pub previous_leaderboard: Leaderboard,
+
+    /// Current version of the leaderboard.
+    pub leaderboard: Leaderboard,
+
+    /// Changes detected.
+    pub changes: Changes,
+}
+
+/// Changes to several leaderboards of the same year, merged so that each member is reported once.
+///
+/// When the same member appears in several leaderboards, the changes to that member are only
+/// kept for the first leaderboard in which they were detected. The IDs of all leaderboards in
+/// which the member appears can be fetched via [`member_leaderboards`](Self::member_leaderboards).
+///
+/// Passed to [`Reporter::report_merged_changes`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MergedChanges {
+    /// Year of the leaderboards.
+    pub year: i32,
+
+    /// Changes to each leaderboard, without duplicate members.
+    ///
+    /// Leaderboards without changes left after removing duplicates are omitted.
+    pub leaderboards: Vec<LeaderboardChanges>,
+
+    /// IDs of the leaderboards in which each member with changes appears, in the order in which
+    /// they were passed to [`merge`](Self::merge).
+    pub member_leaderboards: HashMap<u64, Vec<u64>>,
+}
+
+impl MergedChanges {
+    /// Merges changes to several leaderboards, deduplicating members by [`id`].
+    ///
+    /// Returns one [`MergedChanges`] per year, in chronological order.
+    ///
+    /// [`id`]: aoc_leaderboard::aoc::LeaderboardMember::id
+    #[cfg_attr(not(coverage), tracing::instrument(skip_all, level = "trace"))]
+    pub fn merge<I>(leaderboard_changes: I) -> Vec<Self>
+    where
+        I: IntoIterator<Item = LeaderboardChanges>,
+    {
+        let mut by_year: BTreeMap<i32, Vec<LeaderboardChanges>> = BTreeMap::new();
+        for changes in leaderboard_changes {
+            by_year.entry(changes.year).or_default().push(changes);
+        }
+
+        by_year
+            .into_iter()
+            .map(|(year, leaderboards)| Self::merge_year(year, leaderboards))
+            .collect()
+    }
+
+    /// Returns the IDs of the leaderboards in which the given member appears.
+    ///
+    /// Returns an empty slice for members without changes.
+    pub fn leaderboards_of(&self, member_id: u64) -> &[u64] {
+        self.member_leaderboards
+            .get(&member_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    fn merge_year(year: i32, mut leaderboards: Vec<LeaderboardChanges>) -> Self {
+        let mut member_leaderboards: HashMap<u64, Vec<u64>> = HashMap::new();
+        for member_id in leaderboards.iter().flat_map(|lc| {
+            lc.changes
+                .new_members
+                .iter()
+                .chain(&lc.changes.members_with_new_stars)
+        }) {
+            member_leaderboards.entry(*member_id).or_insert_with(|| {
+                leaderboards
+                    .iter()
+                    .filter(|lc| lc.leaderboard.members.contains_key(member_id))
+                    .map(|lc| lc.leaderboard_id)
+                    .collect()
+            });
+        }
+
+        let mut seen: HashSet<u64> = HashSet::new();
+        for lc in &mut leaderboards {
+            lc.changes.new_members.retain(|id| !seen.contains(id));
+            lc.changes
+                .members_with_new_stars
+                .retain(|id| !seen.contains(id));
+            seen.extend(&lc.changes.new_members);
+            seen.extend(&lc.changes.members_with_new_stars);
+        }
+        leaderboards.retain(|lc| {
+            !lc.changes.new_members.is_empty() || !lc.changes.members_with_new_stars.is_empty()
+        });
+
+        Self { year, leaderboards, member_leaderboards }
+    }
+}
+
+/// Trait that must be implemented to report changes to the leaderboard.
+pub trait Reporter {
+    /// Type of error used by this reporter.
+    type Err: Error + Send;
+
+    /// Report changes to the leaderboard.
+    ///
+    /// The method receives references to both the previous version of the leaderboard,
+    /// the current version of the leaderboard, and the lists of changes detected.
+    ///
+    /// IDs stored in the [`Changes`] point to [leaderboard members] found
+    /// in the current version of the leaderboard.
+    ///
+    /// [leaderboard members]: Leaderboard::members
+    fn report_changes(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        previous_leaderboard: &Leaderboard,
+        leaderboard: &Leaderboard,
+        changes: &Changes,
+    ) -> impl Future<Output = Result<(), Self::Err>> + Send;
+
+    /// Report the first run of the bot for this leaderboard.
+    /// No changes are included, simply the initial leaderboard state.
+    ///
+    /// This function does nothing by default so that implementing it
+    /// is optional for reporters.
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self, view_key)))]
+    fn report_first_run(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        leaderboard: &Leaderboard,
+    ) -> impl Future<Output = Result<(), Self::Err>> + Send {
+        let (_, _, _, _) = (year, leaderboard_id, view_key, leaderboard);
+
+        ready(Ok(()))
+    }
+
+    /// Report an error that occurred while the bot was running.
+    ///
+    /// This can be useful to report things to the same channel as
+    /// the one where we send the leaderboard changes, so that the
+    /// bot owner can fix the issue.
+    ///
+    /// The default implementation prints the error to `stderr`.
+    ///
+    /// # Notes
+    ///
+    /// This method doesn't allow returning an error, because it
+    /// will only be called while processing another error.
+    /// If an error occurs while sending the error report,
+    /// it should simply be ignored internally.
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self, view_key)))]
+    fn report_error(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        error: &crate::Error,
+    ) -> impl Future<Output = ()> + Send {
+        let _ = view_key;
+
+        eprintln!(
+            "Error while looking for changes to leaderboard {leaderboard_id} for year {year}: {error}"
+        );
+        ready(())
+    }
+
+    /// Report that the bot recovered from an error that occurred during a previous run.
+    ///
+    /// This is called after a successful bot run if the previous run resulted in an error.
+    /// The `outage_duration` is the time elapsed since the error was first seen, if the
+    /// [`Storage`] [keeps track of it](Storage::load_error_state).
+    ///
+    /// This function does nothing by default so that implementing it
+    /// is optional for reporters.
+    ///
+    /// # Notes
+    ///
+    /// Like [`report_error`](Self::report_error), this method doesn't allow returning an
+    /// error, because the bot run has already succeeded at this point. If an error occurs
+    /// while sending the recovery report, it should simply be ignored internally.
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self, view_key)))]
+    fn report_recovery(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        error_kind: crate::ErrorKind,
+        outage_duration: Option<TimeDelta>,
+    ) -> impl Future<Output = ()> + Send {
+        let (_, _, _, _, _) = (year, leaderboard_id, view_key, error_kind, outage_duration);
+
+        ready(())
+    }
+
+    /// Report that the bot has not succeeded for a while.
+    ///
+    /// This is called by [`check_heartbeat`] when the last successful bot run is older than the
+    /// [`max_silence`] of the config's [heartbeat policy]. The `silence` is the time elapsed
+    /// since the last successful bot run.
+    ///
+    /// The default implementation prints a message to `stderr`.
+    ///
+    /// # Notes
+    ///
+    /// Like [`report_error`](Self::report_error), this method doesn't allow returning an error.
+    /// If an error occurs while sending the report, it should simply be ignored internally.
+    ///
+    /// [`check_heartbeat`]: heartbeat::check_heartbeat
+    /// [`max_silence`]: HeartbeatPolicy::max_silence
+    /// [heartbeat policy]: Config::heartbeat_policy
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self, view_key)))]
+    fn report_stale(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        silence: TimeDelta,
+    ) -> impl Future<Output = ()> + Send {
+        let _ = view_key;
+
+        eprintln!(
+            "No successful bot run for leaderboard {leaderboard_id} for year {year} in the last {} minute(s)",
+            silence.num_minutes()
+        );
+        ready(())
+    }
+
+    /// Report that the bot is still running, even though nothing changed in the leaderboard.
+    ///
+    /// This is called after a successful bot run that did not report anything if nothing has
+    /// been reported for at least the [`interval`] of the config's [heartbeat policy].
+    ///
+    /// This function does nothing by default so that implementing it
+    /// is optional for reporters.
+    ///
+    /// # Notes
+    ///
+    /// Like [`report_recovery`](Self::report_recovery), this method doesn't allow returning an
+    /// error, because the bot run has already succeeded at this point. If an error occurs
+    /// while sending the heartbeat, it should simply be ignored internally.
+    ///
+    /// [`interval`]: HeartbeatPolicy::interval
+    /// [heartbeat policy]: Config::heartbeat_policy
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self, view_key, leaderboard)))]
+    fn report_heartbeat(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        leaderboard: &Leaderboard,
+    ) -> impl Future<Output = ()> + Send {
+        let (_, _, _, _) = (year, leaderboard_id, view_key, leaderboard);
+
+        ready(())
+    }
+
+    /// Report changes to several leaderboards at once, with each member reported only once.
+    ///
+    /// This is called when changes to several leaderboards are [deduplicated] before being
+    /// reported to the same destination. Reporters can implement it to report all changes in
+    /// a single message, listing the [leaderboards] in which each member appears.
+    ///
+    /// The default implementation calls [`report_changes`](Self::report_changes) for each
+    /// leaderboard with changes.
+    ///
+    /// [deduplicated]: MergedChanges::merge
+    /// [leaderboards]: MergedChanges::leaderboards_of
+    fn report_merged_changes(
+        &mut self,
+        merged_changes: &MergedChanges,
+    ) -> impl Future<Output = Result<(), Self::Err>> + Send
+    where
+        Self: Send,
+    {
+        async move {
+            for lc in &merged_changes.leaderboards {
+                self.report_changes(
+                    lc.year,
+                    lc.leaderboard_id,
+                    lc.view_key.as_deref(),
+                    &lc.previous_leaderboard,
+                    &lc.leaderboard,
+                    &lc.changes,
+                )
+                .await?;
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Checks if reports should currently be deferred.
+    ///
+    /// When this returns `true`, the bot fetches the current leaderboard and detects changes as
+    /// usual, but neither reports nor saves them: the changes (or the first run) will be detected
+    /// and reported during the next bot run instead. The bot run is not considered an error; its
+    /// output is flagged as [`deferred`](BotOutput::deferred).
+    ///
+    /// Errors are reported as usual, but recoveries are only reported once reports are no longer
+    /// deferred, since the previous error is kept until the current leaderboard is saved.
+    ///
+    /// Returns `false` by default.
+    fn defers_reports(&self) -> bool {
+        false
+    }
+}
+
+impl<R> Reporter for &mut R
+where
+    R: Reporter + Send + ?Sized,
+{
+    type Err = R::Err;
+
+    fn report_changes(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        previous_leaderboard: &Leaderboard,
+        leaderboard: &Leaderboard,
+        changes: &Changes,
+    ) -> impl Future<Output = Result<(), Self::Err>> + Send {
+        (**self).report_changes(
+            year,
+            leaderboard_id,
+            view_key,
+            previous_leaderboard,
+            leaderboard,
+            changes,
+        )
+    }
+
+    fn report_first_run(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        leaderboard: &Leaderboard,
+    ) -> impl Future<Output = Result<(), Self::Err>> + Send {
+        (**self).report_first_run(year, leaderboard_id, view_key, leaderboard)
+    }
+
+    fn report_error(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        error: &crate::Error,
+    ) -> impl Future<Output = ()> + Send {
+        (**self).report_error(year, leaderboard_id, view_key, error)
+    }
+
+    fn report_recovery(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        error_kind: crate::ErrorKind,
+        outage_duration: Option<TimeDelta>,
+    ) -> impl Future<Output = ()> + Send {
+        (**self).report_recovery(year, leaderboard_id, view_key, error_kind, outage_duration)
+    }
+
+    fn report_stale(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        silence: TimeDelta,
+    ) -> impl Future<Output = ()> + Send {
+        (**self).report_stale(year, leaderboard_id, view_key, silence)
+    }
+
+    fn report_heartbeat(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        leaderboard: &Leaderboard,
+    ) -> impl Future<Output = ()> + Send {
+        (**self).report_heartbeat(year, leaderboard_id, view_key, leaderboard)
+    }
+
+    fn report_merged_changes(
+        &mut self,
+        merged_changes: &MergedChanges,
+    ) -> impl Future<Output = Result<(), Self::Err>> + Send {
+        (**self).report_merged_changes(merged_changes)
+    }
+
+    fn defers_reports(&self) -> bool {
+        (**self).defers_reports()
+    }
+}
+
+/// Output returned by the [`run_bot`] function.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BotOutput {
+    /// Year for which the bot was run.
+    pub year: i32,
+
+    /// ID of leaderboard checked by the bot.
+    pub leaderboard_id: u64,
+
+    /// Leaderboard data from previous run, if any.
+    ///
+    /// If this was the first bot run, will be set to `None`.
+    pub previous_leaderboard: Option<Leaderboard>,
+
+    /// Current leaderboard data.
+    pub leaderboard: Leaderboard,
+
+    /// Changes detected, if any.
+    pub changes: Option<Changes>,
+
+    /// Diagnostics about the bot run (fetch latency, storage operations, reporter calls, etc.).
+    #[serde(default)]
+    pub diagnostics: RunDiagnostics,
+
+    /// Whether reports were [deferred] by the reporter during this bot run.
+    ///
+    /// If set, the current leaderboard was not saved, so the [`changes`](Self::changes) will be
+    /// detected and reported again during the next bot run.
+    ///
+    /// [deferred]: Reporter::defers_reports
+    #[serde(default)]
+    pub deferred: bool,
+}
+
+/// Runs the bot's core functionality.
+///
+/// Reads the [`config`], fetches the previous leaderboard data from [`storage`], then
+/// fetches current leaderboard data via [`Leaderboard::get`]. If there was no previous
+/// leaderboard (e.g. this is the first run), saves the current leaderboard to storage
+/// and exits; otherwise, computes if the leaderboard has new members and/or members who
+/// got new stars and calls the [`reporter`] if some diff is found.
+///
+/// If the [`config`] returns an [`EventWindow`] and leaderboard data [should not be fetched]
+/// at this time, returns `None` without doing anything. See the [`event`] module for details.
+///
+/// If the `dry_run` parameter is set to `true`, then the bot will fetch data and compute
+/// changes but will not persist or report them.
+///
+/// Changes are reported _before_ the current leaderboard is saved; if saving fails, the same
+/// changes will be reported again during the next bot run. To report changes exactly once,
+/// use [`run_bot_outbox`] with a storage implementing [`OutboxStorage`] instead.
+///
+/// [`config`]: Config
+/// [`storage`]: Storage
+/// [`reporter`]: Reporter
+/// [should not be fetched]: EventWindow::is_run_due
+/// [`run_bot_outbox`]: outbox::run_bot_outbox
+#[cfg_attr(coverage_nightly, coverage(off))]
+#[tracing::instrument(skip(config, storage, reporter), ret, err)]
+pub async fn run_bot<C, S, R>(
+    config: &C,
+    storage: &mut S,
+    reporter: &mut R,
+    dry_run: bool,
+) -> crate::Result<Option<BotOutput>>
+where
+    C: Config,
+    S: Storage,
+    <S as Storage>::Err: Sync + 'static,
+    R: Reporter,
+    <R as Reporter>::Err: Sync + 'static,
+{
+    run_bot_from(None::<String>, config, storage, reporter, dry_run).await
+}
+
+/// Runs the bot's core functionality, using the given base Advent of Code URL
+/// (or the default, `https://adventofcode.com`, if not provided).
+///
+/// This function is mostly exposed for testing; you should use [`run_bot`] instead.
+#[cfg_attr(
+    not(coverage),
+    tracing::instrument(skip(config, storage, reporter), level = "debug", ret, err)
 )]
 pub async fn run_bot_from<B, C, S, R>(
     advent_of_code_base: Option<B>,
@@ -275,7 +1182,252 @@ pub async fn run_bot_from<B, C, S, R>(
     storage: &mut S,
     reporter: &mut R,
     dry_run: bool,
-) -> crate::Result<BotOutput>
+) -> crate::Result<Option<BotOutput>>
+where
+    B: AsRef<str> + Debug,
+    C: Config,
+    S: Storage,
+    <S as Storage>::Err: Sync + 'static,
+    R: Reporter,
+    <R as Reporter>::Err: Sync + 'static,
+{
+    run_bot_with(None, advent_of_code_base, config, storage, reporter, dry_run)
+        .await
+        .map_err(Into::into)
+}
+
+/// Runs the bot's core functionality like [`run_bot`], but returns the [diagnostics] collected
+/// before the bot run failed along with the error if it does.
+///
+/// # Errors
+///
+/// Returns a [`RunError`] wrapping the same error [`run_bot`] would return.
+///
+/// [diagnostics]: diagnostics::RunDiagnostics
+#[cfg_attr(coverage_nightly, coverage(off))]
+#[tracing::instrument(skip(config, storage, reporter), ret, err)]
+pub async fn run_bot_diagnosed<C, S, R>(
+    config: &C,
+    storage: &mut S,
+    reporter: &mut R,
+    dry_run: bool,
+) -> crate::Result<Option<BotOutput>, RunError>
+where
+    C: Config,
+    S: Storage,
+    <S as Storage>::Err: Sync + 'static,
+    R: Reporter,
+    <R as Reporter>::Err: Sync + 'static,
+{
+    run_bot_diagnosed_from(None::<String>, config, storage, reporter, dry_run).await
+}
+
+/// Runs the bot's core functionality like [`run_bot_diagnosed`], using the given base
+/// Advent of Code URL (or the default, `https://adventofcode.com`, if not provided).
+///
+/// This function is mostly exposed for testing; you should use [`run_bot_diagnosed`] instead.
+#[cfg_attr(
+    not(coverage),
+    tracing::instrument(skip(config, storage, reporter), level = "debug", ret, err)
+)]
+pub async fn run_bot_diagnosed_from<B, C, S, R>(
+    advent_of_code_base: Option<B>,
+    config: &C,
+    storage: &mut S,
+    reporter: &mut R,
+    dry_run: bool,
+) -> crate::Result<Option<BotOutput>, RunError>
+where
+    B: AsRef<str> + Debug,
+    C: Config,
+    S: Storage,
+    <S as Storage>::Err: Sync + 'static,
+    R: Reporter,
+    <R as Reporter>::Err: Sync + 'static,
+{
+    run_bot_with(None, advent_of_code_base, config, storage, reporter, dry_run).await
+}
+
+// Runs the bot's core functionality if leaderboard data should be fetched at this time,
+// reusing the given HTTP client (if provided) to fetch leaderboard data.
+pub(crate) async fn run_bot_with<B, C, S, R>(
+    http_client: Option<&reqwest::Client>,
+    advent_of_code_base: Option<B>,
+    config: &C,
+    storage: &mut S,
+    reporter: &mut R,
+    dry_run: bool,
+) -> crate::Result<Option<BotOutput>, RunError>
+where
+    B: AsRef<str> + Debug,
+    C: Config,
+    S: Storage,
+    <S as Storage>::Err: Sync + 'static,
+    R: Reporter,
+    <R as Reporter>::Err: Sync + 'static,
+{
+    run_bot_core(
+        http_client,
+        advent_of_code_base,
+        config,
+        storage,
+        reporter,
+        ImmediateMode,
+        dry_run,
+    )
+    .await
+}
+
+// Steps of a bot run that differ between the ways the bot can be run ([`run_bot`],
+// [`outbox::run_bot_outbox`] and [`digest::run_bot_digest`]). Everything else (loading the
+// previous leaderboard, fetching the current one, detecting changes, handling errors, reporting
+// recoveries and recording heartbeats) is shared and implemented by `run_bot_core`.
+pub(crate) trait RunMode<S>
+where
+    S: Storage,
+{
+    // Output returned by a successful bot run.
+    type Output;
+
+    // Loads mode-specific data, after the previous leaderboard has been loaded.
+    async fn load(
+        &mut self,
+        _storage: &S,
+        _year: i32,
+        _leaderboard_id: u64,
+        _diagnostics: &mut RunDiagnostics,
+    ) -> crate::Result<()> {
+        Ok(())
+    }
+
+    // Called before fetching the current leaderboard (except in dry-run mode).
+    // Returns whether anything was reported.
+    #[allow(clippy::too_many_arguments)]
+    async fn prepare<R>(
+        &mut self,
+        _storage: &mut S,
+        _reporter: &mut R,
+        _year: i32,
+        _leaderboard_id: u64,
+        _view_key: Option<&str>,
+        _previous_leaderboard: Option<&Leaderboard>,
+        _diagnostics: &mut RunDiagnostics,
+    ) -> crate::Result<bool>
+    where
+        R: Reporter,
+        <R as Reporter>::Err: Sync + 'static,
+    {
+        Ok(false)
+    }
+
+    // Saves the current leaderboard and reports what needs to be (except in dry-run mode).
+    // Returns whether anything was reported.
+    async fn save_and_report<R>(
+        &mut self,
+        storage: &mut S,
+        reporter: &mut R,
+        view_key: Option<&str>,
+        output: &BotOutput,
+        now: DateTime<Utc>,
+        diagnostics: &mut RunDiagnostics,
+    ) -> crate::Result<bool>
+    where
+        R: Reporter,
+        <R as Reporter>::Err: Sync + 'static;
+
+    // Builds the output of a successful bot run.
+    fn into_output(self, output: BotOutput) -> Self::Output;
+}
+
+// Run mode used by [`run_bot`]: changes are reported immediately, then the current
+// leaderboard is saved.
+struct ImmediateMode;
+
+impl<S> RunMode<S> for ImmediateMode
+where
+    S: Storage,
+    <S as Storage>::Err: Sync + 'static,
+{
+    type Output = BotOutput;
+
+    async fn save_and_report<R>(
+        &mut self,
+        storage: &mut S,
+        reporter: &mut R,
+        view_key: Option<&str>,
+        output: &BotOutput,
+        _now: DateTime<Utc>,
+        diagnostics: &mut RunDiagnostics,
+    ) -> crate::Result<bool>
+    where
+        R: Reporter,
+        <R as Reporter>::Err: Sync + 'static,
+    {
+        let (year, leaderboard_id) = (output.year, output.leaderboard_id);
+
+        let reported = match (&output.previous_leaderboard, &output.changes) {
+            (Some(previous_leaderboard), Some(changes)) => {
+                diagnostics
+                    .reporter_call(
+                        "report_changes",
+                        reporter.report_changes(
+                            year,
+                            leaderboard_id,
+                            view_key,
+                            previous_leaderboard,
+                            &output.leaderboard,
+                            changes,
+                        ),
+                    )
+                    .await
+                    .map_err(|err| ReporterError::ReportChanges(anyhow!(err)))?;
+                true
+            },
+            (None, None) => {
+                diagnostics
+                    .reporter_call(
+                        "report_first_run",
+                        reporter.report_first_run(
+                            year,
+                            leaderboard_id,
+                            view_key,
+                            &output.leaderboard,
+                        ),
+                    )
+                    .await
+                    .map_err(|err| ReporterError::ReportFirstRun(anyhow!(err)))?;
+                true
+            },
+            _ => false,
+        };
+
+        diagnostics
+            .storage_operation(
+                "save_success",
+                storage.save_success(year, leaderboard_id, &output.leaderboard),
+            )
+            .await
+            .map_err(|err| StorageError::SaveSuccess(anyhow!(err)))?;
+
+        Ok(reported)
+    }
+
+    fn into_output(self, output: BotOutput) -> Self::Output {
+        output
+    }
+}
+
+// Runs the bot's core functionality if leaderboard data should be fetched at this time,
+// using the given run mode to save the current leaderboard and report changes.
+pub(crate) async fn run_bot_core<B, C, S, R, M>(
+    http_client: Option<&reqwest::Client>,
+    advent_of_code_base: Option<B>,
+    config: &C,
+    storage: &mut S,
+    reporter: &mut R,
+    mut mode: M,
+    dry_run: bool,
+) -> crate::Result<Option<M::Output>, RunError>
 where
     B: AsRef<str> + Debug,
     C: Config,
@@ -283,146 +1435,426 @@ where
     <S as Storage>::Err: Sync + 'static,
     R: Reporter,
     <R as Reporter>::Err: Sync + 'static,
+    M: RunMode<S>,
 {
-    async fn get_leaderboard_and_changes<B, R>(
+    #[allow(clippy::too_many_arguments)]
+    async fn run<B, S, R, M>(
+        http_client: Option<&reqwest::Client>,
         advent_of_code_base: Option<B>,
+        storage: &mut S,
+        reporter: &mut R,
+        mode: &mut M,
         year: i32,
         leaderboard_id: u64,
         credentials: &LeaderboardCredentials,
+        merged_leaderboards: &[MergedLeaderboard],
+        member_settings: &MemberSettings,
         previous_leaderboard: Option<Leaderboard>,
-        reporter: &mut R,
+        now: DateTime<Utc>,
         dry_run: bool,
-    ) -> crate::Result<BotOutput, crate::Error>
+        diagnostics: &mut RunDiagnostics,
+    ) -> crate::Result<(BotOutput, bool)>
     where
         B: AsRef<str> + Debug,
+        S: Storage,
         R: Reporter,
         <R as Reporter>::Err: Sync + 'static,
+        M: RunMode<S>,
     {
-        #[cfg_attr(coverage_nightly, coverage(off))]
-        async fn get_leaderboard<B>(
-            advent_of_code_base: Option<B>,
-            year: i32,
-            leaderboard_id: u64,
-            credentials: &LeaderboardCredentials,
-        ) -> crate::Result<Leaderboard>
-        where
-            B: AsRef<str> + Debug,
-        {
-            Ok(match advent_of_code_base {
-                Some(base) => {
-                    Leaderboard::get_from(
-                        Leaderboard::http_client()?,
-                        base,
-                        year,
-                        leaderboard_id,
-                        credentials,
-                    )
-                    .await?
-                },
-                None => Leaderboard::get(year, leaderboard_id, credentials).await?,
-            })
+        // Reports left by a previous run are deferred along with the changes of this run,
+        // so the reporter is only checked once.
+        let deferred = !dry_run && reporter.defers_reports();
+        if deferred {
+            tracing::debug!("reporter defers reports, current leaderboard will not be saved");
+        }
+
+        let mut reported = false;
+        if !dry_run && !deferred {
+            reported |= mode
+                .prepare(
+                    storage,
+                    reporter,
+                    year,
+                    leaderboard_id,
+                    credentials.view_key(),
+                    previous_leaderboard.as_ref(),
+                    diagnostics,
+                )
+                .await?;
         }
 
-        let leaderboard =
-            get_leaderboard(advent_of_code_base, year, leaderboard_id, credentials).await?;
+        let leaderboard = get_merged_leaderboard(
+            http_client,
+            advent_of_code_base,
+            year,
+            leaderboard_id,
+            credentials,
+            merged_leaderboards,
+            diagnostics,
+        )
+        .await
+        .map(|leaderboard| member_settings.applied(leaderboard))?;
+        let previous_leaderboard =
+            previous_leaderboard.map(|leaderboard| member_settings.applied(leaderboard));
 
         let changes = detect_changes(previous_leaderboard.as_ref(), &leaderboard);
-        let output = BotOutput { year, leaderboard_id, previous_leaderboard, leaderboard, changes };
+        let output = BotOutput {
+            year,
+            leaderboard_id,
+            previous_leaderboard,
+            leaderboard,
+            changes,
+            diagnostics: RunDiagnostics::default(),
+            deferred,
+        };
 
-        if !dry_run {
-            match (&output.previous_leaderboard, &output.changes) {
-                (Some(previous_leaderboard), Some(changes)) => {
-                    reporter
-                        .report_changes(
-                            year,
-                            leaderboard_id,
-                            credentials.view_key(),
-                            previous_leaderboard,
-                            &output.leaderboard,
-                            changes,
-                        )
-                        .await
-                        .map_err(|err| ReporterError::ReportChanges(anyhow!(err)))?;
-                },
-                (None, None) => {
-                    reporter
-                        .report_first_run(
-                            year,
-                            leaderboard_id,
-                            credentials.view_key(),
-                            &output.leaderboard,
-                        )
-                        .await
-                        .map_err(|err| ReporterError::ReportFirstRun(anyhow!(err)))?;
-                },
-                _ => (),
-            }
+        if !dry_run && !deferred {
+            reported |= mode
+                .save_and_report(
+                    storage,
+                    reporter,
+                    credentials.view_key(),
+                    &output,
+                    now,
+                    diagnostics,
+                )
+                .await?;
         }
 
-        Ok(output)
+        Ok((output, reported))
+    }
+
+    if event::is_off_season(config) {
+        return Ok(None);
+    }
+
+    let (year, leaderboard_id, credentials, renotify_policy) =
+        (config.year(), config.leaderboard_id(), config.credentials(), config.renotify_policy());
+
+    let mut diagnostics = RunDiagnostics::default();
+    let loaded = match diagnostics
+        .storage_operation("load_previous", storage.load_previous(year, leaderboard_id))
+        .await
+    {
+        Ok(loaded) => mode
+            .load(storage, year, leaderboard_id, &mut diagnostics)
+            .await
+            .map(|()| loaded),
+        Err(err) => Err(StorageError::LoadPrevious(anyhow!(err)).into()),
+    };
+    let (previous_leaderboard, previous_error) = match loaded {
+        Ok(loaded) => loaded,
+        Err(err) if !dry_run => {
+            let error = handle_error(
+                storage,
+                reporter,
+                year,
+                leaderboard_id,
+                credentials.view_key(),
+                err,
+                None,
+                None,
+                &renotify_policy,
+                config.clock().now(),
+                &mut diagnostics,
+            )
+            .await;
+            return Err(RunError { error, diagnostics });
+        },
+        Err(error) => return Err(RunError { error, diagnostics }),
+    };
+
+    let previous_error_state = match previous_error {
+        Some(_) if !dry_run => {
+            load_error_state(storage, year, leaderboard_id, &mut diagnostics).await
+        },
+        _ => None,
+    };
+
+    let output_result = run(
+        http_client,
+        advent_of_code_base,
+        storage,
+        reporter,
+        &mut mode,
+        year,
+        leaderboard_id,
+        &credentials,
+        &config.merged_leaderboards(),
+        &config.member_settings(),
+        previous_leaderboard,
+        config.clock().now(),
+        dry_run,
+        &mut diagnostics,
+    )
+    .await;
+
+    match output_result {
+        Ok((output, reported)) => {
+            // If reports were deferred, the previous error is still stored, so the recovery
+            // will be reported during the next run that saves the current leaderboard.
+            if let Some(error_kind) = previous_error
+                && !dry_run
+                && !output.deferred
+            {
+                report_recovery(
+                    reporter,
+                    year,
+                    leaderboard_id,
+                    credentials.view_key(),
+                    error_kind,
+                    previous_error_state.as_ref(),
+                    config.clock().now(),
+                    &mut diagnostics,
+                )
+                .await;
+            }
+            if !dry_run {
+                heartbeat::record_success(
+                    storage,
+                    reporter,
+                    credentials.view_key(),
+                    &output,
+                    reported,
+                    &config.heartbeat_policy(),
+                    config.clock().now(),
+                    &mut diagnostics,
+                )
+                .await;
+            }
+
+            Ok(Some(mode.into_output(BotOutput { diagnostics, ..output })))
+        },
+        Err(err) if !dry_run => {
+            let error = handle_error(
+                storage,
+                reporter,
+                year,
+                leaderboard_id,
+                credentials.view_key(),
+                err,
+                previous_error,
+                previous_error_state,
+                &renotify_policy,
+                config.clock().now(),
+                &mut diagnostics,
+            )
+            .await;
+            Err(RunError { error, diagnostics })
+        },
+        Err(error) => Err(RunError { error, diagnostics }),
+    }
+}
+
+async fn load_error_state<S>(
+    storage: &S,
+    year: i32,
+    leaderboard_id: u64,
+    diagnostics: &mut RunDiagnostics,
+) -> Option<ErrorState>
+where
+    S: Storage,
+{
+    // Failing to load the error state only means we can't report the outage duration
+    // or remind about an ongoing error, so there's no need to fail the bot run because of it.
+    match diagnostics
+        .storage_operation("load_error_state", storage.load_error_state(year, leaderboard_id))
+        .await
+    {
+        Ok(error_state) => error_state,
+        Err(err) => {
+            tracing::warn!("failed to load previous error state: {err}");
+            None
+        },
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn handle_error<S, R>(
+    storage: &mut S,
+    reporter: &mut R,
+    year: i32,
+    leaderboard_id: u64,
+    view_key: Option<&str>,
+    err: crate::Error,
+    previous_error: Option<crate::ErrorKind>,
+    previous_error_state: Option<ErrorState>,
+    renotify_policy: &RenotifyPolicy,
+    now: DateTime<Utc>,
+    diagnostics: &mut RunDiagnostics,
+) -> crate::Error
+where
+    S: Storage,
+    <S as Storage>::Err: Sync + 'static,
+    R: Reporter,
+{
+    let error_kind = err.discriminant();
+
+    // If the error is of the same kind as the one reported previously, we don't want
+    // to spam the reporter, unless the renotify policy says it's time for a reminder.
+    let (mut error_state, notify) = match (previous_error, previous_error_state) {
+        (Some(previous_error), Some(error_state)) => {
+            let error_state = error_state.recur(error_kind, now);
+            let notify = previous_error != error_kind || renotify_policy.is_due(&error_state, now);
+            (error_state, notify)
+        },
+        (previous_error, _) => {
+            (ErrorState::new(error_kind, now), previous_error != Some(error_kind))
+        },
+    };
+    error_state.last_message = Some(err.to_string());
+
+    if notify {
+        diagnostics
+            .unchecked_reporter_call(
+                "report_error",
+                reporter.report_error(year, leaderboard_id, view_key, &err),
+            )
+            .await;
+        error_state.last_notified = Some(now);
     }
 
-    let (year, leaderboard_id, credentials) =
-        (config.year(), config.leaderboard_id(), config.credentials());
+    if let Err(storage_err) = diagnostics
+        .storage_operation(
+            "save_error_state",
+            storage.save_error_state(year, leaderboard_id, &error_state),
+        )
+        .await
+    {
+        // An error occurred while doing the bot run, and an error also occurred
+        // while trying to persist information about the last error. ¯\_(ツ)_/¯
+        let storage_err = StorageError::SaveError(anyhow!(storage_err)).into();
+        if notify {
+            diagnostics
+                .unchecked_reporter_call(
+                    "report_error",
+                    reporter.report_error(year, leaderboard_id, view_key, &storage_err),
+                )
+                .await;
+        } else {
+            tracing::warn!("{storage_err}");
+        }
+    }
 
-    let previous_result = storage.load_previous(year, leaderboard_id).await;
-    let (mut output_result, previous_error) = match previous_result {
-        Ok((previous_leaderboard, previous_error)) => {
-            let output_result = get_leaderboard_and_changes(
-                advent_of_code_base,
-                year,
-                leaderboard_id,
-                &credentials,
-                previous_leaderboard,
-                reporter,
-                dry_run,
-            )
-            .await;
-            (output_result, previous_error)
-        },
-        Err(err) => (Err(StorageError::LoadPrevious(anyhow!(err)).into()), None),
-    };
+    err
+}
 
-    output_result = match output_result {
-        Ok(output) if !dry_run => {
-            match storage
-                .save_success(year, leaderboard_id, &output.leaderboard)
-                .await
-            {
-                Ok(()) => Ok(output),
-                Err(err) => Err(StorageError::SaveSuccess(anyhow!(err)).into()),
-            }
-        },
-        output_result => output_result,
+#[allow(clippy::too_many_arguments)]
+async fn report_recovery<R>(
+    reporter: &mut R,
+    year: i32,
+    leaderboard_id: u64,
+    view_key: Option<&str>,
+    error_kind: crate::ErrorKind,
+    error_state: Option<&ErrorState>,
+    now: DateTime<Utc>,
+    diagnostics: &mut RunDiagnostics,
+) where
+    R: Reporter,
+{
+    let outage_duration = error_state.map(|error_state| now - error_state.first_seen);
+
+    diagnostics.record_previous_error(error_kind, error_state);
+    diagnostics
+        .unchecked_reporter_call(
+            "report_recovery",
+            reporter.report_recovery(year, leaderboard_id, view_key, error_kind, outage_duration),
+        )
+        .await;
+}
+
+async fn get_leaderboard<B>(
+    http_client: Option<&reqwest::Client>,
+    advent_of_code_base: Option<B>,
+    year: i32,
+    leaderboard_id: u64,
+    credentials: &LeaderboardCredentials,
+    diagnostics: &mut RunDiagnostics,
+) -> crate::Result<Leaderboard>
+where
+    B: AsRef<str> + Debug,
+{
+    let http_client = match http_client {
+        Some(http_client) => http_client.clone(),
+        None => Leaderboard::http_client()?,
     };
+    let base = advent_of_code_base
+        .as_ref()
+        .map_or(DEFAULT_ADVENT_OF_CODE_BASE, AsRef::as_ref);
 
-    match output_result {
-        Err(err) if previous_error.is_some_and(|err_kind| err_kind == err.discriminant()) => {
-            // An error occurred, but it's the same kind of error reported previously; don't
-            // spam the reporter when this happens, simply avoid reporting the same error twice.
-            Err(err)
-        },
-        Err(err) if !dry_run => {
-            reporter
-                .report_error(year, leaderboard_id, credentials.view_key(), &err)
-                .await;
+    let started = Instant::now();
+    let (leaderboard, status) =
+        Leaderboard::get_with_status_from(http_client, base, year, leaderboard_id, credentials)
+            .await?;
+    diagnostics.record_fetch(leaderboard_id, status.as_u16(), started);
 
-            if let Err(storage_err) = storage
-                .save_error(year, leaderboard_id, (&err).into())
-                .await
-            {
-                // An error occurred while doing the bot run, and an error also occurred
-                // while trying to persist information about the last error. ¯\_(ツ)_/¯
-                let storage_err = StorageError::SaveError(anyhow!(storage_err)).into();
-                reporter
-                    .report_error(year, leaderboard_id, credentials.view_key(), &storage_err)
-                    .await;
-            }
+    Ok(leaderboard)
+}
 
-            Err(err)
-        },
-        output_result => output_result,
+async fn get_merged_leaderboard<B>(
+    http_client: Option<&reqwest::Client>,
+    advent_of_code_base: Option<B>,
+    year: i32,
+    leaderboard_id: u64,
+    credentials: &LeaderboardCredentials,
+    merged_leaderboards: &[MergedLeaderboard],
+    diagnostics: &mut RunDiagnostics,
+) -> crate::Result<Leaderboard>
+where
+    B: AsRef<str> + Debug,
+{
+    if merged_leaderboards.is_empty() {
+        return get_leaderboard(
+            http_client,
+            advent_of_code_base,
+            year,
+            leaderboard_id,
+            credentials,
+            diagnostics,
+        )
+        .await;
+    }
+
+    // Reuse the same HTTP client to fetch all leaderboards.
+    let http_client = match http_client {
+        Some(http_client) => http_client.clone(),
+        None => Leaderboard::http_client()?,
+    };
+    let base = advent_of_code_base
+        .as_ref()
+        .map_or(DEFAULT_ADVENT_OF_CODE_BASE, AsRef::as_ref);
+
+    let mut leaderboards = Vec::with_capacity(merged_leaderboards.len() + 1);
+    leaderboards.push(
+        get_leaderboard(
+            Some(&http_client),
+            Some(base),
+            year,
+            leaderboard_id,
+            credentials,
+            diagnostics,
+        )
+        .await?,
+    );
+    for merged_leaderboard in merged_leaderboards {
+        let credentials = merged_leaderboard
+            .credentials
+            .as_ref()
+            .unwrap_or(credentials);
+        leaderboards.push(
+            get_leaderboard(
+                Some(&http_client),
+                Some(base),
+                year,
+                merged_leaderboard.leaderboard_id,
+                credentials,
+                diagnostics,
+            )
+            .await?,
+        );
     }
+
+    Ok(Leaderboard::merge(&leaderboards)?)
 }
 
 #[cfg_attr(not(coverage), tracing::instrument(ret))]
@@ -457,7 +1889,7 @@ fn detect_changes(
 }
 
 #[cfg(test)]
-#[cfg(all(feature = "config-mem", feature = "storage-mem"))]
+#[cfg(all(feature = "config-mem", feature = "storage-mem", feature = "reporter-recording"))]
 #[cfg_attr(coverage_nightly, coverage(off))]
 // noinspection DuplicatedCode
 mod tests {
@@ -546,98 +1978,18 @@ mod tests {
         };
         use aoc_leaderboard::wiremock::MockServer;
         use assert_matches::assert_matches;
-        use mockall::predicate::eq;
+        use mockall::predicate::{eq, function};
 
         use super::*;
         use crate::error::{ReporterErrorKind, StorageErrorKind};
         use crate::leaderbot::config::mem::MemoryConfig;
+        use crate::leaderbot::reporter::recording::RecordingReporter;
         use crate::leaderbot::storage::mem::MemoryStorage;
 
         const OWNER: u64 = 42;
         const MEMBER_1: u64 = 23;
         const MEMBER_2: u64 = 11;
 
-        #[derive(Debug, Clone, PartialEq, Eq)]
-        pub struct SpiedChanges {
-            pub previous_leaderboard: Leaderboard,
-            pub leaderboard: Leaderboard,
-            pub changes: Option<Changes>,
-        }
-
-        impl SpiedChanges {
-            pub fn has_changes(&self) -> bool {
-                self.changes.as_ref().is_some_and(|changes| {
-                    !changes.new_members.is_empty() || !changes.members_with_new_stars.is_empty()
-                })
-            }
-        }
-
-        #[derive(Debug, Default)]
-        pub struct SpyReporter {
-            pub changes: Vec<(i32, u64, SpiedChanges)>,
-            pub first_runs: Vec<(i32, u64, Leaderboard)>,
-            pub errors: Vec<(i32, u64, String)>,
-        }
-
-        impl SpyReporter {
-            pub fn calls(&self) -> usize {
-                self.changes.len() + self.first_runs.len() + self.errors.len()
-            }
-
-            pub fn called(&self) -> bool {
-                self.calls() != 0
-            }
-        }
-
-        impl Reporter for SpyReporter {
-            type Err = crate::Error;
-
-            async fn report_changes(
-                &mut self,
-                year: i32,
-                leaderboard_id: u64,
-                _view_key: Option<&str>,
-                previous_leaderboard: &Leaderboard,
-                leaderboard: &Leaderboard,
-                changes: &Changes,
-            ) -> Result<(), Self::Err> {
-                self.changes.push((
-                    year,
-                    leaderboard_id,
-                    SpiedChanges {
-                        previous_leaderboard: previous_leaderboard.clone(),
-                        leaderboard: leaderboard.clone(),
-                        changes: Some(changes.clone()),
-                    },
-                ));
-
-                Ok(())
-            }
-
-            async fn report_first_run(
-                &mut self,
-                year: i32,
-                leaderboard_id: u64,
-                _view_key: Option<&str>,
-                leaderboard: &Leaderboard,
-            ) -> Result<(), Self::Err> {
-                self.first_runs
-                    .push((year, leaderboard_id, leaderboard.clone()));
-
-                Ok(())
-            }
-
-            async fn report_error(
-                &mut self,
-                year: i32,
-                leaderboard_id: u64,
-                _view_key: Option<&str>,
-                error: &crate::Error,
-            ) {
-                self.errors.push((year, leaderboard_id, error.to_string()));
-            }
-        }
-
         #[fixture]
         fn config(
             #[from(test_leaderboard_credentials)] credentials: LeaderboardCredentials,
@@ -656,8 +2008,21 @@ mod tests {
         }
 
         #[fixture]
-        fn reporter() -> SpyReporter {
-            SpyReporter::default()
+        fn reporter() -> RecordingReporter {
+            RecordingReporter::new()
+        }
+
+        fn error_state_with_kind(
+            error_kind: crate::ErrorKind,
+        ) -> impl mockall::Predicate<ErrorState> + Send {
+            function(move |error_state: &ErrorState| error_state.error_kind == error_kind)
+        }
+
+        fn reported_errors(reporter: &RecordingReporter) -> Vec<(i32, u64, &str)> {
+            reporter
+                .errors()
+                .map(|error| (error.year, error.leaderboard_id, error.message.as_str()))
+                .collect()
         }
 
         #[fixture]
@@ -776,111 +2141,365 @@ mod tests {
             leaderboard
         }
 
-        #[fixture]
-        fn leaderboard_with_both_updates(
-            #[from(leaderboard_with_new_member)] mut leaderboard: Leaderboard,
-        ) -> Leaderboard {
-            add_member_1_stars(&mut leaderboard);
+        #[fixture]
+        fn leaderboard_with_both_updates(
+            #[from(leaderboard_with_new_member)] mut leaderboard: Leaderboard,
+        ) -> Leaderboard {
+            add_member_1_stars(&mut leaderboard);
+
+            leaderboard
+        }
+
+        mod without_previous {
+            use super::*;
+
+            #[rstest]
+            #[case::regular_run(false)]
+            #[case::dry_run(true)]
+            #[awt]
+            #[test_log::test(tokio::test)]
+            async fn and(
+                config: MemoryConfig,
+                mut storage: MemoryStorage,
+                mut reporter: RecordingReporter,
+                #[future]
+                #[from(mock_server_with_leaderboard)]
+                #[with(base_leaderboard::default())]
+                mock_server: MockServer,
+                #[case] dry_run: bool,
+                #[from(base_leaderboard)] expected: Leaderboard,
+            ) {
+                let result = run_bot_from(
+                    Some(mock_server.uri()),
+                    &config,
+                    &mut storage,
+                    &mut reporter,
+                    dry_run,
+                )
+                .await;
+                assert_matches!(result, Ok(Some(BotOutput { year, leaderboard_id, previous_leaderboard, leaderboard, changes, diagnostics, deferred })) => {
+                    assert!(!deferred);
+                    assert_eq!(year, TEST_YEAR);
+                    assert_eq!(leaderboard_id, TEST_LEADERBOARD_ID);
+                    assert!(previous_leaderboard.is_none());
+                    assert_eq!(leaderboard, expected);
+                    assert!(changes.is_none());
+
+                    assert_matches!(diagnostics.fetches.as_slice(), [fetch] => {
+                        assert_eq!(fetch.leaderboard_id, TEST_LEADERBOARD_ID);
+                        assert_eq!(fetch.http_status, Some(200));
+                    });
+                    if dry_run {
+                        assert_eq!(diagnostics.storage_operation_names(), ["load_previous"]);
+                        assert!(diagnostics.reporter_calls.is_empty());
+                    } else {
+                        assert_eq!(diagnostics.storage_operation_names(), ["load_previous", "save_success"]);
+                        assert_eq!(diagnostics.reporter_call_names(), ["report_first_run"]);
+                    }
+                    assert!(diagnostics.previous_error.is_none());
+                });
+
+                assert_eq!(storage.len(), if dry_run { 0 } else { 1 });
+                let (actual_leaderboard, actual_err) = storage
+                    .load_previous(TEST_YEAR, TEST_LEADERBOARD_ID)
+                    .await
+                    .unwrap();
+                assert!(actual_err.is_none());
+                assert_eq!(actual_leaderboard, if dry_run { None } else { Some(expected.clone()) });
+
+                if dry_run {
+                    reporter.assert_not_called();
+                } else {
+                    let first_run =
+                        reporter.assert_first_run_reported(TEST_YEAR, TEST_LEADERBOARD_ID);
+                    assert_eq!(first_run.leaderboard, expected);
+                }
+            }
+        }
+
+        #[allow(clippy::too_many_arguments)]
+        mod with_previous {
+            use super::*;
+
+            #[rstest]
+            #[case::no_changes(base_leaderboard::default(), vec![], vec![])]
+            #[case::new_member(leaderboard_with_new_member::default(), vec![MEMBER_2], vec![])]
+            #[case::member_with_new_stars(leaderboard_with_member_with_new_stars::default(), vec![], vec![MEMBER_1])]
+            #[case::both_updates(leaderboard_with_both_updates::default(), vec![MEMBER_2], vec![MEMBER_1])]
+            #[awt]
+            #[test_log::test(tokio::test)]
+            async fn and(
+                #[from(test_leaderboard_credentials)] credentials: LeaderboardCredentials,
+                #[with(credentials.clone())] config: MemoryConfig,
+                mut storage: MemoryStorage,
+                mut reporter: RecordingReporter,
+                #[from(base_leaderboard)] base: Leaderboard,
+                #[case] leaderboard: Leaderboard,
+                #[case] expected_new_members: Vec<u64>,
+                #[case] expected_members_with_new_stars: Vec<u64>,
+                #[values(false, true)] dry_run: bool,
+                #[future]
+                #[from(mock_server_with_leaderboard)]
+                #[with(leaderboard.clone(), credentials.clone())]
+                mock_server: MockServer,
+            ) {
+                let _ = credentials;
+
+                storage
+                    .save_success(TEST_YEAR, TEST_LEADERBOARD_ID, &base)
+                    .await
+                    .unwrap();
+
+                let expected_changes = Changes::if_needed(
+                    expected_new_members.into_iter().collect(),
+                    expected_members_with_new_stars.into_iter().collect(),
+                );
+
+                let result = run_bot_from(
+                    Some(mock_server.uri()),
+                    &config,
+                    &mut storage,
+                    &mut reporter,
+                    dry_run,
+                )
+                .await;
+                assert_matches!(result, Ok(Some(BotOutput { year, leaderboard_id, previous_leaderboard, leaderboard: output_leaderboard, changes, diagnostics, deferred })) => {
+                    assert!(!deferred);
+                    assert_eq!(year, TEST_YEAR);
+                    assert_eq!(leaderboard_id, TEST_LEADERBOARD_ID);
+                    assert_eq!(previous_leaderboard.as_ref(), Some(&base));
+                    assert_eq!(output_leaderboard, leaderboard);
+                    assert_eq!(changes, expected_changes);
+
+                    assert_eq!(diagnostics.fetches.len(), 1);
+                    match (&expected_changes, dry_run) {
+                        (_, true) => {
+                            assert_eq!(diagnostics.storage_operation_names(), ["load_previous"]);
+                            assert!(diagnostics.reporter_calls.is_empty());
+                        },
+                        (Some(_), false) => {
+                            assert_eq!(diagnostics.storage_operation_names(), ["load_previous", "save_success"]);
+                            assert_eq!(diagnostics.reporter_call_names(), ["report_changes"]);
+                        },
+                        (None, false) => {
+                            assert_eq!(diagnostics.storage_operation_names(), ["load_previous", "save_success"]);
+                            assert!(diagnostics.reporter_calls.is_empty());
+                        },
+                    }
+                });
+
+                assert_eq!(storage.len(), 1);
+                let (current_leaderboard, current_err) = storage
+                    .load_previous(TEST_YEAR, TEST_LEADERBOARD_ID)
+                    .await
+                    .unwrap();
+                assert!(current_err.is_none());
+                assert_eq!(
+                    current_leaderboard.as_ref(),
+                    Some(if dry_run { &base } else { &leaderboard })
+                );
+
+                match (&expected_changes, dry_run) {
+                    (Some(expected_changes), false) => {
+                        let reported = reporter.assert_changes_reported(
+                            TEST_YEAR,
+                            TEST_LEADERBOARD_ID,
+                            expected_changes,
+                        );
+                        assert_eq!(reported.previous_leaderboard, base);
+                        assert_eq!(reported.leaderboard, leaderboard);
+                    },
+                    _ => reporter.assert_not_called(),
+                }
+            }
+        }
+
+        mod with_merged_leaderboards {
+            use aoc_leaderboard::test_helpers::add_credentials_matchers_to_mock_server;
+            use aoc_leaderboard::wiremock::matchers::{method, path};
+            use aoc_leaderboard::wiremock::{Mock, ResponseTemplate};
+
+            use super::*;
+
+            const OTHER_LEADERBOARD_ID: u64 = TEST_LEADERBOARD_ID + 1;
+
+            #[rstest]
+            #[awt]
+            #[test_log::test(tokio::test)]
+            async fn reports_merged_leaderboard(
+                #[from(test_leaderboard_credentials)] credentials: LeaderboardCredentials,
+                #[with(credentials.clone())] mut config: MemoryConfig,
+                mut storage: MemoryStorage,
+                mut reporter: RecordingReporter,
+                #[from(base_leaderboard)] base: Leaderboard,
+                #[from(leaderboard_with_new_member)] other: Leaderboard,
+                #[future]
+                #[from(mock_server_with_leaderboard)]
+                #[with(base.clone(), credentials.clone())]
+                mock_server: MockServer,
+            ) {
+                add_credentials_matchers_to_mock_server(
+                    Mock::given(method("GET")).and(path(format!(
+                        "/{TEST_YEAR}/leaderboard/private/view/{OTHER_LEADERBOARD_ID}.json"
+                    ))),
+                    credentials,
+                )
+                .respond_with(ResponseTemplate::new(200).set_body_json(&other))
+                .mount(&mock_server)
+                .await;
+                config.merged_leaderboards = vec![MergedLeaderboard::new(OTHER_LEADERBOARD_ID)];
+
+                storage
+                    .save_success(TEST_YEAR, TEST_LEADERBOARD_ID, &base)
+                    .await
+                    .unwrap();
+
+                let expected_leaderboard = Leaderboard::merge([&base, &other]).unwrap();
+                let expected_changes = Changes::new([MEMBER_2].into(), HashSet::new());
+
+                let result = run_bot_from(
+                    Some(mock_server.uri()),
+                    &config,
+                    &mut storage,
+                    &mut reporter,
+                    false,
+                )
+                .await;
+                assert_matches!(result, Ok(Some(BotOutput { leaderboard_id, leaderboard, changes, .. })) => {
+                    assert_eq!(leaderboard_id, TEST_LEADERBOARD_ID);
+                    assert_eq!(leaderboard, expected_leaderboard);
+                    assert_eq!(changes.as_ref(), Some(&expected_changes));
+                });
+
+                let (current_leaderboard, _) = storage
+                    .load_previous(TEST_YEAR, TEST_LEADERBOARD_ID)
+                    .await
+                    .unwrap();
+                assert_eq!(current_leaderboard, Some(expected_leaderboard));
+                reporter.assert_changes_reported(TEST_YEAR, TEST_LEADERBOARD_ID, &expected_changes);
+            }
+
+            #[rstest]
+            #[awt]
+            #[test_log::test(tokio::test)]
+            async fn merged_leaderboard_get_error(
+                #[from(test_leaderboard_credentials)] credentials: LeaderboardCredentials,
+                #[with(credentials.clone())] mut config: MemoryConfig,
+                mut storage: MemoryStorage,
+                mut reporter: RecordingReporter,
+                #[future]
+                #[from(mock_server_with_leaderboard)]
+                #[with(base_leaderboard::default(), credentials.clone())]
+                mock_server: MockServer,
+            ) {
+                let _ = credentials;
+                config.merged_leaderboards = vec![MergedLeaderboard::new(OTHER_LEADERBOARD_ID)];
+
+                let result = run_bot_from(
+                    Some(mock_server.uri()),
+                    &config,
+                    &mut storage,
+                    &mut reporter,
+                    false,
+                )
+                .await;
+                assert_matches!(result, Err(crate::Error::Leaderboard(err)) if err.is_http_get());
 
-            leaderboard
+                assert_eq!(reporter.errors().count(), 1);
+            }
         }
 
-        mod without_previous {
+        mod with_member_settings {
             use super::*;
+            use crate::leaderbot::members::MemberSettings;
 
             #[rstest]
-            #[case::regular_run(false)]
-            #[case::dry_run(true)]
             #[awt]
             #[test_log::test(tokio::test)]
-            async fn and(
-                config: MemoryConfig,
+            async fn applies_settings(
+                mut config: MemoryConfig,
                 mut storage: MemoryStorage,
-                mut reporter: SpyReporter,
+                mut reporter: RecordingReporter,
+                #[from(base_leaderboard)] base: Leaderboard,
+                #[from(leaderboard_with_both_updates)] current: Leaderboard,
                 #[future]
                 #[from(mock_server_with_leaderboard)]
-                #[with(base_leaderboard::default())]
+                #[with(current.clone())]
                 mock_server: MockServer,
-                #[case] dry_run: bool,
-                #[from(base_leaderboard)] expected: Leaderboard,
             ) {
+                config.member_settings = MemberSettings {
+                    aliases: [(MEMBER_1, "Zaphod".into()), (OWNER, "Trillian".into())].into(),
+                    ignored: [MEMBER_2].into(),
+                    opted_out: [OWNER].into(),
+                    ..Default::default()
+                };
+
+                storage
+                    .save_success(TEST_YEAR, TEST_LEADERBOARD_ID, &base)
+                    .await
+                    .unwrap();
+
+                let expected_leaderboard = config.member_settings.applied(current);
+                let expected_changes = Changes::new(HashSet::new(), [MEMBER_1].into());
+
                 let result = run_bot_from(
                     Some(mock_server.uri()),
                     &config,
                     &mut storage,
                     &mut reporter,
-                    dry_run,
+                    false,
                 )
                 .await;
-                assert_matches!(result, Ok(BotOutput { year, leaderboard_id, previous_leaderboard, leaderboard, changes }) => {
-                    assert_eq!(year, TEST_YEAR);
-                    assert_eq!(leaderboard_id, TEST_LEADERBOARD_ID);
-                    assert!(previous_leaderboard.is_none());
-                    assert_eq!(leaderboard, expected);
-                    assert!(changes.is_none());
+                assert_matches!(result, Ok(Some(BotOutput { leaderboard, changes, .. })) => {
+                    assert!(!leaderboard.members.contains_key(&MEMBER_2));
+                    assert_eq!(leaderboard.members[&MEMBER_1].name.as_deref(), Some("Zaphod"));
+                    assert_eq!(leaderboard.members[&OWNER].name, None);
+                    assert_eq!(leaderboard, expected_leaderboard);
+                    assert_eq!(changes.as_ref(), Some(&expected_changes));
                 });
 
-                assert_eq!(storage.len(), if dry_run { 0 } else { 1 });
-                let (actual_leaderboard, actual_err) = storage
+                let (current_leaderboard, _) = storage
                     .load_previous(TEST_YEAR, TEST_LEADERBOARD_ID)
                     .await
                     .unwrap();
-                assert!(actual_err.is_none());
-                assert_eq!(actual_leaderboard, if dry_run { None } else { Some(expected.clone()) });
-
-                assert_eq!(reporter.calls(), if dry_run { 0 } else { 1 });
-                assert!(reporter.changes.is_empty());
-                assert!(reporter.errors.is_empty());
-                if !dry_run {
-                    assert_eq!(
-                        reporter.first_runs[0],
-                        (TEST_YEAR, TEST_LEADERBOARD_ID, expected.clone())
-                    );
-                }
+                assert_eq!(current_leaderboard, Some(expected_leaderboard.clone()));
+                let reported = reporter.assert_changes_reported(
+                    TEST_YEAR,
+                    TEST_LEADERBOARD_ID,
+                    &expected_changes,
+                );
+                assert_eq!(reported.leaderboard, expected_leaderboard);
+                assert_eq!(reported.previous_leaderboard.members[&OWNER].name, None);
             }
         }
 
-        #[allow(clippy::too_many_arguments)]
-        mod with_previous {
+        mod after_error {
             use super::*;
 
             #[rstest]
-            #[case::no_changes(base_leaderboard::default(), vec![], vec![])]
-            #[case::new_member(leaderboard_with_new_member::default(), vec![MEMBER_2], vec![])]
-            #[case::member_with_new_stars(leaderboard_with_member_with_new_stars::default(), vec![], vec![MEMBER_1])]
-            #[case::both_updates(leaderboard_with_both_updates::default(), vec![MEMBER_2], vec![MEMBER_1])]
             #[awt]
             #[test_log::test(tokio::test)]
-            async fn and(
-                #[from(test_leaderboard_credentials)] credentials: LeaderboardCredentials,
-                #[with(credentials.clone())] config: MemoryConfig,
+            async fn reports_recovery(
+                config: MemoryConfig,
                 mut storage: MemoryStorage,
-                mut reporter: SpyReporter,
+                mut reporter: RecordingReporter,
                 #[from(base_leaderboard)] base: Leaderboard,
-                #[case] leaderboard: Leaderboard,
-                #[case] expected_new_members: Vec<u64>,
-                #[case] expected_members_with_new_stars: Vec<u64>,
-                #[values(false, true)] dry_run: bool,
                 #[future]
                 #[from(mock_server_with_leaderboard)]
-                #[with(leaderboard.clone(), credentials.clone())]
+                #[with(base_leaderboard::default())]
                 mock_server: MockServer,
+                #[values(false, true)] dry_run: bool,
             ) {
-                let _ = credentials;
-
+                let error_kind =
+                    crate::ErrorKind::Leaderboard(aoc_leaderboard::ErrorKind::NoAccess);
                 storage
                     .save_success(TEST_YEAR, TEST_LEADERBOARD_ID, &base)
                     .await
                     .unwrap();
-
-                let expected = SpiedChanges {
-                    previous_leaderboard: base.clone(),
-                    leaderboard: leaderboard.clone(),
-                    changes: Changes::if_needed(
-                        expected_new_members.into_iter().collect(),
-                        expected_members_with_new_stars.into_iter().collect(),
-                    ),
-                };
+                storage
+                    .save_error(TEST_YEAR, TEST_LEADERBOARD_ID, error_kind)
+                    .await
+                    .unwrap();
 
                 let result = run_bot_from(
                     Some(mock_server.uri()),
@@ -890,32 +2509,77 @@ mod tests {
                     dry_run,
                 )
                 .await;
-                assert_matches!(result, Ok(BotOutput { year, leaderboard_id, previous_leaderboard, leaderboard: output_leaderboard, changes }) => {
-                    assert_eq!(year, TEST_YEAR);
-                    assert_eq!(leaderboard_id, TEST_LEADERBOARD_ID);
-                    assert_eq!(previous_leaderboard.as_ref(), Some(&base));
-                    assert_eq!(output_leaderboard, leaderboard);
-                    assert_eq!(changes, expected.changes);
-                });
+                assert_matches!(result, Ok(_));
 
-                assert_eq!(storage.len(), 1);
-                let (current_leaderboard, current_err) = storage
-                    .load_previous(TEST_YEAR, TEST_LEADERBOARD_ID)
-                    .await
-                    .unwrap();
-                assert!(current_err.is_none());
-                assert_eq!(current_leaderboard, Some(if dry_run { base } else { leaderboard }));
-
-                if expected.has_changes() && !dry_run {
-                    assert!(reporter.called());
-                    let (actual_year, actual_leaderboard_id, actual) = &reporter.changes[0];
-                    assert_eq!(*actual_year, TEST_YEAR);
-                    assert_eq!(*actual_leaderboard_id, TEST_LEADERBOARD_ID);
-                    assert_eq!(*actual, expected);
+                if dry_run {
+                    reporter.assert_not_called();
                 } else {
-                    assert!(!reporter.called());
+                    let reported = reporter.assert_recovery_reported(
+                        TEST_YEAR,
+                        TEST_LEADERBOARD_ID,
+                        error_kind,
+                    );
+                    assert_matches!(reported.outage_duration, Some(duration) => {
+                        assert!(duration >= TimeDelta::zero());
+                    });
+
+                    let (_, current_err) = storage
+                        .load_previous(TEST_YEAR, TEST_LEADERBOARD_ID)
+                        .await
+                        .unwrap();
+                    assert!(current_err.is_none());
                 }
             }
+
+            #[rstest]
+            #[awt]
+            #[test_log::test(tokio::test)]
+            async fn load_error_state_error(
+                config: MemoryConfig,
+                mut reporter: RecordingReporter,
+                #[future]
+                #[from(mock_server_with_leaderboard)]
+                #[with(base_leaderboard::default())]
+                mock_server: MockServer,
+            ) {
+                let error_kind =
+                    crate::ErrorKind::Leaderboard(aoc_leaderboard::ErrorKind::NoAccess);
+
+                let mut storage = MockStorage::new();
+                storage
+                    .expect_load_previous()
+                    .with(eq(TEST_YEAR), eq(TEST_LEADERBOARD_ID))
+                    .times(1)
+                    .returning(move |_, _| {
+                        Box::pin(ready(Ok((Some(base_leaderboard()), Some(error_kind)))))
+                    });
+                storage
+                    .expect_load_error_state()
+                    .with(eq(TEST_YEAR), eq(TEST_LEADERBOARD_ID))
+                    .times(1)
+                    .returning(move |_, _| {
+                        Box::pin(ready(Err(crate::Error::TestLoadPreviousError)))
+                    });
+                storage
+                    .expect_save_success()
+                    .with(eq(TEST_YEAR), eq(TEST_LEADERBOARD_ID), eq(base_leaderboard()))
+                    .times(1)
+                    .returning(move |_, _, _| Box::pin(ready(Ok(()))));
+
+                let result = run_bot_from(
+                    Some(mock_server.uri()),
+                    &config,
+                    &mut storage,
+                    &mut reporter,
+                    false,
+                )
+                .await;
+                assert_matches!(result, Ok(_));
+
+                let reported =
+                    reporter.assert_recovery_reported(TEST_YEAR, TEST_LEADERBOARD_ID, error_kind);
+                assert!(reported.outage_duration.is_none());
+            }
         }
 
         // noinspection DuplicatedCode
@@ -927,7 +2591,7 @@ mod tests {
             #[test_log::test(tokio::test)]
             async fn leaderboard_get_error(
                 config: MemoryConfig,
-                mut reporter: SpyReporter,
+                mut reporter: RecordingReporter,
                 #[future]
                 #[from(mock_server_with_inaccessible_leaderboard)]
                 mock_server: MockServer,
@@ -941,11 +2605,13 @@ mod tests {
                     .returning(|_, _| Box::pin(ready(Ok((None, None)))));
                 if !dry_run {
                     storage
-                        .expect_save_error()
+                        .expect_save_error_state()
                         .with(
                             eq(TEST_YEAR),
                             eq(TEST_LEADERBOARD_ID),
-                            eq(crate::ErrorKind::Leaderboard(aoc_leaderboard::ErrorKind::NoAccess)),
+                            error_state_with_kind(crate::ErrorKind::Leaderboard(
+                                aoc_leaderboard::ErrorKind::NoAccess,
+                            )),
                         )
                         .times(1)
                         .returning(move |_, _, _| Box::pin(ready(Ok(()))));
@@ -964,10 +2630,13 @@ mod tests {
                     Err(crate::Error::Leaderboard(aoc_leaderboard::Error::NoAccess))
                 );
                 if dry_run {
-                    assert!(!reporter.called());
+                    reporter.assert_not_called();
                 } else {
-                    assert!(reporter.called());
-                    assert_eq!(reporter.errors.len(), 1);
+                    reporter.assert_error_reported(
+                        TEST_YEAR,
+                        TEST_LEADERBOARD_ID,
+                        crate::ErrorKind::Leaderboard(aoc_leaderboard::ErrorKind::NoAccess),
+                    );
                 }
             }
 
@@ -976,7 +2645,7 @@ mod tests {
             #[test_log::test(tokio::test)]
             async fn load_previous_error(
                 config: MemoryConfig,
-                mut reporter: SpyReporter,
+                mut reporter: RecordingReporter,
                 #[future]
                 #[from(mock_server_with_leaderboard)]
                 mock_server: MockServer,
@@ -992,11 +2661,13 @@ mod tests {
                     });
                 if !dry_run {
                     storage
-                        .expect_save_error()
+                        .expect_save_error_state()
                         .with(
                             eq(TEST_YEAR),
                             eq(TEST_LEADERBOARD_ID),
-                            eq(crate::ErrorKind::Storage(StorageErrorKind::LoadPrevious)),
+                            error_state_with_kind(crate::ErrorKind::Storage(
+                                StorageErrorKind::LoadPrevious,
+                            )),
                         )
                         .times(1)
                         .returning(move |_, _, _| Box::pin(ready(Ok(()))));
@@ -1012,18 +2683,14 @@ mod tests {
                 .await;
                 assert_matches!(result, Err(crate::Error::Storage(StorageError::LoadPrevious(_))));
                 if dry_run {
-                    assert!(!reporter.called());
+                    reporter.assert_not_called();
                 } else {
-                    assert!(reporter.called());
-                    assert_eq!(reporter.errors.len(), 1);
-                    assert_eq!(
-                        reporter.errors[0],
-                        (
-                            TEST_YEAR,
-                            TEST_LEADERBOARD_ID,
-                            "failed to load previous leaderboard data: test".into(),
-                        )
+                    let reported = reporter.assert_error_reported(
+                        TEST_YEAR,
+                        TEST_LEADERBOARD_ID,
+                        crate::ErrorKind::Storage(StorageErrorKind::LoadPrevious),
                     );
+                    assert_eq!(reported.message, "failed to load previous leaderboard data: test");
                 }
             }
 
@@ -1045,47 +2712,18 @@ mod tests {
                     .times(1)
                     .returning(move |_, _| Box::pin(ready(Ok((Some(base.clone()), None)))));
                 storage
-                    .expect_save_error()
+                    .expect_save_error_state()
                     .with(
                         eq(TEST_YEAR),
                         eq(TEST_LEADERBOARD_ID),
-                        eq(crate::ErrorKind::Reporter(ReporterErrorKind::ReportChanges)),
+                        error_state_with_kind(crate::ErrorKind::Reporter(
+                            ReporterErrorKind::ReportChanges,
+                        )),
                     )
                     .times(1)
                     .returning(move |_, _, _| Box::pin(ready(Ok(()))));
 
-                #[derive(Debug, Default)]
-                struct MockReporter {
-                    pub errors: usize,
-                }
-
-                impl Reporter for MockReporter {
-                    type Err = crate::Error;
-
-                    async fn report_changes(
-                        &mut self,
-                        _year: i32,
-                        _leaderboard_id: u64,
-                        _view_key: Option<&str>,
-                        _previous_leaderboard: &Leaderboard,
-                        _leaderboard: &Leaderboard,
-                        _changes: &Changes,
-                    ) -> Result<(), Self::Err> {
-                        Err(crate::Error::TestReportChangesError)
-                    }
-
-                    async fn report_error(
-                        &mut self,
-                        _year: i32,
-                        _leaderboard_id: u64,
-                        _view_key: Option<&str>,
-                        _error: &crate::Error,
-                    ) {
-                        self.errors += 1;
-                    }
-                }
-
-                let mut reporter = MockReporter::default();
+                let mut reporter = RecordingReporter::new().fail_report_changes(true);
 
                 let result = run_bot_from(
                     Some(mock_server.uri()),
@@ -1099,7 +2737,7 @@ mod tests {
                     result,
                     Err(crate::Error::Reporter(ReporterError::ReportChanges(_)))
                 );
-                assert_eq!(reporter.errors, 1);
+                assert_eq!(reporter.errors().count(), 1);
             }
 
             #[rstest]
@@ -1119,57 +2757,18 @@ mod tests {
                     .times(1)
                     .returning(move |_, _| Box::pin(ready(Ok((None, None)))));
                 storage
-                    .expect_save_error()
+                    .expect_save_error_state()
                     .with(
                         eq(TEST_YEAR),
                         eq(TEST_LEADERBOARD_ID),
-                        eq(crate::ErrorKind::Reporter(ReporterErrorKind::ReportFirstRun)),
+                        error_state_with_kind(crate::ErrorKind::Reporter(
+                            ReporterErrorKind::ReportFirstRun,
+                        )),
                     )
                     .times(1)
                     .returning(move |_, _, _| Box::pin(ready(Ok(()))));
 
-                #[derive(Debug, Default)]
-                struct MockReporter {
-                    pub errors: usize,
-                }
-
-                impl Reporter for MockReporter {
-                    type Err = crate::Error;
-
-                    async fn report_changes(
-                        &mut self,
-                        _year: i32,
-                        _leaderboard_id: u64,
-                        _view_key: Option<&str>,
-                        _previous_leaderboard: &Leaderboard,
-                        _leaderboard: &Leaderboard,
-                        _changes: &Changes,
-                    ) -> Result<(), Self::Err> {
-                        unreachable!("test without previous leaderboard should not report changes");
-                    }
-
-                    async fn report_first_run(
-                        &mut self,
-                        _year: i32,
-                        _leaderboard_id: u64,
-                        _view_key: Option<&str>,
-                        _leaderboard: &Leaderboard,
-                    ) -> Result<(), Self::Err> {
-                        Err(crate::Error::TestReportFirstRunError)
-                    }
-
-                    async fn report_error(
-                        &mut self,
-                        _year: i32,
-                        _leaderboard_id: u64,
-                        _view_key: Option<&str>,
-                        _error: &crate::Error,
-                    ) {
-                        self.errors += 1;
-                    }
-                }
-
-                let mut reporter = MockReporter::default();
+                let mut reporter = RecordingReporter::new().fail_report_first_run(true);
 
                 let result = run_bot_from(
                     Some(mock_server.uri()),
@@ -1183,7 +2782,7 @@ mod tests {
                     result,
                     Err(crate::Error::Reporter(ReporterError::ReportFirstRun(_)))
                 );
-                assert_eq!(reporter.errors, 1);
+                assert_eq!(reporter.errors().count(), 1);
             }
 
             #[rstest]
@@ -1191,7 +2790,7 @@ mod tests {
             #[test_log::test(tokio::test)]
             async fn save_updated_error(
                 config: MemoryConfig,
-                mut reporter: SpyReporter,
+                mut reporter: RecordingReporter,
                 #[future]
                 #[from(mock_server_with_leaderboard)]
                 #[with(leaderboard_with_new_member::default())]
@@ -1211,11 +2810,13 @@ mod tests {
                         Box::pin(ready(Err(crate::Error::TestSaveUpdatedError)))
                     });
                 storage
-                    .expect_save_error()
+                    .expect_save_error_state()
                     .with(
                         eq(TEST_YEAR),
                         eq(TEST_LEADERBOARD_ID),
-                        eq(crate::ErrorKind::Storage(StorageErrorKind::SaveSuccess)),
+                        error_state_with_kind(crate::ErrorKind::Storage(
+                            StorageErrorKind::SaveSuccess,
+                        )),
                     )
                     .times(1)
                     .returning(move |_, _, _| Box::pin(ready(Ok(()))));
@@ -1229,15 +2830,9 @@ mod tests {
                 )
                 .await;
                 assert_matches!(result, Err(crate::Error::Storage(StorageError::SaveSuccess(_))));
-                assert!(reporter.called());
-                assert_eq!(reporter.errors.len(), 1);
                 assert_eq!(
-                    reporter.errors[0],
-                    (
-                        TEST_YEAR,
-                        TEST_LEADERBOARD_ID,
-                        "failed to save leaderboard data: test".into(),
-                    )
+                    reported_errors(&reporter),
+                    vec![(TEST_YEAR, TEST_LEADERBOARD_ID, "failed to save leaderboard data: test")]
                 );
             }
 
@@ -1246,7 +2841,7 @@ mod tests {
             #[test_log::test(tokio::test)]
             async fn save_base_error(
                 config: MemoryConfig,
-                mut reporter: SpyReporter,
+                mut reporter: RecordingReporter,
                 #[future]
                 #[from(mock_server_with_leaderboard)]
                 #[with(base_leaderboard::default())]
@@ -1266,11 +2861,13 @@ mod tests {
                         Box::pin(ready(Err(crate::Error::TestSaveBaseError)))
                     });
                 storage
-                    .expect_save_error()
+                    .expect_save_error_state()
                     .with(
                         eq(TEST_YEAR),
                         eq(TEST_LEADERBOARD_ID),
-                        eq(crate::ErrorKind::Storage(StorageErrorKind::SaveSuccess)),
+                        error_state_with_kind(crate::ErrorKind::Storage(
+                            StorageErrorKind::SaveSuccess,
+                        )),
                     )
                     .times(1)
                     .returning(move |_, _, _| Box::pin(ready(Ok(()))));
@@ -1284,15 +2881,9 @@ mod tests {
                 )
                 .await;
                 assert_matches!(result, Err(crate::Error::Storage(StorageError::SaveSuccess(_))));
-                assert!(reporter.called());
-                assert_eq!(reporter.errors.len(), 1);
                 assert_eq!(
-                    reporter.errors[0],
-                    (
-                        TEST_YEAR,
-                        TEST_LEADERBOARD_ID,
-                        "failed to save leaderboard data: test".into(),
-                    )
+                    reported_errors(&reporter),
+                    vec![(TEST_YEAR, TEST_LEADERBOARD_ID, "failed to save leaderboard data: test")]
                 );
             }
 
@@ -1301,7 +2892,7 @@ mod tests {
             #[test_log::test(tokio::test)]
             async fn save_error_error(
                 config: MemoryConfig,
-                mut reporter: SpyReporter,
+                mut reporter: RecordingReporter,
                 #[future]
                 #[from(mock_server_with_leaderboard)]
                 mock_server: MockServer,
@@ -1315,11 +2906,13 @@ mod tests {
                         Box::pin(ready(Err(crate::Error::TestLoadPreviousError)))
                     });
                 storage
-                    .expect_save_error()
+                    .expect_save_error_state()
                     .with(
                         eq(TEST_YEAR),
                         eq(TEST_LEADERBOARD_ID),
-                        eq(crate::ErrorKind::Storage(StorageErrorKind::LoadPrevious)),
+                        error_state_with_kind(crate::ErrorKind::Storage(
+                            StorageErrorKind::LoadPrevious,
+                        )),
                     )
                     .times(1)
                     .returning(move |_, _, _| {
@@ -1335,20 +2928,17 @@ mod tests {
                 )
                 .await;
                 assert_matches!(result, Err(crate::Error::Storage(StorageError::LoadPrevious(_))));
-                assert!(reporter.called());
-                assert_eq!(reporter.errors.len(), 2);
                 assert_eq!(
-                    reporter.errors[0],
-                    (
-                        TEST_YEAR,
-                        TEST_LEADERBOARD_ID,
-                        "failed to load previous leaderboard data: test".into(),
-                    )
+                    reported_errors(&reporter),
+                    vec![
+                        (
+                            TEST_YEAR,
+                            TEST_LEADERBOARD_ID,
+                            "failed to load previous leaderboard data: test"
+                        ),
+                        (TEST_YEAR, TEST_LEADERBOARD_ID, "failed to save previous error: test"),
+                    ]
                 );
-                assert_eq!(
-                    reporter.errors[1],
-                    (TEST_YEAR, TEST_LEADERBOARD_ID, "failed to save previous error: test".into(),)
-                )
             }
 
             #[rstest]
@@ -1356,7 +2946,7 @@ mod tests {
             #[test_log::test(tokio::test)]
             async fn duplicate_error(
                 config: MemoryConfig,
-                mut reporter: SpyReporter,
+                mut reporter: RecordingReporter,
                 #[future]
                 #[from(mock_server_with_inaccessible_leaderboard)]
                 mock_server: MockServer,
@@ -1374,6 +2964,131 @@ mod tests {
                             )),
                         ))))
                     });
+                storage
+                    .expect_load_error_state()
+                    .with(eq(TEST_YEAR), eq(TEST_LEADERBOARD_ID))
+                    .times(1)
+                    .returning(|_, _| Box::pin(ready(Ok(None))));
+                storage
+                    .expect_save_error_state()
+                    .with(
+                        eq(TEST_YEAR),
+                        eq(TEST_LEADERBOARD_ID),
+                        function(|error_state: &ErrorState| error_state.last_notified.is_none()),
+                    )
+                    .times(1)
+                    .returning(move |_, _, _| Box::pin(ready(Ok(()))));
+
+                let result = run_bot_from(
+                    Some(mock_server.uri()),
+                    &config,
+                    &mut storage,
+                    &mut reporter,
+                    false,
+                )
+                .await;
+                assert_matches!(
+                    result,
+                    Err(crate::Error::Leaderboard(aoc_leaderboard::Error::NoAccess))
+                );
+                reporter.assert_not_called();
+            }
+
+            #[rstest]
+            #[case::never(RenotifyPolicy::never(), false)]
+            #[case::interval_not_elapsed(RenotifyPolicy::every(TimeDelta::days(3)), false)]
+            #[case::interval_elapsed(RenotifyPolicy::every(TimeDelta::days(1)), true)]
+            #[case::failures_not_reached(RenotifyPolicy::after_failures(5), false)]
+            #[case::failures_reached(RenotifyPolicy::after_failures(3), true)]
+            #[awt]
+            #[test_log::test(tokio::test)]
+            async fn ongoing_error(
+                mut config: MemoryConfig,
+                mut storage: MemoryStorage,
+                mut reporter: RecordingReporter,
+                #[future]
+                #[from(mock_server_with_inaccessible_leaderboard)]
+                mock_server: MockServer,
+                #[case] renotify_policy: RenotifyPolicy,
+                #[case] expect_renotify: bool,
+            ) {
+                let error_kind =
+                    crate::ErrorKind::Leaderboard(aoc_leaderboard::ErrorKind::NoAccess);
+                let first_seen = Utc::now() - TimeDelta::days(2);
+                let error_state = ErrorState {
+                    occurrences: 3,
+                    last_notified: Some(first_seen),
+                    ..ErrorState::new(error_kind, first_seen)
+                };
+                storage
+                    .save_error_state(TEST_YEAR, TEST_LEADERBOARD_ID, &error_state)
+                    .await
+                    .unwrap();
+                config.renotify_policy = renotify_policy;
+
+                let before = Utc::now();
+                let result = run_bot_from(
+                    Some(mock_server.uri()),
+                    &config,
+                    &mut storage,
+                    &mut reporter,
+                    false,
+                )
+                .await;
+                assert_matches!(
+                    result,
+                    Err(crate::Error::Leaderboard(aoc_leaderboard::Error::NoAccess))
+                );
+
+                if expect_renotify {
+                    reporter.assert_error_reported(TEST_YEAR, TEST_LEADERBOARD_ID, error_kind);
+                } else {
+                    reporter.assert_not_called();
+                }
+
+                let actual = storage
+                    .load_error_state(TEST_YEAR, TEST_LEADERBOARD_ID)
+                    .await
+                    .unwrap()
+                    .unwrap();
+                assert_eq!(actual.error_kind, error_kind);
+                assert_eq!(actual.first_seen, first_seen);
+                assert!(actual.last_seen >= before);
+                assert_eq!(actual.occurrences, 4);
+                assert_eq!(
+                    actual.last_message.as_deref(),
+                    Some("session does not have access to this leaderboard")
+                );
+                if expect_renotify {
+                    assert_matches!(actual.last_notified, Some(last_notified) => {
+                        assert!(last_notified >= before);
+                    });
+                } else {
+                    assert_eq!(actual.last_notified, Some(first_seen));
+                }
+            }
+
+            #[rstest]
+            #[awt]
+            #[test_log::test(tokio::test)]
+            async fn new_error_kind(
+                mut config: MemoryConfig,
+                mut storage: MemoryStorage,
+                mut reporter: RecordingReporter,
+                #[future]
+                #[from(mock_server_with_inaccessible_leaderboard)]
+                mock_server: MockServer,
+            ) {
+                let first_seen = Utc::now() - TimeDelta::days(2);
+                let error_state = ErrorState {
+                    last_notified: Some(first_seen),
+                    ..ErrorState::new(crate::ErrorKind::MissingField, first_seen)
+                };
+                storage
+                    .save_error_state(TEST_YEAR, TEST_LEADERBOARD_ID, &error_state)
+                    .await
+                    .unwrap();
+                config.renotify_policy = RenotifyPolicy::never();
 
                 let result = run_bot_from(
                     Some(mock_server.uri()),
@@ -1387,7 +3102,19 @@ mod tests {
                     result,
                     Err(crate::Error::Leaderboard(aoc_leaderboard::Error::NoAccess))
                 );
-                assert!(!reporter.called());
+
+                let error_kind =
+                    crate::ErrorKind::Leaderboard(aoc_leaderboard::ErrorKind::NoAccess);
+                reporter.assert_error_reported(TEST_YEAR, TEST_LEADERBOARD_ID, error_kind);
+
+                let actual = storage
+                    .load_error_state(TEST_YEAR, TEST_LEADERBOARD_ID)
+                    .await
+                    .unwrap()
+                    .unwrap();
+                assert_eq!(actual.error_kind, error_kind);
+                assert_eq!(actual.first_seen, first_seen);
+                assert_eq!(actual.occurrences, 2);
             }
         }
     }
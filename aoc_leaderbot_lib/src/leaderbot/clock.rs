@@ -0,0 +1,126 @@
+//! Abstraction of the current time.
+//!
+//! Time-dependent logic of the bot (like the [default year] of a config, error states or the
+//! [event window]) fetches the current time from a [`Clock`] returned by the bot's
+//! [`Config`]. By default, the [`SystemClock`] is used; a [`TestClock`] can be used instead to
+//! test behaviour at specific points in time (like the start of the Advent of Code event or the
+//! new year) deterministically.
+//!
+//! [default year]: crate::leaderbot::Config::year
+//! [event window]: crate::leaderbot::event::EventWindow
+//! [`Config`]: crate::leaderbot::Config
+
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+/// Trait implemented by sources of the current time.
+pub trait Clock: Debug + Send + Sync {
+    /// Returns the current time.
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// [`Clock`] returning the current system time.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// [`Clock`] whose time can be controlled.
+///
+/// The time only changes when [set](Self::set) or [advanced](Self::advance). Clones of a test
+/// clock share the same time, so a clone can be given to the bot while the original is used to
+/// control it.
+///
+/// ```
+/// # use aoc_leaderbot_lib::leaderbot::clock::{Clock, TestClock};
+/// # use chrono::{TimeDelta, TimeZone, Utc};
+/// let clock = TestClock::new(Utc.with_ymd_and_hms(2024, 11, 30, 23, 0, 0).unwrap());
+/// clock.advance(TimeDelta::hours(6));
+///
+/// assert_eq!(clock.now(), Utc.with_ymd_and_hms(2024, 12, 1, 5, 0, 0).unwrap());
+/// ```
+#[derive(Debug, Clone)]
+pub struct TestClock {
+    now: Arc<Mutex<DateTime<Utc>>>,
+}
+
+impl TestClock {
+    /// Creates a test clock set to the given time.
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self { now: Arc::new(Mutex::new(now)) }
+    }
+
+    /// Sets the current time of this clock.
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self
+            .now
+            .lock()
+            .expect("test clock mutex should not be poisoned") = now;
+    }
+
+    /// Moves the current time of this clock by the given delta.
+    pub fn advance(&self, delta: TimeDelta) {
+        *self
+            .now
+            .lock()
+            .expect("test clock mutex should not be poisoned") += delta;
+    }
+}
+
+impl Clock for TestClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self
+            .now
+            .lock()
+            .expect("test clock mutex should not be poisoned")
+    }
+}
+
+/// Shared reference to a [`Clock`] that can be stored in a config.
+///
+/// Two shared clocks are equal if they refer to the same clock. Defaults to the [`SystemClock`].
+#[derive(Debug, Clone)]
+pub struct SharedClock(Arc<dyn Clock>);
+
+impl SharedClock {
+    /// Wraps the given clock.
+    pub fn new<C>(clock: C) -> Self
+    where
+        C: Clock + 'static,
+    {
+        Self(Arc::new(clock))
+    }
+}
+
+impl Default for SharedClock {
+    fn default() -> Self {
+        Self::new(SystemClock)
+    }
+}
+
+impl Clock for SharedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0.now()
+    }
+}
+
+impl PartialEq for SharedClock {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for SharedClock {}
+
+impl Hash for SharedClock {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.0).cast::<()>().hash(state);
+    }
+}
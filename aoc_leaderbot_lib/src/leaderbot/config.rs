@@ -2,5 +2,7 @@
 
 #[cfg(feature = "config-env")]
 pub mod env;
+#[cfg(feature = "config-file")]
+pub mod file;
 #[cfg(feature = "config-mem")]
 pub mod mem;
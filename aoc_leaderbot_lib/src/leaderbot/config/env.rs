@@ -1,13 +1,23 @@
 //! Bot config loading values from the environment.
 
+use std::collections::BTreeMap;
 use std::fmt::Debug;
+use std::num::ParseIntError;
+use std::str::FromStr;
 
-use aoc_leaderboard::aoc::LeaderboardCredentials;
+use anyhow::anyhow;
+use aoc_leaderboard::aoc::{LeaderboardCredentials, LeaderboardCredentialsKind};
+use chrono::TimeDelta;
 
 use crate::detail::{env_var, int_env_var};
 use crate::error::EnvVarError;
-use crate::leaderbot::Config;
 use crate::leaderbot::config::mem::MemoryConfig;
+use crate::leaderbot::credentials::file::FileCredentialsProvider;
+use crate::leaderbot::event::EventWindow;
+use crate::leaderbot::heartbeat::HeartbeatPolicy;
+use crate::leaderbot::members::MemberSettings;
+use crate::leaderbot::team::Team;
+use crate::leaderbot::{Config, MergedLeaderboard, RenotifyPolicy};
 
 /// Environment variable name suffix for `year`. See [`get_env_config`].
 pub const ENV_CONFIG_YEAR_SUFFIX: &str = "YEAR";
@@ -21,17 +31,103 @@ pub const ENV_CONFIG_VIEW_KEY_SUFFIX: &str = "VIEW_KEY";
 /// Environment variable name suffix for `session_cookie`. See [`get_env_config`].
 pub const ENV_CONFIG_SESSION_COOKIE_SUFFIX: &str = "SESSION_COOKIE";
 
+/// Environment variable name suffix for the path of a file containing the `view_key`.
+/// See [`get_env_config`].
+pub const ENV_CONFIG_VIEW_KEY_FILE_SUFFIX: &str = "VIEW_KEY_FILE";
+
+/// Environment variable name suffix for the path of a file containing the `session_cookie`.
+/// See [`get_env_config`].
+pub const ENV_CONFIG_SESSION_COOKIE_FILE_SUFFIX: &str = "SESSION_COOKIE_FILE";
+
+/// Environment variable name suffix for `renotify_policy.interval`, in minutes. See [`get_env_config`].
+pub const ENV_CONFIG_RENOTIFY_INTERVAL_MINUTES_SUFFIX: &str = "RENOTIFY_INTERVAL_MINUTES";
+
+/// Environment variable name suffix for `renotify_policy.failures`. See [`get_env_config`].
+pub const ENV_CONFIG_RENOTIFY_AFTER_FAILURES_SUFFIX: &str = "RENOTIFY_AFTER_FAILURES";
+
+/// Environment variable name suffix for `event_window.grace_period`, in days. See [`get_env_config`].
+pub const ENV_CONFIG_EVENT_GRACE_DAYS_SUFFIX: &str = "EVENT_GRACE_DAYS";
+
+/// Environment variable name suffix for `heartbeat_policy.max_silence`, in minutes.
+/// See [`get_env_config`].
+pub const ENV_CONFIG_HEARTBEAT_MAX_SILENCE_MINUTES_SUFFIX: &str = "HEARTBEAT_MAX_SILENCE_MINUTES";
+
+/// Environment variable name suffix for `heartbeat_policy.interval`, in minutes.
+/// See [`get_env_config`].
+pub const ENV_CONFIG_HEARTBEAT_INTERVAL_MINUTES_SUFFIX: &str = "HEARTBEAT_INTERVAL_MINUTES";
+
+/// Environment variable name suffix for `merged_leaderboards`, as a comma-separated list of
+/// leaderboard IDs. See [`get_env_config`].
+pub const ENV_CONFIG_MERGED_LEADERBOARD_IDS_SUFFIX: &str = "MERGED_LEADERBOARD_IDS";
+
+/// Environment variable name suffix for `teams`. See [`get_env_config`].
+pub const ENV_CONFIG_TEAMS_SUFFIX: &str = "TEAMS";
+
+/// Environment variable name suffix for `member_settings.aliases`. See [`get_env_config`].
+pub const ENV_CONFIG_MEMBER_ALIASES_SUFFIX: &str = "MEMBER_ALIASES";
+
+/// Environment variable name suffix for `member_settings.ignored`, as a comma-separated list of
+/// member IDs. See [`get_env_config`].
+pub const ENV_CONFIG_IGNORED_MEMBER_IDS_SUFFIX: &str = "IGNORED_MEMBER_IDS";
+
+/// Environment variable name suffix for `member_settings.opted_out`, as a comma-separated list of
+/// member IDs. See [`get_env_config`].
+pub const ENV_CONFIG_OPTED_OUT_MEMBER_IDS_SUFFIX: &str = "OPTED_OUT_MEMBER_IDS";
+
+/// Environment variable name suffix for `member_settings.pseudonymize`. See [`get_env_config`].
+pub const ENV_CONFIG_PSEUDONYMIZE_MEMBERS_SUFFIX: &str = "PSEUDONYMIZE_MEMBERS";
+
 /// Loads bot config values from the environment.
 ///
 /// The following environment variables are used:
 ///
-/// | Env var name             | Config field                        | Default value |
-/// |--------------------------|-------------------------------------|---------------|
-/// | `{prefix}YEAR`           | `year`                              | Current year  |
-/// | `{prefix}LEADERBOARD_ID` | `leaderboard_id`                    | -             |
-/// | `{prefix}VIEW_KEY`       | `credentials` (as [view key])       | -             |
-/// | `{prefix}SESSION_COOKIE` | `credentials` (as [session cookie]) | -             |
+/// | Env var name                            | Config field                             | Default value |
+/// |-----------------------------------------|------------------------------------------|---------------|
+/// | `{prefix}YEAR`                          | `year`                                   | [Event year]  |
+/// | `{prefix}LEADERBOARD_ID`                | `leaderboard_id`                         | -             |
+/// | `{prefix}VIEW_KEY`                      | `credentials` (as [view key])            | -             |
+/// | `{prefix}SESSION_COOKIE`                | `credentials` (as [session cookie])      | -             |
+/// | `{prefix}VIEW_KEY_FILE`                 | `credentials` (file with view key)       | -             |
+/// | `{prefix}SESSION_COOKIE_FILE`           | `credentials` (file with cookie)         | -             |
+/// | `{prefix}RENOTIFY_INTERVAL_MINUTES`     | `renotify_policy.interval` (minutes)     | -             |
+/// | `{prefix}RENOTIFY_AFTER_FAILURES`       | `renotify_policy.failures`               | -             |
+/// | `{prefix}EVENT_GRACE_DAYS`              | `event_window.grace_period` (days)       | -             |
+/// | `{prefix}HEARTBEAT_MAX_SILENCE_MINUTES` | `heartbeat_policy.max_silence` (minutes) | -             |
+/// | `{prefix}HEARTBEAT_INTERVAL_MINUTES`    | `heartbeat_policy.interval` (minutes)    | -             |
+/// | `{prefix}MERGED_LEADERBOARD_IDS`        | `merged_leaderboards` (IDs, `,`-sep.)    | -             |
+/// | `{prefix}TEAMS`                         | `teams` (see below)                      | -             |
+/// | `{prefix}MEMBER_ALIASES`                | `member_settings.aliases` (see below)    | -             |
+/// | `{prefix}IGNORED_MEMBER_IDS`            | `member_settings.ignored` (`,`-sep.)     | -             |
+/// | `{prefix}OPTED_OUT_MEMBER_IDS`          | `member_settings.opted_out` (`,`-sep.)   | -             |
+/// | `{prefix}PSEUDONYMIZE_MEMBERS`          | `member_settings.pseudonymize`           | `false`       |
+///
+/// Credentials are loaded from the first variable set among `{prefix}VIEW_KEY`,
+/// `{prefix}VIEW_KEY_FILE`, `{prefix}SESSION_COOKIE` and `{prefix}SESSION_COOKIE_FILE`. The
+/// `_FILE` variants contain the path of a file storing the secret (like a Docker or Kubernetes
+/// secret), which is read via a [`FileCredentialsProvider`]. To fetch credentials from another
+/// source, use [`get_env_config_with_credentials`].
+///
+/// If `{prefix}EVENT_GRACE_DAYS` is set, the config's [`event_window`] skips bot runs outside the
+/// Advent of Code event window.
+///
+/// Leaderboards listed in `{prefix}MERGED_LEADERBOARD_IDS` are [merged] with the monitored
+/// leaderboard and fetched using the same credentials.
+///
+/// `{prefix}TEAMS` lists [teams] separated by `;`, each in the form `name:id1,id2,...`
+/// (for example: `Dev:123,456;Ops:789`).
+///
+/// `{prefix}MEMBER_ALIASES` lists member [aliases] separated by `;`, each in the form `id:alias`
+/// (for example: `1234:Alice;5678:Bob`).
+///
+/// `{prefix}PSEUDONYMIZE_MEMBERS` must be either `true` or `false`; when `true`, member names are
+/// replaced with [pseudonyms].
 ///
+/// [Event year]: crate::leaderbot::event::current_event_year
+/// [`event_window`]: Config::event_window
+/// [merged]: Config::merged_leaderboards
+/// [teams]: Config::teams
+/// [aliases]: crate::leaderbot::members::MemberSettings::aliases
+/// [pseudonyms]: crate::leaderbot::members::pseudonym
 /// [view key]: LeaderboardCredentials::ViewKey
 /// [session cookie]: LeaderboardCredentials::SessionCookie
 #[cfg_attr(not(coverage), tracing::instrument(level = "trace", err))]
@@ -39,31 +135,246 @@ pub fn get_env_config<S>(env_var_prefix: S) -> crate::Result<impl Config + Send
 where
     S: AsRef<str> + Debug,
 {
-    let env_var_prefix = env_var_prefix.as_ref();
+    load_env_config(env_var_prefix.as_ref(), None)
+}
+
+/// Loads bot config values from the environment, using the given credentials.
+///
+/// Works like [`get_env_config`], except that credentials are not loaded from the environment.
+/// Useful to use credentials fetched from a [`CredentialsProvider`] (see [`provide_credentials`]).
+///
+/// [`CredentialsProvider`]: crate::leaderbot::CredentialsProvider
+/// [`provide_credentials`]: crate::leaderbot::provide_credentials
+#[cfg_attr(not(coverage), tracing::instrument(level = "trace", err))]
+pub fn get_env_config_with_credentials<S>(
+    env_var_prefix: S,
+    credentials: LeaderboardCredentials,
+) -> crate::Result<impl Config + Send + Debug>
+where
+    S: AsRef<str> + Debug,
+{
+    load_env_config(env_var_prefix.as_ref(), Some(credentials))
+}
+
+fn load_env_config(
+    env_var_prefix: &str,
+    credentials: Option<LeaderboardCredentials>,
+) -> crate::Result<MemoryConfig> {
     let var_name = |name| format!("{env_var_prefix}{name}");
 
-    let year = match int_env_var(var_name(ENV_CONFIG_YEAR_SUFFIX)) {
-        Ok(year) => Some(year),
-        Err(crate::Error::Env { source: EnvVarError::NotPresent, .. }) => None,
-        Err(err) => return Err(err),
+    let year: Option<i32> = optional_int_env_var(var_name(ENV_CONFIG_YEAR_SUFFIX))?;
+    let renotify_policy = RenotifyPolicy {
+        interval: optional_int_env_var(var_name(ENV_CONFIG_RENOTIFY_INTERVAL_MINUTES_SUFFIX))?
+            .map(TimeDelta::minutes),
+        failures: optional_int_env_var(var_name(ENV_CONFIG_RENOTIFY_AFTER_FAILURES_SUFFIX))?,
+    };
+    let event_window = optional_int_env_var(var_name(ENV_CONFIG_EVENT_GRACE_DAYS_SUFFIX))?
+        .map(|days| EventWindow::new(TimeDelta::days(days)));
+    let heartbeat_policy = HeartbeatPolicy {
+        max_silence: optional_int_env_var(var_name(
+            ENV_CONFIG_HEARTBEAT_MAX_SILENCE_MINUTES_SUFFIX,
+        ))?
+        .map(TimeDelta::minutes),
+        interval: optional_int_env_var(var_name(ENV_CONFIG_HEARTBEAT_INTERVAL_MINUTES_SUFFIX))?
+            .map(TimeDelta::minutes),
+    };
+    let merged_leaderboards =
+        optional_int_list_env_var(var_name(ENV_CONFIG_MERGED_LEADERBOARD_IDS_SUFFIX))?
+            .unwrap_or_default()
+            .into_iter()
+            .map(MergedLeaderboard::new)
+            .collect::<Vec<_>>();
+    let teams = optional_teams_env_var(var_name(ENV_CONFIG_TEAMS_SUFFIX))?.unwrap_or_default();
+    let member_settings = MemberSettings {
+        aliases: optional_aliases_env_var(var_name(ENV_CONFIG_MEMBER_ALIASES_SUFFIX))?
+            .unwrap_or_default(),
+        ignored: optional_int_list_env_var(var_name(ENV_CONFIG_IGNORED_MEMBER_IDS_SUFFIX))?
+            .unwrap_or_default()
+            .into_iter()
+            .collect(),
+        opted_out: optional_int_list_env_var(var_name(ENV_CONFIG_OPTED_OUT_MEMBER_IDS_SUFFIX))?
+            .unwrap_or_default()
+            .into_iter()
+            .collect(),
+        pseudonymize: optional_bool_env_var(var_name(ENV_CONFIG_PSEUDONYMIZE_MEMBERS_SUFFIX))?
+            .unwrap_or_default(),
     };
 
-    let credentials = match env_var(var_name(ENV_CONFIG_VIEW_KEY_SUFFIX)) {
-        Ok(view_key) => LeaderboardCredentials::ViewKey(view_key),
-        Err(crate::Error::Env { source: EnvVarError::NotPresent, .. }) => {
-            LeaderboardCredentials::SessionCookie(env_var(var_name(
-                ENV_CONFIG_SESSION_COOKIE_SUFFIX,
-            ))?)
+    let credentials = match credentials {
+        Some(credentials) => credentials,
+        None => match optional_credentials_env_var(
+            var_name(ENV_CONFIG_VIEW_KEY_SUFFIX),
+            var_name(ENV_CONFIG_VIEW_KEY_FILE_SUFFIX),
+            LeaderboardCredentialsKind::ViewKey,
+        )? {
+            Some(credentials) => credentials,
+            None => optional_credentials_env_var(
+                var_name(ENV_CONFIG_SESSION_COOKIE_SUFFIX),
+                var_name(ENV_CONFIG_SESSION_COOKIE_FILE_SUFFIX),
+                LeaderboardCredentialsKind::SessionCookie,
+            )?
+            .ok_or_else(|| crate::Error::Env {
+                var_name: var_name(ENV_CONFIG_SESSION_COOKIE_SUFFIX),
+                source: EnvVarError::NotPresent,
+            })?,
         },
-        Err(err) => return Err(err),
     };
 
-    let mut builder = MemoryConfig::builder();
-    if let Some(year) = year {
-        builder.year(year);
-    }
-    builder
+    MemoryConfig::builder()
+        .year(year)
         .leaderboard_id(int_env_var(var_name(ENV_CONFIG_LEADERBOARD_ID_SUFFIX))?)
         .credentials(credentials)
+        .renotify_policy(renotify_policy)
+        .event_window(event_window)
+        .heartbeat_policy(heartbeat_policy)
+        .merged_leaderboards(merged_leaderboards)
+        .teams(teams)
+        .member_settings(member_settings)
         .build()
 }
+
+fn optional_credentials_env_var(
+    key: String,
+    file_key: String,
+    kind: LeaderboardCredentialsKind,
+) -> crate::Result<Option<LeaderboardCredentials>> {
+    match env_var(key) {
+        Ok(secret) => {
+            return Ok(Some(match kind {
+                LeaderboardCredentialsKind::ViewKey => LeaderboardCredentials::ViewKey(secret),
+                LeaderboardCredentialsKind::SessionCookie => {
+                    LeaderboardCredentials::SessionCookie(secret)
+                },
+            }));
+        },
+        Err(crate::Error::Env { source: EnvVarError::NotPresent, .. }) => (),
+        Err(err) => return Err(err),
+    }
+
+    match env_var(file_key) {
+        Ok(path) => FileCredentialsProvider::new(path, kind)
+            .read()
+            .map(Some)
+            .map_err(|err| crate::Error::Credentials(anyhow!(err))),
+        Err(crate::Error::Env { source: EnvVarError::NotPresent, .. }) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+fn optional_int_env_var<T>(key: String) -> crate::Result<Option<T>>
+where
+    T: FromStr<Err = ParseIntError>,
+{
+    match int_env_var(key) {
+        Ok(value) => Ok(Some(value)),
+        Err(crate::Error::Env { source: EnvVarError::NotPresent, .. }) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+fn optional_bool_env_var(key: String) -> crate::Result<Option<bool>> {
+    let value = match env_var(&key) {
+        Ok(value) => value,
+        Err(crate::Error::Env { source: EnvVarError::NotPresent, .. }) => return Ok(None),
+        Err(err) => return Err(err),
+    };
+
+    match value.trim().to_ascii_lowercase().as_str() {
+        "true" => Ok(Some(true)),
+        "false" => Ok(Some(false)),
+        _ => Err(crate::Error::Env {
+            var_name: key,
+            source: EnvVarError::InvalidFormat { actual: value, expected: "true or false" },
+        }),
+    }
+}
+
+fn optional_int_list_env_var<T>(key: String) -> crate::Result<Option<Vec<T>>>
+where
+    T: FromStr<Err = ParseIntError>,
+{
+    let value = match env_var(&key) {
+        Ok(value) => value,
+        Err(crate::Error::Env { source: EnvVarError::NotPresent, .. }) => return Ok(None),
+        Err(err) => return Err(err),
+    };
+
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| {
+            item.parse().map_err(|source| crate::Error::Env {
+                var_name: key.clone(),
+                source: EnvVarError::IntExpected { actual: item.into(), source },
+            })
+        })
+        .collect::<crate::Result<_>>()
+        .map(Some)
+}
+
+fn optional_teams_env_var(key: String) -> crate::Result<Option<Vec<Team>>> {
+    let value = match env_var(&key) {
+        Ok(value) => value,
+        Err(crate::Error::Env { source: EnvVarError::NotPresent, .. }) => return Ok(None),
+        Err(err) => return Err(err),
+    };
+
+    let parse_team = |team: &str| {
+        let (name, members) = team.split_once(':')?;
+        let members = members
+            .split(',')
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .map(|id| id.parse().ok())
+            .collect::<Option<Vec<u64>>>()?;
+
+        let name = name.trim();
+        (!name.is_empty()).then(|| Team::new(name, members))
+    };
+
+    value
+        .split(';')
+        .map(str::trim)
+        .filter(|team| !team.is_empty())
+        .map(|team| {
+            parse_team(team).ok_or_else(|| crate::Error::Env {
+                var_name: key.clone(),
+                source: EnvVarError::InvalidFormat {
+                    actual: team.into(),
+                    expected: "name:id1,id2,...",
+                },
+            })
+        })
+        .collect::<crate::Result<_>>()
+        .map(Some)
+}
+
+fn optional_aliases_env_var(key: String) -> crate::Result<Option<BTreeMap<u64, String>>> {
+    let value = match env_var(&key) {
+        Ok(value) => value,
+        Err(crate::Error::Env { source: EnvVarError::NotPresent, .. }) => return Ok(None),
+        Err(err) => return Err(err),
+    };
+
+    let parse_alias = |alias: &str| {
+        let (id, alias) = alias.split_once(':')?;
+        let id = id.trim().parse().ok()?;
+
+        let alias = alias.trim();
+        (!alias.is_empty()).then(|| (id, alias.to_string()))
+    };
+
+    value
+        .split(';')
+        .map(str::trim)
+        .filter(|alias| !alias.is_empty())
+        .map(|alias| {
+            parse_alias(alias).ok_or_else(|| crate::Error::Env {
+                var_name: key.clone(),
+                source: EnvVarError::InvalidFormat { actual: alias.into(), expected: "id:alias" },
+            })
+        })
+        .collect::<crate::Result<_>>()
+        .map(Some)
+}
@@ -0,0 +1,498 @@
+//! Bot config loaded from a structured file.
+//!
+//! A config file describes one or more leaderboards to monitor, along with their credentials
+//! and options, as well as reporter-specific settings. Both [TOML] and [YAML] are supported.
+//!
+//! # Example
+//!
+//! ```toml
+//! [[leaderboards]]
+//! year = 2024
+//! leaderboard_id = 12345
+//! view_key = "${AOC_VIEW_KEY}"
+//! renotify_interval_minutes = 60
+//! event_grace_days = 7
+//!
+//! [[leaderboards.merged_leaderboards]]
+//! leaderboard_id = 67890
+//! session_cookie = "${AOC_SESSION}"
+//!
+//! [[leaderboards.teams]]
+//! name = "Dev"
+//! members = [1, 2, 3]
+//!
+//! [leaderboards.members]
+//! aliases = { 4 = "Zaphod" }
+//! ignored = [5]
+//! opted_out = [6]
+//! pseudonymize = false
+//!
+//! [reporters.slack]
+//! webhook_url = "${SLACK_WEBHOOK_URL}"
+//! channel = "#aoc"
+//! ```
+//!
+//! # Leaderboard fields
+//!
+//! | Field                           | Config field                        | Default value |
+//! |---------------------------------|-------------------------------------|---------------|
+//! | `year`                          | `year`                              | [Event year]  |
+//! | `leaderboard_id`                | `leaderboard_id`                    | -             |
+//! | `view_key`                      | `credentials` (as [view key])       | -             |
+//! | `session_cookie`                | `credentials` (as [session cookie]) | -             |
+//! | `renotify_interval_minutes`     | `renotify_policy.interval`          | -             |
+//! | `renotify_after_failures`       | `renotify_policy.failures`          | -             |
+//! | `event_grace_days`              | `event_window.grace_period`         | -             |
+//! | `heartbeat_max_silence_minutes` | `heartbeat_policy.max_silence`      | -             |
+//! | `heartbeat_interval_minutes`    | `heartbeat_policy.interval`         | -             |
+//! | `merged_leaderboards`           | `merged_leaderboards`               | -             |
+//! | `teams`                         | `teams`                             | -             |
+//! | `members`                       | `member_settings`                   | -             |
+//!
+//! Exactly one of `view_key` or `session_cookie` must be specified for each leaderboard.
+//! Merged leaderboards can specify either one; if they do not, the credentials of the monitored
+//! leaderboard are used.
+//!
+//! # Environment variables
+//!
+//! To avoid storing secrets in the config file, references to environment variables in the form
+//! `${VAR_NAME}` are replaced by the variable's value in string values, once the file is parsed.
+//! Because values are never inserted in the file's content, they can contain any character
+//! (including quotes or newlines) without escaping. References in keys or non-string values are
+//! not replaced. Use `$${` to insert a literal `${`.
+//!
+//! [TOML]: https://toml.io/
+//! [YAML]: https://yaml.org/
+//! [Event year]: crate::leaderbot::event::current_event_year
+//! [view key]: LeaderboardCredentials::ViewKey
+//! [session cookie]: LeaderboardCredentials::SessionCookie
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt::Debug;
+use std::path::Path;
+use std::{env, fs, mem};
+
+use aoc_leaderboard::aoc::LeaderboardCredentials;
+use chrono::TimeDelta;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ConfigFileError;
+use crate::leaderbot::clock::SharedClock;
+use crate::leaderbot::config::mem::MemoryConfig;
+use crate::leaderbot::event::EventWindow;
+use crate::leaderbot::heartbeat::HeartbeatPolicy;
+use crate::leaderbot::members::MemberSettings;
+use crate::leaderbot::team::Team;
+use crate::leaderbot::{Config, MergedLeaderboard, RenotifyPolicy};
+
+/// Format of a config file.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ConfigFileFormat {
+    /// [TOML](https://toml.io/) format.
+    Toml,
+
+    /// [YAML](https://yaml.org/) format.
+    Yaml,
+}
+
+impl ConfigFileFormat {
+    /// Determines the format of a config file from its extension.
+    ///
+    /// Returns `None` if the extension is not `.toml`, `.yaml` or `.yml`.
+    pub fn from_path<P>(path: P) -> Option<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let extension = path.as_ref().extension()?.to_str()?;
+
+        match extension.to_ascii_lowercase().as_str() {
+            "toml" => Some(Self::Toml),
+            "yaml" | "yml" => Some(Self::Yaml),
+            _ => None,
+        }
+    }
+}
+
+/// Bot config loaded from a config file.
+///
+/// See [module documentation](self) for details on the file's content.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigFile {
+    /// Configs of the leaderboards to monitor.
+    pub leaderboards: Vec<MemoryConfig>,
+
+    /// Reporter-specific settings, by reporter name.
+    ///
+    /// Use [`reporter`](Self::reporter) to deserialize them.
+    pub reporters: BTreeMap<String, serde_json::Value>,
+}
+
+impl ConfigFile {
+    /// Loads a config file from the given path.
+    ///
+    /// The file's format is determined from its extension (see [`ConfigFileFormat::from_path`]).
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::ConfigFile`]: if the file cannot be read, parsed or validated
+    ///
+    /// [`Error::ConfigFile`]: crate::Error::ConfigFile
+    #[cfg_attr(not(coverage), tracing::instrument(level = "trace", err))]
+    pub fn load<P>(path: P) -> crate::Result<Self>
+    where
+        P: AsRef<Path> + Debug,
+    {
+        let path = path.as_ref();
+        let format = ConfigFileFormat::from_path(path)
+            .ok_or_else(|| ConfigFileError::UnsupportedFormat { path: path.into() })?;
+        let content = fs::read_to_string(path)
+            .map_err(|source| ConfigFileError::Io { path: path.into(), source })?;
+
+        Self::parse(&content, format)
+    }
+
+    /// Parses the content of a config file in the given format.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::ConfigFile`]: if the content cannot be parsed or validated
+    ///
+    /// [`Error::ConfigFile`]: crate::Error::ConfigFile
+    #[cfg_attr(not(coverage), tracing::instrument(level = "trace", skip(content), err))]
+    pub fn parse(content: &str, format: ConfigFileFormat) -> crate::Result<Self> {
+        let raw: RawConfigFile = match format {
+            ConfigFileFormat::Toml => toml::from_str(content).map_err(|err| {
+                let (line, column) = err
+                    .span()
+                    .map(|span| line_and_column(content, span.start))
+                    .unzip();
+                ConfigFileError::Syntax { line, column, message: err.message().trim().into() }
+            })?,
+            ConfigFileFormat::Yaml => serde_norway::from_str(content).map_err(|err| {
+                let location = err.location();
+                let (line, column) = location
+                    .as_ref()
+                    .map(|location| (location.line(), location.column()))
+                    .unzip();
+
+                let message = err.to_string();
+                let message = location
+                    .and_then(|location| {
+                        message.strip_suffix(&format!(
+                            " at line {} column {}",
+                            location.line(),
+                            location.column()
+                        ))
+                    })
+                    .unwrap_or(&message)
+                    .into();
+                ConfigFileError::Syntax { line, column, message }
+            })?,
+        };
+
+        Ok(raw.validate()?)
+    }
+
+    /// Returns the settings of the reporter with the given name, deserialized into `T`.
+    ///
+    /// Returns `None` if the config file does not contain settings for this reporter.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::ConfigFile`]: if the reporter's settings cannot be deserialized into `T`
+    ///
+    /// [`Error::ConfigFile`]: crate::Error::ConfigFile
+    pub fn reporter<T>(&self, name: &str) -> crate::Result<Option<T>>
+    where
+        T: DeserializeOwned,
+    {
+        self.reporters
+            .get(name)
+            .map(|settings| {
+                serde_json::from_value(settings.clone()).map_err(|err| {
+                    ConfigFileError::Invalid {
+                        field: format!("reporters.{name}"),
+                        message: err.to_string(),
+                    }
+                    .into()
+                })
+            })
+            .transpose()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawConfigFile {
+    #[serde(default)]
+    leaderboards: Vec<RawLeaderboard>,
+
+    #[serde(default)]
+    reporters: BTreeMap<String, serde_json::Value>,
+}
+
+impl RawConfigFile {
+    fn validate(self) -> Result<ConfigFile, ConfigFileError> {
+        if self.leaderboards.is_empty() {
+            return Err(invalid("leaderboards", "at least one leaderboard must be specified"));
+        }
+
+        let mut seen = HashSet::new();
+        let leaderboards = self
+            .leaderboards
+            .into_iter()
+            .enumerate()
+            .map(|(index, leaderboard)| {
+                let field = format!("leaderboards[{index}]");
+                let config = leaderboard.validate(&field)?;
+
+                match seen.insert((config.year(), config.leaderboard_id)) {
+                    true => Ok(config),
+                    false => Err(invalid(
+                        format!("{field}.leaderboard_id"),
+                        format!(
+                            "leaderboard {} is specified more than once for year {}",
+                            config.leaderboard_id,
+                            config.year()
+                        ),
+                    )),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+
+        let reporters = self
+            .reporters
+            .into_iter()
+            .map(|(name, mut settings)| {
+                interpolate_value(&format!("reporters.{name}"), &mut settings)?;
+                Ok((name, settings))
+            })
+            .collect::<Result<_, ConfigFileError>>()?;
+
+        Ok(ConfigFile { leaderboards, reporters })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawLeaderboard {
+    year: Option<i32>,
+    leaderboard_id: u64,
+    view_key: Option<String>,
+    session_cookie: Option<String>,
+    renotify_interval_minutes: Option<u32>,
+    renotify_after_failures: Option<u64>,
+    event_grace_days: Option<u32>,
+    heartbeat_max_silence_minutes: Option<u32>,
+    heartbeat_interval_minutes: Option<u32>,
+    #[serde(default)]
+    merged_leaderboards: Vec<RawMergedLeaderboard>,
+    #[serde(default)]
+    teams: Vec<Team>,
+    #[serde(default)]
+    members: RawMemberSettings,
+}
+
+impl RawLeaderboard {
+    fn validate(self, field: &str) -> Result<MemoryConfig, ConfigFileError> {
+        let credentials = parse_credentials(field, self.view_key, self.session_cookie)?
+            .ok_or_else(|| invalid(field, "one of view_key or session_cookie must be specified"))?;
+
+        let merged_leaderboards = self
+            .merged_leaderboards
+            .into_iter()
+            .enumerate()
+            .map(|(index, merged)| {
+                let field = format!("{field}.merged_leaderboards[{index}]");
+                let credentials =
+                    parse_credentials(&field, merged.view_key, merged.session_cookie)?;
+
+                Ok(MergedLeaderboard { leaderboard_id: merged.leaderboard_id, credentials })
+            })
+            .collect::<Result<_, ConfigFileError>>()?;
+
+        let teams = self
+            .teams
+            .into_iter()
+            .enumerate()
+            .map(|(index, team)| {
+                let field = format!("{field}.teams[{index}].name");
+                let name = interpolate_env_vars(&field, team.name)?;
+                if name.trim().is_empty() {
+                    return Err(invalid(field, "team name cannot be empty"));
+                }
+
+                Ok(Team { name, ..team })
+            })
+            .collect::<Result<_, ConfigFileError>>()?;
+
+        Ok(MemoryConfig {
+            year: self.year,
+            leaderboard_id: self.leaderboard_id,
+            credentials,
+            renotify_policy: RenotifyPolicy {
+                interval: self
+                    .renotify_interval_minutes
+                    .map(|minutes| TimeDelta::minutes(minutes.into())),
+                failures: self.renotify_after_failures,
+            },
+            event_window: self
+                .event_grace_days
+                .map(|days| EventWindow::new(TimeDelta::days(days.into()))),
+            heartbeat_policy: HeartbeatPolicy {
+                max_silence: self
+                    .heartbeat_max_silence_minutes
+                    .map(|minutes| TimeDelta::minutes(minutes.into())),
+                interval: self
+                    .heartbeat_interval_minutes
+                    .map(|minutes| TimeDelta::minutes(minutes.into())),
+            },
+            merged_leaderboards,
+            teams,
+            member_settings: self.members.validate(&format!("{field}.members"))?,
+            clock: SharedClock::default(),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawMergedLeaderboard {
+    leaderboard_id: u64,
+    view_key: Option<String>,
+    session_cookie: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawMemberSettings {
+    // Keys of TOML tables are always strings, so member IDs are parsed during validation.
+    #[serde(default)]
+    aliases: BTreeMap<String, String>,
+    #[serde(default)]
+    ignored: BTreeSet<u64>,
+    #[serde(default)]
+    opted_out: BTreeSet<u64>,
+    #[serde(default)]
+    pseudonymize: bool,
+}
+
+impl RawMemberSettings {
+    fn validate(self, field: &str) -> Result<MemberSettings, ConfigFileError> {
+        let aliases = self
+            .aliases
+            .into_iter()
+            .map(|(id, alias)| {
+                let field = format!("{field}.aliases.{id}");
+                match id.trim().parse() {
+                    Ok(id) => Ok((id, interpolate_env_vars(&field, alias)?)),
+                    Err(err) => Err(invalid(field, format!("invalid member ID: {err}"))),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(MemberSettings {
+            aliases,
+            ignored: self.ignored,
+            opted_out: self.opted_out,
+            pseudonymize: self.pseudonymize,
+        })
+    }
+}
+
+fn parse_credentials(
+    field: &str,
+    view_key: Option<String>,
+    session_cookie: Option<String>,
+) -> Result<Option<LeaderboardCredentials>, ConfigFileError> {
+    let view_key = view_key
+        .map(|value| interpolate_env_vars(&format!("{field}.view_key"), value))
+        .transpose()?;
+    let session_cookie = session_cookie
+        .map(|value| interpolate_env_vars(&format!("{field}.session_cookie"), value))
+        .transpose()?;
+
+    match (view_key, session_cookie) {
+        (Some(view_key), None) => Ok(Some(LeaderboardCredentials::ViewKey(view_key))),
+        (None, Some(session_cookie)) => {
+            Ok(Some(LeaderboardCredentials::SessionCookie(session_cookie)))
+        },
+        (None, None) => Ok(None),
+        (Some(_), Some(_)) => {
+            Err(invalid(field, "only one of view_key or session_cookie can be specified"))
+        },
+    }
+}
+
+fn invalid<F, M>(field: F, message: M) -> ConfigFileError
+where
+    F: Into<String>,
+    M: Into<String>,
+{
+    ConfigFileError::Invalid { field: field.into(), message: message.into() }
+}
+
+fn interpolate_env_vars(field: &str, value: String) -> Result<String, ConfigFileError> {
+    if !value.contains("${") {
+        return Ok(value);
+    }
+
+    let mut interpolated = String::with_capacity(value.len());
+    let mut rest = value.as_str();
+    while let Some(start) = rest.find("${") {
+        if rest[..start].ends_with('$') {
+            interpolated.push_str(&rest[..start - 1]);
+            interpolated.push_str("${");
+            rest = &rest[start + 2..];
+            continue;
+        }
+
+        interpolated.push_str(&rest[..start]);
+        let reference = &rest[start + 2..];
+        let Some(end) = reference.find('}') else {
+            return Err(invalid(field, "unterminated environment variable reference"));
+        };
+
+        let var_name = &reference[..end];
+        let var_value = env::var(var_name).map_err(|err| ConfigFileError::Interpolation {
+            field: field.into(),
+            var_name: var_name.into(),
+            source: err.into(),
+        })?;
+        interpolated.push_str(&var_value);
+        rest = &reference[end + 1..];
+    }
+    interpolated.push_str(rest);
+
+    Ok(interpolated)
+}
+
+fn interpolate_value(field: &str, value: &mut serde_json::Value) -> Result<(), ConfigFileError> {
+    match value {
+        serde_json::Value::String(string) => {
+            *string = interpolate_env_vars(field, mem::take(string))?;
+        },
+        serde_json::Value::Array(values) => {
+            for (index, value) in values.iter_mut().enumerate() {
+                interpolate_value(&format!("{field}[{index}]"), value)?;
+            }
+        },
+        serde_json::Value::Object(values) => {
+            for (key, value) in values.iter_mut() {
+                interpolate_value(&format!("{field}.{key}"), value)?;
+            }
+        },
+        _ => {},
+    }
+
+    Ok(())
+}
+
+fn line_and_column(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset.min(content.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.len() - before.rfind('\n').map_or(0, |index| index + 1) + 1;
+
+    (line, column)
+}
@@ -3,11 +3,15 @@
 use std::any::type_name;
 
 use aoc_leaderboard::aoc::LeaderboardCredentials;
-use chrono::{Datelike, Local};
 use derive_builder::{Builder, UninitializedFieldError};
 use serde::{Deserialize, Serialize};
 
-use crate::leaderbot::Config;
+use crate::leaderbot::clock::{Clock, SharedClock};
+use crate::leaderbot::event::{EventWindow, current_event_year};
+use crate::leaderbot::heartbeat::HeartbeatPolicy;
+use crate::leaderbot::members::MemberSettings;
+use crate::leaderbot::team::Team;
+use crate::leaderbot::{Config, MergedLeaderboard, RenotifyPolicy};
 
 /// Bot config storing values in memory.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Builder)]
@@ -18,9 +22,13 @@ use crate::leaderbot::Config;
 pub struct MemoryConfig {
     /// Year for which to monitor the leaderboard.
     ///
-    /// If not provided, the current year will be used.
-    #[builder(default = "Local::now().year()")]
-    pub year: i32,
+    /// If not provided, the year of the current Advent of Code event will be used, according
+    /// to the config's [`clock`](Self::clock); it is determined every time [`Config::year`] is
+    /// called, so it switches to the new year on December 1st. See [`Config::year`] for info
+    /// on this value.
+    #[builder(default, setter(into))]
+    #[serde(default)]
+    pub year: Option<i32>,
 
     /// Leaderboard ID.
     ///
@@ -32,6 +40,62 @@ pub struct MemoryConfig {
     /// See [`Config::credentials`] for info on this value.
     #[builder(setter(into))]
     pub credentials: LeaderboardCredentials,
+
+    /// Policy determining when an ongoing error should be reported again.
+    ///
+    /// If not provided, ongoing errors are never reported again.
+    /// See [`Config::renotify_policy`] for info on this value.
+    #[builder(default)]
+    #[serde(default)]
+    pub renotify_policy: RenotifyPolicy,
+
+    /// Window during which the bot fetches leaderboard data normally.
+    ///
+    /// If not provided, leaderboard data is fetched every time the bot runs.
+    /// See [`Config::event_window`] for info on this value.
+    #[builder(default, setter(into))]
+    #[serde(default)]
+    pub event_window: Option<EventWindow>,
+
+    /// Policy determining how the bot's heartbeat is monitored.
+    ///
+    /// If not provided, heartbeat monitoring is disabled.
+    /// See [`Config::heartbeat_policy`] for info on this value.
+    #[builder(default)]
+    #[serde(default)]
+    pub heartbeat_policy: HeartbeatPolicy,
+
+    /// Other leaderboards to merge with the monitored leaderboard.
+    ///
+    /// If not provided, only the monitored leaderboard is fetched.
+    /// See [`Config::merged_leaderboards`] for info on this value.
+    #[builder(default, setter(into))]
+    #[serde(default)]
+    pub merged_leaderboards: Vec<MergedLeaderboard>,
+
+    /// Teams of leaderboard members.
+    ///
+    /// If not provided, no teams are defined.
+    /// See [`Config::teams`] for info on this value.
+    #[builder(default, setter(into))]
+    #[serde(default)]
+    pub teams: Vec<Team>,
+
+    /// Aliases, ignored members and opted-out members of the leaderboard.
+    ///
+    /// If not provided, leaderboards are left untouched.
+    /// See [`Config::member_settings`] for info on this value.
+    #[builder(default, setter(into))]
+    #[serde(default)]
+    pub member_settings: MemberSettings,
+
+    /// Clock used to determine the current time.
+    ///
+    /// If not provided, the [`SystemClock`](crate::leaderbot::clock::SystemClock) is used.
+    /// See [`Config::clock`] for info on this value. Not serialized.
+    #[builder(default, setter(custom))]
+    #[serde(skip)]
+    pub clock: SharedClock,
 }
 
 impl MemoryConfig {
@@ -42,7 +106,18 @@ impl MemoryConfig {
 
     /// Creates a new instance with values for all fields.
     pub fn new(year: i32, leaderboard_id: u64, credentials: LeaderboardCredentials) -> Self {
-        Self { year, leaderboard_id, credentials }
+        Self {
+            year: Some(year),
+            leaderboard_id,
+            credentials,
+            renotify_policy: RenotifyPolicy::default(),
+            event_window: None,
+            heartbeat_policy: HeartbeatPolicy::default(),
+            merged_leaderboards: Vec::new(),
+            teams: Vec::new(),
+            member_settings: MemberSettings::default(),
+            clock: SharedClock::default(),
+        }
     }
 }
 
@@ -71,6 +146,17 @@ impl MemoryConfigBuilder {
         self
     }
 
+    /// Sets the config's [`clock`](MemoryConfig::clock).
+    ///
+    /// If the [`year`](MemoryConfig::year) is not specified, it is determined using this clock.
+    pub fn clock<C>(&mut self, clock: C) -> &mut Self
+    where
+        C: Clock + 'static,
+    {
+        self.clock = Some(SharedClock::new(clock));
+        self
+    }
+
     /// Builds a new [`MemoryConfig`].
     ///
     /// # Errors
@@ -93,6 +179,7 @@ impl Config for MemoryConfig {
     #[cfg_attr(not(coverage), tracing::instrument(skip(self), level = "trace", ret))]
     fn year(&self) -> i32 {
         self.year
+            .unwrap_or_else(|| current_event_year(self.clock.now()))
     }
 
     #[cfg_attr(not(coverage), tracing::instrument(skip(self), level = "trace", ret))]
@@ -104,4 +191,38 @@ impl Config for MemoryConfig {
     fn credentials(&self) -> LeaderboardCredentials {
         self.credentials.clone()
     }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self), level = "trace", ret))]
+    fn renotify_policy(&self) -> RenotifyPolicy {
+        self.renotify_policy
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self), level = "trace", ret))]
+    fn event_window(&self) -> Option<EventWindow> {
+        self.event_window
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self), level = "trace", ret))]
+    fn heartbeat_policy(&self) -> HeartbeatPolicy {
+        self.heartbeat_policy
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self), level = "trace", ret))]
+    fn merged_leaderboards(&self) -> Vec<MergedLeaderboard> {
+        self.merged_leaderboards.clone()
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self), level = "trace", ret))]
+    fn teams(&self) -> Vec<Team> {
+        self.teams.clone()
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self), level = "trace", ret))]
+    fn member_settings(&self) -> MemberSettings {
+        self.member_settings.clone()
+    }
+
+    fn clock(&self) -> &dyn Clock {
+        &self.clock
+    }
 }
@@ -0,0 +1,47 @@
+//! Implementations of [`CredentialsProvider`] fetching leaderboard credentials from external
+//! sources.
+//!
+//! Advent of Code credentials are secrets: a [session cookie] grants access to a user's account.
+//! Instead of storing them in the bot's [`Config`] (for example, in environment variables),
+//! credentials can be fetched from:
+//!
+//! - a file, like a Docker or Kubernetes secret (see [`FileCredentialsProvider`])
+//! - the output of a command, like a password manager (see [`CommandCredentialsProvider`])
+//!
+//! Other crates include providers for other sources, like cloud secret stores.
+//!
+//! [`CredentialsProvider`]: crate::leaderbot::CredentialsProvider
+//! [session cookie]: LeaderboardCredentials::SessionCookie
+//! [`Config`]: crate::leaderbot::Config
+//! [`FileCredentialsProvider`]: file::FileCredentialsProvider
+//! [`CommandCredentialsProvider`]: command::CommandCredentialsProvider
+
+#[cfg(feature = "credentials-command")]
+pub mod command;
+#[cfg(feature = "credentials-file")]
+pub mod file;
+
+use aoc_leaderboard::aoc::{LeaderboardCredentials, LeaderboardCredentialsKind};
+
+/// Creates [`LeaderboardCredentials`] of the given kind from a secret value.
+///
+/// The secret is trimmed, so that trailing newlines (often found in secret files or in
+/// the output of commands) are ignored. Returns `None` if the secret is empty.
+pub fn credentials_from_secret<S>(
+    kind: LeaderboardCredentialsKind,
+    secret: S,
+) -> Option<LeaderboardCredentials>
+where
+    S: AsRef<str>,
+{
+    let secret = secret.as_ref().trim();
+    if secret.is_empty() {
+        return None;
+    }
+
+    let secret = secret.to_string();
+    Some(match kind {
+        LeaderboardCredentialsKind::ViewKey => LeaderboardCredentials::ViewKey(secret),
+        LeaderboardCredentialsKind::SessionCookie => LeaderboardCredentials::SessionCookie(secret),
+    })
+}
@@ -0,0 +1,111 @@
+//! [`CredentialsProvider`] fetching credentials from the output of a command.
+//!
+//! [`CredentialsProvider`]: crate::leaderbot::CredentialsProvider
+
+use std::ffi::OsString;
+use std::future::Future;
+use std::io;
+
+use aoc_leaderboard::aoc::{LeaderboardCredentials, LeaderboardCredentialsKind};
+use tokio::process::Command;
+
+use crate::leaderbot::CredentialsProvider;
+use crate::leaderbot::credentials::credentials_from_secret;
+
+/// [`CredentialsProvider`] fetching credentials from the output of a command.
+///
+/// The command must print only the secret (the view key or the session cookie) to its standard
+/// output; leading and trailing whitespace is ignored. This can be used to fetch credentials
+/// from a password manager, like [`pass`]:
+///
+/// ```no_run
+/// # use aoc_leaderbot_lib::leaderbot::credentials::command::CommandCredentialsProvider;
+/// let provider = CommandCredentialsProvider::session_cookie("pass", ["show", "aoc/session"]);
+/// ```
+///
+/// The command is run directly (not through a shell) every time credentials are requested.
+/// It is run asynchronously using [Tokio](tokio::process), so credentials must be requested
+/// from within a Tokio runtime.
+///
+/// [`pass`]: https://www.passwordstore.org/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandCredentialsProvider {
+    /// Program to run.
+    pub program: OsString,
+
+    /// Arguments to pass to the [program](Self::program).
+    pub args: Vec<OsString>,
+
+    /// Kind of credentials printed by the command.
+    pub kind: LeaderboardCredentialsKind,
+}
+
+impl CommandCredentialsProvider {
+    /// Creates a provider fetching credentials of the given kind from the output of a command.
+    pub fn new<P, I, A>(program: P, args: I, kind: LeaderboardCredentialsKind) -> Self
+    where
+        P: Into<OsString>,
+        I: IntoIterator<Item = A>,
+        A: Into<OsString>,
+    {
+        Self { program: program.into(), args: args.into_iter().map(Into::into).collect(), kind }
+    }
+
+    /// Creates a provider fetching a [view key](LeaderboardCredentials::ViewKey) from the
+    /// output of a command.
+    pub fn view_key<P, I, A>(program: P, args: I) -> Self
+    where
+        P: Into<OsString>,
+        I: IntoIterator<Item = A>,
+        A: Into<OsString>,
+    {
+        Self::new(program, args, LeaderboardCredentialsKind::ViewKey)
+    }
+
+    /// Creates a provider fetching a [session cookie](LeaderboardCredentials::SessionCookie)
+    /// from the output of a command.
+    pub fn session_cookie<P, I, A>(program: P, args: I) -> Self
+    where
+        P: Into<OsString>,
+        I: IntoIterator<Item = A>,
+        A: Into<OsString>,
+    {
+        Self::new(program, args, LeaderboardCredentialsKind::SessionCookie)
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(level = "debug", err))]
+    async fn run(&self) -> io::Result<LeaderboardCredentials> {
+        let output = Command::new(&self.program)
+            .args(&self.args)
+            .kill_on_drop(true)
+            .output()
+            .await?;
+        if !output.status.success() {
+            return Err(io::Error::other(format!(
+                "command {:?} failed ({}): {}",
+                self.program,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim(),
+            )));
+        }
+
+        let secret = String::from_utf8(output.stdout)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        credentials_from_secret(self.kind, secret).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("command {:?} did not output any credentials", self.program),
+            )
+        })
+    }
+}
+
+impl CredentialsProvider for CommandCredentialsProvider {
+    type Err = io::Error;
+
+    fn get_credentials(
+        &self,
+    ) -> impl Future<Output = Result<LeaderboardCredentials, Self::Err>> + Send {
+        self.run()
+    }
+}
@@ -0,0 +1,81 @@
+//! [`CredentialsProvider`] reading credentials from a file.
+//!
+//! [`CredentialsProvider`]: crate::leaderbot::CredentialsProvider
+
+use std::fs;
+use std::future::Future;
+use std::io;
+use std::path::PathBuf;
+
+use aoc_leaderboard::aoc::{LeaderboardCredentials, LeaderboardCredentialsKind};
+
+use crate::leaderbot::CredentialsProvider;
+use crate::leaderbot::credentials::credentials_from_secret;
+
+/// [`CredentialsProvider`] reading credentials from a file.
+///
+/// The file must contain only the secret (the view key or the session cookie); leading and
+/// trailing whitespace is ignored. This can be used to read [Docker secrets] or
+/// [Kubernetes secrets] mounted as files.
+///
+/// The file is read every time credentials are requested, so that the secret can be rotated
+/// without restarting the bot.
+///
+/// [Docker secrets]: https://docs.docker.com/engine/swarm/secrets/
+/// [Kubernetes secrets]: https://kubernetes.io/docs/concepts/configuration/secret/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileCredentialsProvider {
+    /// Path of the file containing the secret.
+    pub path: PathBuf,
+
+    /// Kind of credentials stored in the file.
+    pub kind: LeaderboardCredentialsKind,
+}
+
+impl FileCredentialsProvider {
+    /// Creates a provider reading credentials of the given kind from a file.
+    pub fn new<P>(path: P, kind: LeaderboardCredentialsKind) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        Self { path: path.into(), kind }
+    }
+
+    /// Creates a provider reading a [view key](LeaderboardCredentials::ViewKey) from a file.
+    pub fn view_key<P>(path: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        Self::new(path, LeaderboardCredentialsKind::ViewKey)
+    }
+
+    /// Creates a provider reading a [session cookie](LeaderboardCredentials::SessionCookie)
+    /// from a file.
+    pub fn session_cookie<P>(path: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        Self::new(path, LeaderboardCredentialsKind::SessionCookie)
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(level = "debug", err))]
+    pub(crate) fn read(&self) -> io::Result<LeaderboardCredentials> {
+        let secret = fs::read_to_string(&self.path)?;
+        credentials_from_secret(self.kind, secret).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("credentials file {} is empty", self.path.display()),
+            )
+        })
+    }
+}
+
+impl CredentialsProvider for FileCredentialsProvider {
+    type Err = io::Error;
+
+    fn get_credentials(
+        &self,
+    ) -> impl Future<Output = Result<LeaderboardCredentials, Self::Err>> + Send {
+        std::future::ready(self.read())
+    }
+}
@@ -0,0 +1,233 @@
+//! Long-running daemon mode with a built-in scheduler.
+//!
+//! [`run_bot`](crate::leaderbot::run_bot) performs a single bot run and returns, leaving the
+//! scheduling of bot runs to the caller (for example, AWS EventBridge). [`run_bot_forever`]
+//! instead runs the bot in a loop following a [`DaemonSchedule`], until the process receives a
+//! shutdown signal. The HTTP client, [`Storage`] and [`Reporter`] are kept alive between bot runs,
+//! which makes it possible to deploy the bot as a long-running process in a container or a VM.
+
+use std::fmt::Debug;
+use std::future::{Future, pending};
+use std::pin::pin;
+
+use aoc_leaderboard::aoc::Leaderboard;
+use chrono::{DateTime, TimeDelta, Utc};
+
+use crate::leaderbot::event::{EventWindow, MIN_INTERVAL, puzzle_unlock_times};
+use crate::leaderbot::{Config, Reporter, Storage, run_bot_with};
+
+/// Default delay between the unlock of a puzzle and the extra bot run following it.
+pub const DEFAULT_UNLOCK_DELAY: TimeDelta = TimeDelta::minutes(15);
+
+/// Schedule determining when the bot runs in daemon mode.
+///
+/// The bot runs at a regular interval, which can never be less than [`MIN_INTERVAL`]. During the
+/// Advent of Code event, an extra bot run can also be scheduled shortly after each puzzle unlocks
+/// (at midnight EST), so that the first stars are reported promptly even with a long interval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DaemonSchedule {
+    interval: TimeDelta,
+    unlock_delay: Option<TimeDelta>,
+}
+
+impl Default for DaemonSchedule {
+    fn default() -> Self {
+        Self::new(MIN_INTERVAL)
+    }
+}
+
+impl DaemonSchedule {
+    /// Creates a new schedule running the bot at the given interval.
+    ///
+    /// If the interval is less than [`MIN_INTERVAL`], [`MIN_INTERVAL`] is used instead.
+    /// Extra bot runs are scheduled [`DEFAULT_UNLOCK_DELAY`] after each puzzle unlock.
+    pub fn new(interval: TimeDelta) -> Self {
+        Self { interval: interval.max(MIN_INTERVAL), unlock_delay: Some(DEFAULT_UNLOCK_DELAY) }
+    }
+
+    /// Returns a copy of this schedule with extra bot runs scheduled the given delay after each
+    /// puzzle unlock, or without extra bot runs if `None`.
+    pub fn with_unlock_delay(self, unlock_delay: Option<TimeDelta>) -> Self {
+        Self { unlock_delay: unlock_delay.map(|delay| delay.max(TimeDelta::zero())), ..self }
+    }
+
+    /// Returns the interval between regular bot runs.
+    pub fn interval(&self) -> TimeDelta {
+        self.interval
+    }
+
+    /// Returns the delay between the unlock of a puzzle and the extra bot run following it,
+    /// if extra bot runs are scheduled.
+    pub fn unlock_delay(&self) -> Option<TimeDelta> {
+        self.unlock_delay
+    }
+
+    /// Returns the time of the next bot run for the given year, given the time of the last one.
+    ///
+    /// The next bot run is never scheduled less than [`MIN_INTERVAL`] after the last one. If an
+    /// [`EventWindow`] is provided, the next bot run is postponed to the [next time] at which
+    /// leaderboard data should be fetched.
+    ///
+    /// [next time]: EventWindow::next_run_time
+    pub fn next_run(
+        &self,
+        year: i32,
+        last_run: DateTime<Utc>,
+        event_window: Option<&EventWindow>,
+    ) -> DateTime<Utc> {
+        let regular_run = last_run + self.interval;
+        let unlock_run = self.unlock_delay.and_then(|delay| {
+            puzzle_unlock_times(year)
+                .map(|unlock_time| unlock_time + delay)
+                .find(|run| *run > last_run)
+        });
+
+        let next_run = match unlock_run {
+            Some(unlock_run) if unlock_run < regular_run => unlock_run.max(last_run + MIN_INTERVAL),
+            _ => regular_run,
+        };
+        match event_window {
+            Some(event_window) => event_window.next_run_time(next_run),
+            None => next_run,
+        }
+    }
+}
+
+/// Runs the bot in a loop following the given [`schedule`], until a shutdown signal is received.
+///
+/// Each bot run works like [`run_bot`](crate::leaderbot::run_bot); errors are reported via the
+/// [`reporter`] but do not stop the loop. This includes the first bot run, which does not fetch
+/// leaderboard data if the daemon is started outside of the config's [event window]. The same
+/// HTTP client is used to fetch leaderboard data during every bot run.
+///
+/// The loop stops when the process receives `SIGTERM` or `SIGINT` (or `Ctrl+C` on platforms that
+/// do not support Unix signals). If a bot run is in progress when that happens, it is allowed to
+/// complete first.
+///
+/// # Errors
+///
+/// - [`Error::Leaderboard`]: if the HTTP client could not be created
+///
+/// [`schedule`]: DaemonSchedule
+/// [`reporter`]: Reporter
+/// [event window]: Config::event_window
+/// [`Error::Leaderboard`]: crate::Error::Leaderboard
+#[cfg_attr(coverage_nightly, coverage(off))]
+#[tracing::instrument(skip(config, storage, reporter), err)]
+pub async fn run_bot_forever<C, S, R>(
+    config: &C,
+    storage: &mut S,
+    reporter: &mut R,
+    schedule: &DaemonSchedule,
+    dry_run: bool,
+) -> crate::Result<()>
+where
+    C: Config,
+    S: Storage,
+    <S as Storage>::Err: Sync + 'static,
+    R: Reporter,
+    <R as Reporter>::Err: Sync + 'static,
+{
+    let shutdown = shutdown_signal();
+
+    run_bot_forever_from(None::<String>, config, storage, reporter, schedule, dry_run, shutdown)
+        .await
+}
+
+/// Runs the bot in a loop until the given `shutdown` future completes, using the given base
+/// Advent of Code URL (or the default, `https://adventofcode.com`, if not provided).
+///
+/// This function is mostly exposed for testing; you should use [`run_bot_forever`] instead.
+#[cfg_attr(
+    not(coverage),
+    tracing::instrument(skip(config, storage, reporter, shutdown), level = "debug", err)
+)]
+pub async fn run_bot_forever_from<B, C, S, R, F>(
+    advent_of_code_base: Option<B>,
+    config: &C,
+    storage: &mut S,
+    reporter: &mut R,
+    schedule: &DaemonSchedule,
+    dry_run: bool,
+    shutdown: F,
+) -> crate::Result<()>
+where
+    B: AsRef<str> + Debug,
+    C: Config,
+    S: Storage,
+    <S as Storage>::Err: Sync + 'static,
+    R: Reporter,
+    <R as Reporter>::Err: Sync + 'static,
+    F: Future<Output = ()>,
+{
+    let http_client = Leaderboard::http_client()?;
+    let mut shutdown = pin!(shutdown);
+
+    loop {
+        let last_run = config.clock().now();
+        if let Err(err) = run_bot_with(
+            Some(&http_client),
+            advent_of_code_base.as_ref(),
+            config,
+            storage,
+            reporter,
+            dry_run,
+        )
+        .await
+        {
+            // The error has already been reported (if needed); the next bot run might succeed.
+            tracing::warn!("bot run failed: {err}");
+        }
+
+        let next_run = schedule.next_run(config.year(), last_run, config.event_window().as_ref());
+        tracing::debug!("next bot run scheduled at {next_run}");
+
+        let delay = (next_run - config.clock().now())
+            .to_std()
+            .unwrap_or_default();
+        tokio::select! {
+            biased;
+            () = &mut shutdown => break,
+            () = tokio::time::sleep(delay) => (),
+        }
+    }
+
+    tracing::info!("shutdown requested, stopping bot");
+    Ok(())
+}
+
+// Returns a future that completes when the process receives a shutdown signal.
+// Signal handlers are registered immediately, so that signals received while
+// a bot run is in progress are not missed.
+#[cfg_attr(coverage_nightly, coverage(off))]
+fn shutdown_signal() -> impl Future<Output = ()> {
+    #[cfg(unix)]
+    let signals = {
+        use tokio::signal::unix::{SignalKind, signal};
+
+        signal(SignalKind::terminate())
+            .and_then(|sigterm| Ok((sigterm, signal(SignalKind::interrupt())?)))
+    };
+
+    async move {
+        #[cfg(unix)]
+        let result = match signals {
+            Ok((mut sigterm, mut sigint)) => {
+                tokio::select! {
+                    _ = sigterm.recv() => (),
+                    _ = sigint.recv() => (),
+                }
+                Ok(())
+            },
+            Err(err) => Err(err),
+        };
+        #[cfg(not(unix))]
+        let result = tokio::signal::ctrl_c().await;
+
+        if let Err(err) = result {
+            // Without signal handlers, the process will simply be terminated.
+            tracing::warn!("failed to listen for shutdown signals: {err}");
+            pending::<()>().await;
+        }
+    }
+}
@@ -0,0 +1,255 @@
+//! Diagnostics about a bot run.
+//!
+//! Every [`BotOutput`] includes [`RunDiagnostics`] describing what happened during the bot run:
+//! how long it took to fetch leaderboard data, which [`Storage`] operations and [`Reporter`] calls
+//! were made (along with their duration and outcome) and whether the bot recovered from an error.
+//! Since they are serializable, they can be logged or returned to the caller to debug a bot run
+//! without having to enable trace logs.
+//!
+//! When a bot run fails, the diagnostics collected up to that point (including the operations
+//! performed while handling the error) are returned in a [`RunError`] by the `*_diagnosed`
+//! variants of the run functions, like [`run_bot_diagnosed_from`].
+//!
+//! [`BotOutput`]: crate::leaderbot::BotOutput
+//! [`RunError`]: crate::error::RunError
+//! [`run_bot_diagnosed_from`]: crate::leaderbot::run_bot_diagnosed_from
+//! [`Storage`]: crate::leaderbot::Storage
+//! [`Reporter`]: crate::leaderbot::Reporter
+
+use std::fmt::Display;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::leaderbot::ErrorState;
+
+/// Diagnostics about a bot run, returned in its [`BotOutput`](crate::leaderbot::BotOutput).
+///
+/// See [module documentation](self) for details.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunDiagnostics {
+    /// Leaderboards fetched from the Advent of Code website, in order.
+    ///
+    /// When the [`Config`] has [merged leaderboards], every fetched leaderboard is listed.
+    ///
+    /// [`Config`]: crate::leaderbot::Config
+    /// [merged leaderboards]: crate::leaderbot::Config::merged_leaderboards
+    pub fetches: Vec<FetchDiagnostics>,
+
+    /// [`Storage`](crate::leaderbot::Storage) operations performed, in order.
+    pub storage_operations: Vec<OperationDiagnostics>,
+
+    /// [`Reporter`](crate::leaderbot::Reporter) calls made, in order.
+    pub reporter_calls: Vec<OperationDiagnostics>,
+
+    /// Error that occurred during the previous bot run, if any.
+    ///
+    /// If set, this bot run recovered from that error.
+    pub previous_error: Option<PreviousErrorDiagnostics>,
+}
+
+impl RunDiagnostics {
+    /// Returns the total time spent fetching leaderboard data.
+    ///
+    /// [Throttled](FetchDiagnostics::throttled) fetches are not included.
+    pub fn fetch_latency(&self) -> Duration {
+        self.fetches
+            .iter()
+            .filter(|fetch| !fetch.throttled)
+            .map(|fetch| Duration::from_millis(fetch.latency_ms))
+            .sum()
+    }
+
+    /// Returns the names of the [`Storage`](crate::leaderbot::Storage) operations performed,
+    /// in order.
+    pub fn storage_operation_names(&self) -> Vec<&str> {
+        self.storage_operations
+            .iter()
+            .map(|operation| operation.name.as_str())
+            .collect()
+    }
+
+    /// Returns the names of the [`Reporter`](crate::leaderbot::Reporter) calls made, in order.
+    pub fn reporter_call_names(&self) -> Vec<&str> {
+        self.reporter_calls
+            .iter()
+            .map(|call| call.name.as_str())
+            .collect()
+    }
+
+    pub(crate) fn record_fetch(&mut self, leaderboard_id: u64, http_status: u16, started: Instant) {
+        self.fetches.push(FetchDiagnostics {
+            leaderboard_id,
+            http_status: Some(http_status),
+            latency_ms: elapsed_ms(started),
+            throttled: false,
+        });
+    }
+
+    #[cfg(feature = "multi-target")]
+    pub(crate) fn record_throttled_fetch(&mut self, leaderboard_id: u64) {
+        self.fetches.push(FetchDiagnostics {
+            leaderboard_id,
+            http_status: None,
+            latency_ms: 0,
+            throttled: true,
+        });
+    }
+
+    pub(crate) fn record_previous_error(
+        &mut self,
+        error_kind: crate::ErrorKind,
+        error_state: Option<&ErrorState>,
+    ) {
+        self.previous_error = Some(PreviousErrorDiagnostics {
+            error_kind,
+            occurrences: error_state.map(|error_state| error_state.occurrences),
+            suppressed: error_state
+                .map(|error_state| error_state.last_notified != Some(error_state.last_seen)),
+        });
+    }
+
+    /// Awaits the given [`Storage`](crate::leaderbot::Storage) operation, recording its
+    /// duration and outcome.
+    pub async fn storage_operation<F, T, E>(&mut self, name: &str, future: F) -> Result<T, E>
+    where
+        F: Future<Output = Result<T, E>>,
+        E: Display,
+    {
+        let (diagnostics, result) = OperationDiagnostics::checked(name, future).await;
+        self.storage_operations.push(diagnostics);
+        result
+    }
+
+    /// Awaits the given [`Reporter`](crate::leaderbot::Reporter) call, recording its
+    /// duration and outcome.
+    pub async fn reporter_call<F, T, E>(&mut self, name: &str, future: F) -> Result<T, E>
+    where
+        F: Future<Output = Result<T, E>>,
+        E: Display,
+    {
+        let (diagnostics, result) = OperationDiagnostics::checked(name, future).await;
+        self.reporter_calls.push(diagnostics);
+        result
+    }
+
+    /// Awaits the given [`Reporter`](crate::leaderbot::Reporter) call that does not return
+    /// its outcome, recording its duration.
+    pub async fn unchecked_reporter_call<F>(&mut self, name: &str, future: F)
+    where
+        F: Future<Output = ()>,
+    {
+        let started = Instant::now();
+        future.await;
+        self.reporter_calls.push(OperationDiagnostics {
+            name: name.into(),
+            duration_ms: elapsed_ms(started),
+            outcome: OperationOutcome::Unchecked,
+        });
+    }
+}
+
+/// Diagnostics about a leaderboard fetched from the Advent of Code website.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FetchDiagnostics {
+    /// ID of the fetched leaderboard.
+    pub leaderboard_id: u64,
+
+    /// HTTP status code returned by the Advent of Code website.
+    ///
+    /// Will be `None` if the leaderboard was not actually fetched (see [`throttled`]).
+    ///
+    /// [`throttled`]: Self::throttled
+    pub http_status: Option<u16>,
+
+    /// Time taken to fetch the leaderboard, in milliseconds.
+    pub latency_ms: u64,
+
+    /// Whether the leaderboard was not fetched because it had been fetched too recently.
+    ///
+    /// This happens when a [`MultiTargetRunner`] throttles fetches; the bot does not run
+    /// for the leaderboard in that case.
+    ///
+    /// [`MultiTargetRunner`]: crate::leaderbot::multi_target::MultiTargetRunner
+    #[serde(default)]
+    pub throttled: bool,
+}
+
+/// Diagnostics about a [`Storage`] operation or a [`Reporter`] call.
+///
+/// [`Storage`]: crate::leaderbot::Storage
+/// [`Reporter`]: crate::leaderbot::Reporter
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperationDiagnostics {
+    /// Name of the operation (e.g. the name of the trait method called).
+    pub name: String,
+
+    /// Time taken by the operation, in milliseconds.
+    pub duration_ms: u64,
+
+    /// Outcome of the operation.
+    pub outcome: OperationOutcome,
+}
+
+impl OperationDiagnostics {
+    async fn checked<F, T, E>(name: &str, future: F) -> (Self, Result<T, E>)
+    where
+        F: Future<Output = Result<T, E>>,
+        E: Display,
+    {
+        let started = Instant::now();
+        let result = future.await;
+        let outcome = match &result {
+            Ok(_) => OperationOutcome::Succeeded,
+            Err(err) => OperationOutcome::Failed { message: err.to_string() },
+        };
+
+        (Self { name: name.into(), duration_ms: elapsed_ms(started), outcome }, result)
+    }
+}
+
+/// Outcome of an operation (see [`OperationDiagnostics`]).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "status")]
+pub enum OperationOutcome {
+    /// The operation succeeded.
+    Succeeded,
+
+    /// The operation failed.
+    Failed {
+        /// Error message.
+        message: String,
+    },
+
+    /// The operation does not return its outcome.
+    ///
+    /// This is the case for [`Reporter`] calls that handle their own errors, like
+    /// [`report_error`](crate::leaderbot::Reporter::report_error).
+    ///
+    /// [`Reporter`]: crate::leaderbot::Reporter
+    Unchecked,
+}
+
+/// Diagnostics about the error that occurred during the previous bot run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PreviousErrorDiagnostics {
+    /// Kind of the previous error.
+    pub error_kind: crate::ErrorKind,
+
+    /// Number of consecutive bot runs that failed, if known.
+    ///
+    /// Only known if the [`Storage`](crate::leaderbot::Storage) keeps track of [`ErrorState`]s.
+    pub occurrences: Option<u64>,
+
+    /// Whether the previous error was suppressed (e.g. not reported because an error of the
+    /// same kind had already been reported), if known.
+    ///
+    /// Only known if the [`Storage`](crate::leaderbot::Storage) keeps track of [`ErrorState`]s.
+    pub suppressed: Option<bool>,
+}
+
+fn elapsed_ms(started: Instant) -> u64 {
+    started.elapsed().as_millis().try_into().unwrap_or(u64::MAX)
+}
@@ -0,0 +1,337 @@
+//! Digest mode: accumulate changes and report them on a schedule.
+//!
+//! When the bot runs in digest mode (via [`run_bot_digest`]), changes detected to the leaderboard
+//! are not reported immediately. Instead, they are [merged] with any changes detected during
+//! previous runs and persisted via a [`DigestStorage`]. Accumulated changes are then reported as
+//! a single consolidated report during the first bot run following one of the flush times of the
+//! [`DigestSchedule`] (for example, at 08:00 and 18:00 local time).
+//!
+//! [merged]: Changes::merge
+
+use std::fmt::Debug;
+
+use anyhow::anyhow;
+use aoc_leaderboard::aoc::Leaderboard;
+use chrono::{DateTime, Local, NaiveDateTime, NaiveTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ReporterError, StorageError};
+use crate::leaderbot::diagnostics::RunDiagnostics;
+use crate::leaderbot::{
+    BotOutput, Changes, Config, DigestStorage, PendingChanges, Reporter, RunMode, Storage,
+    run_bot_core,
+};
+
+/// Schedule determining when changes accumulated in digest mode are reported.
+///
+/// A schedule is a list of flush times, in local time. Accumulated changes are reported during
+/// the first bot run following any of those times. A schedule without flush times never reports
+/// accumulated changes.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DigestSchedule {
+    flush_times: Vec<NaiveTime>,
+}
+
+impl DigestSchedule {
+    /// Creates a new schedule with the given flush times.
+    pub fn new<I>(flush_times: I) -> Self
+    where
+        I: IntoIterator<Item = NaiveTime>,
+    {
+        let mut flush_times: Vec<_> = flush_times.into_iter().collect();
+        flush_times.sort();
+        flush_times.dedup();
+
+        Self { flush_times }
+    }
+
+    /// Returns the flush times of this schedule, in chronological order.
+    pub fn flush_times(&self) -> &[NaiveTime] {
+        &self.flush_times
+    }
+
+    /// Checks if a flush time occurred after `since` (exclusive) and up to `now` (inclusive).
+    ///
+    /// Both times are expected to be in local time.
+    pub fn is_flush_due(&self, since: NaiveDateTime, now: NaiveDateTime) -> bool {
+        if self.flush_times.is_empty() || since >= now {
+            return false;
+        }
+        if now - since >= TimeDelta::days(1) {
+            return true;
+        }
+
+        since
+            .date()
+            .iter_days()
+            .take_while(|date| *date <= now.date())
+            .flat_map(|date| {
+                self.flush_times
+                    .iter()
+                    .map(move |time| date.and_time(*time))
+            })
+            .any(|flush_time| since < flush_time && flush_time <= now)
+    }
+}
+
+/// Output returned by the [`run_bot_digest`] function.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DigestOutput {
+    /// Output of the bot run itself.
+    ///
+    /// The [`changes`](BotOutput::changes) only include changes detected during this run.
+    pub bot_output: BotOutput,
+
+    /// Accumulated changes reported during this run, if any.
+    pub reported_changes: Option<Changes>,
+
+    /// Changes accumulated so far that have not been reported yet, if any.
+    pub pending_changes: Option<PendingChanges>,
+}
+
+/// Runs the bot's core functionality in digest mode.
+///
+/// Works like [`run_bot`](crate::leaderbot::run_bot), except that changes detected are merged with
+/// pending changes persisted in [`storage`] instead of being reported immediately. If a flush time
+/// of the [`schedule`] has passed since changes started to accumulate, all pending changes are
+/// reported in a single call to [`Reporter::report_changes`] and cleared from storage.
+///
+/// The first bot run for a leaderboard is reported immediately, as are errors.
+///
+/// Pending changes are saved _before_ being reported, and both happen before the current
+/// leaderboard is saved. If reporting fails, pending changes are kept and reported again during
+/// the next bot run.
+///
+/// Like [`run_bot`](crate::leaderbot::run_bot), returns `None` without doing anything if
+/// leaderboard data should not be fetched at this time according to the config's [event window].
+///
+/// If the `dry_run` parameter is set to `true`, then the bot will fetch data and compute
+/// changes but will not persist or report them.
+///
+/// [`storage`]: DigestStorage
+/// [`schedule`]: DigestSchedule
+/// [event window]: Config::event_window
+#[cfg_attr(coverage_nightly, coverage(off))]
+#[tracing::instrument(skip(config, storage, reporter), ret, err)]
+pub async fn run_bot_digest<C, S, R>(
+    config: &C,
+    storage: &mut S,
+    reporter: &mut R,
+    schedule: &DigestSchedule,
+    dry_run: bool,
+) -> crate::Result<Option<DigestOutput>>
+where
+    C: Config,
+    S: DigestStorage,
+    <S as Storage>::Err: Sync + 'static,
+    R: Reporter + Send,
+    <R as Reporter>::Err: Sync + 'static,
+{
+    run_bot_digest_from(None::<String>, config, storage, reporter, schedule, dry_run).await
+}
+
+/// Runs the bot's core functionality in digest mode, using the given base Advent of Code URL
+/// (or the default, `https://adventofcode.com`, if not provided).
+///
+/// This function is mostly exposed for testing; you should use [`run_bot_digest`] instead.
+#[cfg_attr(
+    not(coverage),
+    tracing::instrument(skip(config, storage, reporter), level = "debug", ret, err)
+)]
+pub async fn run_bot_digest_from<B, C, S, R>(
+    advent_of_code_base: Option<B>,
+    config: &C,
+    storage: &mut S,
+    reporter: &mut R,
+    schedule: &DigestSchedule,
+    dry_run: bool,
+) -> crate::Result<Option<DigestOutput>>
+where
+    B: AsRef<str> + Debug,
+    C: Config,
+    S: DigestStorage,
+    <S as Storage>::Err: Sync + 'static,
+    R: Reporter + Send,
+    <R as Reporter>::Err: Sync + 'static,
+{
+    let mode = DigestMode { schedule, pending_changes: None, reported_changes: None };
+    run_bot_core(None, advent_of_code_base, config, storage, reporter, mode, dry_run)
+        .await
+        .map_err(Into::into)
+}
+
+// Run mode used by [`run_bot_digest`]: changes are accumulated in storage and only
+// reported when a flush time of the schedule has passed.
+#[derive(Debug)]
+struct DigestMode<'a> {
+    schedule: &'a DigestSchedule,
+    pending_changes: Option<PendingChanges>,
+    reported_changes: Option<Changes>,
+}
+
+impl<S> RunMode<S> for DigestMode<'_>
+where
+    S: DigestStorage,
+    <S as Storage>::Err: Sync + 'static,
+{
+    type Output = DigestOutput;
+
+    async fn load(
+        &mut self,
+        storage: &S,
+        year: i32,
+        leaderboard_id: u64,
+        diagnostics: &mut RunDiagnostics,
+    ) -> crate::Result<()> {
+        self.pending_changes = diagnostics
+            .storage_operation(
+                "load_pending_changes",
+                storage.load_pending_changes(year, leaderboard_id),
+            )
+            .await
+            .map_err(|err| StorageError::LoadPendingChanges(anyhow!(err)))?;
+
+        Ok(())
+    }
+
+    async fn save_and_report<R>(
+        &mut self,
+        storage: &mut S,
+        reporter: &mut R,
+        view_key: Option<&str>,
+        output: &BotOutput,
+        now: DateTime<Utc>,
+        diagnostics: &mut RunDiagnostics,
+    ) -> crate::Result<bool>
+    where
+        R: Reporter,
+        <R as Reporter>::Err: Sync + 'static,
+    {
+        let (year, leaderboard_id) = (output.year, output.leaderboard_id);
+
+        let (mut pending_changes, updated) =
+            match (self.pending_changes.take(), &output.previous_leaderboard, &output.changes) {
+                (Some(mut pending_changes), _, Some(changes)) => {
+                    pending_changes.changes.merge(changes);
+                    (Some(pending_changes), true)
+                },
+                (None, Some(previous_leaderboard), Some(changes)) => (
+                    Some(PendingChanges::new(previous_leaderboard.clone(), changes.clone(), now)),
+                    true,
+                ),
+                (pending_changes, _, _) => (pending_changes, false),
+            };
+
+        // Pending changes are saved before being reported, so that they are not lost if reporting
+        // fails. Since the current leaderboard is only saved afterwards, the changes detected
+        // during this run will also be detected again during the next run if saving fails.
+        if let Some(pending_changes) = &pending_changes
+            && updated
+        {
+            diagnostics
+                .storage_operation(
+                    "save_pending_changes",
+                    storage.save_pending_changes(year, leaderboard_id, pending_changes),
+                )
+                .await
+                .map_err(|err| StorageError::SavePendingChanges(anyhow!(err)))?;
+        }
+
+        if output.previous_leaderboard.is_none() {
+            diagnostics
+                .reporter_call(
+                    "report_first_run",
+                    reporter.report_first_run(year, leaderboard_id, view_key, &output.leaderboard),
+                )
+                .await
+                .map_err(|err| ReporterError::ReportFirstRun(anyhow!(err)))?;
+        }
+
+        let schedule = self.schedule;
+        self.reported_changes = match pending_changes.take_if(|pending_changes| {
+            schedule.is_flush_due(
+                pending_changes.since.with_timezone(&Local).naive_local(),
+                now.with_timezone(&Local).naive_local(),
+            )
+        }) {
+            Some(pending_changes) => {
+                flush(
+                    storage,
+                    reporter,
+                    year,
+                    leaderboard_id,
+                    view_key,
+                    &pending_changes,
+                    &output.leaderboard,
+                    diagnostics,
+                )
+                .await?;
+                Some(pending_changes.changes)
+            },
+            None => None,
+        };
+        self.pending_changes = pending_changes;
+
+        diagnostics
+            .storage_operation(
+                "save_success",
+                storage.save_success(year, leaderboard_id, &output.leaderboard),
+            )
+            .await
+            .map_err(|err| StorageError::SaveSuccess(anyhow!(err)))?;
+
+        // Changes merged into pending changes are not reported until they are flushed.
+        Ok(output.previous_leaderboard.is_none() || self.reported_changes.is_some())
+    }
+
+    fn into_output(self, bot_output: BotOutput) -> Self::Output {
+        DigestOutput {
+            bot_output,
+            reported_changes: self.reported_changes,
+            pending_changes: self.pending_changes,
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn flush<S, R>(
+    storage: &mut S,
+    reporter: &mut R,
+    year: i32,
+    leaderboard_id: u64,
+    view_key: Option<&str>,
+    pending_changes: &PendingChanges,
+    leaderboard: &Leaderboard,
+    diagnostics: &mut RunDiagnostics,
+) -> crate::Result<()>
+where
+    S: DigestStorage,
+    <S as Storage>::Err: Sync + 'static,
+    R: Reporter,
+    <R as Reporter>::Err: Sync + 'static,
+{
+    diagnostics
+        .reporter_call(
+            "report_changes",
+            reporter.report_changes(
+                year,
+                leaderboard_id,
+                view_key,
+                &pending_changes.previous_leaderboard,
+                leaderboard,
+                &pending_changes.changes,
+            ),
+        )
+        .await
+        .map_err(|err| ReporterError::ReportChanges(anyhow!(err)))?;
+
+    diagnostics
+        .storage_operation(
+            "clear_pending_changes",
+            storage.clear_pending_changes(year, leaderboard_id),
+        )
+        .await
+        .map_err(|err| StorageError::ClearPendingChanges(anyhow!(err)))?;
+
+    Ok(())
+}
@@ -0,0 +1,995 @@
+//! Object-safe versions of [`Storage`] and [`Reporter`], for runtime backend selection.
+//!
+//! Because [`Storage`] and [`Reporter`] methods return `impl Future`, these traits cannot be used
+//! as trait objects. This module provides object-safe equivalents, [`DynStorage`] and
+//! [`DynReporter`], that are implemented for every [`Storage`] and [`Reporter`]. Boxed trait
+//! objects (e.g. `Box<dyn DynStorage>`) in turn implement [`Storage`] and [`Reporter`], so they
+//! can be passed to [`run_bot`].
+//!
+//! A [`BackendRegistry`] can be used to build backends by name at runtime, for example from
+//! settings found in a [config file].
+//!
+//! ```no_run
+//! use aoc_leaderbot_lib::leaderbot::dynamic::BackendRegistry;
+//! use aoc_leaderbot_lib::leaderbot::{Config, run_bot};
+//!
+//! async fn run_with_backends(
+//!     config: &impl Config,
+//!     registry: &BackendRegistry,
+//!     storage_name: &str,
+//!     reporter_name: &str,
+//! ) -> aoc_leaderbot_lib::Result<()> {
+//!     let mut storage = registry
+//!         .build_storage(storage_name, serde_json::Value::Null)
+//!         .await?;
+//!     let mut reporter = registry
+//!         .build_reporter(reporter_name, serde_json::Value::Null)
+//!         .await?;
+//!
+//!     run_bot(config, &mut storage, &mut reporter, false).await?;
+//!     Ok(())
+//! }
+//! ```
+//!
+//! # Notes
+//!
+//! [`DynStorage`] only covers the methods of [`Storage`]; extension traits like
+//! [`DigestStorage`] are not available through it. Storages that implement [`OutboxStorage`] can
+//! be used through [`DynOutboxStorage`] instead (see [`BackendRegistry::with_outbox_storage`]).
+//!
+//! [`Storage`]: crate::leaderbot::Storage
+//! [`Reporter`]: crate::leaderbot::Reporter
+//! [`run_bot`]: crate::leaderbot::run_bot
+//! [config file]: crate::leaderbot::config::file
+//! [`DigestStorage`]: crate::leaderbot::DigestStorage
+//! [`OutboxStorage`]: crate::leaderbot::OutboxStorage
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+use aoc_leaderboard::aoc::Leaderboard;
+use chrono::TimeDelta;
+use futures_util::future::BoxFuture;
+
+use crate::error::{BackendKind, DynError};
+use crate::leaderbot::heartbeat::HeartbeatState;
+use crate::leaderbot::{
+    Changes, ErrorState, MergedChanges, OutboxReport, OutboxStorage, Reporter, Storage,
+};
+
+/// Object-safe version of [`Storage`].
+///
+/// Implemented for every [`Storage`] whose error type is `Sync`. See [module documentation](self)
+/// for details.
+pub trait DynStorage: Send + Sync {
+    /// See [`Storage::load_previous`].
+    fn load_previous(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> BoxFuture<'_, anyhow::Result<(Option<Leaderboard>, Option<crate::ErrorKind>)>>;
+
+    /// See [`Storage::save_success`].
+    fn save_success<'a>(
+        &'a mut self,
+        year: i32,
+        leaderboard_id: u64,
+        leaderboard: &'a Leaderboard,
+    ) -> BoxFuture<'a, anyhow::Result<()>>;
+
+    /// See [`Storage::save_error`].
+    fn save_error(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        error_kind: crate::ErrorKind,
+    ) -> BoxFuture<'_, anyhow::Result<()>>;
+
+    /// See [`Storage::save_error_state`].
+    fn save_error_state<'a>(
+        &'a mut self,
+        year: i32,
+        leaderboard_id: u64,
+        error_state: &'a ErrorState,
+    ) -> BoxFuture<'a, anyhow::Result<()>>;
+
+    /// See [`Storage::load_error_state`].
+    fn load_error_state(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> BoxFuture<'_, anyhow::Result<Option<ErrorState>>>;
+
+    /// See [`Storage::load_heartbeat`].
+    fn load_heartbeat(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> BoxFuture<'_, anyhow::Result<Option<HeartbeatState>>>;
+
+    /// See [`Storage::save_heartbeat`].
+    fn save_heartbeat<'a>(
+        &'a mut self,
+        year: i32,
+        leaderboard_id: u64,
+        heartbeat: &'a HeartbeatState,
+    ) -> BoxFuture<'a, anyhow::Result<()>>;
+}
+
+impl<S> DynStorage for S
+where
+    S: Storage + Send + Sync,
+    S::Err: Sync + 'static,
+{
+    fn load_previous(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> BoxFuture<'_, anyhow::Result<(Option<Leaderboard>, Option<crate::ErrorKind>)>> {
+        Box::pin(async move {
+            Storage::load_previous(self, year, leaderboard_id)
+                .await
+                .map_err(anyhow::Error::new)
+        })
+    }
+
+    fn save_success<'a>(
+        &'a mut self,
+        year: i32,
+        leaderboard_id: u64,
+        leaderboard: &'a Leaderboard,
+    ) -> BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(async move {
+            Storage::save_success(self, year, leaderboard_id, leaderboard)
+                .await
+                .map_err(anyhow::Error::new)
+        })
+    }
+
+    fn save_error(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        error_kind: crate::ErrorKind,
+    ) -> BoxFuture<'_, anyhow::Result<()>> {
+        Box::pin(async move {
+            Storage::save_error(self, year, leaderboard_id, error_kind)
+                .await
+                .map_err(anyhow::Error::new)
+        })
+    }
+
+    fn save_error_state<'a>(
+        &'a mut self,
+        year: i32,
+        leaderboard_id: u64,
+        error_state: &'a ErrorState,
+    ) -> BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(async move {
+            Storage::save_error_state(self, year, leaderboard_id, error_state)
+                .await
+                .map_err(anyhow::Error::new)
+        })
+    }
+
+    fn load_error_state(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> BoxFuture<'_, anyhow::Result<Option<ErrorState>>> {
+        Box::pin(async move {
+            Storage::load_error_state(self, year, leaderboard_id)
+                .await
+                .map_err(anyhow::Error::new)
+        })
+    }
+
+    fn load_heartbeat(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> BoxFuture<'_, anyhow::Result<Option<HeartbeatState>>> {
+        Box::pin(async move {
+            Storage::load_heartbeat(self, year, leaderboard_id)
+                .await
+                .map_err(anyhow::Error::new)
+        })
+    }
+
+    fn save_heartbeat<'a>(
+        &'a mut self,
+        year: i32,
+        leaderboard_id: u64,
+        heartbeat: &'a HeartbeatState,
+    ) -> BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(async move {
+            Storage::save_heartbeat(self, year, leaderboard_id, heartbeat)
+                .await
+                .map_err(anyhow::Error::new)
+        })
+    }
+}
+
+impl Debug for dyn DynStorage + '_ {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DynStorage").finish_non_exhaustive()
+    }
+}
+
+impl Storage for Box<dyn DynStorage + '_> {
+    type Err = DynError;
+
+    async fn load_previous(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> Result<(Option<Leaderboard>, Option<crate::ErrorKind>), Self::Err> {
+        Ok(DynStorage::load_previous(self.as_ref(), year, leaderboard_id).await?)
+    }
+
+    async fn save_success(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        leaderboard: &Leaderboard,
+    ) -> Result<(), Self::Err> {
+        Ok(DynStorage::save_success(self.as_mut(), year, leaderboard_id, leaderboard).await?)
+    }
+
+    async fn save_error(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        error_kind: crate::ErrorKind,
+    ) -> Result<(), Self::Err> {
+        Ok(DynStorage::save_error(self.as_mut(), year, leaderboard_id, error_kind).await?)
+    }
+
+    async fn save_error_state(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        error_state: &ErrorState,
+    ) -> Result<(), Self::Err> {
+        Ok(DynStorage::save_error_state(self.as_mut(), year, leaderboard_id, error_state).await?)
+    }
+
+    async fn load_error_state(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> Result<Option<ErrorState>, Self::Err> {
+        Ok(DynStorage::load_error_state(self.as_ref(), year, leaderboard_id).await?)
+    }
+
+    async fn load_heartbeat(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> Result<Option<HeartbeatState>, Self::Err> {
+        Ok(DynStorage::load_heartbeat(self.as_ref(), year, leaderboard_id).await?)
+    }
+
+    async fn save_heartbeat(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        heartbeat: &HeartbeatState,
+    ) -> Result<(), Self::Err> {
+        Ok(DynStorage::save_heartbeat(self.as_mut(), year, leaderboard_id, heartbeat).await?)
+    }
+}
+
+/// Object-safe version of [`OutboxStorage`].
+///
+/// Implemented for every [`OutboxStorage`] whose error type is `Sync`. See
+/// [module documentation](self) for details.
+pub trait DynOutboxStorage: DynStorage {
+    /// See [`OutboxStorage::load_outbox`].
+    fn load_outbox(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> BoxFuture<'_, anyhow::Result<Option<OutboxReport>>>;
+
+    /// See [`OutboxStorage::save_success_with_outbox`].
+    fn save_success_with_outbox<'a>(
+        &'a mut self,
+        year: i32,
+        leaderboard_id: u64,
+        leaderboard: &'a Leaderboard,
+        report: &'a OutboxReport,
+    ) -> BoxFuture<'a, anyhow::Result<()>>;
+
+    /// See [`OutboxStorage::mark_delivered`].
+    fn mark_delivered(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> BoxFuture<'_, anyhow::Result<()>>;
+}
+
+impl<S> DynOutboxStorage for S
+where
+    S: OutboxStorage + Send + Sync,
+    S::Err: Sync + 'static,
+{
+    fn load_outbox(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> BoxFuture<'_, anyhow::Result<Option<OutboxReport>>> {
+        Box::pin(async move {
+            OutboxStorage::load_outbox(self, year, leaderboard_id)
+                .await
+                .map_err(anyhow::Error::new)
+        })
+    }
+
+    fn save_success_with_outbox<'a>(
+        &'a mut self,
+        year: i32,
+        leaderboard_id: u64,
+        leaderboard: &'a Leaderboard,
+        report: &'a OutboxReport,
+    ) -> BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(async move {
+            OutboxStorage::save_success_with_outbox(self, year, leaderboard_id, leaderboard, report)
+                .await
+                .map_err(anyhow::Error::new)
+        })
+    }
+
+    fn mark_delivered(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> BoxFuture<'_, anyhow::Result<()>> {
+        Box::pin(async move {
+            OutboxStorage::mark_delivered(self, year, leaderboard_id)
+                .await
+                .map_err(anyhow::Error::new)
+        })
+    }
+}
+
+impl Debug for dyn DynOutboxStorage + '_ {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DynOutboxStorage").finish_non_exhaustive()
+    }
+}
+
+impl Storage for Box<dyn DynOutboxStorage + '_> {
+    type Err = DynError;
+
+    async fn load_previous(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> Result<(Option<Leaderboard>, Option<crate::ErrorKind>), Self::Err> {
+        Ok(DynStorage::load_previous(self.as_ref(), year, leaderboard_id).await?)
+    }
+
+    async fn save_success(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        leaderboard: &Leaderboard,
+    ) -> Result<(), Self::Err> {
+        Ok(DynStorage::save_success(self.as_mut(), year, leaderboard_id, leaderboard).await?)
+    }
+
+    async fn save_error(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        error_kind: crate::ErrorKind,
+    ) -> Result<(), Self::Err> {
+        Ok(DynStorage::save_error(self.as_mut(), year, leaderboard_id, error_kind).await?)
+    }
+
+    async fn save_error_state(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        error_state: &ErrorState,
+    ) -> Result<(), Self::Err> {
+        Ok(DynStorage::save_error_state(self.as_mut(), year, leaderboard_id, error_state).await?)
+    }
+
+    async fn load_error_state(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> Result<Option<ErrorState>, Self::Err> {
+        Ok(DynStorage::load_error_state(self.as_ref(), year, leaderboard_id).await?)
+    }
+
+    async fn load_heartbeat(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> Result<Option<HeartbeatState>, Self::Err> {
+        Ok(DynStorage::load_heartbeat(self.as_ref(), year, leaderboard_id).await?)
+    }
+
+    async fn save_heartbeat(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        heartbeat: &HeartbeatState,
+    ) -> Result<(), Self::Err> {
+        Ok(DynStorage::save_heartbeat(self.as_mut(), year, leaderboard_id, heartbeat).await?)
+    }
+}
+
+impl OutboxStorage for Box<dyn DynOutboxStorage + '_> {
+    async fn load_outbox(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> Result<Option<OutboxReport>, Self::Err> {
+        Ok(DynOutboxStorage::load_outbox(self.as_ref(), year, leaderboard_id).await?)
+    }
+
+    async fn save_success_with_outbox(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        leaderboard: &Leaderboard,
+        report: &OutboxReport,
+    ) -> Result<(), Self::Err> {
+        Ok(DynOutboxStorage::save_success_with_outbox(
+            self.as_mut(),
+            year,
+            leaderboard_id,
+            leaderboard,
+            report,
+        )
+        .await?)
+    }
+
+    async fn mark_delivered(&mut self, year: i32, leaderboard_id: u64) -> Result<(), Self::Err> {
+        Ok(DynOutboxStorage::mark_delivered(self.as_mut(), year, leaderboard_id).await?)
+    }
+}
+
+/// Object-safe version of [`Reporter`].
+///
+/// Implemented for every [`Reporter`] whose error type is `Sync`. See
+/// [module documentation](self) for details.
+pub trait DynReporter: Send {
+    /// See [`Reporter::report_changes`].
+    fn report_changes<'a>(
+        &'a mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&'a str>,
+        previous_leaderboard: &'a Leaderboard,
+        leaderboard: &'a Leaderboard,
+        changes: &'a Changes,
+    ) -> BoxFuture<'a, anyhow::Result<()>>;
+
+    /// See [`Reporter::report_first_run`].
+    fn report_first_run<'a>(
+        &'a mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&'a str>,
+        leaderboard: &'a Leaderboard,
+    ) -> BoxFuture<'a, anyhow::Result<()>>;
+
+    /// See [`Reporter::report_error`].
+    fn report_error<'a>(
+        &'a mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&'a str>,
+        error: &'a crate::Error,
+    ) -> BoxFuture<'a, ()>;
+
+    /// See [`Reporter::report_recovery`].
+    fn report_recovery<'a>(
+        &'a mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&'a str>,
+        error_kind: crate::ErrorKind,
+        outage_duration: Option<TimeDelta>,
+    ) -> BoxFuture<'a, ()>;
+
+    /// See [`Reporter::report_stale`].
+    fn report_stale<'a>(
+        &'a mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&'a str>,
+        silence: TimeDelta,
+    ) -> BoxFuture<'a, ()>;
+
+    /// See [`Reporter::report_heartbeat`].
+    fn report_heartbeat<'a>(
+        &'a mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&'a str>,
+        leaderboard: &'a Leaderboard,
+    ) -> BoxFuture<'a, ()>;
+
+    /// See [`Reporter::report_merged_changes`].
+    fn report_merged_changes<'a>(
+        &'a mut self,
+        merged_changes: &'a MergedChanges,
+    ) -> BoxFuture<'a, anyhow::Result<()>>;
+
+    /// See [`Reporter::defers_reports`].
+    fn defers_reports(&self) -> bool;
+}
+
+impl<R> DynReporter for R
+where
+    R: Reporter + Send,
+    R::Err: Sync + 'static,
+{
+    fn report_changes<'a>(
+        &'a mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&'a str>,
+        previous_leaderboard: &'a Leaderboard,
+        leaderboard: &'a Leaderboard,
+        changes: &'a Changes,
+    ) -> BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(async move {
+            Reporter::report_changes(
+                self,
+                year,
+                leaderboard_id,
+                view_key,
+                previous_leaderboard,
+                leaderboard,
+                changes,
+            )
+            .await
+            .map_err(anyhow::Error::new)
+        })
+    }
+
+    fn report_first_run<'a>(
+        &'a mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&'a str>,
+        leaderboard: &'a Leaderboard,
+    ) -> BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(async move {
+            Reporter::report_first_run(self, year, leaderboard_id, view_key, leaderboard)
+                .await
+                .map_err(anyhow::Error::new)
+        })
+    }
+
+    fn report_error<'a>(
+        &'a mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&'a str>,
+        error: &'a crate::Error,
+    ) -> BoxFuture<'a, ()> {
+        Box::pin(Reporter::report_error(self, year, leaderboard_id, view_key, error))
+    }
+
+    fn report_recovery<'a>(
+        &'a mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&'a str>,
+        error_kind: crate::ErrorKind,
+        outage_duration: Option<TimeDelta>,
+    ) -> BoxFuture<'a, ()> {
+        Box::pin(Reporter::report_recovery(
+            self,
+            year,
+            leaderboard_id,
+            view_key,
+            error_kind,
+            outage_duration,
+        ))
+    }
+
+    fn report_stale<'a>(
+        &'a mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&'a str>,
+        silence: TimeDelta,
+    ) -> BoxFuture<'a, ()> {
+        Box::pin(Reporter::report_stale(self, year, leaderboard_id, view_key, silence))
+    }
+
+    fn report_heartbeat<'a>(
+        &'a mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&'a str>,
+        leaderboard: &'a Leaderboard,
+    ) -> BoxFuture<'a, ()> {
+        Box::pin(Reporter::report_heartbeat(self, year, leaderboard_id, view_key, leaderboard))
+    }
+
+    fn report_merged_changes<'a>(
+        &'a mut self,
+        merged_changes: &'a MergedChanges,
+    ) -> BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(async move {
+            Reporter::report_merged_changes(self, merged_changes)
+                .await
+                .map_err(anyhow::Error::new)
+        })
+    }
+
+    fn defers_reports(&self) -> bool {
+        Reporter::defers_reports(self)
+    }
+}
+
+impl Debug for dyn DynReporter + '_ {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DynReporter").finish_non_exhaustive()
+    }
+}
+
+impl Reporter for Box<dyn DynReporter + '_> {
+    type Err = DynError;
+
+    async fn report_changes(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        previous_leaderboard: &Leaderboard,
+        leaderboard: &Leaderboard,
+        changes: &Changes,
+    ) -> Result<(), Self::Err> {
+        Ok(DynReporter::report_changes(
+            self.as_mut(),
+            year,
+            leaderboard_id,
+            view_key,
+            previous_leaderboard,
+            leaderboard,
+            changes,
+        )
+        .await?)
+    }
+
+    async fn report_first_run(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        leaderboard: &Leaderboard,
+    ) -> Result<(), Self::Err> {
+        Ok(DynReporter::report_first_run(
+            self.as_mut(),
+            year,
+            leaderboard_id,
+            view_key,
+            leaderboard,
+        )
+        .await?)
+    }
+
+    async fn report_error(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        error: &crate::Error,
+    ) {
+        DynReporter::report_error(self.as_mut(), year, leaderboard_id, view_key, error).await
+    }
+
+    async fn report_recovery(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        error_kind: crate::ErrorKind,
+        outage_duration: Option<TimeDelta>,
+    ) {
+        DynReporter::report_recovery(
+            self.as_mut(),
+            year,
+            leaderboard_id,
+            view_key,
+            error_kind,
+            outage_duration,
+        )
+        .await
+    }
+
+    async fn report_stale(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        silence: TimeDelta,
+    ) {
+        DynReporter::report_stale(self.as_mut(), year, leaderboard_id, view_key, silence).await
+    }
+
+    async fn report_heartbeat(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        leaderboard: &Leaderboard,
+    ) {
+        DynReporter::report_heartbeat(self.as_mut(), year, leaderboard_id, view_key, leaderboard)
+            .await
+    }
+
+    async fn report_merged_changes(
+        &mut self,
+        merged_changes: &MergedChanges,
+    ) -> Result<(), Self::Err> {
+        Ok(DynReporter::report_merged_changes(self.as_mut(), merged_changes).await?)
+    }
+
+    fn defers_reports(&self) -> bool {
+        DynReporter::defers_reports(self.as_ref())
+    }
+}
+
+type Factory<T> =
+    Box<dyn Fn(serde_json::Value) -> BoxFuture<'static, anyhow::Result<T>> + Send + Sync>;
+
+/// Registry of [`Storage`] and [`Reporter`] backends that can be built by name at runtime.
+///
+/// Each backend is registered with a factory that receives backend-specific settings as a
+/// [`serde_json::Value`] (like the [reporter settings] of a config file) and asynchronously
+/// builds the backend.
+///
+/// See [module documentation](self) for an example.
+///
+/// [reporter settings]: crate::leaderbot::config::file::ConfigFile::reporters
+#[derive(Default)]
+pub struct BackendRegistry {
+    storages: BTreeMap<String, Factory<Box<dyn DynStorage>>>,
+    outbox_storages: BTreeMap<String, Factory<Box<dyn DynOutboxStorage>>>,
+    reporters: BTreeMap<String, Factory<Box<dyn DynReporter>>>,
+}
+
+impl BackendRegistry {
+    /// Creates a new, empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a [`Storage`] backend with the given name.
+    pub fn with_storage<N, F, Fut, S, E>(mut self, name: N, factory: F) -> Self
+    where
+        N: Into<String>,
+        F: Fn(serde_json::Value) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<S, E>> + Send + 'static,
+        S: Storage + Send + Sync + 'static,
+        S::Err: Sync + 'static,
+        E: Into<anyhow::Error>,
+    {
+        self.register_storage(name, factory);
+        self
+    }
+
+    /// Registers a [`Storage`] backend with the given name.
+    ///
+    /// If a storage backend was already registered with this name, it is replaced.
+    pub fn register_storage<N, F, Fut, S, E>(&mut self, name: N, factory: F)
+    where
+        N: Into<String>,
+        F: Fn(serde_json::Value) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<S, E>> + Send + 'static,
+        S: Storage + Send + Sync + 'static,
+        S::Err: Sync + 'static,
+        E: Into<anyhow::Error>,
+    {
+        let factory: Factory<Box<dyn DynStorage>> = Box::new(move |settings| {
+            let storage = factory(settings);
+            Box::pin(async move {
+                storage
+                    .await
+                    .map(|storage| Box::new(storage) as Box<dyn DynStorage>)
+                    .map_err(Into::into)
+            })
+        });
+        let name = name.into();
+        self.outbox_storages.remove(&name);
+        self.storages.insert(name, factory);
+    }
+
+    /// Registers a [`Storage`] backend that supports an [outbox](OutboxStorage) with the
+    /// given name.
+    ///
+    /// The backend can be built either via [`build_outbox_storage`](Self::build_outbox_storage)
+    /// or [`build_storage`](Self::build_storage).
+    pub fn with_outbox_storage<N, F, Fut, S, E>(mut self, name: N, factory: F) -> Self
+    where
+        N: Into<String>,
+        F: Fn(serde_json::Value) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<S, E>> + Send + 'static,
+        S: OutboxStorage + Send + Sync + 'static,
+        S::Err: Sync + 'static,
+        E: Into<anyhow::Error>,
+    {
+        self.register_outbox_storage(name, factory);
+        self
+    }
+
+    /// Registers a [`Storage`] backend that supports an [outbox](OutboxStorage) with the
+    /// given name.
+    ///
+    /// If a storage backend was already registered with this name, it is replaced.
+    pub fn register_outbox_storage<N, F, Fut, S, E>(&mut self, name: N, factory: F)
+    where
+        N: Into<String>,
+        F: Fn(serde_json::Value) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<S, E>> + Send + 'static,
+        S: OutboxStorage + Send + Sync + 'static,
+        S::Err: Sync + 'static,
+        E: Into<anyhow::Error>,
+    {
+        let name = name.into();
+        let factory = Arc::new(factory);
+
+        let storage_factory = Arc::clone(&factory);
+        self.register_storage(name.clone(), move |settings| storage_factory(settings));
+
+        let factory: Factory<Box<dyn DynOutboxStorage>> = Box::new(move |settings| {
+            let storage = factory(settings);
+            Box::pin(async move {
+                storage
+                    .await
+                    .map(|storage| Box::new(storage) as Box<dyn DynOutboxStorage>)
+                    .map_err(Into::into)
+            })
+        });
+        self.outbox_storages.insert(name, factory);
+    }
+
+    /// Registers a [`Reporter`] backend with the given name.
+    pub fn with_reporter<N, F, Fut, R, E>(mut self, name: N, factory: F) -> Self
+    where
+        N: Into<String>,
+        F: Fn(serde_json::Value) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<R, E>> + Send + 'static,
+        R: Reporter + Send + 'static,
+        R::Err: Sync + 'static,
+        E: Into<anyhow::Error>,
+    {
+        self.register_reporter(name, factory);
+        self
+    }
+
+    /// Registers a [`Reporter`] backend with the given name.
+    ///
+    /// If a reporter backend was already registered with this name, it is replaced.
+    pub fn register_reporter<N, F, Fut, R, E>(&mut self, name: N, factory: F)
+    where
+        N: Into<String>,
+        F: Fn(serde_json::Value) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<R, E>> + Send + 'static,
+        R: Reporter + Send + 'static,
+        R::Err: Sync + 'static,
+        E: Into<anyhow::Error>,
+    {
+        let factory: Factory<Box<dyn DynReporter>> = Box::new(move |settings| {
+            let reporter = factory(settings);
+            Box::pin(async move {
+                reporter
+                    .await
+                    .map(|reporter| Box::new(reporter) as Box<dyn DynReporter>)
+                    .map_err(Into::into)
+            })
+        });
+        self.reporters.insert(name.into(), factory);
+    }
+
+    /// Returns the names of all registered storage backends, in alphabetical order.
+    pub fn storage_names(&self) -> impl Iterator<Item = &str> {
+        self.storages.keys().map(String::as_str)
+    }
+
+    /// Returns the names of all registered storage backends that support an
+    /// [outbox](OutboxStorage), in alphabetical order.
+    pub fn outbox_storage_names(&self) -> impl Iterator<Item = &str> {
+        self.outbox_storages.keys().map(String::as_str)
+    }
+
+    /// Returns the names of all registered reporter backends, in alphabetical order.
+    pub fn reporter_names(&self) -> impl Iterator<Item = &str> {
+        self.reporters.keys().map(String::as_str)
+    }
+
+    /// Builds the storage backend with the given name, passing it the given settings.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::UnknownBackend`]: if no storage backend was registered with this name
+    /// - [`Error::Backend`]: if the backend's factory returned an error
+    ///
+    /// [`Error::UnknownBackend`]: crate::Error::UnknownBackend
+    /// [`Error::Backend`]: crate::Error::Backend
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self, settings), err))]
+    pub async fn build_storage(
+        &self,
+        name: &str,
+        settings: serde_json::Value,
+    ) -> crate::Result<Box<dyn DynStorage>> {
+        Self::build(&self.storages, BackendKind::Storage, name, settings).await
+    }
+
+    /// Builds the storage backend that supports an [outbox](OutboxStorage) with the given name,
+    /// passing it the given settings.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::UnknownBackend`]: if no storage backend supporting an outbox was registered
+    ///   with this name
+    /// - [`Error::Backend`]: if the backend's factory returned an error
+    ///
+    /// [`Error::UnknownBackend`]: crate::Error::UnknownBackend
+    /// [`Error::Backend`]: crate::Error::Backend
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self, settings), err))]
+    pub async fn build_outbox_storage(
+        &self,
+        name: &str,
+        settings: serde_json::Value,
+    ) -> crate::Result<Box<dyn DynOutboxStorage>> {
+        Self::build(&self.outbox_storages, BackendKind::Storage, name, settings).await
+    }
+
+    /// Builds the reporter backend with the given name, passing it the given settings.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::UnknownBackend`]: if no reporter backend was registered with this name
+    /// - [`Error::Backend`]: if the backend's factory returned an error
+    ///
+    /// [`Error::UnknownBackend`]: crate::Error::UnknownBackend
+    /// [`Error::Backend`]: crate::Error::Backend
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self, settings), err))]
+    pub async fn build_reporter(
+        &self,
+        name: &str,
+        settings: serde_json::Value,
+    ) -> crate::Result<Box<dyn DynReporter>> {
+        Self::build(&self.reporters, BackendKind::Reporter, name, settings).await
+    }
+
+    async fn build<T>(
+        factories: &BTreeMap<String, Factory<T>>,
+        kind: BackendKind,
+        name: &str,
+        settings: serde_json::Value,
+    ) -> crate::Result<T> {
+        let factory = factories
+            .get(name)
+            .ok_or_else(|| crate::Error::UnknownBackend { kind, name: name.into() })?;
+
+        factory(settings)
+            .await
+            .map_err(|source| crate::Error::Backend { kind, name: name.into(), source })
+    }
+}
+
+impl Debug for BackendRegistry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BackendRegistry")
+            .field("storages", &self.storage_names().collect::<Vec<_>>())
+            .field("outbox_storages", &self.outbox_storage_names().collect::<Vec<_>>())
+            .field("reporters", &self.reporter_names().collect::<Vec<_>>())
+            .finish()
+    }
+}
@@ -0,0 +1,161 @@
+//! Awareness of the Advent of Code event window.
+//!
+//! Advent of Code puzzles unlock every day from December 1st until the event's last day; outside
+//! of that window, leaderboards rarely change. When a [`Config`] returns an [`EventWindow`] from
+//! its [`event_window`] method, bot runs (like [`run_bot`]) skip fetching leaderboard data outside
+//! the event (or only fetch it once a day), and bots running in daemon mode schedule their runs
+//! accordingly.
+//!
+//! [`event_window`]: Config::event_window
+//! [`run_bot`]: crate::leaderbot::run_bot
+
+use chrono::{DateTime, Datelike, NaiveTime, TimeDelta, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::leaderbot::Config;
+
+/// Minimum interval between two fetches of a leaderboard's data.
+///
+/// Advent of Code asks that private leaderboards not be fetched more often than every 15 minutes.
+pub const MIN_INTERVAL: TimeDelta = TimeDelta::minutes(15);
+
+/// Default grace period after the end of the event, during which the bot keeps running normally.
+pub const DEFAULT_GRACE_PERIOD: TimeDelta = TimeDelta::days(7);
+
+/// Returns the times at which the puzzles of the given year's Advent of Code event unlock,
+/// in chronological order.
+///
+/// Puzzles unlock at midnight EST (UTC-5) every day from December 1st. Starting in 2025,
+/// the event lasts 12 days instead of 25.
+pub fn puzzle_unlock_times(year: i32) -> impl Iterator<Item = DateTime<Utc>> {
+    let last_day = if year >= 2025 { 12 } else { 25 };
+
+    (1..=last_day).filter_map(move |day| Utc.with_ymd_and_hms(year, 12, day, 5, 0, 0).single())
+}
+
+/// Returns the time at which the given year's Advent of Code event starts,
+/// e.g. when its first puzzle unlocks.
+pub fn event_start(year: i32) -> DateTime<Utc> {
+    puzzle_unlock_times(year)
+        .next()
+        .expect("event should have puzzles")
+}
+
+/// Returns the time at which the given year's Advent of Code event ends,
+/// e.g. one day after its last puzzle unlocks.
+pub fn event_end(year: i32) -> DateTime<Utc> {
+    puzzle_unlock_times(year)
+        .last()
+        .expect("event should have puzzles")
+        + TimeDelta::days(1)
+}
+
+/// Returns the year of the latest Advent of Code event that started on or before `now`.
+///
+/// This switches to the new year on December 1st, when the event starts; before that,
+/// the previous year is returned.
+pub fn current_event_year(now: DateTime<Utc>) -> i32 {
+    if now >= event_start(now.year()) { now.year() } else { now.year() - 1 }
+}
+
+/// Policy determining whether the bot runs outside the [`EventWindow`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum OffSeasonPolicy {
+    /// Do not fetch leaderboard data outside the event window.
+    #[default]
+    Skip,
+
+    /// Fetch leaderboard data once a day, at the given time (UTC), outside the event window.
+    ///
+    /// When the bot is not running in daemon mode, it fetches data if it runs less than
+    /// [`MIN_INTERVAL`] after that time. The bot should thus run at least that often.
+    Daily(NaiveTime),
+}
+
+/// Window during which the bot fetches leaderboard data normally.
+///
+/// The window starts when the first puzzle of an Advent of Code event unlocks and ends a grace
+/// period after the end of the event's last day. Outside that window, the [`off_season`] policy
+/// determines whether the bot runs.
+///
+/// [`off_season`]: EventWindow::off_season
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct EventWindow {
+    /// Grace period after the end of the event, during which the bot keeps running normally.
+    pub grace_period: TimeDelta,
+
+    /// Policy determining whether the bot runs outside the event window.
+    pub off_season: OffSeasonPolicy,
+}
+
+impl Default for EventWindow {
+    fn default() -> Self {
+        Self::new(DEFAULT_GRACE_PERIOD)
+    }
+}
+
+impl EventWindow {
+    /// Creates a new event window with the given grace period, skipping bot runs outside of it.
+    pub fn new(grace_period: TimeDelta) -> Self {
+        Self { grace_period, off_season: OffSeasonPolicy::Skip }
+    }
+
+    /// Returns a copy of this event window with the given off-season policy.
+    pub fn with_off_season(self, off_season: OffSeasonPolicy) -> Self {
+        Self { off_season, ..self }
+    }
+
+    /// Checks if the given time is within the event window.
+    pub fn is_in_season(&self, now: DateTime<Utc>) -> bool {
+        now < event_end(current_event_year(now)) + self.grace_period
+    }
+
+    /// Checks if the bot should fetch leaderboard data when run at the given time.
+    pub fn is_run_due(&self, now: DateTime<Utc>) -> bool {
+        if self.is_in_season(now) {
+            return true;
+        }
+
+        match self.off_season {
+            OffSeasonPolicy::Skip => false,
+            OffSeasonPolicy::Daily(time) => {
+                let run_time = now.date_naive().and_time(time).and_utc();
+                run_time <= now && now < run_time + MIN_INTERVAL
+            },
+        }
+    }
+
+    /// Returns the first time at or after `time` at which the bot should fetch leaderboard data.
+    pub fn next_run_time(&self, time: DateTime<Utc>) -> DateTime<Utc> {
+        if self.is_in_season(time) {
+            return time;
+        }
+
+        let next_event = event_start(current_event_year(time) + 1);
+        match self.off_season {
+            OffSeasonPolicy::Skip => next_event,
+            OffSeasonPolicy::Daily(run_time) => {
+                let mut daily_run = time.date_naive().and_time(run_time).and_utc();
+                if daily_run < time {
+                    daily_run += TimeDelta::days(1);
+                }
+                daily_run.min(next_event)
+            },
+        }
+    }
+}
+
+// Checks if leaderboard data should not be fetched at this time, according to the config's
+// event window. Bot runs should be skipped entirely in that case.
+pub(crate) fn is_off_season<C>(config: &C) -> bool
+where
+    C: Config + ?Sized,
+{
+    match config.event_window() {
+        Some(event_window) if !event_window.is_run_due(config.clock().now()) => {
+            tracing::info!("outside of the Advent of Code event window, skipping bot run");
+            true
+        },
+        _ => false,
+    }
+}
@@ -0,0 +1,276 @@
+//! Heartbeat monitoring of the bot ("dead man's switch").
+//!
+//! If whatever schedules bot runs stops working, the bot does not run anymore and thus cannot
+//! report anything, so nobody notices. When the [`Config`] returns a [`HeartbeatPolicy`], the bot
+//! records the time of every successful run in its [`Storage`] (see [`HeartbeatState`]).
+//! [`check_heartbeat`] can then be called on a separate schedule (e.g. by another process) to
+//! [report] when the bot has not succeeded for too long during the event.
+//!
+//! The policy can also ask the bot to send a "still alive" [heartbeat] when nothing has been
+//! reported for some time, so that users know the bot is still watching the leaderboard.
+//!
+//! ```
+//! use aoc_leaderbot_lib::leaderbot::heartbeat::HeartbeatPolicy;
+//! use chrono::TimeDelta;
+//!
+//! // Report when the bot hasn't succeeded for 2 hours and say hi once a day if nothing changed.
+//! let policy = HeartbeatPolicy::default()
+//!     .with_max_silence(TimeDelta::hours(2))
+//!     .with_interval(TimeDelta::days(1));
+//! assert!(policy.is_enabled());
+//! ```
+//!
+//! [report]: Reporter::report_stale
+//! [heartbeat]: Reporter::report_heartbeat
+
+use anyhow::anyhow;
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::StorageError;
+use crate::leaderbot::diagnostics::RunDiagnostics;
+use crate::leaderbot::event::event_start;
+use crate::leaderbot::{BotOutput, Config, Reporter, Storage};
+
+/// Policy determining how the bot's heartbeat is monitored.
+///
+/// See [module documentation](self) for details.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct HeartbeatPolicy {
+    /// Maximum time without a successful bot run before [`check_heartbeat`] reports it.
+    pub max_silence: Option<TimeDelta>,
+
+    /// Minimum time between two "still alive" [heartbeats](Reporter::report_heartbeat).
+    ///
+    /// A heartbeat is sent after a successful bot run that reported nothing, if nothing has
+    /// been reported for at least this long.
+    pub interval: Option<TimeDelta>,
+}
+
+impl HeartbeatPolicy {
+    /// Returns a copy of this policy reporting when the bot hasn't succeeded for `max_silence`.
+    pub fn with_max_silence(self, max_silence: TimeDelta) -> Self {
+        Self { max_silence: Some(max_silence), ..self }
+    }
+
+    /// Returns a copy of this policy sending heartbeats at the given interval.
+    pub fn with_interval(self, interval: TimeDelta) -> Self {
+        Self { interval: Some(interval), ..self }
+    }
+
+    /// Checks if heartbeat monitoring is enabled.
+    ///
+    /// The bot only records [`HeartbeatState`]s when it is.
+    pub fn is_enabled(&self) -> bool {
+        self.max_silence.is_some() || self.interval.is_some()
+    }
+
+    /// Checks if a heartbeat should be sent, given the time at which something was last
+    /// reported (if ever).
+    pub fn is_heartbeat_due(&self, last_report: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+        self.interval.is_some_and(|interval| {
+            last_report.is_none_or(|last_report| now - last_report >= interval)
+        })
+    }
+}
+
+/// Information about the bot's heartbeat, persisted in [`Storage`].
+///
+/// Saved via [`Storage::save_heartbeat`] after every successful bot run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeartbeatState {
+    /// Time of the last successful bot run, if any.
+    ///
+    /// Set to `None` if [`check_heartbeat`] reported that the bot was stale before it
+    /// succeeded even once during the event.
+    #[serde(default)]
+    pub last_success: Option<DateTime<Utc>>,
+
+    /// Time at which something (changes, first run or heartbeat) was last reported, if ever.
+    #[serde(default)]
+    pub last_report: Option<DateTime<Utc>>,
+
+    /// Time at which [`check_heartbeat`] reported that the bot was stale, if it did since
+    /// the last successful bot run.
+    #[serde(default)]
+    pub stale_reported: Option<DateTime<Utc>>,
+}
+
+impl HeartbeatState {
+    /// Creates a new heartbeat state for a successful bot run at the given time.
+    pub fn new(last_success: DateTime<Utc>) -> Self {
+        Self { last_success: Some(last_success), last_report: None, stale_reported: None }
+    }
+}
+
+/// Status of the bot's heartbeat, as returned by [`check_heartbeat`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HeartbeatStatus {
+    /// The [`Config`] does not have a [`max_silence`](HeartbeatPolicy::max_silence).
+    Disabled,
+
+    /// The event is not in progress, so the bot is not expected to run.
+    OffSeason,
+
+    /// The bot has not recorded a successful run yet, but the event started less than
+    /// [`max_silence`](HeartbeatPolicy::max_silence) ago.
+    NoSuccessRecorded,
+
+    /// The bot succeeded recently enough.
+    Alive {
+        /// Time of the last successful bot run.
+        last_success: DateTime<Utc>,
+    },
+
+    /// The bot has not succeeded for longer than the [`max_silence`](HeartbeatPolicy::max_silence).
+    Stale {
+        /// Time of the last successful bot run, if the bot succeeded during the event.
+        last_success: Option<DateTime<Utc>>,
+
+        /// Time elapsed since the last successful bot run (or since the [start of the event]
+        /// if the bot has not succeeded yet).
+        ///
+        /// [start of the event]: crate::leaderbot::event::event_start
+        silence: TimeDelta,
+    },
+}
+
+/// Checks if the bot has succeeded recently and reports it if not.
+///
+/// If the bot has not succeeded for longer than the [`max_silence`] of the config's
+/// [`heartbeat_policy`] while the [event is in progress], calls [`Reporter::report_stale`].
+/// The bot is only reported as stale once; it will be reported again if it goes silent
+/// after another successful run.
+///
+/// Successful runs are recorded per [year](Config::year). If the bot has not recorded any for the
+/// current year yet (for example, when the scheduler stopped before the event started), silence
+/// is measured since the [start of the event], so a bot that never runs is still reported.
+///
+/// This function is meant to be called on a different schedule than [`run_bot`], since it
+/// must still run when the bot itself does not.
+///
+/// [`max_silence`]: HeartbeatPolicy::max_silence
+/// [`heartbeat_policy`]: Config::heartbeat_policy
+/// [event is in progress]: crate::leaderbot::event::EventWindow::is_in_season
+/// [start of the event]: event_start
+/// [`run_bot`]: crate::leaderbot::run_bot
+#[cfg_attr(
+    not(coverage),
+    tracing::instrument(skip(config, storage, reporter), level = "debug", ret, err)
+)]
+pub async fn check_heartbeat<C, S, R>(
+    config: &C,
+    storage: &mut S,
+    reporter: &mut R,
+) -> crate::Result<HeartbeatStatus>
+where
+    C: Config,
+    S: Storage,
+    <S as Storage>::Err: Sync + 'static,
+    R: Reporter,
+{
+    let Some(max_silence) = config.heartbeat_policy().max_silence else {
+        return Ok(HeartbeatStatus::Disabled);
+    };
+
+    let now = config.clock().now();
+    if !config.event_window().unwrap_or_default().is_in_season(now) {
+        return Ok(HeartbeatStatus::OffSeason);
+    }
+
+    let (year, leaderboard_id) = (config.year(), config.leaderboard_id());
+    let mut heartbeat = storage
+        .load_heartbeat(year, leaderboard_id)
+        .await
+        .map_err(|err| StorageError::LoadHeartbeat(anyhow!(err)))?
+        .unwrap_or(HeartbeatState { last_success: None, last_report: None, stale_reported: None });
+
+    let last_success = heartbeat.last_success;
+    let silence = now - last_success.unwrap_or_else(|| event_start(year));
+    match last_success {
+        Some(last_success) if silence < max_silence => {
+            return Ok(HeartbeatStatus::Alive { last_success });
+        },
+        None if silence < max_silence => return Ok(HeartbeatStatus::NoSuccessRecorded),
+        _ => (),
+    }
+
+    if heartbeat.stale_reported.is_none() {
+        reporter
+            .report_stale(year, leaderboard_id, config.credentials().view_key(), silence)
+            .await;
+
+        heartbeat.stale_reported = Some(now);
+        storage
+            .save_heartbeat(year, leaderboard_id, &heartbeat)
+            .await
+            .map_err(|err| StorageError::SaveHeartbeat(anyhow!(err)))?;
+    }
+
+    Ok(HeartbeatStatus::Stale { last_success, silence })
+}
+
+// Records a successful bot run and sends a heartbeat if one is due, given whether
+// anything was reported during the run.
+//
+// Failing to do so only means the heartbeat is not monitored properly,
+// so there's no need to fail the bot run because of it.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn record_success<S, R>(
+    storage: &mut S,
+    reporter: &mut R,
+    view_key: Option<&str>,
+    output: &BotOutput,
+    reported: bool,
+    policy: &HeartbeatPolicy,
+    now: DateTime<Utc>,
+    diagnostics: &mut RunDiagnostics,
+) where
+    S: Storage,
+    R: Reporter,
+{
+    if !policy.is_enabled() {
+        return;
+    }
+
+    let (year, leaderboard_id) = (output.year, output.leaderboard_id);
+    let previous = match diagnostics
+        .storage_operation("load_heartbeat", storage.load_heartbeat(year, leaderboard_id))
+        .await
+    {
+        Ok(previous) => previous,
+        Err(err) => {
+            tracing::warn!("failed to load heartbeat state: {err}");
+            None
+        },
+    };
+
+    let mut heartbeat = HeartbeatState {
+        last_report: previous.and_then(|previous| previous.last_report),
+        ..HeartbeatState::new(now)
+    };
+    if output.deferred {
+        // Deferred reports will be sent during a later run, which will also count as a report.
+        tracing::debug!("reports deferred, skipping heartbeat");
+    } else if reported {
+        heartbeat.last_report = Some(now);
+    } else if policy.is_heartbeat_due(heartbeat.last_report, now) {
+        diagnostics
+            .unchecked_reporter_call(
+                "report_heartbeat",
+                reporter.report_heartbeat(year, leaderboard_id, view_key, &output.leaderboard),
+            )
+            .await;
+        heartbeat.last_report = Some(now);
+    }
+
+    if let Err(err) = diagnostics
+        .storage_operation(
+            "save_heartbeat",
+            storage.save_heartbeat(year, leaderboard_id, &heartbeat),
+        )
+        .await
+    {
+        tracing::warn!("failed to save heartbeat state: {err}");
+    }
+}
@@ -0,0 +1,180 @@
+//! Per-leaderboard configuration of members.
+//!
+//! The bot's [`Config`] can return [`MemberSettings`] to customize how leaderboard members are
+//! handled:
+//!
+//! - [aliases] give display names to members (e.g. anonymous members, who would otherwise
+//!   show up as `(anonymous user #1234)`)
+//! - [ignored] members (e.g. bots or test accounts) are removed from the leaderboard
+//! - [opted-out] members are kept in the leaderboard, but their name is removed
+//! - in [privacy mode], every member's name is replaced by a stable [pseudonym]
+//!
+//! The settings are applied to leaderboards as soon as they are loaded or fetched, so they affect
+//! change detection, storage and every [`Reporter`].
+//!
+//! [`Config`]: crate::leaderbot::Config
+//! [aliases]: MemberSettings::aliases
+//! [ignored]: MemberSettings::ignored
+//! [opted-out]: MemberSettings::opted_out
+//! [privacy mode]: MemberSettings::pseudonymize
+//! [pseudonym]: pseudonym
+//! [`Reporter`]: crate::leaderbot::Reporter
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use aoc_leaderboard::aoc::Leaderboard;
+use serde::{Deserialize, Serialize};
+
+/// Configuration of the members of a leaderboard.
+///
+/// See [module documentation](self) for details.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MemberSettings {
+    /// Display names to use for members, by member ID.
+    #[serde(default)]
+    pub aliases: BTreeMap<u64, String>,
+
+    /// IDs of members to remove from the leaderboard.
+    #[serde(default)]
+    pub ignored: BTreeSet<u64>,
+
+    /// IDs of members who do not want to be named.
+    ///
+    /// Opting out takes precedence over [aliases](Self::aliases).
+    #[serde(default)]
+    pub opted_out: BTreeSet<u64>,
+
+    /// Whether to replace the names of all members with [pseudonyms](pseudonym).
+    ///
+    /// Useful when reports are posted in public places. Privacy mode takes precedence over
+    /// [aliases](Self::aliases) and [opt-outs](Self::opted_out); stars and scores are untouched.
+    #[serde(default)]
+    pub pseudonymize: bool,
+}
+
+impl MemberSettings {
+    /// Returns `true` if these settings do not affect leaderboards.
+    pub fn is_empty(&self) -> bool {
+        self.aliases.is_empty()
+            && self.ignored.is_empty()
+            && self.opted_out.is_empty()
+            && !self.pseudonymize
+    }
+
+    /// Applies these settings to the given leaderboard.
+    pub fn apply(&self, leaderboard: &mut Leaderboard) {
+        leaderboard
+            .members
+            .retain(|id, _| !self.ignored.contains(id));
+
+        for member in leaderboard.members.values_mut() {
+            if self.pseudonymize {
+                member.name = Some(pseudonym(member.id));
+            } else if self.opted_out.contains(&member.id) {
+                member.name = None;
+            } else if let Some(alias) = self.aliases.get(&member.id) {
+                member.name = Some(alias.clone());
+            }
+        }
+    }
+
+    /// Applies these settings to the given leaderboard and returns it.
+    pub fn applied(&self, mut leaderboard: Leaderboard) -> Leaderboard {
+        self.apply(&mut leaderboard);
+        leaderboard
+    }
+}
+
+/// Returns a pseudonym for the leaderboard member with the given ID.
+///
+/// Pseudonyms are in the form `adjective-animal` (e.g. `brave-otter`) and are stable: the same
+/// member ID always returns the same pseudonym.
+pub fn pseudonym(member_id: u64) -> String {
+    // Mix the bits of the member ID (using the SplitMix64 finalizer) so that
+    // consecutive IDs don't end up with similar pseudonyms.
+    let mut hash = member_id.wrapping_add(0x9e3779b97f4a7c15);
+    hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d049bb133111eb);
+    hash ^= hash >> 31;
+
+    let adjective = ADJECTIVES[(hash % ADJECTIVES.len() as u64) as usize];
+    let animal = ANIMALS[((hash >> 32) % ANIMALS.len() as u64) as usize];
+    format!("{adjective}-{animal}")
+}
+
+const ADJECTIVES: &[&str] = &[
+    "agile",
+    "amber",
+    "ancient",
+    "bold",
+    "brave",
+    "bright",
+    "calm",
+    "clever",
+    "cosmic",
+    "crimson",
+    "curious",
+    "daring",
+    "dizzy",
+    "eager",
+    "electric",
+    "fancy",
+    "fearless",
+    "fluffy",
+    "frosty",
+    "gentle",
+    "giant",
+    "golden",
+    "happy",
+    "hidden",
+    "humble",
+    "jolly",
+    "keen",
+    "kind",
+    "lively",
+    "lucky",
+    "merry",
+    "mighty",
+    "misty",
+    "nimble",
+    "noble",
+    "patient",
+    "plucky",
+    "polite",
+    "proud",
+    "quick",
+    "quiet",
+    "rapid",
+    "rusty",
+    "shiny",
+    "silent",
+    "silver",
+    "sleepy",
+    "sly",
+    "smooth",
+    "snowy",
+    "spicy",
+    "steady",
+    "stormy",
+    "sunny",
+    "swift",
+    "tidy",
+    "tiny",
+    "tranquil",
+    "vivid",
+    "wandering",
+    "wild",
+    "wise",
+    "witty",
+    "zesty",
+];
+
+const ANIMALS: &[&str] = &[
+    "alpaca", "badger", "beaver", "bison", "camel", "cheetah", "cobra", "cougar", "coyote",
+    "crane", "dingo", "dolphin", "eagle", "falcon", "ferret", "flamingo", "fox", "gazelle",
+    "gecko", "giraffe", "gorilla", "hamster", "hedgehog", "heron", "hippo", "ibex", "iguana",
+    "jackal", "jaguar", "koala", "lemur", "leopard", "llama", "lynx", "marmot", "meerkat", "mole",
+    "moose", "narwhal", "ocelot", "octopus", "orca", "otter", "owl", "panda", "panther", "pelican",
+    "penguin", "puffin", "quokka", "raccoon", "raven", "reindeer", "salmon", "seal", "sloth",
+    "squirrel", "stork", "tapir", "tiger", "toucan", "walrus", "wombat", "yak",
+];
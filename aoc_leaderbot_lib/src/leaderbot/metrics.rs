@@ -0,0 +1,407 @@
+//! [Prometheus] metrics about the bot and the leaderboards it monitors.
+//!
+//! [`BotMetrics`] keeps track of counters about bot runs (number of runs, fetch errors, reporter
+//! failures, fetch latency) as well as gauges storing the stars and local score of every member
+//! of the monitored leaderboards. Metrics are updated by [observing] the result of each bot run.
+//!
+//! Metrics can then be exported in two ways:
+//!
+//! - Long-running processes can [serve] them over HTTP, at the `/metrics` path, to be scraped
+//!   by Prometheus.
+//! - Short-lived processes (like AWS Lambda functions) can [push] them to a [Pushgateway] after
+//!   each bot run.
+//!
+//! ```no_run
+//! use aoc_leaderbot_lib::leaderbot::metrics::BotMetrics;
+//! use aoc_leaderbot_lib::leaderbot::{Config, Reporter, Storage, run_bot};
+//!
+//! async fn run_and_push<C, S, R>(
+//!     config: &C,
+//!     storage: &mut S,
+//!     reporter: &mut R,
+//!     metrics: &BotMetrics,
+//! ) -> aoc_leaderbot_lib::Result<()>
+//! where
+//!     C: Config,
+//!     S: Storage,
+//!     <S as Storage>::Err: Sync + 'static,
+//!     R: Reporter,
+//!     <R as Reporter>::Err: Sync + 'static,
+//! {
+//!     let result = run_bot(config, storage, reporter, false).await;
+//!     metrics.observe(config.year(), config.leaderboard_id(), &result);
+//!
+//!     metrics
+//!         .push(&reqwest::Client::new(), "http://localhost:9091", "aoc_leaderbot")
+//!         .await
+//! }
+//! ```
+//!
+//! [Prometheus]: https://prometheus.io/
+//! [observing]: BotMetrics::observe
+//! [serve]: BotMetrics::serve
+//! [push]: BotMetrics::push
+//! [Pushgateway]: https://github.com/prometheus/pushgateway
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use std::future::Future;
+use std::pin::pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::anyhow;
+use aoc_leaderboard::aoc::Leaderboard;
+use futures_util::StreamExt;
+use futures_util::stream::FuturesUnordered;
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::leaderbot::BotOutput;
+
+/// Namespace of all metrics exported by [`BotMetrics`].
+pub const METRICS_NAMESPACE: &str = "aoc_leaderbot";
+
+/// Path at which metrics are [served](BotMetrics::serve).
+pub const METRICS_PATH: &str = "/metrics";
+
+/// Maximum time allowed to handle a request when [serving](BotMetrics::serve) metrics.
+///
+/// Connections of clients that take longer to send their request are closed.
+pub const METRICS_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Label values identifying a member gauge: year, leaderboard ID, member ID and member name.
+type MemberLabels = [String; 4];
+
+// Labels of the member gauges set for each leaderboard, to remove those of departed members.
+type LeaderboardMembers = HashMap<(i32, u64), HashSet<MemberLabels>>;
+
+/// [Prometheus] metrics about the bot and the leaderboards it monitors.
+///
+/// The following metrics are exported (all prefixed with [`METRICS_NAMESPACE`]):
+///
+/// | Metric                         | Type      | Labels                                          |
+/// |--------------------------------|-----------|-------------------------------------------------|
+/// | `runs_total`                   | counter   | `year`, `leaderboard_id`, `outcome`             |
+/// | `fetch_errors_total`           | counter   | `year`, `leaderboard_id`, `kind`                |
+/// | `reporter_failures_total`      | counter   | `year`, `leaderboard_id`, `kind`                |
+/// | `fetch_latency_seconds`        | histogram | `year`, `leaderboard_id`                        |
+/// | `member_stars`                 | gauge     | `year`, `leaderboard_id`, `member_id`, `member` |
+/// | `member_local_score`           | gauge     | `year`, `leaderboard_id`, `member_id`, `member` |
+///
+/// `outcome` is either `success`, `deferred` (for successful bot runs that [deferred] reports) or
+/// `error`, while `kind` is the [`ErrorKind`] of the error that occurred. Member gauges reflect the leaderboard returned by the last successful bot run; members
+/// that left the leaderboard (or were renamed) are removed.
+///
+/// Cloning a `BotMetrics` returns a handle to the same metrics, so that a clone can be [served]
+/// while the original is used to [observe] bot runs.
+///
+/// [Prometheus]: https://prometheus.io/
+/// [deferred]: BotOutput::deferred
+/// [`ErrorKind`]: crate::ErrorKind
+/// [served]: Self::serve
+/// [observe]: Self::observe
+#[derive(Debug, Clone)]
+pub struct BotMetrics {
+    registry: Registry,
+    runs: IntCounterVec,
+    fetch_errors: IntCounterVec,
+    reporter_failures: IntCounterVec,
+    fetch_latency: HistogramVec,
+    member_stars: IntGaugeVec,
+    member_local_score: IntGaugeVec,
+    members: Arc<Mutex<LeaderboardMembers>>,
+}
+
+impl BotMetrics {
+    /// Creates a new set of bot metrics, registered in a new [`Registry`].
+    pub fn new() -> crate::Result<Self> {
+        Self::with_registry(Registry::new())
+    }
+
+    /// Creates a new set of bot metrics, registered in the given [`Registry`].
+    ///
+    /// Useful to export the bot metrics along with other metrics of the process.
+    pub fn with_registry(registry: Registry) -> crate::Result<Self> {
+        let metrics = Self {
+            runs: IntCounterVec::new(
+                opts("runs_total", "Number of bot runs."),
+                &["year", "leaderboard_id", "outcome"],
+            )
+            .map_err(metrics_error)?,
+            fetch_errors: IntCounterVec::new(
+                opts(
+                    "fetch_errors_total",
+                    "Number of errors that occurred while fetching leaderboard data.",
+                ),
+                &["year", "leaderboard_id", "kind"],
+            )
+            .map_err(metrics_error)?,
+            reporter_failures: IntCounterVec::new(
+                opts("reporter_failures_total", "Number of errors that occurred while reporting."),
+                &["year", "leaderboard_id", "kind"],
+            )
+            .map_err(metrics_error)?,
+            fetch_latency: HistogramVec::new(
+                HistogramOpts::new(
+                    "fetch_latency_seconds",
+                    "Time taken to fetch leaderboard data.",
+                )
+                .namespace(METRICS_NAMESPACE),
+                &["year", "leaderboard_id"],
+            )
+            .map_err(metrics_error)?,
+            member_stars: IntGaugeVec::new(
+                opts("member_stars", "Number of stars of a leaderboard member."),
+                &["year", "leaderboard_id", "member_id", "member"],
+            )
+            .map_err(metrics_error)?,
+            member_local_score: IntGaugeVec::new(
+                opts("member_local_score", "Local score of a leaderboard member."),
+                &["year", "leaderboard_id", "member_id", "member"],
+            )
+            .map_err(metrics_error)?,
+            members: Arc::default(),
+            registry,
+        };
+
+        metrics.register(Box::new(metrics.runs.clone()))?;
+        metrics.register(Box::new(metrics.fetch_errors.clone()))?;
+        metrics.register(Box::new(metrics.reporter_failures.clone()))?;
+        metrics.register(Box::new(metrics.fetch_latency.clone()))?;
+        metrics.register(Box::new(metrics.member_stars.clone()))?;
+        metrics.register(Box::new(metrics.member_local_score.clone()))?;
+
+        Ok(metrics)
+    }
+
+    /// Returns the [`Registry`] where the metrics are registered.
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    /// Updates the metrics given the result of a bot run for the given leaderboard.
+    ///
+    /// Bot runs that were skipped (because they happened outside the [event window]) are not
+    /// observed.
+    ///
+    /// [event window]: crate::leaderbot::Config::event_window
+    pub fn observe(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+        result: &crate::Result<Option<BotOutput>>,
+    ) {
+        let (year_label, leaderboard_id_label) = (year.to_string(), leaderboard_id.to_string());
+        let labels = [year_label.as_str(), leaderboard_id_label.as_str()];
+
+        match result {
+            Ok(None) => (),
+            Ok(Some(output)) => {
+                let outcome = if output.deferred { "deferred" } else { "success" };
+                self.runs
+                    .with_label_values(&[labels[0], labels[1], outcome])
+                    .inc();
+                for fetch in output
+                    .diagnostics
+                    .fetches
+                    .iter()
+                    .filter(|fetch| !fetch.throttled)
+                {
+                    self.fetch_latency
+                        .with_label_values(&labels)
+                        .observe(fetch.latency_ms as f64 / 1_000.0);
+                }
+                self.set_members(year, leaderboard_id, &output.leaderboard);
+            },
+            Err(err) => {
+                self.runs
+                    .with_label_values(&[labels[0], labels[1], "error"])
+                    .inc();
+                match err {
+                    crate::Error::Leaderboard(source) => {
+                        let kind = format!("{:?}", aoc_leaderboard::ErrorKind::from(source));
+                        self.fetch_errors
+                            .with_label_values(&[labels[0], labels[1], &kind])
+                            .inc();
+                    },
+                    crate::Error::Reporter(source) => {
+                        let kind = format!("{:?}", crate::error::ReporterErrorKind::from(source));
+                        self.reporter_failures
+                            .with_label_values(&[labels[0], labels[1], &kind])
+                            .inc();
+                    },
+                    _ => (),
+                }
+            },
+        }
+    }
+
+    /// Encodes the current value of the metrics in the Prometheus text format.
+    pub fn encode(&self) -> crate::Result<String> {
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .map_err(metrics_error)?;
+
+        String::from_utf8(buffer).map_err(metrics_error)
+    }
+
+    /// Serves the metrics over HTTP using the given listener, until the `shutdown` future
+    /// completes.
+    ///
+    /// Metrics are returned for `GET` requests to [`METRICS_PATH`]; other requests are answered
+    /// with a `404 Not Found`. Errors occurring while accepting a connection or handling a request
+    /// are logged and do not stop the server; requests that take longer than
+    /// [`METRICS_REQUEST_TIMEOUT`] are dropped.
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self, shutdown), err))]
+    pub async fn serve<F>(&self, listener: TcpListener, shutdown: F) -> crate::Result<()>
+    where
+        F: Future<Output = ()>,
+    {
+        let mut shutdown = pin!(shutdown);
+        let mut requests = FuturesUnordered::new();
+
+        loop {
+            tokio::select! {
+                biased;
+                () = &mut shutdown => break,
+                Some(result) = requests.next(), if !requests.is_empty() => {
+                    if let Err(err) = result {
+                        tracing::warn!("error serving metrics: {err}");
+                    }
+                },
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, _)) => requests.push(async move {
+                        tokio::time::timeout(METRICS_REQUEST_TIMEOUT, self.handle_request(stream))
+                            .await
+                            .map_err(metrics_error)?
+                    }),
+                    Err(err) => tracing::warn!("error accepting metrics connection: {err}"),
+                },
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Pushes the metrics to a [Pushgateway] under the given job name.
+    ///
+    /// Metrics previously pushed for the same job are replaced.
+    ///
+    /// [Pushgateway]: https://github.com/prometheus/pushgateway
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self, http_client), err))]
+    pub async fn push<U>(
+        &self,
+        http_client: &reqwest::Client,
+        gateway_url: U,
+        job: &str,
+    ) -> crate::Result<()>
+    where
+        U: AsRef<str> + Debug,
+    {
+        let url = format!("{}/metrics/job/{job}", gateway_url.as_ref().trim_end_matches('/'));
+
+        http_client
+            .put(url)
+            .header(reqwest::header::CONTENT_TYPE, TextEncoder::new().format_type())
+            .body(self.encode()?)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(metrics_error)?;
+
+        Ok(())
+    }
+
+    fn register(&self, collector: Box<dyn prometheus::core::Collector>) -> crate::Result<()> {
+        self.registry.register(collector).map_err(metrics_error)
+    }
+
+    fn set_members(&self, year: i32, leaderboard_id: u64, leaderboard: &Leaderboard) {
+        let mut current = HashSet::with_capacity(leaderboard.members.len());
+        for member in leaderboard.members.values() {
+            let name = member
+                .name
+                .clone()
+                .unwrap_or_else(|| format!("(anonymous user #{})", member.id));
+            let labels =
+                [year.to_string(), leaderboard_id.to_string(), member.id.to_string(), name];
+
+            let label_values = labels.each_ref().map(String::as_str);
+            self.member_stars
+                .with_label_values(&label_values)
+                .set(member.stars.into());
+            self.member_local_score
+                .with_label_values(&label_values)
+                .set(member.local_score.try_into().unwrap_or(i64::MAX));
+            current.insert(labels);
+        }
+
+        // Remove gauges of members who left the leaderboard or were renamed.
+        let mut members = self
+            .members
+            .lock()
+            .expect("members mutex should not be poisoned");
+        if let Some(previous) = members.get(&(year, leaderboard_id)) {
+            for labels in previous.difference(&current) {
+                let label_values = labels.each_ref().map(String::as_str);
+                let _ = self.member_stars.remove_label_values(&label_values);
+                let _ = self.member_local_score.remove_label_values(&label_values);
+            }
+        }
+        members.insert((year, leaderboard_id), current);
+    }
+
+    async fn handle_request(&self, mut stream: TcpStream) -> crate::Result<()> {
+        // We only need the request line, but we read the whole request header
+        // to avoid resetting the connection before the client is done sending it.
+        let mut request = Vec::new();
+        let mut buffer = [0; 1024];
+        while !request.windows(4).any(|window| window == b"\r\n\r\n") && request.len() < 8192 {
+            let read = stream.read(&mut buffer).await.map_err(metrics_error)?;
+            if read == 0 {
+                break;
+            }
+            request.extend_from_slice(&buffer[..read]);
+        }
+
+        let request = String::from_utf8_lossy(&request);
+        let mut request_line = request
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .split_whitespace();
+        let response = match (request_line.next(), request_line.next()) {
+            (Some("GET"), Some(METRICS_PATH)) => {
+                let body = self.encode()?;
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    TextEncoder::new().format_type(),
+                    body.len(),
+                )
+            },
+            _ => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".into(),
+        };
+
+        stream
+            .write_all(response.as_bytes())
+            .await
+            .map_err(metrics_error)?;
+        stream.shutdown().await.map_err(metrics_error)
+    }
+}
+
+fn opts(name: &str, help: &str) -> Opts {
+    Opts::new(name, help).namespace(METRICS_NAMESPACE)
+}
+
+fn metrics_error<E>(err: E) -> crate::Error
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    crate::Error::Metrics(anyhow!(err))
+}
@@ -0,0 +1,441 @@
+//! Runner monitoring several leaderboards (and/or years) in a single run.
+//!
+//! [`run_bot`](crate::leaderbot::run_bot) monitors a single leaderboard for a single year. A
+//! [`MultiTargetRunner`] instead monitors a list of [`Target`]s, each with its own [`Config`] and
+//! [`Reporter`], sharing a single [`Storage`] and HTTP client. Targets are run concurrently (up to
+//! a configurable limit), and an error occurring for one target does not affect the others.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+
+use aoc_leaderboard::aoc::Leaderboard;
+use chrono::{DateTime, TimeDelta, Utc};
+use futures_util::StreamExt;
+use futures_util::lock::Mutex;
+use futures_util::stream;
+
+use crate::ErrorKind;
+use crate::leaderbot::diagnostics::RunDiagnostics;
+use crate::leaderbot::event::MIN_INTERVAL;
+use crate::leaderbot::heartbeat::HeartbeatState;
+use crate::leaderbot::{BotOutput, Config, ErrorState, Reporter, Storage, run_bot_with};
+
+/// Default maximum number of targets run concurrently by a [`MultiTargetRunner`].
+pub const DEFAULT_MAX_CONCURRENCY: usize = 4;
+
+/// Leaderboard monitored by a [`MultiTargetRunner`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Target<C, R> {
+    /// Config determining the leaderboard to monitor, the year and the credentials to use.
+    pub config: C,
+
+    /// Reporter used to report changes and errors for this leaderboard.
+    pub reporter: R,
+}
+
+impl<C, R> Target<C, R> {
+    /// Creates a new target.
+    pub fn new(config: C, reporter: R) -> Self {
+        Self { config, reporter }
+    }
+}
+
+/// Output returned by a [`MultiTargetRunner`] for one of its [`Target`]s.
+#[derive(Debug)]
+pub struct TargetOutput {
+    /// Year for which the bot was run.
+    pub year: i32,
+
+    /// ID of the leaderboard checked by the bot.
+    pub leaderboard_id: u64,
+
+    /// Result of the bot run for this target.
+    ///
+    /// Will be `Ok(None)` if leaderboard data was not fetched, either because it was fetched
+    /// too recently or because the bot is [outside the event window].
+    ///
+    /// [outside the event window]: crate::leaderbot::event
+    pub result: crate::Result<Option<BotOutput>>,
+
+    /// Diagnostics about the bot run for this target.
+    ///
+    /// If the bot run succeeded, these are the same as the output's [diagnostics]. If it failed,
+    /// they include the operations performed up to the failure. If the leaderboard was fetched
+    /// too recently, they contain a single [throttled] fetch.
+    ///
+    /// [diagnostics]: BotOutput::diagnostics
+    /// [throttled]: crate::leaderbot::diagnostics::FetchDiagnostics::throttled
+    pub diagnostics: RunDiagnostics,
+}
+
+/// Runner monitoring several leaderboards in a single run.
+///
+/// Every call to [`run`] runs the bot for each [`Target`], like [`run_bot`] would.
+/// Targets are run concurrently, but never more than [`max_concurrency`] at a time.
+///
+/// The runner keeps track of the last time each leaderboard was fetched, and skips fetching it
+/// again until [`min_fetch_interval`] has elapsed. For this reason, the same runner should be
+/// kept between runs. A leaderboard can only be monitored by one target for a given year.
+///
+/// [`run`]: Self::run
+/// [`run_bot`]: crate::leaderbot::run_bot
+/// [`max_concurrency`]: Self::max_concurrency
+/// [`min_fetch_interval`]: Self::min_fetch_interval
+#[derive(Debug, Clone)]
+pub struct MultiTargetRunner<C, R> {
+    targets: Vec<Target<C, R>>,
+    max_concurrency: usize,
+    min_fetch_interval: TimeDelta,
+    last_fetches: HashMap<(i32, u64), DateTime<Utc>>,
+}
+
+impl<C, R> Default for MultiTargetRunner<C, R> {
+    fn default() -> Self {
+        Self {
+            targets: Vec::new(),
+            max_concurrency: DEFAULT_MAX_CONCURRENCY,
+            min_fetch_interval: MIN_INTERVAL,
+            last_fetches: HashMap::new(),
+        }
+    }
+}
+
+impl<C, R> MultiTargetRunner<C, R> {
+    /// Creates a new runner without targets.
+    ///
+    /// Up to [`DEFAULT_MAX_CONCURRENCY`] targets are run concurrently, and leaderboards are
+    /// not fetched more often than every [`MIN_INTERVAL`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum number of targets run concurrently.
+    ///
+    /// If `0`, targets are run one at a time.
+    pub fn with_max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.max_concurrency = max_concurrency.max(1);
+        self
+    }
+
+    /// Sets the minimum interval between two fetches of a leaderboard's data.
+    ///
+    /// If less than [`MIN_INTERVAL`], [`MIN_INTERVAL`] is used instead.
+    pub fn with_min_fetch_interval(mut self, min_fetch_interval: TimeDelta) -> Self {
+        self.min_fetch_interval = min_fetch_interval.max(MIN_INTERVAL);
+        self
+    }
+
+    /// Returns the targets of this runner, in the order they were added.
+    pub fn targets(&self) -> &[Target<C, R>] {
+        &self.targets
+    }
+
+    /// Returns the targets of this runner, in the order they were added.
+    pub fn targets_mut(&mut self) -> &mut [Target<C, R>] {
+        &mut self.targets
+    }
+
+    /// Returns the maximum number of targets run concurrently.
+    pub fn max_concurrency(&self) -> usize {
+        self.max_concurrency
+    }
+
+    /// Returns the minimum interval between two fetches of a leaderboard's data.
+    pub fn min_fetch_interval(&self) -> TimeDelta {
+        self.min_fetch_interval
+    }
+
+    /// Returns the last time the given leaderboard was fetched by this runner, if ever.
+    pub fn last_fetch(&self, year: i32, leaderboard_id: u64) -> Option<DateTime<Utc>> {
+        self.last_fetches.get(&(year, leaderboard_id)).copied()
+    }
+}
+
+impl<C, R> MultiTargetRunner<C, R>
+where
+    C: Config,
+{
+    /// Adds a target monitoring a leaderboard.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::DuplicateTarget`]: if another target already monitors the same leaderboard
+    ///   for the same year
+    ///
+    /// [`Error::DuplicateTarget`]: crate::Error::DuplicateTarget
+    pub fn with_target(mut self, config: C, reporter: R) -> crate::Result<Self> {
+        self.add_target(config, reporter)?;
+        Ok(self)
+    }
+
+    /// Adds a target monitoring a leaderboard.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::DuplicateTarget`]: if another target already monitors the same leaderboard
+    ///   for the same year
+    ///
+    /// [`Error::DuplicateTarget`]: crate::Error::DuplicateTarget
+    pub fn add_target(&mut self, config: C, reporter: R) -> crate::Result<()> {
+        let (year, leaderboard_id) = (config.year(), config.leaderboard_id());
+        if self.targets.iter().any(|target| {
+            target.config.year() == year && target.config.leaderboard_id() == leaderboard_id
+        }) {
+            return Err(crate::Error::DuplicateTarget { year, leaderboard_id });
+        }
+
+        self.targets.push(Target::new(config, reporter));
+        Ok(())
+    }
+}
+
+impl<C, R> MultiTargetRunner<C, R>
+where
+    C: Config,
+    R: Reporter,
+    <R as Reporter>::Err: Sync + 'static,
+{
+    /// Runs the bot for every target of this runner.
+    ///
+    /// Returns one [`TargetOutput`] per target, in the same order as the [`targets`]. Errors
+    /// are reported via each target's [`reporter`] and returned in its output; they do not
+    /// prevent other targets from running. If several targets monitor the same leaderboard
+    /// for the same year (for example, because they were modified via [`targets_mut`]), only
+    /// the first one is run; the others return [`Error::DuplicateTarget`].
+    ///
+    /// If the `dry_run` parameter is set to `true`, then the bot will fetch data and compute
+    /// changes but will not persist or report them.
+    ///
+    /// # Errors
+    ///
+    /// - [`Error::Leaderboard`]: if the HTTP client could not be created
+    ///
+    /// [`targets`]: Self::targets
+    /// [`reporter`]: Target::reporter
+    /// [`targets_mut`]: Self::targets_mut
+    /// [`Error::DuplicateTarget`]: crate::Error::DuplicateTarget
+    /// [`Error::Leaderboard`]: crate::Error::Leaderboard
+    #[cfg_attr(coverage_nightly, coverage(off))]
+    #[tracing::instrument(skip(self, storage), err)]
+    pub async fn run<S>(
+        &mut self,
+        storage: &mut S,
+        dry_run: bool,
+    ) -> crate::Result<Vec<TargetOutput>>
+    where
+        S: Storage + Send,
+        <S as Storage>::Err: Sync + 'static,
+    {
+        self.run_from(None::<String>, storage, dry_run).await
+    }
+
+    /// Runs the bot for every target of this runner, using the given base Advent of Code URL
+    /// (or the default, `https://adventofcode.com`, if not provided).
+    ///
+    /// This function is mostly exposed for testing; you should use [`run`](Self::run) instead.
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self, storage), level = "debug", err))]
+    pub async fn run_from<B, S>(
+        &mut self,
+        advent_of_code_base: Option<B>,
+        storage: &mut S,
+        dry_run: bool,
+    ) -> crate::Result<Vec<TargetOutput>>
+    where
+        B: AsRef<str> + Debug,
+        S: Storage + Send,
+        <S as Storage>::Err: Sync + 'static,
+    {
+        let http_client = Leaderboard::http_client()?;
+        let storage = Mutex::new(storage);
+
+        let mut scheduled = HashSet::new();
+        let runs: Vec<_> = self
+            .targets
+            .iter_mut()
+            .map(|target| {
+                let key = (target.config.year(), target.config.leaderboard_id());
+                let now = target.config.clock().now();
+                let throttled = self
+                    .last_fetches
+                    .get(&key)
+                    .is_some_and(|last_fetch| now - *last_fetch < self.min_fetch_interval);
+                let run = if !scheduled.insert(key) {
+                    Run::Duplicate
+                } else if throttled {
+                    Run::Throttled
+                } else {
+                    Run::Due
+                };
+                (target, key, now, run)
+            })
+            .collect();
+
+        let outputs: Vec<_> = stream::iter(runs)
+            .map(|(target, (year, leaderboard_id), now, run)| {
+                let (http_client, advent_of_code_base, storage) =
+                    (&http_client, advent_of_code_base.as_ref(), &storage);
+
+                async move {
+                    let mut diagnostics = RunDiagnostics::default();
+                    let result = match run {
+                        Run::Due => {
+                            match run_bot_with(
+                                Some(http_client),
+                                advent_of_code_base,
+                                &target.config,
+                                &mut SharedStorage(storage),
+                                &mut target.reporter,
+                                dry_run,
+                            )
+                            .await
+                            {
+                                Ok(output) => {
+                                    if let Some(output) = &output {
+                                        diagnostics = output.diagnostics.clone();
+                                    }
+                                    Ok(output)
+                                },
+                                Err(err) => {
+                                    diagnostics = err.diagnostics;
+                                    Err(err.error)
+                                },
+                            }
+                        },
+                        Run::Throttled => {
+                            tracing::info!(
+                                year,
+                                leaderboard_id,
+                                "leaderboard fetched too recently, skipping target"
+                            );
+                            diagnostics.record_throttled_fetch(leaderboard_id);
+                            Ok(None)
+                        },
+                        Run::Duplicate => {
+                            Err(crate::Error::DuplicateTarget { year, leaderboard_id })
+                        },
+                    };
+
+                    (TargetOutput { year, leaderboard_id, result, diagnostics }, now, run)
+                }
+            })
+            .buffered(self.max_concurrency)
+            .collect()
+            .await;
+
+        Ok(outputs
+            .into_iter()
+            .map(|(output, now, run)| {
+                if run == Run::Due && !matches!(output.result, Ok(None)) {
+                    self.last_fetches
+                        .insert((output.year, output.leaderboard_id), now);
+                }
+                output
+            })
+            .collect())
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Run {
+    Due,
+    Throttled,
+    Duplicate,
+}
+
+// Storage wrapper used to share a storage between targets run concurrently.
+struct SharedStorage<'a, 's, S>(&'a Mutex<&'s mut S>);
+
+impl<S> Storage for SharedStorage<'_, '_, S>
+where
+    S: Storage + Send,
+{
+    type Err = S::Err;
+
+    async fn load_previous(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> Result<(Option<Leaderboard>, Option<ErrorKind>), Self::Err> {
+        self.0
+            .lock()
+            .await
+            .load_previous(year, leaderboard_id)
+            .await
+    }
+
+    async fn save_success(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        leaderboard: &Leaderboard,
+    ) -> Result<(), Self::Err> {
+        self.0
+            .lock()
+            .await
+            .save_success(year, leaderboard_id, leaderboard)
+            .await
+    }
+
+    async fn save_error(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        error_kind: ErrorKind,
+    ) -> Result<(), Self::Err> {
+        self.0
+            .lock()
+            .await
+            .save_error(year, leaderboard_id, error_kind)
+            .await
+    }
+
+    async fn save_error_state(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        error_state: &ErrorState,
+    ) -> Result<(), Self::Err> {
+        self.0
+            .lock()
+            .await
+            .save_error_state(year, leaderboard_id, error_state)
+            .await
+    }
+
+    async fn load_error_state(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> Result<Option<ErrorState>, Self::Err> {
+        self.0
+            .lock()
+            .await
+            .load_error_state(year, leaderboard_id)
+            .await
+    }
+
+    async fn load_heartbeat(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> Result<Option<HeartbeatState>, Self::Err> {
+        self.0
+            .lock()
+            .await
+            .load_heartbeat(year, leaderboard_id)
+            .await
+    }
+
+    async fn save_heartbeat(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        heartbeat: &HeartbeatState,
+    ) -> Result<(), Self::Err> {
+        self.0
+            .lock()
+            .await
+            .save_heartbeat(year, leaderboard_id, heartbeat)
+            .await
+    }
+}
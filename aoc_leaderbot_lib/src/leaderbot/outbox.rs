@@ -0,0 +1,329 @@
+//! Exactly-once reporting using an outbox.
+//!
+//! When running the bot via [`run_bot`](crate::leaderbot::run_bot), changes are reported before
+//! the current leaderboard is saved. If saving fails, the next bot run detects the same changes
+//! and reports them again; if reporting fails, the current leaderboard is never saved.
+//!
+//! [`run_bot_outbox`] avoids this by using an [`OutboxStorage`]. When changes are detected, the
+//! report to send is saved in the storage's outbox alongside the current leaderboard, in a single
+//! write. The report is then delivered to the [`Reporter`] and marked as delivered. If delivery
+//! fails, the report stays in the outbox and delivery is retried during the next bot run.
+
+use std::fmt::Debug;
+
+use anyhow::anyhow;
+use aoc_leaderboard::aoc::Leaderboard;
+use chrono::{DateTime, Utc};
+
+use crate::error::{ReporterError, RunError, StorageError};
+use crate::leaderbot::diagnostics::RunDiagnostics;
+use crate::leaderbot::{
+    BotOutput, Config, OutboxReport, OutboxStorage, Reporter, RunMode, Storage, run_bot_core,
+};
+
+/// Runs the bot's core functionality, using an outbox to report changes exactly once.
+///
+/// Works like [`run_bot`](crate::leaderbot::run_bot), except that:
+///
+/// - If the outbox contains a report that was not delivered during a previous bot run,
+///   it is delivered first.
+/// - When changes are detected (or during the first bot run), the report is saved in the
+///   outbox along with the current leaderboard _before_ being delivered to the [`reporter`].
+///
+/// Like [`run_bot`](crate::leaderbot::run_bot), returns `None` without doing anything if
+/// leaderboard data should not be fetched at this time according to the config's [event window].
+///
+/// If the `dry_run` parameter is set to `true`, then the bot will fetch data and compute
+/// changes but will not persist or report them (nor deliver undelivered reports).
+///
+/// [`reporter`]: Reporter
+/// [event window]: Config::event_window
+#[cfg_attr(coverage_nightly, coverage(off))]
+#[tracing::instrument(skip(config, storage, reporter), ret, err)]
+pub async fn run_bot_outbox<C, S, R>(
+    config: &C,
+    storage: &mut S,
+    reporter: &mut R,
+    dry_run: bool,
+) -> crate::Result<Option<BotOutput>>
+where
+    C: Config,
+    S: OutboxStorage,
+    <S as Storage>::Err: Sync + 'static,
+    R: Reporter,
+    <R as Reporter>::Err: Sync + 'static,
+{
+    run_bot_outbox_from(None::<String>, config, storage, reporter, dry_run).await
+}
+
+/// Runs the bot's core functionality using an outbox, using the given base Advent of Code URL
+/// (or the default, `https://adventofcode.com`, if not provided).
+///
+/// This function is mostly exposed for testing; you should use [`run_bot_outbox`] instead.
+#[cfg_attr(
+    not(coverage),
+    tracing::instrument(skip(config, storage, reporter), level = "debug", ret, err)
+)]
+pub async fn run_bot_outbox_from<B, C, S, R>(
+    advent_of_code_base: Option<B>,
+    config: &C,
+    storage: &mut S,
+    reporter: &mut R,
+    dry_run: bool,
+) -> crate::Result<Option<BotOutput>>
+where
+    B: AsRef<str> + Debug,
+    C: Config,
+    S: OutboxStorage,
+    <S as Storage>::Err: Sync + 'static,
+    R: Reporter,
+    <R as Reporter>::Err: Sync + 'static,
+{
+    run_bot_outbox_diagnosed_from(advent_of_code_base, config, storage, reporter, dry_run)
+        .await
+        .map_err(Into::into)
+}
+
+/// Runs the bot's core functionality using an outbox like [`run_bot_outbox`], but returns the
+/// [diagnostics] collected before the bot run failed along with the error if it does.
+///
+/// # Errors
+///
+/// Returns a [`RunError`] wrapping the same error [`run_bot_outbox`] would return.
+///
+/// [diagnostics]: crate::leaderbot::diagnostics::RunDiagnostics
+#[cfg_attr(coverage_nightly, coverage(off))]
+#[tracing::instrument(skip(config, storage, reporter), ret, err)]
+pub async fn run_bot_outbox_diagnosed<C, S, R>(
+    config: &C,
+    storage: &mut S,
+    reporter: &mut R,
+    dry_run: bool,
+) -> crate::Result<Option<BotOutput>, RunError>
+where
+    C: Config,
+    S: OutboxStorage,
+    <S as Storage>::Err: Sync + 'static,
+    R: Reporter,
+    <R as Reporter>::Err: Sync + 'static,
+{
+    run_bot_outbox_diagnosed_from(None::<String>, config, storage, reporter, dry_run).await
+}
+
+/// Runs the bot's core functionality using an outbox like [`run_bot_outbox_diagnosed`], using
+/// the given base Advent of Code URL (or the default, `https://adventofcode.com`, if not provided).
+///
+/// This function is mostly exposed for testing; you should use [`run_bot_outbox_diagnosed`]
+/// instead.
+#[cfg_attr(
+    not(coverage),
+    tracing::instrument(skip(config, storage, reporter), level = "debug", ret, err)
+)]
+pub async fn run_bot_outbox_diagnosed_from<B, C, S, R>(
+    advent_of_code_base: Option<B>,
+    config: &C,
+    storage: &mut S,
+    reporter: &mut R,
+    dry_run: bool,
+) -> crate::Result<Option<BotOutput>, RunError>
+where
+    B: AsRef<str> + Debug,
+    C: Config,
+    S: OutboxStorage,
+    <S as Storage>::Err: Sync + 'static,
+    R: Reporter,
+    <R as Reporter>::Err: Sync + 'static,
+{
+    run_bot_core(
+        None,
+        advent_of_code_base,
+        config,
+        storage,
+        reporter,
+        OutboxMode::default(),
+        dry_run,
+    )
+    .await
+}
+
+// Run mode used by [`run_bot_outbox`]: reports are saved in the outbox along with
+// the current leaderboard, then delivered.
+#[derive(Debug, Default)]
+struct OutboxMode {
+    report: Option<OutboxReport>,
+}
+
+impl<S> RunMode<S> for OutboxMode
+where
+    S: OutboxStorage,
+    <S as Storage>::Err: Sync + 'static,
+{
+    type Output = BotOutput;
+
+    async fn load(
+        &mut self,
+        storage: &S,
+        year: i32,
+        leaderboard_id: u64,
+        diagnostics: &mut RunDiagnostics,
+    ) -> crate::Result<()> {
+        self.report = diagnostics
+            .storage_operation("load_outbox", storage.load_outbox(year, leaderboard_id))
+            .await
+            .map_err(|err| StorageError::LoadOutbox(anyhow!(err)))?;
+
+        Ok(())
+    }
+
+    async fn prepare<R>(
+        &mut self,
+        storage: &mut S,
+        reporter: &mut R,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        previous_leaderboard: Option<&Leaderboard>,
+        diagnostics: &mut RunDiagnostics,
+    ) -> crate::Result<bool>
+    where
+        R: Reporter,
+        <R as Reporter>::Err: Sync + 'static,
+    {
+        // A report saved in the outbox was saved alongside the last leaderboard,
+        // so we need to deliver it before looking for new changes.
+        if let (Some(report), Some(leaderboard)) = (&self.report, previous_leaderboard) {
+            tracing::debug!("delivering report left in outbox by previous run");
+            deliver(
+                storage,
+                reporter,
+                year,
+                leaderboard_id,
+                view_key,
+                report,
+                leaderboard,
+                diagnostics,
+            )
+            .await?;
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    async fn save_and_report<R>(
+        &mut self,
+        storage: &mut S,
+        reporter: &mut R,
+        view_key: Option<&str>,
+        output: &BotOutput,
+        _now: DateTime<Utc>,
+        diagnostics: &mut RunDiagnostics,
+    ) -> crate::Result<bool>
+    where
+        R: Reporter,
+        <R as Reporter>::Err: Sync + 'static,
+    {
+        let (year, leaderboard_id) = (output.year, output.leaderboard_id);
+
+        let report = match (&output.previous_leaderboard, &output.changes) {
+            (Some(previous_leaderboard), Some(changes)) => Some(OutboxReport::Changes {
+                previous_leaderboard: previous_leaderboard.clone(),
+                changes: changes.clone(),
+            }),
+            (None, None) => Some(OutboxReport::FirstRun),
+            _ => None,
+        };
+
+        match &report {
+            Some(report) => {
+                diagnostics
+                    .storage_operation(
+                        "save_success_with_outbox",
+                        storage.save_success_with_outbox(
+                            year,
+                            leaderboard_id,
+                            &output.leaderboard,
+                            report,
+                        ),
+                    )
+                    .await
+                    .map_err(|err| StorageError::SaveSuccess(anyhow!(err)))?;
+                deliver(
+                    storage,
+                    reporter,
+                    year,
+                    leaderboard_id,
+                    view_key,
+                    report,
+                    &output.leaderboard,
+                    diagnostics,
+                )
+                .await?;
+            },
+            None => {
+                diagnostics
+                    .storage_operation(
+                        "save_success",
+                        storage.save_success(year, leaderboard_id, &output.leaderboard),
+                    )
+                    .await
+                    .map_err(|err| StorageError::SaveSuccess(anyhow!(err)))?;
+            },
+        }
+
+        Ok(report.is_some())
+    }
+
+    fn into_output(self, output: BotOutput) -> Self::Output {
+        output
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn deliver<S, R>(
+    storage: &mut S,
+    reporter: &mut R,
+    year: i32,
+    leaderboard_id: u64,
+    view_key: Option<&str>,
+    report: &OutboxReport,
+    leaderboard: &Leaderboard,
+    diagnostics: &mut RunDiagnostics,
+) -> crate::Result<()>
+where
+    S: OutboxStorage,
+    <S as Storage>::Err: Sync + 'static,
+    R: Reporter,
+    <R as Reporter>::Err: Sync + 'static,
+{
+    match report {
+        OutboxReport::Changes { previous_leaderboard, changes } => diagnostics
+            .reporter_call(
+                "report_changes",
+                reporter.report_changes(
+                    year,
+                    leaderboard_id,
+                    view_key,
+                    previous_leaderboard,
+                    leaderboard,
+                    changes,
+                ),
+            )
+            .await
+            .map_err(|err| ReporterError::ReportChanges(anyhow!(err)))?,
+        OutboxReport::FirstRun => diagnostics
+            .reporter_call(
+                "report_first_run",
+                reporter.report_first_run(year, leaderboard_id, view_key, leaderboard),
+            )
+            .await
+            .map_err(|err| ReporterError::ReportFirstRun(anyhow!(err)))?,
+    }
+
+    diagnostics
+        .storage_operation("mark_delivered", storage.mark_delivered(year, leaderboard_id))
+        .await
+        .map_err(|err| StorageError::MarkDelivered(anyhow!(err)))?;
+
+    Ok(())
+}
@@ -0,0 +1,10 @@
+//! Implementations of [`leaderbot::Reporter`](crate::leaderbot::Reporter).
+
+#[cfg(feature = "reporter-dedup")]
+pub mod dedup;
+#[cfg(feature = "reporter-middleware")]
+pub mod middleware;
+#[cfg(feature = "reporter-multi")]
+pub mod multi;
+#[cfg(feature = "reporter-recording")]
+pub mod recording;
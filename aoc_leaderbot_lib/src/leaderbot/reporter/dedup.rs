@@ -0,0 +1,235 @@
+//! Bot reporter deduplicating members across several leaderboards.
+//!
+//! When several leaderboards are monitored (for example via a `MultiTargetRunner`) and their
+//! changes are reported to the same destination, members appearing in more than one leaderboard
+//! would normally be reported once per leaderboard. To avoid this, each leaderboard can use a
+//! [`DedupReporter`] created from the same [`ChangesCollector`]; changes are then collected
+//! instead of being reported immediately. After all leaderboards have been checked, the collector
+//! can be [flushed] to the destination reporter, which receives [merged changes] where each
+//! member appears only once.
+//!
+//! [flushed]: ChangesCollector::flush
+//! [merged changes]: MergedChanges
+
+use std::mem;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use aoc_leaderboard::aoc::Leaderboard;
+use chrono::TimeDelta;
+
+use crate::leaderbot::{Changes, LeaderboardChanges, MergedChanges, Reporter};
+
+/// Collector of leaderboard changes reported via [`DedupReporter`]s.
+///
+/// Cloning a collector returns a new handle to the same collected changes.
+#[derive(Debug, Default, Clone)]
+pub struct ChangesCollector {
+    changes: Arc<Mutex<Vec<LeaderboardChanges>>>,
+}
+
+impl ChangesCollector {
+    /// Creates a new collector without collected changes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a [`DedupReporter`] collecting changes in this collector.
+    ///
+    /// Calls other than [`report_changes`] are forwarded to the `inner` reporter.
+    ///
+    /// [`report_changes`]: Reporter::report_changes
+    pub fn reporter<R>(&self, inner: R) -> DedupReporter<R> {
+        DedupReporter { collector: self.clone(), inner }
+    }
+
+    /// Returns the number of leaderboards with collected changes.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Checks if no changes have been collected.
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Removes all collected changes and returns them, [merged] by year.
+    ///
+    /// [merged]: MergedChanges::merge
+    pub fn take(&self) -> Vec<MergedChanges> {
+        MergedChanges::merge(mem::take(&mut *self.lock()))
+    }
+
+    /// Removes all collected changes and reports them via the given reporter's
+    /// [`report_merged_changes`] method, once per year.
+    ///
+    /// If the reporter returns an error, changes of the years that were not reported are kept
+    /// in the collector, so that they can be flushed again later. The same goes for all changes
+    /// if the reporter currently [defers reports].
+    ///
+    /// [`report_merged_changes`]: Reporter::report_merged_changes
+    /// [defers reports]: Reporter::defers_reports
+    #[cfg_attr(not(coverage), tracing::instrument(skip_all, err))]
+    pub async fn flush<R>(&self, reporter: &mut R) -> Result<(), R::Err>
+    where
+        R: Reporter + Send,
+    {
+        if reporter.defers_reports() {
+            tracing::debug!("reporter defers reports, keeping collected changes");
+            return Ok(());
+        }
+
+        let changes = mem::take(&mut *self.lock());
+
+        for merged_changes in MergedChanges::merge(changes.clone()) {
+            if let Err(err) = reporter.report_merged_changes(&merged_changes).await {
+                // Years are reported in chronological order, so changes of this year and
+                // later years have not been reported. They are put back before any changes
+                // collected in the meantime, to keep the order in which they were collected.
+                let unreported = changes
+                    .into_iter()
+                    .filter(|changes| changes.year >= merged_changes.year);
+                self.lock().splice(0..0, unreported);
+                return Err(err);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn push(&self, changes: LeaderboardChanges) {
+        self.lock().push(changes);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<LeaderboardChanges>> {
+        // Collected changes are always left in a consistent state, even on panic.
+        self.changes.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Bot reporter collecting changes in a [`ChangesCollector`] instead of reporting them.
+///
+/// Calls to [`report_first_run`], [`report_error`], [`report_recovery`],
+/// [`report_stale`] and [`report_heartbeat`] are forwarded to the inner reporter.
+///
+/// Create instances via [`ChangesCollector::reporter`].
+///
+/// [`report_first_run`]: Reporter::report_first_run
+/// [`report_error`]: Reporter::report_error
+/// [`report_recovery`]: Reporter::report_recovery
+/// [`report_stale`]: Reporter::report_stale
+/// [`report_heartbeat`]: Reporter::report_heartbeat
+#[derive(Debug, Clone)]
+pub struct DedupReporter<R> {
+    collector: ChangesCollector,
+    inner: R,
+}
+
+impl<R> DedupReporter<R> {
+    /// Returns the collector in which changes are collected.
+    pub fn collector(&self) -> &ChangesCollector {
+        &self.collector
+    }
+
+    /// Returns a reference to the inner reporter.
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+
+    /// Consumes this wrapper and returns the inner reporter.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R> Reporter for DedupReporter<R>
+where
+    R: Reporter + Send,
+{
+    type Err = R::Err;
+
+    async fn report_changes(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        previous_leaderboard: &Leaderboard,
+        leaderboard: &Leaderboard,
+        changes: &Changes,
+    ) -> Result<(), Self::Err> {
+        self.collector.push(LeaderboardChanges {
+            year,
+            leaderboard_id,
+            view_key: view_key.map(Into::into),
+            previous_leaderboard: previous_leaderboard.clone(),
+            leaderboard: leaderboard.clone(),
+            changes: changes.clone(),
+        });
+
+        Ok(())
+    }
+
+    async fn report_first_run(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        leaderboard: &Leaderboard,
+    ) -> Result<(), Self::Err> {
+        self.inner
+            .report_first_run(year, leaderboard_id, view_key, leaderboard)
+            .await
+    }
+
+    async fn report_error(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        error: &crate::Error,
+    ) {
+        self.inner
+            .report_error(year, leaderboard_id, view_key, error)
+            .await
+    }
+
+    async fn report_recovery(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        error_kind: crate::ErrorKind,
+        outage_duration: Option<TimeDelta>,
+    ) {
+        self.inner
+            .report_recovery(year, leaderboard_id, view_key, error_kind, outage_duration)
+            .await
+    }
+
+    async fn report_stale(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        silence: TimeDelta,
+    ) {
+        self.inner
+            .report_stale(year, leaderboard_id, view_key, silence)
+            .await
+    }
+
+    async fn report_heartbeat(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        leaderboard: &Leaderboard,
+    ) {
+        self.inner
+            .report_heartbeat(year, leaderboard_id, view_key, leaderboard)
+            .await
+    }
+
+    fn defers_reports(&self) -> bool {
+        self.inner.defers_reports()
+    }
+}
@@ -0,0 +1,590 @@
+//! Bot reporters wrapping other reporters to alter their behaviour.
+//!
+//! The wrappers in this module can be composed, either by creating them directly or via
+//! the methods of the [`ReporterExt`] trait:
+//!
+//! - [`MemberFilterReporter`]: drops changes for ignored members
+//! - [`QuietHoursReporter`]: defers reports during a daily time window
+//! - [`ErrorRouterReporter`]: sends errors to a different reporter than changes
+
+use std::collections::HashSet;
+
+use aoc_leaderboard::aoc::Leaderboard;
+use chrono::{Local, NaiveTime, TimeDelta};
+
+use crate::leaderbot::clock::{Clock, SharedClock};
+use crate::leaderbot::{Changes, LeaderboardChanges, MergedChanges, Reporter};
+
+/// Extension trait adding methods to wrap a [`Reporter`] in middleware.
+pub trait ReporterExt: Reporter + Sized {
+    /// Wraps this reporter in a [`MemberFilterReporter`] ignoring the given members.
+    fn filter_members<I>(self, ignored_members: I) -> MemberFilterReporter<Self>
+    where
+        I: IntoIterator<Item = u64>,
+    {
+        MemberFilterReporter::new(self, ignored_members)
+    }
+
+    /// Wraps this reporter in a [`QuietHoursReporter`] using the given time window.
+    fn quiet_hours(self, start: NaiveTime, end: NaiveTime) -> QuietHoursReporter<Self> {
+        QuietHoursReporter::new(self, start, end)
+    }
+
+    /// Wraps this reporter in an [`ErrorRouterReporter`] sending errors to `error_reporter`.
+    fn route_errors_to<E>(self, error_reporter: E) -> ErrorRouterReporter<Self, E>
+    where
+        E: Reporter,
+    {
+        ErrorRouterReporter::new(self, error_reporter)
+    }
+}
+
+impl<R> ReporterExt for R where R: Reporter {}
+
+/// Bot reporter that drops changes for ignored members before forwarding
+/// them to an inner reporter.
+///
+/// If all changes detected by the bot concern ignored members, the inner reporter
+/// is not called at all. Leaderboards are forwarded as-is; only [`Changes`] are filtered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemberFilterReporter<R> {
+    inner: R,
+    ignored_members: HashSet<u64>,
+}
+
+impl<R> MemberFilterReporter<R> {
+    /// Creates a new instance wrapping `inner` and ignoring the given members.
+    pub fn new<I>(inner: R, ignored_members: I) -> Self
+    where
+        I: IntoIterator<Item = u64>,
+    {
+        Self { inner, ignored_members: ignored_members.into_iter().collect() }
+    }
+
+    /// Returns the IDs of ignored members.
+    pub fn ignored_members(&self) -> &HashSet<u64> {
+        &self.ignored_members
+    }
+
+    /// Returns a reference to the inner reporter.
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+
+    /// Consumes this wrapper and returns the inner reporter.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn filter_changes(&self, changes: &Changes) -> Option<Changes> {
+        let keep = |id: &&u64| !self.ignored_members.contains(*id);
+
+        Changes::if_needed(
+            changes.new_members.iter().filter(keep).copied().collect(),
+            changes
+                .members_with_new_stars
+                .iter()
+                .filter(keep)
+                .copied()
+                .collect(),
+        )
+    }
+}
+
+impl<R> Reporter for MemberFilterReporter<R>
+where
+    R: Reporter + Send,
+{
+    type Err = R::Err;
+
+    async fn report_changes(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        previous_leaderboard: &Leaderboard,
+        leaderboard: &Leaderboard,
+        changes: &Changes,
+    ) -> Result<(), Self::Err> {
+        match self.filter_changes(changes) {
+            Some(changes) => {
+                self.inner
+                    .report_changes(
+                        year,
+                        leaderboard_id,
+                        view_key,
+                        previous_leaderboard,
+                        leaderboard,
+                        &changes,
+                    )
+                    .await
+            },
+            None => {
+                tracing::debug!("all changes concern ignored members, skipping report");
+                Ok(())
+            },
+        }
+    }
+
+    async fn report_first_run(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        leaderboard: &Leaderboard,
+    ) -> Result<(), Self::Err> {
+        self.inner
+            .report_first_run(year, leaderboard_id, view_key, leaderboard)
+            .await
+    }
+
+    async fn report_error(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        error: &crate::Error,
+    ) {
+        self.inner
+            .report_error(year, leaderboard_id, view_key, error)
+            .await
+    }
+
+    async fn report_recovery(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        error_kind: crate::ErrorKind,
+        outage_duration: Option<TimeDelta>,
+    ) {
+        self.inner
+            .report_recovery(year, leaderboard_id, view_key, error_kind, outage_duration)
+            .await
+    }
+
+    async fn report_stale(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        silence: TimeDelta,
+    ) {
+        self.inner
+            .report_stale(year, leaderboard_id, view_key, silence)
+            .await
+    }
+
+    async fn report_heartbeat(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        leaderboard: &Leaderboard,
+    ) {
+        self.inner
+            .report_heartbeat(year, leaderboard_id, view_key, leaderboard)
+            .await
+    }
+
+    async fn report_merged_changes(
+        &mut self,
+        merged_changes: &MergedChanges,
+    ) -> Result<(), Self::Err> {
+        let leaderboards: Vec<_> = merged_changes
+            .leaderboards
+            .iter()
+            .filter_map(|leaderboard_changes| {
+                self.filter_changes(&leaderboard_changes.changes)
+                    .map(|changes| LeaderboardChanges { changes, ..leaderboard_changes.clone() })
+            })
+            .collect();
+        if leaderboards.is_empty() {
+            tracing::debug!("all changes concern ignored members, skipping report");
+            return Ok(());
+        }
+
+        let member_leaderboards = merged_changes
+            .member_leaderboards
+            .iter()
+            .filter(|(id, _)| !self.ignored_members.contains(*id))
+            .map(|(id, leaderboard_ids)| (*id, leaderboard_ids.clone()))
+            .collect();
+        self.inner
+            .report_merged_changes(&MergedChanges {
+                year: merged_changes.year,
+                leaderboards,
+                member_leaderboards,
+            })
+            .await
+    }
+
+    fn defers_reports(&self) -> bool {
+        self.inner.defers_reports()
+    }
+}
+
+/// Bot reporter that suppresses reports during a daily time window ("quiet hours").
+///
+/// The window starts at `start` (inclusive) and ends at `end` (exclusive), in local time.
+/// If `start` is after `end`, the window wraps around midnight (e.g. from 22:00 to 07:00).
+/// If `start` and `end` are equal, the window is empty.
+///
+/// During quiet hours, this reporter [defers reports](Reporter::defers_reports): the bot does
+/// not report nor save the changes it detects (or the first run), so they are reported during
+/// the first bot run after quiet hours instead. Changes and first runs reported anyway during
+/// quiet hours (for example, by calling this reporter directly) are dropped.
+///
+/// Heartbeats reported during quiet hours are dropped. Errors, recoveries and stale bot reports
+/// are forwarded to the inner reporter at all times, unless [`suppress_errors`] is set to `true`.
+///
+/// [`suppress_errors`]: Self::suppress_errors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuietHoursReporter<R> {
+    inner: R,
+    start: NaiveTime,
+    end: NaiveTime,
+    suppress_errors: bool,
+    clock: SharedClock,
+}
+
+impl<R> QuietHoursReporter<R> {
+    /// Creates a new instance wrapping `inner` with quiet hours from `start` to `end`.
+    pub fn new(inner: R, start: NaiveTime, end: NaiveTime) -> Self {
+        Self { inner, start, end, suppress_errors: false, clock: SharedClock::default() }
+    }
+
+    /// Sets whether errors should also be suppressed during quiet hours.
+    pub fn suppress_errors(mut self, suppress_errors: bool) -> Self {
+        self.suppress_errors = suppress_errors;
+        self
+    }
+
+    /// Sets the [`Clock`] used to determine whether it is currently quiet hours.
+    ///
+    /// Defaults to the [`SystemClock`](crate::leaderbot::clock::SystemClock).
+    pub fn with_clock<C>(mut self, clock: C) -> Self
+    where
+        C: Clock + 'static,
+    {
+        self.clock = SharedClock::new(clock);
+        self
+    }
+
+    /// Returns the start of quiet hours.
+    pub fn start(&self) -> NaiveTime {
+        self.start
+    }
+
+    /// Returns the end of quiet hours.
+    pub fn end(&self) -> NaiveTime {
+        self.end
+    }
+
+    /// Returns a reference to the inner reporter.
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+
+    /// Consumes this wrapper and returns the inner reporter.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Checks if the given time falls within quiet hours.
+    pub fn is_quiet_at(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+
+    fn is_quiet_now(&self) -> bool {
+        self.is_quiet_at(self.clock.now().with_timezone(&Local).time())
+    }
+
+    fn is_quiet(&self) -> bool {
+        let quiet = self.is_quiet_now();
+        if quiet {
+            tracing::debug!("in quiet hours, skipping report");
+        }
+        quiet
+    }
+}
+
+impl<R> Reporter for QuietHoursReporter<R>
+where
+    R: Reporter + Send,
+{
+    type Err = R::Err;
+
+    async fn report_changes(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        previous_leaderboard: &Leaderboard,
+        leaderboard: &Leaderboard,
+        changes: &Changes,
+    ) -> Result<(), Self::Err> {
+        if self.is_quiet() {
+            return Ok(());
+        }
+
+        self.inner
+            .report_changes(
+                year,
+                leaderboard_id,
+                view_key,
+                previous_leaderboard,
+                leaderboard,
+                changes,
+            )
+            .await
+    }
+
+    async fn report_first_run(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        leaderboard: &Leaderboard,
+    ) -> Result<(), Self::Err> {
+        if self.is_quiet() {
+            return Ok(());
+        }
+
+        self.inner
+            .report_first_run(year, leaderboard_id, view_key, leaderboard)
+            .await
+    }
+
+    async fn report_error(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        error: &crate::Error,
+    ) {
+        if self.suppress_errors && self.is_quiet() {
+            return;
+        }
+
+        self.inner
+            .report_error(year, leaderboard_id, view_key, error)
+            .await
+    }
+
+    async fn report_recovery(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        error_kind: crate::ErrorKind,
+        outage_duration: Option<TimeDelta>,
+    ) {
+        if self.suppress_errors && self.is_quiet() {
+            return;
+        }
+
+        self.inner
+            .report_recovery(year, leaderboard_id, view_key, error_kind, outage_duration)
+            .await
+    }
+
+    async fn report_stale(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        silence: TimeDelta,
+    ) {
+        if self.suppress_errors && self.is_quiet() {
+            return;
+        }
+
+        self.inner
+            .report_stale(year, leaderboard_id, view_key, silence)
+            .await
+    }
+
+    async fn report_heartbeat(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        leaderboard: &Leaderboard,
+    ) {
+        if self.is_quiet() {
+            return;
+        }
+
+        self.inner
+            .report_heartbeat(year, leaderboard_id, view_key, leaderboard)
+            .await
+    }
+
+    async fn report_merged_changes(
+        &mut self,
+        merged_changes: &MergedChanges,
+    ) -> Result<(), Self::Err> {
+        if self.is_quiet() {
+            return Ok(());
+        }
+
+        self.inner.report_merged_changes(merged_changes).await
+    }
+
+    fn defers_reports(&self) -> bool {
+        self.is_quiet_now()
+    }
+}
+
+/// Bot reporter that sends errors to a different reporter than changes.
+///
+/// Calls to [`report_changes`], [`report_first_run`] and [`report_heartbeat`] are forwarded
+/// to the changes reporter, while calls to [`report_error`], [`report_recovery`] and
+/// [`report_stale`] are forwarded to the error reporter. This
+/// can be used, for example, to post leaderboard changes in a public channel but post
+/// bot errors in a channel monitored by the bot owner.
+///
+/// Reports are [deferred](Reporter::defers_reports) whenever the changes reporter defers them.
+///
+/// [`report_changes`]: Reporter::report_changes
+/// [`report_first_run`]: Reporter::report_first_run
+/// [`report_error`]: Reporter::report_error
+/// [`report_recovery`]: Reporter::report_recovery
+/// [`report_heartbeat`]: Reporter::report_heartbeat
+/// [`report_stale`]: Reporter::report_stale
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorRouterReporter<R, E> {
+    changes_reporter: R,
+    error_reporter: E,
+}
+
+impl<R, E> ErrorRouterReporter<R, E> {
+    /// Creates a new instance sending changes to `changes_reporter`
+    /// and errors to `error_reporter`.
+    pub fn new(changes_reporter: R, error_reporter: E) -> Self {
+        Self { changes_reporter, error_reporter }
+    }
+
+    /// Returns a reference to the reporter receiving changes.
+    pub fn changes_reporter(&self) -> &R {
+        &self.changes_reporter
+    }
+
+    /// Returns a reference to the reporter receiving errors.
+    pub fn error_reporter(&self) -> &E {
+        &self.error_reporter
+    }
+
+    /// Consumes this wrapper and returns the changes and error reporters.
+    pub fn into_inner(self) -> (R, E) {
+        (self.changes_reporter, self.error_reporter)
+    }
+}
+
+impl<R, E> Reporter for ErrorRouterReporter<R, E>
+where
+    R: Reporter + Send,
+    E: Reporter + Send,
+{
+    type Err = R::Err;
+
+    async fn report_changes(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        previous_leaderboard: &Leaderboard,
+        leaderboard: &Leaderboard,
+        changes: &Changes,
+    ) -> Result<(), Self::Err> {
+        self.changes_reporter
+            .report_changes(
+                year,
+                leaderboard_id,
+                view_key,
+                previous_leaderboard,
+                leaderboard,
+                changes,
+            )
+            .await
+    }
+
+    async fn report_first_run(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        leaderboard: &Leaderboard,
+    ) -> Result<(), Self::Err> {
+        self.changes_reporter
+            .report_first_run(year, leaderboard_id, view_key, leaderboard)
+            .await
+    }
+
+    async fn report_error(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        error: &crate::Error,
+    ) {
+        self.error_reporter
+            .report_error(year, leaderboard_id, view_key, error)
+            .await
+    }
+
+    async fn report_recovery(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        error_kind: crate::ErrorKind,
+        outage_duration: Option<TimeDelta>,
+    ) {
+        self.error_reporter
+            .report_recovery(year, leaderboard_id, view_key, error_kind, outage_duration)
+            .await
+    }
+
+    async fn report_stale(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        silence: TimeDelta,
+    ) {
+        self.error_reporter
+            .report_stale(year, leaderboard_id, view_key, silence)
+            .await
+    }
+
+    async fn report_heartbeat(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        leaderboard: &Leaderboard,
+    ) {
+        self.changes_reporter
+            .report_heartbeat(year, leaderboard_id, view_key, leaderboard)
+            .await
+    }
+
+    async fn report_merged_changes(
+        &mut self,
+        merged_changes: &MergedChanges,
+    ) -> Result<(), Self::Err> {
+        self.changes_reporter
+            .report_merged_changes(merged_changes)
+            .await
+    }
+
+    fn defers_reports(&self) -> bool {
+        self.changes_reporter.defers_reports()
+    }
+}
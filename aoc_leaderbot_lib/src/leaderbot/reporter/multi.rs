@@ -0,0 +1,283 @@
+//! Bot reporter forwarding calls to several other reporters.
+
+use std::fmt;
+use std::fmt::{Debug, Formatter};
+
+use aoc_leaderboard::aoc::Leaderboard;
+use chrono::TimeDelta;
+use futures_util::future::join_all;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ChildReporterError, ReporterError};
+use crate::leaderbot::dynamic::DynReporter;
+use crate::leaderbot::{Changes, MergedChanges, Reporter};
+
+/// Policy used by a [`MultiReporter`] to determine whether a call failed
+/// when one or more of its child reporters return an error.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FailurePolicy {
+    /// The call fails if any child reporter fails.
+    #[default]
+    FailIfAny,
+
+    /// The call fails only if all child reporters fail.
+    FailIfAll,
+
+    /// The call never fails; errors returned by child reporters are logged and ignored.
+    BestEffort,
+}
+
+/// Bot reporter that forwards every call to several child reporters.
+///
+/// Child reporters are called concurrently. If some of them fail, the [`FailurePolicy`]
+/// determines whether the call fails; if it does, the errors returned by the failing
+/// children are aggregated in a [`ReporterError::Children`].
+///
+/// Each child reporter is given a name, which is used to identify it in errors.
+///
+/// Calls to [`report_error`] and [`report_recovery`] are always forwarded to all child reporters.
+/// Reports are [deferred](Reporter::defers_reports) as long as any child reporter defers them,
+/// so that no child misses changes.
+///
+/// [`report_error`]: Reporter::report_error
+/// [`report_recovery`]: Reporter::report_recovery
+#[derive(Default)]
+pub struct MultiReporter<'a> {
+    reporters: Vec<(String, Box<dyn DynReporter + 'a>)>,
+    failure_policy: FailurePolicy,
+}
+
+impl<'a> MultiReporter<'a> {
+    /// Creates a new instance without child reporters, using the default [`FailurePolicy`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the [`FailurePolicy`] used by this reporter.
+    pub fn with_failure_policy(mut self, failure_policy: FailurePolicy) -> Self {
+        self.failure_policy = failure_policy;
+        self
+    }
+
+    /// Adds a child reporter with the given name.
+    pub fn with_reporter<N, R>(mut self, name: N, reporter: R) -> Self
+    where
+        N: Into<String>,
+        R: Reporter + Send + 'a,
+        R::Err: Sync + 'static,
+    {
+        self.add_reporter(name, reporter);
+        self
+    }
+
+    /// Adds a child reporter with the given name.
+    pub fn add_reporter<N, R>(&mut self, name: N, reporter: R)
+    where
+        N: Into<String>,
+        R: Reporter + Send + 'a,
+        R::Err: Sync + 'static,
+    {
+        self.reporters.push((name.into(), Box::new(reporter)));
+    }
+
+    /// Returns the [`FailurePolicy`] used by this reporter.
+    pub fn failure_policy(&self) -> FailurePolicy {
+        self.failure_policy
+    }
+
+    /// Returns the names of all child reporters, in the order they were added.
+    pub fn reporter_names(&self) -> impl Iterator<Item = &str> {
+        self.reporters.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Returns the number of child reporters.
+    pub fn len(&self) -> usize {
+        self.reporters.len()
+    }
+
+    /// Checks if this reporter has no child reporters.
+    pub fn is_empty(&self) -> bool {
+        self.reporters.is_empty()
+    }
+
+    fn aggregate_results(&self, results: Vec<anyhow::Result<()>>) -> Result<(), ReporterError> {
+        let total = results.len();
+        let failures: Vec<_> = self
+            .reporters
+            .iter()
+            .zip(results)
+            .filter_map(|((name, _), result)| {
+                result
+                    .err()
+                    .map(|source| ChildReporterError { name: name.clone(), source })
+            })
+            .collect();
+
+        let failed = match self.failure_policy {
+            FailurePolicy::FailIfAny => !failures.is_empty(),
+            FailurePolicy::FailIfAll => !failures.is_empty() && failures.len() == total,
+            FailurePolicy::BestEffort => false,
+        };
+        if failed {
+            return Err(ReporterError::Children(failures));
+        }
+
+        for failure in failures {
+            tracing::warn!("ignoring error from child reporter: {failure}");
+        }
+        Ok(())
+    }
+}
+
+impl Debug for MultiReporter<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MultiReporter")
+            .field("reporters", &self.reporter_names().collect::<Vec<_>>())
+            .field("failure_policy", &self.failure_policy)
+            .finish()
+    }
+}
+
+impl Reporter for MultiReporter<'_> {
+    type Err = ReporterError;
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip_all, err))]
+    async fn report_changes(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        previous_leaderboard: &Leaderboard,
+        leaderboard: &Leaderboard,
+        changes: &Changes,
+    ) -> Result<(), Self::Err> {
+        let results = join_all(self.reporters.iter_mut().map(|(_, reporter)| {
+            DynReporter::report_changes(
+                reporter.as_mut(),
+                year,
+                leaderboard_id,
+                view_key,
+                previous_leaderboard,
+                leaderboard,
+                changes,
+            )
+        }))
+        .await;
+
+        self.aggregate_results(results)
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip_all, err))]
+    async fn report_first_run(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        leaderboard: &Leaderboard,
+    ) -> Result<(), Self::Err> {
+        let results = join_all(self.reporters.iter_mut().map(|(_, reporter)| {
+            DynReporter::report_first_run(
+                reporter.as_mut(),
+                year,
+                leaderboard_id,
+                view_key,
+                leaderboard,
+            )
+        }))
+        .await;
+
+        self.aggregate_results(results)
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip_all))]
+    async fn report_error(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        error: &crate::Error,
+    ) {
+        join_all(self.reporters.iter_mut().map(|(_, reporter)| {
+            DynReporter::report_error(reporter.as_mut(), year, leaderboard_id, view_key, error)
+        }))
+        .await;
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip_all))]
+    async fn report_recovery(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        error_kind: crate::ErrorKind,
+        outage_duration: Option<TimeDelta>,
+    ) {
+        join_all(self.reporters.iter_mut().map(|(_, reporter)| {
+            DynReporter::report_recovery(
+                reporter.as_mut(),
+                year,
+                leaderboard_id,
+                view_key,
+                error_kind,
+                outage_duration,
+            )
+        }))
+        .await;
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip_all))]
+    async fn report_stale(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        silence: TimeDelta,
+    ) {
+        join_all(self.reporters.iter_mut().map(|(_, reporter)| {
+            DynReporter::report_stale(reporter.as_mut(), year, leaderboard_id, view_key, silence)
+        }))
+        .await;
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip_all))]
+    async fn report_heartbeat(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        leaderboard: &Leaderboard,
+    ) {
+        join_all(self.reporters.iter_mut().map(|(_, reporter)| {
+            DynReporter::report_heartbeat(
+                reporter.as_mut(),
+                year,
+                leaderboard_id,
+                view_key,
+                leaderboard,
+            )
+        }))
+        .await;
+    }
+
+    // Using `async fn` here trips the compiler on the `Self: Send` bound of this method.
+    #[allow(clippy::manual_async_fn)]
+    fn report_merged_changes(
+        &mut self,
+        merged_changes: &MergedChanges,
+    ) -> impl Future<Output = Result<(), Self::Err>> + Send {
+        async move {
+            let results = join_all(self.reporters.iter_mut().map(|(_, reporter)| {
+                DynReporter::report_merged_changes(reporter.as_mut(), merged_changes)
+            }))
+            .await;
+
+            self.aggregate_results(results)
+        }
+    }
+
+    fn defers_reports(&self) -> bool {
+        self.reporters
+            .iter()
+            .any(|(_, reporter)| DynReporter::defers_reports(reporter.as_ref()))
+    }
+}
@@ -0,0 +1,519 @@
+//! Bot reporter recording every call it receives, for use in tests.
+
+use anyhow::anyhow;
+use aoc_leaderboard::aoc::Leaderboard;
+use chrono::TimeDelta;
+use serde::{Deserialize, Serialize};
+
+use crate::ErrorKind;
+use crate::error::ReporterError;
+use crate::leaderbot::{Changes, Reporter};
+
+/// Arguments of a call to [`Reporter::report_changes`] recorded by a [`RecordingReporter`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReportedChanges {
+    /// Year of the leaderboard.
+    pub year: i32,
+
+    /// ID of the leaderboard.
+    pub leaderboard_id: u64,
+
+    /// View key passed to the reporter, if any.
+    pub view_key: Option<String>,
+
+    /// Previous version of the leaderboard.
+    pub previous_leaderboard: Leaderboard,
+
+    /// Current version of the leaderboard.
+    pub leaderboard: Leaderboard,
+
+    /// Changes detected by the bot.
+    pub changes: Changes,
+}
+
+/// Arguments of a call to [`Reporter::report_first_run`] recorded by a [`RecordingReporter`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReportedFirstRun {
+    /// Year of the leaderboard.
+    pub year: i32,
+
+    /// ID of the leaderboard.
+    pub leaderboard_id: u64,
+
+    /// View key passed to the reporter, if any.
+    pub view_key: Option<String>,
+
+    /// Initial version of the leaderboard.
+    pub leaderboard: Leaderboard,
+}
+
+/// Arguments of a call to [`Reporter::report_error`] recorded by a [`RecordingReporter`].
+///
+/// Since [`Error`](crate::Error) cannot be cloned, the error's [kind] and message are
+/// recorded instead.
+///
+/// [kind]: ErrorKind
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReportedError {
+    /// Year of the leaderboard.
+    pub year: i32,
+
+    /// ID of the leaderboard.
+    pub leaderboard_id: u64,
+
+    /// View key passed to the reporter, if any.
+    pub view_key: Option<String>,
+
+    /// Kind of error reported.
+    pub error_kind: ErrorKind,
+
+    /// Error message, as returned by the error's [`Display`](std::fmt::Display) implementation.
+    pub message: String,
+}
+
+/// Arguments of a call to [`Reporter::report_recovery`] recorded by a [`RecordingReporter`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReportedRecovery {
+    /// Year of the leaderboard.
+    pub year: i32,
+
+    /// ID of the leaderboard.
+    pub leaderboard_id: u64,
+
+    /// View key passed to the reporter, if any.
+    pub view_key: Option<String>,
+
+    /// Kind of error the bot recovered from.
+    pub error_kind: ErrorKind,
+
+    /// Duration of the outage, if known.
+    pub outage_duration: Option<TimeDelta>,
+}
+
+/// Arguments of a call to [`Reporter::report_stale`] recorded by a [`RecordingReporter`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReportedStale {
+    /// Year of the leaderboard.
+    pub year: i32,
+
+    /// ID of the leaderboard.
+    pub leaderboard_id: u64,
+
+    /// View key passed to the reporter, if any.
+    pub view_key: Option<String>,
+
+    /// Time elapsed since the last successful bot run.
+    pub silence: TimeDelta,
+}
+
+/// Arguments of a call to [`Reporter::report_heartbeat`] recorded by a [`RecordingReporter`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReportedHeartbeat {
+    /// Year of the leaderboard.
+    pub year: i32,
+
+    /// ID of the leaderboard.
+    pub leaderboard_id: u64,
+
+    /// View key passed to the reporter, if any.
+    pub view_key: Option<String>,
+
+    /// Current version of the leaderboard.
+    pub leaderboard: Leaderboard,
+}
+
+/// A call recorded by a [`RecordingReporter`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReportedCall {
+    /// Call to [`Reporter::report_changes`].
+    Changes(ReportedChanges),
+
+    /// Call to [`Reporter::report_first_run`].
+    FirstRun(ReportedFirstRun),
+
+    /// Call to [`Reporter::report_error`].
+    Error(ReportedError),
+
+    /// Call to [`Reporter::report_recovery`].
+    Recovery(ReportedRecovery),
+
+    /// Call to [`Reporter::report_stale`].
+    Stale(ReportedStale),
+
+    /// Call to [`Reporter::report_heartbeat`].
+    Heartbeat(ReportedHeartbeat),
+}
+
+/// Bot reporter that records every call it receives.
+///
+/// Calls are kept in order and can be inspected via [`calls`] (or via [`changes`],
+/// [`first_runs`], [`errors`], [`recoveries`], [`stales`] and [`heartbeats`] for a specific
+/// type of call). The reporter also
+/// provides helper methods to assert on recorded calls, like [`assert_changes_reported`].
+///
+/// The reporter can optionally be configured to fail when asked to [report changes]
+/// or to [report the first run], to test error handling. Failed calls are still recorded.
+///
+/// [`calls`]: Self::calls
+/// [`changes`]: Self::changes
+/// [`first_runs`]: Self::first_runs
+/// [`errors`]: Self::errors
+/// [`recoveries`]: Self::recoveries
+/// [`stales`]: Self::stales
+/// [`heartbeats`]: Self::heartbeats
+/// [`assert_changes_reported`]: Self::assert_changes_reported
+/// [report changes]: Self::fail_report_changes
+/// [report the first run]: Self::fail_report_first_run
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RecordingReporter {
+    calls: Vec<ReportedCall>,
+    fail_report_changes: bool,
+    fail_report_first_run: bool,
+}
+
+impl RecordingReporter {
+    /// Creates a new instance without any recorded calls.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Configures the reporter to return an error when [`report_changes`] is called.
+    ///
+    /// [`report_changes`]: Reporter::report_changes
+    pub fn fail_report_changes(mut self, fail: bool) -> Self {
+        self.fail_report_changes = fail;
+        self
+    }
+
+    /// Configures the reporter to return an error when [`report_first_run`] is called.
+    ///
+    /// [`report_first_run`]: Reporter::report_first_run
+    pub fn fail_report_first_run(mut self, fail: bool) -> Self {
+        self.fail_report_first_run = fail;
+        self
+    }
+
+    /// Returns all recorded calls, in order.
+    pub fn calls(&self) -> &[ReportedCall] {
+        &self.calls
+    }
+
+    /// Returns the number of recorded calls.
+    pub fn call_count(&self) -> usize {
+        self.calls.len()
+    }
+
+    /// Checks if any call has been recorded.
+    pub fn called(&self) -> bool {
+        !self.calls.is_empty()
+    }
+
+    /// Returns all recorded calls to [`report_changes`], in order.
+    ///
+    /// [`report_changes`]: Reporter::report_changes
+    pub fn changes(&self) -> impl Iterator<Item = &ReportedChanges> {
+        self.calls.iter().filter_map(|call| match call {
+            ReportedCall::Changes(changes) => Some(changes),
+            _ => None,
+        })
+    }
+
+    /// Returns all recorded calls to [`report_first_run`], in order.
+    ///
+    /// [`report_first_run`]: Reporter::report_first_run
+    pub fn first_runs(&self) -> impl Iterator<Item = &ReportedFirstRun> {
+        self.calls.iter().filter_map(|call| match call {
+            ReportedCall::FirstRun(first_run) => Some(first_run),
+            _ => None,
+        })
+    }
+
+    /// Returns all recorded calls to [`report_error`], in order.
+    ///
+    /// [`report_error`]: Reporter::report_error
+    pub fn errors(&self) -> impl Iterator<Item = &ReportedError> {
+        self.calls.iter().filter_map(|call| match call {
+            ReportedCall::Error(error) => Some(error),
+            _ => None,
+        })
+    }
+
+    /// Returns all recorded calls to [`report_recovery`], in order.
+    ///
+    /// [`report_recovery`]: Reporter::report_recovery
+    pub fn recoveries(&self) -> impl Iterator<Item = &ReportedRecovery> {
+        self.calls.iter().filter_map(|call| match call {
+            ReportedCall::Recovery(recovery) => Some(recovery),
+            _ => None,
+        })
+    }
+
+    /// Returns all recorded calls to [`report_stale`], in order.
+    ///
+    /// [`report_stale`]: Reporter::report_stale
+    pub fn stales(&self) -> impl Iterator<Item = &ReportedStale> {
+        self.calls.iter().filter_map(|call| match call {
+            ReportedCall::Stale(stale) => Some(stale),
+            _ => None,
+        })
+    }
+
+    /// Returns all recorded calls to [`report_heartbeat`], in order.
+    ///
+    /// [`report_heartbeat`]: Reporter::report_heartbeat
+    pub fn heartbeats(&self) -> impl Iterator<Item = &ReportedHeartbeat> {
+        self.calls.iter().filter_map(|call| match call {
+            ReportedCall::Heartbeat(heartbeat) => Some(heartbeat),
+            _ => None,
+        })
+    }
+
+    /// Forgets all recorded calls.
+    pub fn clear(&mut self) {
+        self.calls.clear();
+    }
+
+    /// Asserts that no call has been recorded.
+    ///
+    /// # Panics
+    ///
+    /// If any call has been recorded.
+    #[track_caller]
+    pub fn assert_not_called(&self) {
+        assert!(self.calls.is_empty(), "expected no reporter calls, got {:?}", self.calls);
+    }
+
+    /// Asserts that exactly one call has been recorded, and that it was a call to
+    /// [`report_changes`] for the given leaderboard with the given changes.
+    ///
+    /// Returns the recorded call so that further assertions can be performed.
+    ///
+    /// # Panics
+    ///
+    /// If the recorded calls do not match.
+    ///
+    /// [`report_changes`]: Reporter::report_changes
+    #[track_caller]
+    pub fn assert_changes_reported(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+        changes: &Changes,
+    ) -> &ReportedChanges {
+        match self.calls.as_slice() {
+            [ReportedCall::Changes(reported)] => {
+                assert_eq!(
+                    (year, leaderboard_id, changes),
+                    (reported.year, reported.leaderboard_id, &reported.changes),
+                    "unexpected changes reported"
+                );
+                reported
+            },
+            calls => panic!("expected a single call to report_changes, got {calls:?}"),
+        }
+    }
+
+    /// Asserts that exactly one call has been recorded, and that it was a call to
+    /// [`report_first_run`] for the given leaderboard.
+    ///
+    /// Returns the recorded call so that further assertions can be performed.
+    ///
+    /// # Panics
+    ///
+    /// If the recorded calls do not match.
+    ///
+    /// [`report_first_run`]: Reporter::report_first_run
+    #[track_caller]
+    pub fn assert_first_run_reported(&self, year: i32, leaderboard_id: u64) -> &ReportedFirstRun {
+        match self.calls.as_slice() {
+            [ReportedCall::FirstRun(reported)] => {
+                assert_eq!(
+                    (year, leaderboard_id),
+                    (reported.year, reported.leaderboard_id),
+                    "unexpected first run reported"
+                );
+                reported
+            },
+            calls => panic!("expected a single call to report_first_run, got {calls:?}"),
+        }
+    }
+
+    /// Asserts that exactly one call has been recorded, and that it was a call to
+    /// [`report_error`] for the given leaderboard with an error of the given kind.
+    ///
+    /// Returns the recorded call so that further assertions can be performed.
+    ///
+    /// # Panics
+    ///
+    /// If the recorded calls do not match.
+    ///
+    /// [`report_error`]: Reporter::report_error
+    #[track_caller]
+    pub fn assert_error_reported(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+        error_kind: ErrorKind,
+    ) -> &ReportedError {
+        match self.calls.as_slice() {
+            [ReportedCall::Error(reported)] => {
+                assert_eq!(
+                    (year, leaderboard_id, error_kind),
+                    (reported.year, reported.leaderboard_id, reported.error_kind),
+                    "unexpected error reported"
+                );
+                reported
+            },
+            calls => panic!("expected a single call to report_error, got {calls:?}"),
+        }
+    }
+
+    /// Asserts that exactly one call has been recorded, and that it was a call to
+    /// [`report_recovery`] for the given leaderboard from an error of the given kind.
+    ///
+    /// Returns the recorded call so that further assertions can be performed.
+    ///
+    /// # Panics
+    ///
+    /// If the recorded calls do not match.
+    ///
+    /// [`report_recovery`]: Reporter::report_recovery
+    #[track_caller]
+    pub fn assert_recovery_reported(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+        error_kind: ErrorKind,
+    ) -> &ReportedRecovery {
+        match self.calls.as_slice() {
+            [ReportedCall::Recovery(reported)] => {
+                assert_eq!(
+                    (year, leaderboard_id, error_kind),
+                    (reported.year, reported.leaderboard_id, reported.error_kind),
+                    "unexpected recovery reported"
+                );
+                reported
+            },
+            calls => panic!("expected a single call to report_recovery, got {calls:?}"),
+        }
+    }
+}
+
+impl Reporter for RecordingReporter {
+    type Err = crate::Error;
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip_all, err))]
+    async fn report_changes(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        previous_leaderboard: &Leaderboard,
+        leaderboard: &Leaderboard,
+        changes: &Changes,
+    ) -> Result<(), Self::Err> {
+        self.calls.push(ReportedCall::Changes(ReportedChanges {
+            year,
+            leaderboard_id,
+            view_key: view_key.map(Into::into),
+            previous_leaderboard: previous_leaderboard.clone(),
+            leaderboard: leaderboard.clone(),
+            changes: changes.clone(),
+        }));
+
+        if self.fail_report_changes {
+            return Err(ReporterError::ReportChanges(anyhow!("recording reporter failure")).into());
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip_all, err))]
+    async fn report_first_run(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        leaderboard: &Leaderboard,
+    ) -> Result<(), Self::Err> {
+        self.calls.push(ReportedCall::FirstRun(ReportedFirstRun {
+            year,
+            leaderboard_id,
+            view_key: view_key.map(Into::into),
+            leaderboard: leaderboard.clone(),
+        }));
+
+        if self.fail_report_first_run {
+            return Err(ReporterError::ReportFirstRun(anyhow!("recording reporter failure")).into());
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip_all))]
+    async fn report_error(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        error: &crate::Error,
+    ) {
+        self.calls.push(ReportedCall::Error(ReportedError {
+            year,
+            leaderboard_id,
+            view_key: view_key.map(Into::into),
+            error_kind: error.into(),
+            message: error.to_string(),
+        }));
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip_all))]
+    async fn report_recovery(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        error_kind: ErrorKind,
+        outage_duration: Option<TimeDelta>,
+    ) {
+        self.calls.push(ReportedCall::Recovery(ReportedRecovery {
+            year,
+            leaderboard_id,
+            view_key: view_key.map(Into::into),
+            error_kind,
+            outage_duration,
+        }));
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip_all))]
+    async fn report_stale(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        silence: TimeDelta,
+    ) {
+        self.calls.push(ReportedCall::Stale(ReportedStale {
+            year,
+            leaderboard_id,
+            view_key: view_key.map(Into::into),
+            silence,
+        }));
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip_all))]
+    async fn report_heartbeat(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        leaderboard: &Leaderboard,
+    ) {
+        self.calls.push(ReportedCall::Heartbeat(ReportedHeartbeat {
+            year,
+            leaderboard_id,
+            view_key: view_key.map(Into::into),
+            leaderboard: leaderboard.clone(),
+        }));
+    }
+}
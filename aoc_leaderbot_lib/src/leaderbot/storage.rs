@@ -1,4 +1,6 @@
 //! Implementations of [`leaderbot::Storage`](crate::leaderbot::Storage).
 
+#[cfg(feature = "storage-conformance")]
+pub mod conformance;
 #[cfg(feature = "storage-mem")]
 pub mod mem;
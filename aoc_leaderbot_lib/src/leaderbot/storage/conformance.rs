@@ -0,0 +1,1329 @@
+//! Conformance test suite for implementations of [`Storage`] and its extension traits
+//! ([`StorageAdmin`], [`DigestStorage`] and [`OutboxStorage`]).
+//!
+//! The rules that a [`Storage`] must honour are spelled out in the trait's documentation, but
+//! some of them are subtle (for example, [`save_error`] must not clear leaderboard data). This
+//! module includes checks that can be run against any storage implementation to validate that
+//! it behaves like the bot expects.
+//!
+//! Each check is an `async` function that panics if the storage does not conform. Checks only
+//! touch data for the leaderboard ID they are given (and the one right after it), for the years
+//! [`CONFORMANCE_YEAR`] and `CONFORMANCE_YEAR - 1`; the storage must not contain any data for
+//! those leaderboards when the check starts.
+//!
+//! To run all checks at once, use [`check_storage`], [`check_storage_admin`],
+//! [`check_digest_storage`], [`check_outbox_storage`], [`check_error_state`] and
+//! [`check_heartbeat`]. These only need
+//! an empty storage and use a different leaderboard ID for each check, so they can be called from
+//! a single test.
+//!
+//! [`save_error`]: Storage::save_error
+
+use std::collections::HashMap;
+
+use aoc_leaderboard::aoc::{Leaderboard, LeaderboardMember};
+use chrono::{DateTime, TimeDelta, Utc};
+
+use crate::ErrorKind;
+use crate::leaderbot::heartbeat::HeartbeatState;
+use crate::leaderbot::{
+    Changes, DigestStorage, ErrorState, OutboxReport, OutboxStorage, PendingChanges, Storage,
+    StorageAdmin, StorageEntry,
+};
+
+/// Year used for the leaderboards saved by the conformance checks.
+pub const CONFORMANCE_YEAR: i32 = 2024;
+
+/// First leaderboard ID used by [`check_storage`], [`check_storage_admin`],
+/// [`check_digest_storage`], [`check_outbox_storage`], [`check_error_state`] and
+/// [`check_heartbeat`].
+///
+/// Each check run by those functions uses a different leaderboard ID, starting with this one.
+pub const CONFORMANCE_BASE_LEADERBOARD_ID: u64 = 4_242_000;
+
+/// Returns a [`Leaderboard`] suitable for conformance checks.
+///
+/// The `generation` parameter can be used to get different versions of the leaderboard.
+pub fn conformance_leaderboard(year: i32, leaderboard_id: u64, generation: u32) -> Leaderboard {
+    let owner = LeaderboardMember {
+        name: Some("Conformance Owner".into()),
+        id: leaderboard_id,
+        stars: generation,
+        local_score: generation.into(),
+        global_score: 0,
+        last_star_ts: generation.into(),
+        completion_day_level: HashMap::new(),
+    };
+
+    Leaderboard {
+        year,
+        owner_id: leaderboard_id,
+        day1_ts: 1_733_029_200,
+        members: [(owner.id, owner)].into(),
+    }
+}
+
+/// Returns [`PendingChanges`] suitable for conformance checks.
+///
+/// The `generation` parameter can be used to get different pending changes.
+pub fn conformance_pending_changes(
+    year: i32,
+    leaderboard_id: u64,
+    generation: u32,
+) -> PendingChanges {
+    PendingChanges::new(
+        conformance_leaderboard(year, leaderboard_id, generation),
+        Changes::new([leaderboard_id].into(), [u64::from(generation)].into()),
+        DateTime::<Utc>::from_timestamp(1_733_029_200 + i64::from(generation), 0)
+            .expect("conformance timestamp should be valid"),
+    )
+}
+
+/// Returns an [`OutboxReport`] suitable for conformance checks.
+///
+/// The `generation` parameter can be used to get different reports.
+pub fn conformance_outbox_report(year: i32, leaderboard_id: u64, generation: u32) -> OutboxReport {
+    OutboxReport::Changes {
+        previous_leaderboard: conformance_leaderboard(year, leaderboard_id, generation),
+        changes: Changes::new([].into(), [leaderboard_id].into()),
+    }
+}
+
+fn conformance_error_kind() -> ErrorKind {
+    ErrorKind::Leaderboard(aoc_leaderboard::ErrorKind::NoAccess)
+}
+
+fn conformance_error_state() -> ErrorState {
+    let first_seen = DateTime::from_timestamp(1_733_029_200, 0).unwrap();
+
+    ErrorState {
+        last_seen: first_seen + TimeDelta::hours(2),
+        occurrences: 3,
+        last_message: Some("no access to leaderboard".into()),
+        last_notified: Some(first_seen + TimeDelta::hours(1)),
+        ..ErrorState::new(conformance_error_kind(), first_seen)
+    }
+}
+
+/// Returns a [`HeartbeatState`] suitable for conformance checks.
+///
+/// The `generation` parameter can be used to get different heartbeat states.
+pub fn conformance_heartbeat(generation: u32) -> HeartbeatState {
+    let last_success = DateTime::from_timestamp(1_733_029_200 + i64::from(generation), 0)
+        .expect("conformance timestamp should be valid");
+
+    HeartbeatState {
+        last_report: Some(last_success - TimeDelta::hours(1)),
+        stale_reported: Some(last_success + TimeDelta::hours(2)),
+        ..HeartbeatState::new(last_success)
+    }
+}
+
+async fn load<S>(
+    storage: &S,
+    year: i32,
+    leaderboard_id: u64,
+) -> (Option<Leaderboard>, Option<ErrorKind>)
+where
+    S: Storage,
+{
+    storage
+        .load_previous(year, leaderboard_id)
+        .await
+        .unwrap_or_else(|err| panic!("load_previous({year}, {leaderboard_id}) failed: {err}"))
+}
+
+async fn save_success<S>(storage: &mut S, year: i32, leaderboard_id: u64, leaderboard: &Leaderboard)
+where
+    S: Storage,
+{
+    storage
+        .save_success(year, leaderboard_id, leaderboard)
+        .await
+        .unwrap_or_else(|err| panic!("save_success({year}, {leaderboard_id}) failed: {err}"));
+}
+
+async fn save_error<S>(storage: &mut S, year: i32, leaderboard_id: u64, error_kind: ErrorKind)
+where
+    S: Storage,
+{
+    storage
+        .save_error(year, leaderboard_id, error_kind)
+        .await
+        .unwrap_or_else(|err| panic!("save_error({year}, {leaderboard_id}) failed: {err}"));
+}
+
+async fn save_error_state<S>(
+    storage: &mut S,
+    year: i32,
+    leaderboard_id: u64,
+    error_state: &ErrorState,
+) where
+    S: Storage,
+{
+    storage
+        .save_error_state(year, leaderboard_id, error_state)
+        .await
+        .unwrap_or_else(|err| panic!("save_error_state({year}, {leaderboard_id}) failed: {err}"));
+}
+
+async fn load_error_state<S>(storage: &S, year: i32, leaderboard_id: u64) -> Option<ErrorState>
+where
+    S: Storage,
+{
+    storage
+        .load_error_state(year, leaderboard_id)
+        .await
+        .unwrap_or_else(|err| panic!("load_error_state({year}, {leaderboard_id}) failed: {err}"))
+}
+
+async fn load_heartbeat<S>(storage: &S, year: i32, leaderboard_id: u64) -> Option<HeartbeatState>
+where
+    S: Storage,
+{
+    storage
+        .load_heartbeat(year, leaderboard_id)
+        .await
+        .unwrap_or_else(|err| panic!("load_heartbeat({year}, {leaderboard_id}) failed: {err}"))
+}
+
+async fn save_heartbeat<S>(
+    storage: &mut S,
+    year: i32,
+    leaderboard_id: u64,
+    heartbeat: &HeartbeatState,
+) where
+    S: Storage,
+{
+    storage
+        .save_heartbeat(year, leaderboard_id, heartbeat)
+        .await
+        .unwrap_or_else(|err| panic!("save_heartbeat({year}, {leaderboard_id}) failed: {err}"));
+}
+
+async fn list<S>(storage: &S, leaderboard_id: u64) -> Vec<StorageEntry>
+where
+    S: StorageAdmin,
+{
+    let mut entries: Vec<_> = storage
+        .list()
+        .await
+        .unwrap_or_else(|err| panic!("list() failed: {err}"))
+        .into_iter()
+        .filter(|entry| entry.leaderboard_id == leaderboard_id)
+        .collect();
+    entries.sort_by_key(|entry| entry.year);
+    entries
+}
+
+async fn load_pending<S>(storage: &S, year: i32, leaderboard_id: u64) -> Option<PendingChanges>
+where
+    S: DigestStorage,
+{
+    storage
+        .load_pending_changes(year, leaderboard_id)
+        .await
+        .unwrap_or_else(|err| {
+            panic!("load_pending_changes({year}, {leaderboard_id}) failed: {err}")
+        })
+}
+
+async fn save_pending<S>(
+    storage: &mut S,
+    year: i32,
+    leaderboard_id: u64,
+    pending_changes: &PendingChanges,
+) where
+    S: DigestStorage,
+{
+    storage
+        .save_pending_changes(year, leaderboard_id, pending_changes)
+        .await
+        .unwrap_or_else(|err| {
+            panic!("save_pending_changes({year}, {leaderboard_id}) failed: {err}")
+        });
+}
+
+async fn load_outbox<S>(storage: &S, year: i32, leaderboard_id: u64) -> Option<OutboxReport>
+where
+    S: OutboxStorage,
+{
+    storage
+        .load_outbox(year, leaderboard_id)
+        .await
+        .unwrap_or_else(|err| panic!("load_outbox({year}, {leaderboard_id}) failed: {err}"))
+}
+
+async fn save_success_with_outbox<S>(
+    storage: &mut S,
+    year: i32,
+    leaderboard_id: u64,
+    leaderboard: &Leaderboard,
+    report: &OutboxReport,
+) where
+    S: OutboxStorage,
+{
+    storage
+        .save_success_with_outbox(year, leaderboard_id, leaderboard, report)
+        .await
+        .unwrap_or_else(|err| {
+            panic!("save_success_with_outbox({year}, {leaderboard_id}) failed: {err}")
+        });
+}
+
+async fn mark_delivered<S>(storage: &mut S, year: i32, leaderboard_id: u64)
+where
+    S: OutboxStorage,
+{
+    storage
+        .mark_delivered(year, leaderboard_id)
+        .await
+        .unwrap_or_else(|err| panic!("mark_delivered({year}, {leaderboard_id}) failed: {err}"));
+}
+
+/// Runs all [`Storage`] conformance checks against the given storage.
+pub async fn check_storage<S>(storage: &mut S)
+where
+    S: Storage,
+{
+    let mut leaderboard_id = CONFORMANCE_BASE_LEADERBOARD_ID;
+    let mut next_leaderboard_id = || {
+        let id = leaderboard_id;
+        leaderboard_id += 2;
+        id
+    };
+
+    load_previous_without_existing(storage, next_leaderboard_id()).await;
+    save_success_then_load_previous(storage, next_leaderboard_id()).await;
+    save_success_overwrites_leaderboard(storage, next_leaderboard_id()).await;
+    save_success_clears_error(storage, next_leaderboard_id()).await;
+    save_error_without_existing(storage, next_leaderboard_id()).await;
+    save_error_keeps_leaderboard(storage, next_leaderboard_id()).await;
+    save_error_overwrites_error(storage, next_leaderboard_id()).await;
+    leaderboards_are_isolated(storage, next_leaderboard_id()).await;
+}
+
+/// Runs all [`StorageAdmin`] conformance checks against the given storage.
+///
+/// Does not run the checks in [`check_storage`]; these need to be run separately.
+pub async fn check_storage_admin<S>(storage: &mut S)
+where
+    S: StorageAdmin,
+{
+    let mut leaderboard_id = CONFORMANCE_BASE_LEADERBOARD_ID + 1_000;
+    let mut next_leaderboard_id = || {
+        let id = leaderboard_id;
+        leaderboard_id += 2;
+        id
+    };
+
+    list_returns_saved_entries(storage, next_leaderboard_id()).await;
+    delete_removes_leaderboard_and_error(storage, next_leaderboard_id()).await;
+    delete_without_existing(storage, next_leaderboard_id()).await;
+    clear_error_keeps_leaderboard(storage, next_leaderboard_id()).await;
+    clear_error_without_leaderboard(storage, next_leaderboard_id()).await;
+    clear_error_without_existing(storage, next_leaderboard_id()).await;
+}
+
+/// Checks that [`load_previous`] returns nothing for a leaderboard that was never saved.
+///
+/// [`load_previous`]: Storage::load_previous
+pub async fn load_previous_without_existing<S>(storage: &mut S, leaderboard_id: u64)
+where
+    S: Storage,
+{
+    let previous = load(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(
+        (None, None),
+        previous,
+        "load_previous must not return data for a leaderboard that was never saved"
+    );
+}
+
+/// Checks that [`load_previous`] returns the leaderboard saved by [`save_success`].
+///
+/// [`load_previous`]: Storage::load_previous
+/// [`save_success`]: Storage::save_success
+pub async fn save_success_then_load_previous<S>(storage: &mut S, leaderboard_id: u64)
+where
+    S: Storage,
+{
+    let leaderboard = conformance_leaderboard(CONFORMANCE_YEAR, leaderboard_id, 1);
+    save_success(storage, CONFORMANCE_YEAR, leaderboard_id, &leaderboard).await;
+
+    let previous = load(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(
+        (Some(leaderboard), None),
+        previous,
+        "load_previous must return the leaderboard saved via save_success"
+    );
+}
+
+/// Checks that [`save_success`] replaces any previously-saved leaderboard.
+///
+/// [`save_success`]: Storage::save_success
+pub async fn save_success_overwrites_leaderboard<S>(storage: &mut S, leaderboard_id: u64)
+where
+    S: Storage,
+{
+    let first = conformance_leaderboard(CONFORMANCE_YEAR, leaderboard_id, 1);
+    let second = conformance_leaderboard(CONFORMANCE_YEAR, leaderboard_id, 2);
+    save_success(storage, CONFORMANCE_YEAR, leaderboard_id, &first).await;
+    save_success(storage, CONFORMANCE_YEAR, leaderboard_id, &second).await;
+
+    let previous = load(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(
+        (Some(second), None),
+        previous,
+        "save_success must replace the previously-saved leaderboard"
+    );
+}
+
+/// Checks that [`save_success`] clears the error saved by [`save_error`].
+///
+/// [`save_success`]: Storage::save_success
+/// [`save_error`]: Storage::save_error
+pub async fn save_success_clears_error<S>(storage: &mut S, leaderboard_id: u64)
+where
+    S: Storage,
+{
+    let first = conformance_leaderboard(CONFORMANCE_YEAR, leaderboard_id, 1);
+    let second = conformance_leaderboard(CONFORMANCE_YEAR, leaderboard_id, 2);
+    save_success(storage, CONFORMANCE_YEAR, leaderboard_id, &first).await;
+    save_error(storage, CONFORMANCE_YEAR, leaderboard_id, conformance_error_kind()).await;
+    save_success(storage, CONFORMANCE_YEAR, leaderboard_id, &second).await;
+
+    let previous = load(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!((Some(second), None), previous, "save_success must clear the last error");
+
+    let other_leaderboard_id = leaderboard_id + 1;
+    let leaderboard = conformance_leaderboard(CONFORMANCE_YEAR, other_leaderboard_id, 1);
+    save_error(storage, CONFORMANCE_YEAR, other_leaderboard_id, conformance_error_kind()).await;
+    save_success(storage, CONFORMANCE_YEAR, other_leaderboard_id, &leaderboard).await;
+
+    let previous = load(storage, CONFORMANCE_YEAR, other_leaderboard_id).await;
+    assert_eq!(
+        (Some(leaderboard), None),
+        previous,
+        "save_success must clear the last error even if no leaderboard was saved before"
+    );
+}
+
+/// Checks that [`load_previous`] returns the error saved by [`save_error`] for a
+/// leaderboard that was never saved.
+///
+/// [`load_previous`]: Storage::load_previous
+/// [`save_error`]: Storage::save_error
+pub async fn save_error_without_existing<S>(storage: &mut S, leaderboard_id: u64)
+where
+    S: Storage,
+{
+    save_error(storage, CONFORMANCE_YEAR, leaderboard_id, conformance_error_kind()).await;
+
+    let previous = load(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(
+        (None, Some(conformance_error_kind())),
+        previous,
+        "load_previous must return the error saved via save_error"
+    );
+}
+
+/// Checks that [`save_error`] does not clear the leaderboard saved by [`save_success`].
+///
+/// [`save_error`]: Storage::save_error
+/// [`save_success`]: Storage::save_success
+pub async fn save_error_keeps_leaderboard<S>(storage: &mut S, leaderboard_id: u64)
+where
+    S: Storage,
+{
+    let leaderboard = conformance_leaderboard(CONFORMANCE_YEAR, leaderboard_id, 1);
+    save_success(storage, CONFORMANCE_YEAR, leaderboard_id, &leaderboard).await;
+    save_error(storage, CONFORMANCE_YEAR, leaderboard_id, conformance_error_kind()).await;
+
+    let previous = load(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(
+        (Some(leaderboard), Some(conformance_error_kind())),
+        previous,
+        "save_error must not clear the leaderboard saved via save_success"
+    );
+}
+
+/// Checks that [`save_error`] replaces any previously-saved error.
+///
+/// [`save_error`]: Storage::save_error
+pub async fn save_error_overwrites_error<S>(storage: &mut S, leaderboard_id: u64)
+where
+    S: Storage,
+{
+    let leaderboard = conformance_leaderboard(CONFORMANCE_YEAR, leaderboard_id, 1);
+    save_success(storage, CONFORMANCE_YEAR, leaderboard_id, &leaderboard).await;
+    save_error(storage, CONFORMANCE_YEAR, leaderboard_id, ErrorKind::MissingField).await;
+    save_error(storage, CONFORMANCE_YEAR, leaderboard_id, conformance_error_kind()).await;
+
+    let previous = load(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(
+        (Some(leaderboard), Some(conformance_error_kind())),
+        previous,
+        "save_error must replace the previously-saved error"
+    );
+}
+
+/// Checks that data saved for a leaderboard is kept separate from data saved for the
+/// same leaderboard in another year, or for another leaderboard in the same year.
+///
+/// In addition to `leaderboard_id`, this check uses `leaderboard_id + 1`.
+pub async fn leaderboards_are_isolated<S>(storage: &mut S, leaderboard_id: u64)
+where
+    S: Storage,
+{
+    let other_leaderboard_id = leaderboard_id + 1;
+    let previous_year = CONFORMANCE_YEAR - 1;
+
+    let leaderboard = conformance_leaderboard(CONFORMANCE_YEAR, leaderboard_id, 1);
+    let previous_year_leaderboard = conformance_leaderboard(previous_year, leaderboard_id, 2);
+    save_success(storage, CONFORMANCE_YEAR, leaderboard_id, &leaderboard).await;
+    save_success(storage, previous_year, leaderboard_id, &previous_year_leaderboard).await;
+    save_error(storage, previous_year, leaderboard_id, conformance_error_kind()).await;
+
+    let previous = load(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(
+        (Some(leaderboard), None),
+        previous,
+        "data saved for a leaderboard must not affect the same leaderboard in another year"
+    );
+
+    let previous = load(storage, previous_year, leaderboard_id).await;
+    assert_eq!(
+        (Some(previous_year_leaderboard), Some(conformance_error_kind())),
+        previous,
+        "data saved for a leaderboard must not affect the same leaderboard in another year"
+    );
+
+    let previous = load(storage, CONFORMANCE_YEAR, other_leaderboard_id).await;
+    assert_eq!(
+        (None, None),
+        previous,
+        "data saved for a leaderboard must not affect another leaderboard in the same year"
+    );
+}
+
+/// Checks that [`list`] returns entries for all leaderboards in storage.
+///
+/// [`list`]: StorageAdmin::list
+pub async fn list_returns_saved_entries<S>(storage: &mut S, leaderboard_id: u64)
+where
+    S: StorageAdmin,
+{
+    assert!(
+        list(storage, leaderboard_id).await.is_empty(),
+        "list must not return entries for a leaderboard that was never saved"
+    );
+
+    let previous_year = CONFORMANCE_YEAR - 1;
+    let leaderboard = conformance_leaderboard(CONFORMANCE_YEAR, leaderboard_id, 1);
+    save_success(storage, CONFORMANCE_YEAR, leaderboard_id, &leaderboard).await;
+    save_error(storage, previous_year, leaderboard_id, conformance_error_kind()).await;
+
+    let entries = list(storage, leaderboard_id).await;
+    assert_eq!(
+        vec![
+            StorageEntry {
+                year: previous_year,
+                leaderboard_id,
+                leaderboard: None,
+                last_error: Some(conformance_error_kind()),
+            },
+            StorageEntry {
+                year: CONFORMANCE_YEAR,
+                leaderboard_id,
+                leaderboard: Some(leaderboard),
+                last_error: None,
+            },
+        ],
+        entries,
+        "list must return one entry per leaderboard and year in storage"
+    );
+}
+
+/// Checks that [`delete`] removes both the leaderboard and the last error.
+///
+/// [`delete`]: StorageAdmin::delete
+pub async fn delete_removes_leaderboard_and_error<S>(storage: &mut S, leaderboard_id: u64)
+where
+    S: StorageAdmin,
+{
+    let previous_year = CONFORMANCE_YEAR - 1;
+    let leaderboard = conformance_leaderboard(CONFORMANCE_YEAR, leaderboard_id, 1);
+    let previous_year_leaderboard = conformance_leaderboard(previous_year, leaderboard_id, 1);
+    save_success(storage, CONFORMANCE_YEAR, leaderboard_id, &leaderboard).await;
+    save_error(storage, CONFORMANCE_YEAR, leaderboard_id, conformance_error_kind()).await;
+    save_success(storage, previous_year, leaderboard_id, &previous_year_leaderboard).await;
+
+    storage
+        .delete(CONFORMANCE_YEAR, leaderboard_id)
+        .await
+        .unwrap_or_else(|err| panic!("delete({CONFORMANCE_YEAR}, {leaderboard_id}) failed: {err}"));
+
+    let previous = load(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(
+        (None, None),
+        previous,
+        "delete must remove both the leaderboard and the last error"
+    );
+
+    let previous = load(storage, previous_year, leaderboard_id).await;
+    assert_eq!(
+        (Some(previous_year_leaderboard), None),
+        previous,
+        "delete must not remove the same leaderboard in another year"
+    );
+}
+
+/// Checks that [`delete`] succeeds for a leaderboard that is not in storage.
+///
+/// [`delete`]: StorageAdmin::delete
+pub async fn delete_without_existing<S>(storage: &mut S, leaderboard_id: u64)
+where
+    S: StorageAdmin,
+{
+    if let Err(err) = storage.delete(CONFORMANCE_YEAR, leaderboard_id).await {
+        panic!("delete must not fail for a leaderboard that is not in storage: {err}");
+    }
+}
+
+/// Checks that [`clear_error`] clears the last error but keeps the leaderboard.
+///
+/// [`clear_error`]: StorageAdmin::clear_error
+pub async fn clear_error_keeps_leaderboard<S>(storage: &mut S, leaderboard_id: u64)
+where
+    S: StorageAdmin,
+{
+    let leaderboard = conformance_leaderboard(CONFORMANCE_YEAR, leaderboard_id, 1);
+    save_success(storage, CONFORMANCE_YEAR, leaderboard_id, &leaderboard).await;
+    save_error(storage, CONFORMANCE_YEAR, leaderboard_id, conformance_error_kind()).await;
+
+    storage
+        .clear_error(CONFORMANCE_YEAR, leaderboard_id)
+        .await
+        .unwrap_or_else(|err| {
+            panic!("clear_error({CONFORMANCE_YEAR}, {leaderboard_id}) failed: {err}")
+        });
+
+    let previous = load(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(
+        (Some(leaderboard), None),
+        previous,
+        "clear_error must clear the last error but keep the leaderboard"
+    );
+}
+
+/// Checks that [`clear_error`] leaves nothing in storage for a leaderboard
+/// that only had a last error.
+///
+/// [`clear_error`]: StorageAdmin::clear_error
+pub async fn clear_error_without_leaderboard<S>(storage: &mut S, leaderboard_id: u64)
+where
+    S: StorageAdmin,
+{
+    save_error(storage, CONFORMANCE_YEAR, leaderboard_id, conformance_error_kind()).await;
+
+    storage
+        .clear_error(CONFORMANCE_YEAR, leaderboard_id)
+        .await
+        .unwrap_or_else(|err| {
+            panic!("clear_error({CONFORMANCE_YEAR}, {leaderboard_id}) failed: {err}")
+        });
+
+    let previous = load(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!((None, None), previous, "clear_error must clear the last error");
+    assert!(
+        list(storage, leaderboard_id).await.is_empty(),
+        "list must not return entries without leaderboard or last error"
+    );
+}
+
+/// Checks that [`clear_error`] succeeds for a leaderboard that is not in storage.
+///
+/// [`clear_error`]: StorageAdmin::clear_error
+pub async fn clear_error_without_existing<S>(storage: &mut S, leaderboard_id: u64)
+where
+    S: StorageAdmin,
+{
+    if let Err(err) = storage.clear_error(CONFORMANCE_YEAR, leaderboard_id).await {
+        panic!("clear_error must not fail for a leaderboard that is not in storage: {err}");
+    }
+}
+
+/// Runs all [`DigestStorage`] conformance checks against the given storage.
+///
+/// Does not run the checks in [`check_storage`]; these need to be run separately.
+pub async fn check_digest_storage<S>(storage: &mut S)
+where
+    S: DigestStorage,
+{
+    let mut leaderboard_id = CONFORMANCE_BASE_LEADERBOARD_ID + 2_000;
+    let mut next_leaderboard_id = || {
+        let id = leaderboard_id;
+        leaderboard_id += 2;
+        id
+    };
+
+    load_pending_changes_without_existing(storage, next_leaderboard_id()).await;
+    save_pending_changes_then_load(storage, next_leaderboard_id()).await;
+    save_pending_changes_overwrites_pending_changes(storage, next_leaderboard_id()).await;
+    pending_changes_are_kept_by_save_success_and_save_error(storage, next_leaderboard_id()).await;
+    clear_pending_changes_keeps_leaderboard(storage, next_leaderboard_id()).await;
+    clear_pending_changes_without_existing(storage, next_leaderboard_id()).await;
+}
+
+/// Checks that [`load_pending_changes`] returns nothing for a leaderboard that was never saved.
+///
+/// [`load_pending_changes`]: DigestStorage::load_pending_changes
+pub async fn load_pending_changes_without_existing<S>(storage: &mut S, leaderboard_id: u64)
+where
+    S: DigestStorage,
+{
+    let pending_changes = load_pending(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(
+        None, pending_changes,
+        "load_pending_changes must not return data for a leaderboard that was never saved"
+    );
+}
+
+/// Checks that [`load_pending_changes`] returns the changes saved by [`save_pending_changes`].
+///
+/// [`load_pending_changes`]: DigestStorage::load_pending_changes
+/// [`save_pending_changes`]: DigestStorage::save_pending_changes
+pub async fn save_pending_changes_then_load<S>(storage: &mut S, leaderboard_id: u64)
+where
+    S: DigestStorage,
+{
+    let leaderboard = conformance_leaderboard(CONFORMANCE_YEAR, leaderboard_id, 1);
+    let pending_changes = conformance_pending_changes(CONFORMANCE_YEAR, leaderboard_id, 1);
+    save_success(storage, CONFORMANCE_YEAR, leaderboard_id, &leaderboard).await;
+    save_pending(storage, CONFORMANCE_YEAR, leaderboard_id, &pending_changes).await;
+
+    let actual = load_pending(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(
+        Some(pending_changes),
+        actual,
+        "load_pending_changes must return the changes saved via save_pending_changes"
+    );
+
+    let previous = load(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(
+        (Some(leaderboard), None),
+        previous,
+        "save_pending_changes must not modify the saved leaderboard"
+    );
+}
+
+/// Checks that [`save_pending_changes`] replaces any previously-saved pending changes.
+///
+/// [`save_pending_changes`]: DigestStorage::save_pending_changes
+pub async fn save_pending_changes_overwrites_pending_changes<S>(
+    storage: &mut S,
+    leaderboard_id: u64,
+) where
+    S: DigestStorage,
+{
+    let leaderboard = conformance_leaderboard(CONFORMANCE_YEAR, leaderboard_id, 1);
+    let first = conformance_pending_changes(CONFORMANCE_YEAR, leaderboard_id, 1);
+    let second = conformance_pending_changes(CONFORMANCE_YEAR, leaderboard_id, 2);
+    save_success(storage, CONFORMANCE_YEAR, leaderboard_id, &leaderboard).await;
+    save_pending(storage, CONFORMANCE_YEAR, leaderboard_id, &first).await;
+    save_pending(storage, CONFORMANCE_YEAR, leaderboard_id, &second).await;
+
+    let actual = load_pending(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(
+        Some(second),
+        actual,
+        "save_pending_changes must replace the previously-saved pending changes"
+    );
+}
+
+/// Checks that neither [`save_success`] nor [`save_error`] clear pending changes.
+///
+/// [`save_success`]: Storage::save_success
+/// [`save_error`]: Storage::save_error
+pub async fn pending_changes_are_kept_by_save_success_and_save_error<S>(
+    storage: &mut S,
+    leaderboard_id: u64,
+) where
+    S: DigestStorage,
+{
+    let first = conformance_leaderboard(CONFORMANCE_YEAR, leaderboard_id, 1);
+    let second = conformance_leaderboard(CONFORMANCE_YEAR, leaderboard_id, 2);
+    let pending_changes = conformance_pending_changes(CONFORMANCE_YEAR, leaderboard_id, 1);
+    save_success(storage, CONFORMANCE_YEAR, leaderboard_id, &first).await;
+    save_pending(storage, CONFORMANCE_YEAR, leaderboard_id, &pending_changes).await;
+
+    save_success(storage, CONFORMANCE_YEAR, leaderboard_id, &second).await;
+    let actual = load_pending(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(
+        Some(&pending_changes),
+        actual.as_ref(),
+        "save_success must not clear pending changes"
+    );
+
+    save_error(storage, CONFORMANCE_YEAR, leaderboard_id, conformance_error_kind()).await;
+    let actual = load_pending(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(Some(pending_changes), actual, "save_error must not clear pending changes");
+}
+
+/// Checks that [`clear_pending_changes`] clears pending changes but keeps the leaderboard.
+///
+/// [`clear_pending_changes`]: DigestStorage::clear_pending_changes
+pub async fn clear_pending_changes_keeps_leaderboard<S>(storage: &mut S, leaderboard_id: u64)
+where
+    S: DigestStorage,
+{
+    let leaderboard = conformance_leaderboard(CONFORMANCE_YEAR, leaderboard_id, 1);
+    let pending_changes = conformance_pending_changes(CONFORMANCE_YEAR, leaderboard_id, 1);
+    save_success(storage, CONFORMANCE_YEAR, leaderboard_id, &leaderboard).await;
+    save_pending(storage, CONFORMANCE_YEAR, leaderboard_id, &pending_changes).await;
+
+    storage
+        .clear_pending_changes(CONFORMANCE_YEAR, leaderboard_id)
+        .await
+        .unwrap_or_else(|err| {
+            panic!("clear_pending_changes({CONFORMANCE_YEAR}, {leaderboard_id}) failed: {err}")
+        });
+
+    let actual = load_pending(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(None, actual, "clear_pending_changes must clear pending changes");
+
+    let previous = load(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(
+        (Some(leaderboard), None),
+        previous,
+        "clear_pending_changes must keep the leaderboard"
+    );
+}
+
+/// Checks that [`clear_pending_changes`] succeeds for a leaderboard that is not in storage.
+///
+/// [`clear_pending_changes`]: DigestStorage::clear_pending_changes
+pub async fn clear_pending_changes_without_existing<S>(storage: &mut S, leaderboard_id: u64)
+where
+    S: DigestStorage,
+{
+    if let Err(err) = storage
+        .clear_pending_changes(CONFORMANCE_YEAR, leaderboard_id)
+        .await
+    {
+        panic!(
+            "clear_pending_changes must not fail for a leaderboard that is not in storage: {err}"
+        );
+    }
+
+    let previous = load(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(
+        (None, None),
+        previous,
+        "clear_pending_changes must not create data for a leaderboard that is not in storage"
+    );
+}
+
+/// Runs all [`OutboxStorage`] conformance checks against the given storage.
+///
+/// Does not run the checks in [`check_storage`]; these need to be run separately.
+pub async fn check_outbox_storage<S>(storage: &mut S)
+where
+    S: OutboxStorage,
+{
+    let mut leaderboard_id = CONFORMANCE_BASE_LEADERBOARD_ID + 3_000;
+    let mut next_leaderboard_id = || {
+        let id = leaderboard_id;
+        leaderboard_id += 2;
+        id
+    };
+
+    load_outbox_without_existing(storage, next_leaderboard_id()).await;
+    save_success_with_outbox_then_load(storage, next_leaderboard_id()).await;
+    save_success_with_outbox_overwrites_outbox(storage, next_leaderboard_id()).await;
+    outbox_is_kept_by_save_success_and_save_error(storage, next_leaderboard_id()).await;
+    mark_delivered_keeps_leaderboard(storage, next_leaderboard_id()).await;
+    mark_delivered_without_existing(storage, next_leaderboard_id()).await;
+}
+
+/// Checks that [`load_outbox`] returns nothing for a leaderboard that was never saved.
+///
+/// [`load_outbox`]: OutboxStorage::load_outbox
+pub async fn load_outbox_without_existing<S>(storage: &mut S, leaderboard_id: u64)
+where
+    S: OutboxStorage,
+{
+    let report = load_outbox(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(
+        None, report,
+        "load_outbox must not return a report for a leaderboard that was never saved"
+    );
+}
+
+/// Checks that [`save_success_with_outbox`] saves both the leaderboard and the report,
+/// and clears the last error.
+///
+/// [`save_success_with_outbox`]: OutboxStorage::save_success_with_outbox
+pub async fn save_success_with_outbox_then_load<S>(storage: &mut S, leaderboard_id: u64)
+where
+    S: OutboxStorage,
+{
+    let first = conformance_leaderboard(CONFORMANCE_YEAR, leaderboard_id, 1);
+    let second = conformance_leaderboard(CONFORMANCE_YEAR, leaderboard_id, 2);
+    let report = conformance_outbox_report(CONFORMANCE_YEAR, leaderboard_id, 1);
+    save_success(storage, CONFORMANCE_YEAR, leaderboard_id, &first).await;
+    save_error(storage, CONFORMANCE_YEAR, leaderboard_id, conformance_error_kind()).await;
+    save_success_with_outbox(storage, CONFORMANCE_YEAR, leaderboard_id, &second, &report).await;
+
+    let previous = load(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(
+        (Some(second), None),
+        previous,
+        "save_success_with_outbox must save the leaderboard and clear the last error"
+    );
+
+    let actual = load_outbox(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(
+        Some(report),
+        actual,
+        "load_outbox must return the report saved via save_success_with_outbox"
+    );
+}
+
+/// Checks that [`save_success_with_outbox`] replaces any report already in the outbox.
+///
+/// [`save_success_with_outbox`]: OutboxStorage::save_success_with_outbox
+pub async fn save_success_with_outbox_overwrites_outbox<S>(storage: &mut S, leaderboard_id: u64)
+where
+    S: OutboxStorage,
+{
+    let leaderboard = conformance_leaderboard(CONFORMANCE_YEAR, leaderboard_id, 1);
+    let first = conformance_outbox_report(CONFORMANCE_YEAR, leaderboard_id, 1);
+    let second = OutboxReport::FirstRun;
+    save_success_with_outbox(storage, CONFORMANCE_YEAR, leaderboard_id, &leaderboard, &first).await;
+    save_success_with_outbox(storage, CONFORMANCE_YEAR, leaderboard_id, &leaderboard, &second)
+        .await;
+
+    let actual = load_outbox(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(
+        Some(second),
+        actual,
+        "save_success_with_outbox must replace the report already in the outbox"
+    );
+}
+
+/// Checks that neither [`save_success`] nor [`save_error`] clear the outbox.
+///
+/// [`save_success`]: Storage::save_success
+/// [`save_error`]: Storage::save_error
+pub async fn outbox_is_kept_by_save_success_and_save_error<S>(storage: &mut S, leaderboard_id: u64)
+where
+    S: OutboxStorage,
+{
+    let first = conformance_leaderboard(CONFORMANCE_YEAR, leaderboard_id, 1);
+    let second = conformance_leaderboard(CONFORMANCE_YEAR, leaderboard_id, 2);
+    let report = conformance_outbox_report(CONFORMANCE_YEAR, leaderboard_id, 1);
+    save_success_with_outbox(storage, CONFORMANCE_YEAR, leaderboard_id, &first, &report).await;
+
+    save_error(storage, CONFORMANCE_YEAR, leaderboard_id, conformance_error_kind()).await;
+    let actual = load_outbox(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(Some(&report), actual.as_ref(), "save_error must not clear the outbox");
+
+    save_success(storage, CONFORMANCE_YEAR, leaderboard_id, &second).await;
+    let actual = load_outbox(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(Some(report), actual, "save_success must not clear the outbox");
+}
+
+/// Checks that [`mark_delivered`] clears the outbox but keeps the leaderboard.
+///
+/// [`mark_delivered`]: OutboxStorage::mark_delivered
+pub async fn mark_delivered_keeps_leaderboard<S>(storage: &mut S, leaderboard_id: u64)
+where
+    S: OutboxStorage,
+{
+    let leaderboard = conformance_leaderboard(CONFORMANCE_YEAR, leaderboard_id, 1);
+    let report = conformance_outbox_report(CONFORMANCE_YEAR, leaderboard_id, 1);
+    save_success_with_outbox(storage, CONFORMANCE_YEAR, leaderboard_id, &leaderboard, &report)
+        .await;
+
+    mark_delivered(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+
+    let actual = load_outbox(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(None, actual, "mark_delivered must clear the outbox");
+
+    let previous = load(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!((Some(leaderboard), None), previous, "mark_delivered must keep the leaderboard");
+}
+
+/// Checks that [`mark_delivered`] succeeds for a leaderboard that is not in storage.
+///
+/// [`mark_delivered`]: OutboxStorage::mark_delivered
+pub async fn mark_delivered_without_existing<S>(storage: &mut S, leaderboard_id: u64)
+where
+    S: OutboxStorage,
+{
+    if let Err(err) = storage
+        .mark_delivered(CONFORMANCE_YEAR, leaderboard_id)
+        .await
+    {
+        panic!("mark_delivered must not fail for a leaderboard that is not in storage: {err}");
+    }
+
+    let previous = load(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(
+        (None, None),
+        previous,
+        "mark_delivered must not create data for a leaderboard that is not in storage"
+    );
+}
+
+/// Runs all conformance checks for [`load_error_state`] against the given storage.
+///
+/// Since [`load_error_state`] has a default implementation that does not keep track of errors,
+/// these checks only apply to storages that implement it. Does not run the checks in
+/// [`check_storage`]; these need to be run separately.
+///
+/// [`load_error_state`]: Storage::load_error_state
+pub async fn check_error_state<S>(storage: &mut S)
+where
+    S: Storage,
+{
+    let mut leaderboard_id = CONFORMANCE_BASE_LEADERBOARD_ID + 4_000;
+    let mut next_leaderboard_id = || {
+        let id = leaderboard_id;
+        leaderboard_id += 2;
+        id
+    };
+
+    load_error_state_without_error(storage, next_leaderboard_id()).await;
+    save_error_sets_error_state(storage, next_leaderboard_id()).await;
+    save_error_keeps_first_seen(storage, next_leaderboard_id()).await;
+    save_success_clears_error_state(storage, next_leaderboard_id()).await;
+    save_error_state_sets_error_state(storage, next_leaderboard_id()).await;
+    save_error_after_save_error_state(storage, next_leaderboard_id()).await;
+}
+
+/// Checks that [`load_error_state`] returns nothing when there is no last error.
+///
+/// [`load_error_state`]: Storage::load_error_state
+pub async fn load_error_state_without_error<S>(storage: &mut S, leaderboard_id: u64)
+where
+    S: Storage,
+{
+    let actual = load_error_state(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(
+        None, actual,
+        "load_error_state must not return data for a leaderboard that was never saved"
+    );
+
+    let leaderboard = conformance_leaderboard(CONFORMANCE_YEAR, leaderboard_id, 1);
+    save_success(storage, CONFORMANCE_YEAR, leaderboard_id, &leaderboard).await;
+
+    let actual = load_error_state(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(None, actual, "load_error_state must not return data when there is no last error");
+}
+
+/// Checks that [`load_error_state`] returns the error saved by [`save_error`], along with
+/// the time at which it was saved.
+///
+/// [`load_error_state`]: Storage::load_error_state
+/// [`save_error`]: Storage::save_error
+pub async fn save_error_sets_error_state<S>(storage: &mut S, leaderboard_id: u64)
+where
+    S: Storage,
+{
+    let before = Utc::now();
+    save_error(storage, CONFORMANCE_YEAR, leaderboard_id, conformance_error_kind()).await;
+    let after = Utc::now();
+
+    let actual = load_error_state(storage, CONFORMANCE_YEAR, leaderboard_id)
+        .await
+        .expect("load_error_state must return the error saved via save_error");
+    assert_eq!(conformance_error_kind(), actual.error_kind);
+    assert!(
+        before <= actual.first_seen && actual.first_seen <= after,
+        "first_seen must be the time at which save_error was called (expected between {before} and {after}, got {})",
+        actual.first_seen
+    );
+    assert_eq!(
+        ErrorState::new(conformance_error_kind(), actual.first_seen),
+        actual,
+        "load_error_state must return a single occurrence of the error saved via save_error"
+    );
+}
+
+/// Checks that [`save_error`] keeps the time at which the first error was saved
+/// when called repeatedly, even if the kind of error changes, and counts occurrences.
+///
+/// [`save_error`]: Storage::save_error
+pub async fn save_error_keeps_first_seen<S>(storage: &mut S, leaderboard_id: u64)
+where
+    S: Storage,
+{
+    save_error(storage, CONFORMANCE_YEAR, leaderboard_id, ErrorKind::MissingField).await;
+    let first = load_error_state(storage, CONFORMANCE_YEAR, leaderboard_id)
+        .await
+        .expect("load_error_state must return the error saved via save_error");
+
+    save_error(storage, CONFORMANCE_YEAR, leaderboard_id, conformance_error_kind()).await;
+    let actual = load_error_state(storage, CONFORMANCE_YEAR, leaderboard_id)
+        .await
+        .expect("load_error_state must return the error saved via save_error");
+    assert_eq!(
+        first.first_seen, actual.first_seen,
+        "save_error must keep the time of the first error"
+    );
+    assert_eq!(
+        conformance_error_kind(),
+        actual.error_kind,
+        "save_error must update the error kind"
+    );
+    assert!(
+        actual.last_seen >= first.last_seen,
+        "save_error must update the time of the last error"
+    );
+    assert_eq!(2, actual.occurrences, "save_error must count occurrences of errors");
+}
+
+/// Checks that [`save_success`] clears the error state, so that the next error
+/// starts a new outage.
+///
+/// [`save_success`]: Storage::save_success
+pub async fn save_success_clears_error_state<S>(storage: &mut S, leaderboard_id: u64)
+where
+    S: Storage,
+{
+    let leaderboard = conformance_leaderboard(CONFORMANCE_YEAR, leaderboard_id, 1);
+    save_error(storage, CONFORMANCE_YEAR, leaderboard_id, conformance_error_kind()).await;
+    let first = load_error_state(storage, CONFORMANCE_YEAR, leaderboard_id)
+        .await
+        .expect("load_error_state must return the error saved via save_error");
+    save_success(storage, CONFORMANCE_YEAR, leaderboard_id, &leaderboard).await;
+
+    let actual = load_error_state(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(None, actual, "save_success must clear the error state");
+
+    save_error(storage, CONFORMANCE_YEAR, leaderboard_id, conformance_error_kind()).await;
+    let actual = load_error_state(storage, CONFORMANCE_YEAR, leaderboard_id)
+        .await
+        .expect("load_error_state must return the error saved via save_error");
+    assert!(
+        actual.first_seen >= first.first_seen,
+        "save_error must start a new outage after save_success"
+    );
+}
+
+/// Checks that [`load_error_state`] returns the error state saved by [`save_error_state`],
+/// and that [`load_previous`] returns its error kind.
+///
+/// [`load_error_state`]: Storage::load_error_state
+/// [`save_error_state`]: Storage::save_error_state
+/// [`load_previous`]: Storage::load_previous
+pub async fn save_error_state_sets_error_state<S>(storage: &mut S, leaderboard_id: u64)
+where
+    S: Storage,
+{
+    let leaderboard = conformance_leaderboard(CONFORMANCE_YEAR, leaderboard_id, 1);
+    save_success(storage, CONFORMANCE_YEAR, leaderboard_id, &leaderboard).await;
+
+    let error_state = conformance_error_state();
+    save_error_state(storage, CONFORMANCE_YEAR, leaderboard_id, &error_state).await;
+
+    let previous = load(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(
+        (Some(leaderboard), Some(conformance_error_kind())),
+        previous,
+        "save_error_state must save the error kind but not clear leaderboard data"
+    );
+
+    let actual = load_error_state(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(
+        Some(error_state.clone()),
+        actual,
+        "load_error_state must return the error state saved via save_error_state"
+    );
+
+    let error_state = ErrorState {
+        last_message: None,
+        last_notified: None,
+        ..error_state.recur(ErrorKind::MissingField, Utc::now())
+    };
+    save_error_state(storage, CONFORMANCE_YEAR, leaderboard_id, &error_state).await;
+
+    let actual = load_error_state(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(
+        Some(error_state),
+        actual,
+        "save_error_state must replace the previous error state, including optional fields"
+    );
+}
+
+/// Checks that [`save_error`] updates an error state saved by [`save_error_state`].
+///
+/// [`save_error`]: Storage::save_error
+/// [`save_error_state`]: Storage::save_error_state
+pub async fn save_error_after_save_error_state<S>(storage: &mut S, leaderboard_id: u64)
+where
+    S: Storage,
+{
+    let error_state = conformance_error_state();
+    save_error_state(storage, CONFORMANCE_YEAR, leaderboard_id, &error_state).await;
+    save_error(storage, CONFORMANCE_YEAR, leaderboard_id, ErrorKind::MissingField).await;
+
+    let actual = load_error_state(storage, CONFORMANCE_YEAR, leaderboard_id)
+        .await
+        .expect("load_error_state must return the error saved via save_error");
+    assert_eq!(ErrorKind::MissingField, actual.error_kind, "save_error must update the error kind");
+    assert_eq!(
+        error_state.first_seen, actual.first_seen,
+        "save_error must keep the time of the first error"
+    );
+    assert!(
+        actual.last_seen > error_state.last_seen,
+        "save_error must update the time of the last error"
+    );
+    assert_eq!(
+        error_state.occurrences + 1,
+        actual.occurrences,
+        "save_error must count occurrences of errors"
+    );
+    assert_eq!(
+        None, actual.last_message,
+        "save_error must clear the message of the previous error"
+    );
+    assert_eq!(
+        error_state.last_notified, actual.last_notified,
+        "save_error must keep the time at which the error was last reported"
+    );
+}
+
+/// Runs all conformance checks for [`load_heartbeat`] and [`save_heartbeat`] against the given
+/// storage.
+///
+/// Since these methods have default implementations that do not persist anything, these checks
+/// only apply to storages that implement them. Does not run the checks in [`check_storage`];
+/// these need to be run separately.
+///
+/// [`load_heartbeat`]: Storage::load_heartbeat
+/// [`save_heartbeat`]: Storage::save_heartbeat
+pub async fn check_heartbeat<S>(storage: &mut S)
+where
+    S: Storage,
+{
+    let mut leaderboard_id = CONFORMANCE_BASE_LEADERBOARD_ID + 5_000;
+    let mut next_leaderboard_id = || {
+        let id = leaderboard_id;
+        leaderboard_id += 2;
+        id
+    };
+
+    load_heartbeat_without_existing(storage, next_leaderboard_id()).await;
+    save_heartbeat_then_load(storage, next_leaderboard_id()).await;
+    save_heartbeat_overwrites_heartbeat(storage, next_leaderboard_id()).await;
+    heartbeat_is_kept_by_save_success_and_save_error(storage, next_leaderboard_id()).await;
+}
+
+/// Checks that [`load_heartbeat`] returns nothing for a leaderboard without heartbeat,
+/// even if leaderboard data was saved.
+///
+/// [`load_heartbeat`]: Storage::load_heartbeat
+pub async fn load_heartbeat_without_existing<S>(storage: &mut S, leaderboard_id: u64)
+where
+    S: Storage,
+{
+    let actual = load_heartbeat(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(
+        None, actual,
+        "load_heartbeat must not return data for a leaderboard that was never saved"
+    );
+
+    let leaderboard = conformance_leaderboard(CONFORMANCE_YEAR, leaderboard_id, 1);
+    save_success(storage, CONFORMANCE_YEAR, leaderboard_id, &leaderboard).await;
+
+    let actual = load_heartbeat(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(
+        None, actual,
+        "load_heartbeat must not return data for a leaderboard without heartbeat"
+    );
+}
+
+/// Checks that [`load_heartbeat`] returns the heartbeat saved by [`save_heartbeat`], and that
+/// saving a heartbeat does not create leaderboard data.
+///
+/// [`load_heartbeat`]: Storage::load_heartbeat
+/// [`save_heartbeat`]: Storage::save_heartbeat
+pub async fn save_heartbeat_then_load<S>(storage: &mut S, leaderboard_id: u64)
+where
+    S: Storage,
+{
+    let heartbeat = conformance_heartbeat(1);
+    save_heartbeat(storage, CONFORMANCE_YEAR, leaderboard_id, &heartbeat).await;
+
+    let actual = load_heartbeat(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(
+        Some(heartbeat),
+        actual,
+        "load_heartbeat must return the heartbeat saved via save_heartbeat"
+    );
+
+    let previous = load(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!((None, None), previous, "save_heartbeat must not create leaderboard data");
+
+    let actual = load_heartbeat(storage, CONFORMANCE_YEAR - 1, leaderboard_id).await;
+    assert_eq!(None, actual, "heartbeats must be stored separately for each year");
+}
+
+/// Checks that [`save_heartbeat`] replaces the previous heartbeat, including optional fields.
+///
+/// [`save_heartbeat`]: Storage::save_heartbeat
+pub async fn save_heartbeat_overwrites_heartbeat<S>(storage: &mut S, leaderboard_id: u64)
+where
+    S: Storage,
+{
+    save_heartbeat(storage, CONFORMANCE_YEAR, leaderboard_id, &conformance_heartbeat(1)).await;
+
+    let heartbeat =
+        HeartbeatState { last_report: None, stale_reported: None, ..conformance_heartbeat(2) };
+    save_heartbeat(storage, CONFORMANCE_YEAR, leaderboard_id, &heartbeat).await;
+
+    let actual = load_heartbeat(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(
+        Some(heartbeat),
+        actual,
+        "save_heartbeat must replace the previous heartbeat, including optional fields"
+    );
+}
+
+/// Checks that [`save_success`] and [`save_error`] do not clear the heartbeat, and that
+/// [`save_heartbeat`] does not clear leaderboard data.
+///
+/// [`save_success`]: Storage::save_success
+/// [`save_error`]: Storage::save_error
+/// [`save_heartbeat`]: Storage::save_heartbeat
+pub async fn heartbeat_is_kept_by_save_success_and_save_error<S>(
+    storage: &mut S,
+    leaderboard_id: u64,
+) where
+    S: Storage,
+{
+    let heartbeat = conformance_heartbeat(1);
+    save_heartbeat(storage, CONFORMANCE_YEAR, leaderboard_id, &heartbeat).await;
+
+    let leaderboard = conformance_leaderboard(CONFORMANCE_YEAR, leaderboard_id, 1);
+    save_success(storage, CONFORMANCE_YEAR, leaderboard_id, &leaderboard).await;
+    let actual = load_heartbeat(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(Some(heartbeat.clone()), actual, "save_success must not clear the heartbeat");
+
+    save_error(storage, CONFORMANCE_YEAR, leaderboard_id, conformance_error_kind()).await;
+    let actual = load_heartbeat(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(Some(heartbeat.clone()), actual, "save_error must not clear the heartbeat");
+
+    save_heartbeat(storage, CONFORMANCE_YEAR, leaderboard_id, &conformance_heartbeat(2)).await;
+    let previous = load(storage, CONFORMANCE_YEAR, leaderboard_id).await;
+    assert_eq!(
+        (Some(leaderboard), Some(conformance_error_kind())),
+        previous,
+        "save_heartbeat must not clear leaderboard data"
+    );
+}
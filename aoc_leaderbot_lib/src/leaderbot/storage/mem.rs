@@ -3,10 +3,15 @@
 use std::collections::HashMap;
 
 use aoc_leaderboard::aoc::Leaderboard;
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
 
 use crate::ErrorKind;
-use crate::leaderbot::Storage;
+use crate::leaderbot::heartbeat::HeartbeatState;
+use crate::leaderbot::{
+    DigestStorage, ErrorState, OutboxReport, OutboxStorage, PendingChanges, Storage, StorageAdmin,
+    StorageEntry,
+};
 
 /// Bot storage that keeps data in memory.
 ///
@@ -14,6 +19,14 @@ use crate::leaderbot::Storage;
 #[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MemoryStorage {
     previous: HashMap<(i32, u64), (Option<Leaderboard>, Option<ErrorKind>)>,
+    #[serde(default)]
+    pending_changes: HashMap<(i32, u64), PendingChanges>,
+    #[serde(default)]
+    outbox: HashMap<(i32, u64), OutboxReport>,
+    #[serde(default)]
+    error_states: HashMap<(i32, u64), ErrorState>,
+    #[serde(default)]
+    heartbeats: HashMap<(i32, u64), HeartbeatState>,
 }
 
 impl MemoryStorage {
@@ -57,6 +70,7 @@ impl Storage for MemoryStorage {
     ) -> Result<(), Self::Err> {
         self.previous
             .insert((year, leaderboard_id), (Some(leaderboard.clone()), None));
+        self.error_states.remove(&(year, leaderboard_id));
 
         Ok(())
     }
@@ -71,6 +85,170 @@ impl Storage for MemoryStorage {
         let (_, prev_err) = self.previous.entry((year, leaderboard_id)).or_default();
         *prev_err = Some(error_kind);
 
+        let now = Utc::now();
+        let error_state = match self.error_states.remove(&(year, leaderboard_id)) {
+            Some(error_state) => error_state.recur(error_kind, now),
+            None => ErrorState::new(error_kind, now),
+        };
+        self.error_states
+            .insert((year, leaderboard_id), error_state);
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self), ret, err))]
+    async fn save_error_state(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        error_state: &ErrorState,
+    ) -> Result<(), Self::Err> {
+        let (_, prev_err) = self.previous.entry((year, leaderboard_id)).or_default();
+        *prev_err = Some(error_state.error_kind);
+        self.error_states
+            .insert((year, leaderboard_id), error_state.clone());
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self), ret, err))]
+    async fn load_error_state(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> Result<Option<ErrorState>, Self::Err> {
+        Ok(self.error_states.get(&(year, leaderboard_id)).cloned())
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self), ret, err))]
+    async fn load_heartbeat(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> Result<Option<HeartbeatState>, Self::Err> {
+        Ok(self.heartbeats.get(&(year, leaderboard_id)).cloned())
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self), ret, err))]
+    async fn save_heartbeat(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        heartbeat: &HeartbeatState,
+    ) -> Result<(), Self::Err> {
+        self.heartbeats
+            .insert((year, leaderboard_id), heartbeat.clone());
+
+        Ok(())
+    }
+}
+
+impl StorageAdmin for MemoryStorage {
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self), ret, err))]
+    async fn list(&self) -> Result<Vec<StorageEntry>, Self::Err> {
+        Ok(self
+            .previous
+            .iter()
+            .map(|(&(year, leaderboard_id), (leaderboard, last_error))| StorageEntry {
+                year,
+                leaderboard_id,
+                leaderboard: leaderboard.clone(),
+                last_error: *last_error,
+            })
+            .collect())
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self), ret, err))]
+    async fn delete(&mut self, year: i32, leaderboard_id: u64) -> Result<(), Self::Err> {
+        self.previous.remove(&(year, leaderboard_id));
+        self.pending_changes.remove(&(year, leaderboard_id));
+        self.outbox.remove(&(year, leaderboard_id));
+        self.error_states.remove(&(year, leaderboard_id));
+        self.heartbeats.remove(&(year, leaderboard_id));
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self), ret, err))]
+    async fn clear_error(&mut self, year: i32, leaderboard_id: u64) -> Result<(), Self::Err> {
+        self.error_states.remove(&(year, leaderboard_id));
+        if let Some((leaderboard, prev_err)) = self.previous.get_mut(&(year, leaderboard_id)) {
+            *prev_err = None;
+
+            if leaderboard.is_none() {
+                self.previous.remove(&(year, leaderboard_id));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl DigestStorage for MemoryStorage {
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self), ret, err))]
+    async fn load_pending_changes(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> Result<Option<PendingChanges>, Self::Err> {
+        Ok(self.pending_changes.get(&(year, leaderboard_id)).cloned())
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self, pending_changes), ret, err))]
+    async fn save_pending_changes(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        pending_changes: &PendingChanges,
+    ) -> Result<(), Self::Err> {
+        self.pending_changes
+            .insert((year, leaderboard_id), pending_changes.clone());
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self), ret, err))]
+    async fn clear_pending_changes(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> Result<(), Self::Err> {
+        self.pending_changes.remove(&(year, leaderboard_id));
+
+        Ok(())
+    }
+}
+
+impl OutboxStorage for MemoryStorage {
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self), ret, err))]
+    async fn load_outbox(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+    ) -> Result<Option<OutboxReport>, Self::Err> {
+        Ok(self.outbox.get(&(year, leaderboard_id)).cloned())
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self, leaderboard, report), ret, err))]
+    async fn save_success_with_outbox(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        leaderboard: &Leaderboard,
+        report: &OutboxReport,
+    ) -> Result<(), Self::Err> {
+        self.previous
+            .insert((year, leaderboard_id), (Some(leaderboard.clone()), None));
+        self.error_states.remove(&(year, leaderboard_id));
+        self.outbox.insert((year, leaderboard_id), report.clone());
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self), ret, err))]
+    async fn mark_delivered(&mut self, year: i32, leaderboard_id: u64) -> Result<(), Self::Err> {
+        self.outbox.remove(&(year, leaderboard_id));
+
         Ok(())
     }
 }
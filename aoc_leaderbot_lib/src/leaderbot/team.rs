@@ -0,0 +1,115 @@
+//! Team standings computed from a leaderboard.
+//!
+//! Members of a leaderboard can be assigned to named [`Team`]s via the bot's [`Config`]. The
+//! [`team_standings`] function can then be used to compute aggregates for each team, so that
+//! teams (e.g. departments of a company) can compete against each other.
+//!
+//! [`Config`]: crate::leaderbot::Config
+
+use std::cmp::Ordering;
+
+use aoc_leaderboard::aoc::{Leaderboard, LeaderboardMember};
+use serde::{Deserialize, Serialize};
+
+use crate::leaderbot::event::puzzle_unlock_times;
+
+/// Named group of leaderboard members.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Team {
+    /// Name of the team.
+    pub name: String,
+
+    /// IDs of the [members] of the team.
+    ///
+    /// [members]: LeaderboardMember::id
+    #[serde(default)]
+    pub members: Vec<u64>,
+}
+
+impl Team {
+    /// Creates a new team.
+    pub fn new<N, M>(name: N, members: M) -> Self
+    where
+        N: Into<String>,
+        M: IntoIterator<Item = u64>,
+    {
+        Self { name: name.into(), members: members.into_iter().collect() }
+    }
+}
+
+/// Aggregated stats of a [`Team`] in a leaderboard.
+///
+/// Returned by [`team_standings`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TeamStanding {
+    /// Name of the team.
+    pub name: String,
+
+    /// Number of team members found in the leaderboard.
+    pub members: usize,
+
+    /// Total number of stars obtained by team members.
+    pub total_stars: u32,
+
+    /// Sum of the [local scores] of team members.
+    ///
+    /// [local scores]: LeaderboardMember::local_score
+    pub total_local_score: u64,
+
+    /// Number of team members who obtained every star of the event.
+    pub members_finished: usize,
+}
+
+impl TeamStanding {
+    /// Returns the average [local score] of team members, or `0.0` if the team has no members
+    /// in the leaderboard.
+    ///
+    /// [local score]: LeaderboardMember::local_score
+    pub fn average_local_score(&self) -> f64 {
+        match self.members {
+            0 => 0.0,
+            members => self.total_local_score as f64 / members as f64,
+        }
+    }
+
+    fn cmp_standing(&self, other: &Self) -> Ordering {
+        other
+            .total_stars
+            .cmp(&self.total_stars)
+            .then_with(|| other.total_local_score.cmp(&self.total_local_score))
+            .then_with(|| self.name.cmp(&other.name))
+    }
+}
+
+/// Computes the standings of the given teams in a leaderboard.
+///
+/// Team members who are not found in the leaderboard are ignored. Standings are returned sorted
+/// by total number of stars, then by total local score (both descending).
+pub fn team_standings(leaderboard: &Leaderboard, teams: &[Team]) -> Vec<TeamStanding> {
+    let max_stars = (puzzle_unlock_times(leaderboard.year).count() * 2) as u32;
+
+    let mut standings: Vec<_> = teams
+        .iter()
+        .map(|team| {
+            let members: Vec<&LeaderboardMember> = team
+                .members
+                .iter()
+                .filter_map(|id| leaderboard.members.get(id))
+                .collect();
+
+            TeamStanding {
+                name: team.name.clone(),
+                members: members.len(),
+                total_stars: members.iter().map(|member| member.stars).sum(),
+                total_local_score: members.iter().map(|member| member.local_score).sum(),
+                members_finished: members
+                    .iter()
+                    .filter(|member| member.stars >= max_stars)
+                    .count(),
+            }
+        })
+        .collect();
+
+    standings.sort_by(TeamStanding::cmp_standing);
+    standings
+}
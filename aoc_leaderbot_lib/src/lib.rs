@@ -17,7 +17,8 @@
 //!
 //! This trait is used by the bot to fetch information about what AoC leaderboard to watch.
 //! It is a read-only trait providing three pieces of information: the [leaderboard ID], the
-//! [AoC credentials] and the [year]. The latter defaults to the current year.
+//! [AoC credentials] and the [year]. The latter defaults to the year of the current Advent of Code
+//! event, switching to the new year on December 1st.
 //!
 //! ### [`Storage`]
 //!
@@ -33,14 +34,121 @@
 //!
 //! The reporter can also be used to report any error occurring during bot execution (ex: expired
 //! AoC session token, etc.) via its [`report_error`] method, as well as report the first bot run
-//! via its [`report_first_run`] method.
+//! via its [`report_first_run`] method. Once the bot runs successfully after an error, it calls
+//! the reporter's [`report_recovery`] method, including how long the outage lasted if the storage
+//! keeps track of it (see [`Storage::load_error_state`]). By default, an error that keeps
+//! occurring is only reported once; the config's [`renotify_policy`] can be used to report it
+//! again after some time or every few failed runs.
+//!
+//! ### Digest mode
+//!
+//! To avoid posting a message every time the bot runs, the bot can also be run via
+//! [`run_bot_digest`]. In digest mode, changes are merged with the ones detected during previous
+//! runs and persisted in storage, then reported as a single consolidated report at the flush
+//! times of a [`DigestSchedule`] (for example, at 08:00 and 18:00 local time). This requires a
+//! storage implementing [`DigestStorage`].
+//!
+//! ### Exactly-once reporting
+//!
+//! [`run_bot`] reports changes before saving the current leaderboard, so a storage failure can
+//! cause the same changes to be reported twice. To avoid this, the bot can be run via
+//! [`run_bot_outbox`] with a storage implementing [`OutboxStorage`]: the report is saved in an
+//! outbox alongside the current leaderboard in a single write, then delivered and marked as
+//! delivered. Reports that could not be delivered are retried during the next bot run.
+//!
+//! ### Run diagnostics
+//!
+//! The [`BotOutput`] returned by a bot run includes [`RunDiagnostics`]: the latency and HTTP status
+//! of every leaderboard fetch, the [`Storage`] operations and [`Reporter`] calls made (with their
+//! duration and outcome) and whether the bot recovered from a previous error (and whether that
+//! error had been reported). They can be logged to debug a bot run without enabling trace logs.
+//! To get them when a bot run fails, use [`run_bot_diagnosed`]: the returned [`RunError`] includes
+//! the diagnostics collected up to the failure.
+//!
+//! ### Metrics
+//!
+//! Required feature: `metrics`
+//!
+//! [`BotMetrics`] exports Prometheus metrics about bot runs: the number of runs, fetch errors and
+//! reporter failures, leaderboard fetch latency as well as each member's stars and local score.
+//! After each bot run, pass its result to [`observe`]; metrics can then be served over HTTP via
+//! [`serve`] or pushed to a Prometheus Pushgateway via [`push`].
+//!
+//! ### Daemon mode
+//!
+//! Required feature: `daemon`
+//!
+//! [`run_bot`] performs a single bot run, leaving scheduling to the caller. To deploy the bot as
+//! a long-running process (in a container or a VM, for example), it can instead be run via
+//! [`run_bot_forever`], which runs the bot in a loop following a [`DaemonSchedule`] (never more
+//! often than every 15 minutes, with extra runs shortly after each puzzle unlocks) until the
+//! process receives `SIGTERM`.
+//!
+//! ### Event window
+//!
+//! Outside of the Advent of Code event, leaderboards rarely change. If a [`Config`] returns an
+//! [`EventWindow`] (the event's days plus a grace period), bot runs skip fetching leaderboard data
+//! outside that window (returning `None`), or only fetch it once a day. In daemon mode, runs are
+//! postponed until the next time data should be fetched.
+//!
+//! ### Heartbeat monitoring
+//!
+//! If bot runs stop being scheduled, nothing gets reported and nobody notices. If a [`Config`]
+//! returns a [`HeartbeatPolicy`], the bot records each successful run in its [`Storage`];
+//! [`check_heartbeat`], called on a separate schedule, then calls the reporter's [`report_stale`]
+//! method when the bot has not succeeded for too long during the event. The policy can also ask
+//! the bot to call [`report_heartbeat`] when nothing has been reported for a while, so that users
+//! know the bot is still watching.
+//!
+//! ### Clock
+//!
+//! Time-dependent logic (the default [year], the event window, error states, digests, quiet
+//! hours) gets the current time from the [`Clock`] returned by the [`Config`], which defaults to
+//! the system clock. Using a [`TestClock`] makes it possible to test behaviour around the start
+//! of the event or the new year deterministically.
+//!
+//! ### Multiple leaderboards
+//!
+//! Required feature: `multi-target`
+//!
+//! To monitor several leaderboards (or years) in a single run, a [`MultiTargetRunner`] can be
+//! given a list of targets, each with its own [`Config`] and [`Reporter`]. Targets share the same
+//! [`Storage`] and are run concurrently (up to a configurable limit); an error occurring for one
+//! target does not prevent others from running. The runner also makes sure leaderboards are not
+//! fetched more often than every 15 minutes.
+//!
+//! ### Merged leaderboards
+//!
+//! Private leaderboards are limited to 200 members. To monitor a single ranking spread across
+//! several private leaderboards, a [`Config`] can return [`merged_leaderboards`]: the bot then
+//! fetches every leaderboard and [merges] them into one, where each member appears once and local
+//! scores are recomputed over the combined membership. The merged leaderboard is stored and
+//! reported on as if it was the main leaderboard.
+//!
+//! ### Team standings
+//!
+//! Leaderboard members can be assigned to named [`Team`]s through the [`Config`]'s [`teams`].
+//! The [`team_standings`] function computes aggregates for each team (total stars, total and
+//! average local score, number of members who finished the event); reporters can use them to
+//! let teams compete against each other.
+//!
+//! ### Member settings
+//!
+//! A [`Config`] can return [`member_settings`] to give aliases to members (useful for anonymous
+//! members), ignore some members (e.g. bots or test accounts) or remove the names of members who
+//! opted out. The settings are applied to leaderboards before changes are detected, so they
+//! affect storage and every [`Reporter`].
+//!
+//! When reports are posted in public places, member settings can also enable privacy mode, which
+//! replaces the names of all members with stable [pseudonyms] like `brave-otter`. Stars and
+//! scores are left untouched.
 //!
 //! ## Concrete implementations
 //!
 //! Although this library includes the bot's core function, it does not provide all possible
 //! implementations of the traits it needs for operations. This library includes two implementations
-//! of [`Config`], one implementation of [`Storage`] and no implementation of [`Reporter`]. Users
-//! will thus need to implement a [`Reporter`] at a minimum.
+//! of [`Config`], one implementation of [`Storage`] and no implementation of [`Reporter`] that can
+//! report to users. Users will thus need to implement a [`Reporter`] at a minimum.
 //!
 //! For other trait implementations, you can look at related crates like [`aoc_leaderbot_slack_lib`].
 //!
@@ -59,6 +167,25 @@
 //! environment variables.  This is possibly the most flexible way of providing the bot its
 //! config, which is why it is enabled by default.
 //!
+//! ### [`ConfigFile`]
+//!
+//! Required feature: `config-file`
+//!
+//! This type loads the configs of one or more leaderboards from a TOML or YAML file, along with
+//! reporter-specific settings. Secrets can be kept out of the file by referencing environment
+//! variables (e.g. `${AOC_SESSION}`), and invalid files are reported with the line or field
+//! where the problem was found.
+//!
+//! ### Credentials providers
+//!
+//! Required features: `credentials-file` (enabled by `config-env`), `credentials-command`
+//!
+//! To avoid storing AoC credentials in the [`Config`] itself, they can be fetched from a
+//! [`CredentialsProvider`] via [`provide_credentials`]. The [`credentials`] module includes
+//! providers reading credentials from a file (like a Docker or Kubernetes secret) or from the
+//! output of a command (like a password manager). [`get_env_config`] can also read credentials
+//! from a file through the `{prefix}VIEW_KEY_FILE` and `{prefix}SESSION_COOKIE_FILE` variables.
+//!
 //! ### [`MemoryStorage`]
 //!
 //! Required feature: `storage-mem`
@@ -67,6 +194,63 @@
 //! it would technically lose its data upon program exit, the whole storage can be persisted using
 //! [`serde`], which means it's a possibly-decent implementation.
 //!
+//! ### [`MultiReporter`]
+//!
+//! Required feature: `reporter-multi`
+//!
+//! This implementation of [`Reporter`] forwards every call to several child reporters
+//! concurrently, which makes it possible to report changes to more than one channel. A
+//! [`FailurePolicy`] determines whether a call fails when some of the children fail.
+//!
+//! ### Reporter middleware
+//!
+//! Required feature: `reporter-middleware`
+//!
+//! The [`middleware`] module includes implementations of [`Reporter`] that wrap another reporter
+//! to alter its behaviour: [`MemberFilterReporter`] drops changes concerning ignored members,
+//! [`QuietHoursReporter`] defers reports during a daily time window and [`ErrorRouterReporter`]
+//! sends errors to a different reporter than changes. They can be composed via [`ReporterExt`].
+//!
+//! ### [`DedupReporter`]
+//!
+//! Required feature: `reporter-dedup`
+//!
+//! When several leaderboards report to the same destination, members appearing in more than one
+//! of them would be reported multiple times. [`DedupReporter`]s created from the same
+//! [`ChangesCollector`] collect changes instead; the collector can then be flushed to the
+//! destination reporter, which receives [`MergedChanges`] where each member appears only once.
+//!
+//! ### [`RecordingReporter`]
+//!
+//! Required feature: `reporter-recording`
+//!
+//! This implementation of [`Reporter`] records every call it receives, along with its arguments,
+//! and includes helpers to assert on them. It is not meant to report changes to actual users;
+//! instead, it can be used in tests of [`Config`] or [`Storage`] integrations.
+//!
+//! ### Dynamic backends
+//!
+//! Required feature: `dynamic`
+//!
+//! Since [`Storage`] and [`Reporter`] cannot be used as trait objects, the [`dynamic`] module
+//! includes object-safe equivalents, [`DynStorage`] and [`DynReporter`] ([`DynOutboxStorage`]
+//! for storages supporting an outbox). Boxed trait objects implement [`Storage`] and
+//! [`Reporter`], so they can be passed to [`run_bot`]. A
+//! [`BackendRegistry`] maps names to backend factories, which makes it possible to pick backends
+//! at runtime (e.g. from a [`ConfigFile`]).
+//!
+//! ## Testing storage implementations
+//!
+//! Required feature: `storage-conformance`
+//!
+//! The [`conformance`] module includes a suite of checks that can be run against any
+//! implementation of [`Storage`] (and [`StorageAdmin`], [`DigestStorage`] or [`OutboxStorage`]) to validate that it honours the rules
+//! expected by the bot, like the fact that [`save_error`] must not clear leaderboard data.
+//! Storage implementers should enable this feature in their `dev-dependencies` and call
+//! [`check_storage`], [`check_storage_admin`], [`check_digest_storage`],
+//! [`check_outbox_storage`], [`check_error_state`] and [`check_heartbeat`][conformance-heartbeat]
+//! from their tests.
+//!
 //! [`aoc_leaderbot`]: https://github.com/clechasseur/aoc_leaderbot
 //! [Advent of Code]: https://adventofcode.com/
 //! [`run_bot`]: leaderbot::run_bot
@@ -79,11 +263,73 @@
 //! [`report_changes`]: leaderbot::Reporter::report_changes
 //! [`report_error`]: leaderbot::Reporter::report_error
 //! [`report_first_run`]: leaderbot::Reporter::report_first_run
+//! [`report_recovery`]: leaderbot::Reporter::report_recovery
+//! [`Storage::load_error_state`]: leaderbot::Storage::load_error_state
+//! [`renotify_policy`]: leaderbot::Config::renotify_policy
 //! [`aoc_leaderbot_slack_lib`]: https://crates.io/crates/aoc_leaderbot_slack_lib
 //! [`MemoryConfig`]: leaderbot::config::mem::MemoryConfig
 //! [`get_env_config`]: leaderbot::config::env::get_env_config
+//! [`ConfigFile`]: leaderbot::config::file::ConfigFile
 //! [`MemoryStorage`]: leaderbot::storage::mem::MemoryStorage
+//! [`CredentialsProvider`]: leaderbot::CredentialsProvider
+//! [`Clock`]: leaderbot::clock::Clock
+//! [`TestClock`]: leaderbot::clock::TestClock
+//! [`provide_credentials`]: leaderbot::provide_credentials
+//! [`credentials`]: leaderbot::credentials
+//! [`MultiReporter`]: leaderbot::reporter::multi::MultiReporter
+//! [`FailurePolicy`]: leaderbot::reporter::multi::FailurePolicy
+//! [`middleware`]: leaderbot::reporter::middleware
+//! [`MemberFilterReporter`]: leaderbot::reporter::middleware::MemberFilterReporter
+//! [`QuietHoursReporter`]: leaderbot::reporter::middleware::QuietHoursReporter
+//! [`ErrorRouterReporter`]: leaderbot::reporter::middleware::ErrorRouterReporter
+//! [`ReporterExt`]: leaderbot::reporter::middleware::ReporterExt
+//! [`DedupReporter`]: leaderbot::reporter::dedup::DedupReporter
+//! [`ChangesCollector`]: leaderbot::reporter::dedup::ChangesCollector
+//! [`MergedChanges`]: leaderbot::MergedChanges
+//! [`RecordingReporter`]: leaderbot::reporter::recording::RecordingReporter
+//! [`dynamic`]: leaderbot::dynamic
+//! [`DynStorage`]: leaderbot::dynamic::DynStorage
+//! [`DynOutboxStorage`]: leaderbot::dynamic::DynOutboxStorage
+//! [`DynReporter`]: leaderbot::dynamic::DynReporter
+//! [`BackendRegistry`]: leaderbot::dynamic::BackendRegistry
 //! [`serde`]: https://serde.rs/
+//! [`conformance`]: leaderbot::storage::conformance
+//! [`StorageAdmin`]: leaderbot::StorageAdmin
+//! [`save_error`]: leaderbot::Storage::save_error
+//! [`check_storage`]: leaderbot::storage::conformance::check_storage
+//! [`check_storage_admin`]: leaderbot::storage::conformance::check_storage_admin
+//! [`check_digest_storage`]: leaderbot::storage::conformance::check_digest_storage
+//! [`run_bot_digest`]: leaderbot::digest::run_bot_digest
+//! [`DigestSchedule`]: leaderbot::digest::DigestSchedule
+//! [`DigestStorage`]: leaderbot::DigestStorage
+//! [`check_outbox_storage`]: leaderbot::storage::conformance::check_outbox_storage
+//! [`run_bot_outbox`]: leaderbot::outbox::run_bot_outbox
+//! [`OutboxStorage`]: leaderbot::OutboxStorage
+//! [`BotOutput`]: leaderbot::BotOutput
+//! [`RunDiagnostics`]: leaderbot::diagnostics::RunDiagnostics
+//! [`run_bot_diagnosed`]: leaderbot::run_bot_diagnosed
+//! [`RunError`]: error::RunError
+//! [`BotMetrics`]: leaderbot::metrics::BotMetrics
+//! [`observe`]: leaderbot::metrics::BotMetrics::observe
+//! [`serve`]: leaderbot::metrics::BotMetrics::serve
+//! [`push`]: leaderbot::metrics::BotMetrics::push
+//! [`check_error_state`]: leaderbot::storage::conformance::check_error_state
+//! [conformance-heartbeat]: leaderbot::storage::conformance::check_heartbeat
+//! [`HeartbeatPolicy`]: leaderbot::heartbeat::HeartbeatPolicy
+//! [`check_heartbeat`]: leaderbot::heartbeat::check_heartbeat
+//! [`report_stale`]: leaderbot::Reporter::report_stale
+//! [`report_heartbeat`]: leaderbot::Reporter::report_heartbeat
+//! [`run_bot_forever`]: leaderbot::daemon::run_bot_forever
+//! [`DaemonSchedule`]: leaderbot::daemon::DaemonSchedule
+//! [`EventWindow`]: leaderbot::event::EventWindow
+//! [`MultiTargetRunner`]: leaderbot::multi_target::MultiTargetRunner
+//! [`merged_leaderboards`]: leaderbot::Config::merged_leaderboards
+//! [merges]: aoc_leaderboard::aoc::Leaderboard::merge
+//! [`Team`]: leaderbot::team::Team
+//! [`teams`]: leaderbot::Config::teams
+//! [`team_standings`]: leaderbot::team::team_standings
+//! [`member_settings`]: leaderbot::Config::member_settings
+//! [pseudonyms]: leaderbot::members::pseudonym
 
 #![deny(missing_docs)]
 #![deny(rustdoc::missing_crate_level_docs)]
@@ -0,0 +1,193 @@
+use aoc_leaderbot_lib::leaderbot::clock::{Clock, SharedClock, SystemClock, TestClock};
+use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+
+fn utc(year: i32, month: u32, day: u32, hour: u32, min: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(year, month, day, hour, min, 0)
+        .unwrap()
+}
+
+mod system_clock {
+    use super::*;
+
+    #[test_log::test]
+    fn now() {
+        let before = Utc::now();
+        let now = SystemClock.now();
+        let after = Utc::now();
+
+        assert!(before <= now && now <= after);
+    }
+}
+
+mod test_clock {
+    use super::*;
+
+    #[test_log::test]
+    fn set_and_advance() {
+        let clock = TestClock::new(utc(2024, 11, 30, 23, 0));
+        assert_eq!(clock.now(), utc(2024, 11, 30, 23, 0));
+
+        clock.advance(TimeDelta::hours(2));
+        assert_eq!(clock.now(), utc(2024, 12, 1, 1, 0));
+
+        clock.set(utc(2025, 1, 1, 0, 0));
+        assert_eq!(clock.now(), utc(2025, 1, 1, 0, 0));
+    }
+
+    #[test_log::test]
+    fn clones_share_time() {
+        let clock = TestClock::new(utc(2024, 12, 1, 5, 0));
+        let clone = clock.clone();
+
+        clock.advance(TimeDelta::days(1));
+        assert_eq!(clone.now(), utc(2024, 12, 2, 5, 0));
+    }
+}
+
+mod shared_clock {
+    use super::*;
+
+    #[test_log::test]
+    fn delegates_to_clock() {
+        let clock = TestClock::new(utc(2024, 12, 1, 5, 0));
+        let shared = SharedClock::new(clock.clone());
+
+        clock.advance(TimeDelta::minutes(30));
+        assert_eq!(shared.now(), utc(2024, 12, 1, 5, 30));
+    }
+
+    #[test_log::test]
+    fn eq() {
+        let shared = SharedClock::default();
+
+        assert_eq!(shared, shared.clone());
+        assert_ne!(shared, SharedClock::default());
+    }
+}
+
+#[cfg(feature = "config-mem")]
+mod memory_config {
+    use aoc_leaderboard::test_helpers::{TEST_LEADERBOARD_ID, test_leaderboard_credentials};
+    use aoc_leaderbot_lib::leaderbot::Config;
+    use aoc_leaderbot_lib::leaderbot::config::mem::MemoryConfig;
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case::before_event(utc(2024, 11, 30, 23, 59), 2023)]
+    #[case::event_start(utc(2024, 12, 1, 5, 0), 2024)]
+    #[case::new_year(utc(2025, 1, 1, 0, 0), 2024)]
+    #[test_log::test]
+    fn default_year(#[case] now: DateTime<Utc>, #[case] expected: i32) {
+        let config = MemoryConfig::builder()
+            .leaderboard_id(TEST_LEADERBOARD_ID)
+            .credentials(test_leaderboard_credentials::default())
+            .clock(TestClock::new(now))
+            .build()
+            .unwrap();
+
+        assert_eq!(config.year(), expected);
+        assert_eq!(config.clock().now(), now);
+    }
+
+    #[test_log::test]
+    fn default_year_follows_clock() {
+        let clock = TestClock::new(utc(2024, 12, 1, 4, 59));
+        let config = MemoryConfig::builder()
+            .leaderboard_id(TEST_LEADERBOARD_ID)
+            .credentials(test_leaderboard_credentials::default())
+            .clock(clock.clone())
+            .build()
+            .unwrap();
+        assert_eq!(config.year(), 2023);
+
+        clock.advance(TimeDelta::minutes(1));
+        assert_eq!(config.year(), 2024);
+    }
+}
+
+mod config {
+    use aoc_leaderboard::aoc::LeaderboardCredentials;
+    use aoc_leaderboard::test_helpers::{TEST_LEADERBOARD_ID, test_leaderboard_credentials};
+    use aoc_leaderbot_lib::leaderbot::Config;
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct ClockConfig(TestClock);
+
+    impl Config for ClockConfig {
+        fn leaderboard_id(&self) -> u64 {
+            TEST_LEADERBOARD_ID
+        }
+
+        fn credentials(&self) -> LeaderboardCredentials {
+            test_leaderboard_credentials::default()
+        }
+
+        fn clock(&self) -> &dyn Clock {
+            &self.0
+        }
+    }
+
+    #[test_log::test]
+    fn year_follows_clock() {
+        let clock = TestClock::new(utc(2024, 12, 1, 4, 59));
+        let config = ClockConfig(clock.clone());
+        assert_eq!(config.year(), 2023);
+
+        clock.advance(TimeDelta::minutes(1));
+        assert_eq!(config.year(), 2024);
+
+        clock.set(utc(2025, 11, 30, 12, 0));
+        assert_eq!(config.year(), 2024);
+    }
+}
+
+#[cfg(all(feature = "config-mem", feature = "storage-mem", feature = "reporter-recording"))]
+mod run_bot {
+    use aoc_leaderboard::test_helpers::{
+        TEST_LEADERBOARD_ID, TEST_YEAR, test_leaderboard_credentials,
+    };
+    use aoc_leaderboard::wiremock::MockServer;
+    use aoc_leaderbot_lib::leaderbot::config::mem::MemoryConfig;
+    use aoc_leaderbot_lib::leaderbot::reporter::recording::RecordingReporter;
+    use aoc_leaderbot_lib::leaderbot::storage::mem::MemoryStorage;
+    use aoc_leaderbot_lib::leaderbot::{Storage, run_bot_from};
+
+    use super::*;
+
+    #[test_log::test(tokio::test)]
+    async fn error_state_uses_clock() {
+        // No mocks are mounted, so fetching the leaderboard fails.
+        let mock_server = MockServer::start().await;
+        let clock = TestClock::new(utc(TEST_YEAR, 12, 3, 5, 0));
+        let config = MemoryConfig::builder()
+            .year(TEST_YEAR)
+            .leaderboard_id(TEST_LEADERBOARD_ID)
+            .credentials(test_leaderboard_credentials::default())
+            .clock(clock.clone())
+            .build()
+            .unwrap();
+        let mut storage = MemoryStorage::new();
+        let mut reporter = RecordingReporter::new();
+
+        for _ in 0..2 {
+            let result =
+                run_bot_from(Some(mock_server.uri()), &config, &mut storage, &mut reporter, false)
+                    .await;
+            assert!(result.is_err());
+            clock.advance(TimeDelta::hours(1));
+        }
+
+        let error_state = storage
+            .load_error_state(TEST_YEAR, TEST_LEADERBOARD_ID)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(error_state.occurrences, 2);
+        assert_eq!(error_state.first_seen, utc(TEST_YEAR, 12, 3, 5, 0));
+        assert_eq!(error_state.last_seen, utc(TEST_YEAR, 12, 3, 6, 0));
+    }
+}
@@ -1,5 +1,5 @@
 mod get_env_config {
-    use std::env;
+    use std::{env, fs};
 
     use aoc_leaderboard::aoc::{LeaderboardCredentials, LeaderboardCredentialsKind};
     use aoc_leaderboard::test_helpers::{
@@ -7,13 +7,24 @@ mod get_env_config {
     };
     use aoc_leaderbot_lib::Error;
     use aoc_leaderbot_lib::error::EnvVarError;
-    use aoc_leaderbot_lib::leaderbot::Config;
     use aoc_leaderbot_lib::leaderbot::config::env::{
-        ENV_CONFIG_LEADERBOARD_ID_SUFFIX, ENV_CONFIG_SESSION_COOKIE_SUFFIX,
+        ENV_CONFIG_EVENT_GRACE_DAYS_SUFFIX, ENV_CONFIG_HEARTBEAT_INTERVAL_MINUTES_SUFFIX,
+        ENV_CONFIG_HEARTBEAT_MAX_SILENCE_MINUTES_SUFFIX, ENV_CONFIG_IGNORED_MEMBER_IDS_SUFFIX,
+        ENV_CONFIG_LEADERBOARD_ID_SUFFIX, ENV_CONFIG_MEMBER_ALIASES_SUFFIX,
+        ENV_CONFIG_MERGED_LEADERBOARD_IDS_SUFFIX, ENV_CONFIG_OPTED_OUT_MEMBER_IDS_SUFFIX,
+        ENV_CONFIG_PSEUDONYMIZE_MEMBERS_SUFFIX, ENV_CONFIG_RENOTIFY_AFTER_FAILURES_SUFFIX,
+        ENV_CONFIG_RENOTIFY_INTERVAL_MINUTES_SUFFIX, ENV_CONFIG_SESSION_COOKIE_FILE_SUFFIX,
+        ENV_CONFIG_SESSION_COOKIE_SUFFIX, ENV_CONFIG_TEAMS_SUFFIX, ENV_CONFIG_VIEW_KEY_FILE_SUFFIX,
         ENV_CONFIG_VIEW_KEY_SUFFIX, ENV_CONFIG_YEAR_SUFFIX, get_env_config,
+        get_env_config_with_credentials,
     };
+    use aoc_leaderbot_lib::leaderbot::event::{EventWindow, current_event_year};
+    use aoc_leaderbot_lib::leaderbot::heartbeat::HeartbeatPolicy;
+    use aoc_leaderbot_lib::leaderbot::members::MemberSettings;
+    use aoc_leaderbot_lib::leaderbot::team::Team;
+    use aoc_leaderbot_lib::leaderbot::{Config, MergedLeaderboard, RenotifyPolicy};
     use assert_matches::assert_matches;
-    use chrono::{Datelike, Local};
+    use chrono::{TimeDelta, Utc};
     use rstest::{fixture, rstest};
     use serial_test::serial;
     use uuid::Uuid;
@@ -54,8 +65,17 @@ mod get_env_config {
 
         let actual = get_env_config(env_var_prefix).unwrap();
 
-        assert_eq!(actual.year(), if set_year { TEST_YEAR } else { Local::now().year() });
+        assert_eq!(
+            actual.year(),
+            if set_year { TEST_YEAR } else { current_event_year(Utc::now()) }
+        );
         assert_eq!(actual.leaderboard_id(), TEST_LEADERBOARD_ID);
+        assert_eq!(actual.renotify_policy(), RenotifyPolicy::never());
+        assert_eq!(actual.event_window(), None);
+        assert_eq!(actual.heartbeat_policy(), HeartbeatPolicy::default());
+        assert!(actual.merged_leaderboards().is_empty());
+        assert!(actual.teams().is_empty());
+        assert!(actual.member_settings().is_empty());
         match (actual.credentials(), credentials_kind) {
             (LeaderboardCredentials::ViewKey(actual_key), LeaderboardCredentialsKind::ViewKey) => {
                 assert_eq!(actual_key, TEST_AOC_VIEW_KEY);
@@ -73,6 +93,212 @@ mod get_env_config {
         }
     }
 
+    #[rstest]
+    #[test_log::test]
+    #[serial(env)]
+    fn with_renotify_policy(env_var_prefix: String) {
+        let var_name = |name| format!("{env_var_prefix}{name}");
+
+        unsafe {
+            env::set_var(
+                var_name(ENV_CONFIG_LEADERBOARD_ID_SUFFIX),
+                TEST_LEADERBOARD_ID.to_string(),
+            );
+            env::set_var(var_name(ENV_CONFIG_VIEW_KEY_SUFFIX), TEST_AOC_VIEW_KEY);
+            env::set_var(var_name(ENV_CONFIG_RENOTIFY_INTERVAL_MINUTES_SUFFIX), "1440");
+            env::set_var(var_name(ENV_CONFIG_RENOTIFY_AFTER_FAILURES_SUFFIX), "10");
+        }
+
+        let actual = get_env_config(env_var_prefix).unwrap();
+
+        assert_eq!(
+            actual.renotify_policy(),
+            RenotifyPolicy { interval: Some(TimeDelta::days(1)), failures: Some(10) }
+        );
+    }
+
+    #[rstest]
+    #[test_log::test]
+    #[serial(env)]
+    fn with_event_window(env_var_prefix: String) {
+        let var_name = |name| format!("{env_var_prefix}{name}");
+
+        unsafe {
+            env::set_var(
+                var_name(ENV_CONFIG_LEADERBOARD_ID_SUFFIX),
+                TEST_LEADERBOARD_ID.to_string(),
+            );
+            env::set_var(var_name(ENV_CONFIG_VIEW_KEY_SUFFIX), TEST_AOC_VIEW_KEY);
+            env::set_var(var_name(ENV_CONFIG_EVENT_GRACE_DAYS_SUFFIX), "14");
+        }
+
+        let actual = get_env_config(env_var_prefix).unwrap();
+
+        assert_eq!(actual.event_window(), Some(EventWindow::new(TimeDelta::days(14))));
+    }
+
+    #[rstest]
+    #[test_log::test]
+    #[serial(env)]
+    fn with_heartbeat_policy(env_var_prefix: String) {
+        let var_name = |name| format!("{env_var_prefix}{name}");
+
+        unsafe {
+            env::set_var(
+                var_name(ENV_CONFIG_LEADERBOARD_ID_SUFFIX),
+                TEST_LEADERBOARD_ID.to_string(),
+            );
+            env::set_var(var_name(ENV_CONFIG_VIEW_KEY_SUFFIX), TEST_AOC_VIEW_KEY);
+            env::set_var(var_name(ENV_CONFIG_HEARTBEAT_MAX_SILENCE_MINUTES_SUFFIX), "120");
+            env::set_var(var_name(ENV_CONFIG_HEARTBEAT_INTERVAL_MINUTES_SUFFIX), "1440");
+        }
+
+        let actual = get_env_config(env_var_prefix).unwrap();
+
+        assert_eq!(
+            actual.heartbeat_policy(),
+            HeartbeatPolicy {
+                max_silence: Some(TimeDelta::hours(2)),
+                interval: Some(TimeDelta::days(1)),
+            }
+        );
+    }
+
+    #[rstest]
+    #[test_log::test]
+    #[serial(env)]
+    fn with_merged_leaderboards(env_var_prefix: String) {
+        let var_name = |name| format!("{env_var_prefix}{name}");
+
+        unsafe {
+            env::set_var(
+                var_name(ENV_CONFIG_LEADERBOARD_ID_SUFFIX),
+                TEST_LEADERBOARD_ID.to_string(),
+            );
+            env::set_var(var_name(ENV_CONFIG_SESSION_COOKIE_SUFFIX), TEST_AOC_SESSION);
+            env::set_var(var_name(ENV_CONFIG_MERGED_LEADERBOARD_IDS_SUFFIX), "23, 42,");
+        }
+
+        let actual = get_env_config(env_var_prefix).unwrap();
+
+        assert_eq!(
+            actual.merged_leaderboards(),
+            [MergedLeaderboard::new(23), MergedLeaderboard::new(42)]
+        );
+    }
+
+    #[rstest]
+    #[test_log::test]
+    #[serial(env)]
+    fn with_teams(env_var_prefix: String) {
+        let var_name = |name| format!("{env_var_prefix}{name}");
+
+        unsafe {
+            env::set_var(
+                var_name(ENV_CONFIG_LEADERBOARD_ID_SUFFIX),
+                TEST_LEADERBOARD_ID.to_string(),
+            );
+            env::set_var(var_name(ENV_CONFIG_VIEW_KEY_SUFFIX), TEST_AOC_VIEW_KEY);
+            env::set_var(var_name(ENV_CONFIG_TEAMS_SUFFIX), "Dev: 1, 2; Ops:3;Empty:;");
+        }
+
+        let actual = get_env_config(env_var_prefix).unwrap();
+
+        assert_eq!(
+            actual.teams(),
+            [Team::new("Dev", [1, 2]), Team::new("Ops", [3]), Team::new("Empty", [])]
+        );
+    }
+
+    #[rstest]
+    #[test_log::test]
+    #[serial(env)]
+    fn with_member_settings(env_var_prefix: String) {
+        let var_name = |name| format!("{env_var_prefix}{name}");
+
+        unsafe {
+            env::set_var(
+                var_name(ENV_CONFIG_LEADERBOARD_ID_SUFFIX),
+                TEST_LEADERBOARD_ID.to_string(),
+            );
+            env::set_var(var_name(ENV_CONFIG_VIEW_KEY_SUFFIX), TEST_AOC_VIEW_KEY);
+            env::set_var(var_name(ENV_CONFIG_MEMBER_ALIASES_SUFFIX), "1: Zaphod ;2:Ford: Prefect;");
+            env::set_var(var_name(ENV_CONFIG_IGNORED_MEMBER_IDS_SUFFIX), "3,4");
+            env::set_var(var_name(ENV_CONFIG_OPTED_OUT_MEMBER_IDS_SUFFIX), "5");
+            env::set_var(var_name(ENV_CONFIG_PSEUDONYMIZE_MEMBERS_SUFFIX), "TRUE");
+        }
+
+        let actual = get_env_config(env_var_prefix).unwrap();
+
+        assert_eq!(
+            actual.member_settings(),
+            MemberSettings {
+                aliases: [(1, "Zaphod".into()), (2, "Ford: Prefect".into())].into(),
+                ignored: [3, 4].into(),
+                opted_out: [5].into(),
+                pseudonymize: true,
+            }
+        );
+    }
+
+    #[rstest]
+    #[test_log::test]
+    #[serial(env)]
+    fn with_credentials_file(
+        env_var_prefix: String,
+        #[values(LeaderboardCredentialsKind::ViewKey, LeaderboardCredentialsKind::SessionCookie)]
+        credentials_kind: LeaderboardCredentialsKind,
+    ) {
+        let var_name = |name| format!("{env_var_prefix}{name}");
+        let path = env::temp_dir().join(format!("aoc_leaderbot_{}", Uuid::new_v4()));
+        let (suffix, secret, expected) = match credentials_kind {
+            LeaderboardCredentialsKind::ViewKey => (
+                ENV_CONFIG_VIEW_KEY_FILE_SUFFIX,
+                TEST_AOC_VIEW_KEY,
+                LeaderboardCredentials::ViewKey(TEST_AOC_VIEW_KEY.into()),
+            ),
+            LeaderboardCredentialsKind::SessionCookie => (
+                ENV_CONFIG_SESSION_COOKIE_FILE_SUFFIX,
+                TEST_AOC_SESSION,
+                LeaderboardCredentials::SessionCookie(TEST_AOC_SESSION.into()),
+            ),
+        };
+        fs::write(&path, format!("{secret}\n")).unwrap();
+
+        unsafe {
+            env::set_var(
+                var_name(ENV_CONFIG_LEADERBOARD_ID_SUFFIX),
+                TEST_LEADERBOARD_ID.to_string(),
+            );
+            env::set_var(var_name(suffix), &path);
+        }
+
+        let actual = get_env_config(env_var_prefix);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(actual.unwrap().credentials(), expected);
+    }
+
+    #[rstest]
+    #[test_log::test]
+    #[serial(env)]
+    fn with_credentials(env_var_prefix: String) {
+        let var_name = |name| format!("{env_var_prefix}{name}");
+
+        unsafe {
+            env::set_var(
+                var_name(ENV_CONFIG_LEADERBOARD_ID_SUFFIX),
+                TEST_LEADERBOARD_ID.to_string(),
+            );
+        }
+
+        let credentials = LeaderboardCredentials::SessionCookie(TEST_AOC_SESSION.into());
+        let actual = get_env_config_with_credentials(env_var_prefix, credentials.clone()).unwrap();
+
+        assert_eq!(actual.leaderboard_id(), TEST_LEADERBOARD_ID);
+        assert_eq!(actual.credentials(), credentials);
+    }
+
     mod missing_vars {
         use super::*;
 
@@ -108,6 +334,27 @@ mod get_env_config {
                 assert_eq!(actual_var_name, var_name(ENV_CONFIG_SESSION_COOKIE_SUFFIX));
             });
         }
+
+        #[rstest]
+        #[test_log::test]
+        #[serial(env)]
+        fn missing_credentials_file(env_var_prefix: String) {
+            let var_name = |name| format!("{env_var_prefix}{name}");
+
+            unsafe {
+                env::set_var(
+                    var_name(ENV_CONFIG_LEADERBOARD_ID_SUFFIX),
+                    TEST_LEADERBOARD_ID.to_string(),
+                );
+                env::set_var(
+                    var_name(ENV_CONFIG_SESSION_COOKIE_FILE_SUFFIX),
+                    env::temp_dir().join(format!("aoc_leaderbot_{}", Uuid::new_v4())),
+                );
+            }
+
+            let actual = get_env_config(&env_var_prefix);
+            assert_matches!(actual, Err(Error::Credentials(_)));
+        }
     }
 
     mod invalid_values {
@@ -148,5 +395,101 @@ mod get_env_config {
                 assert_matches!(source, EnvVarError::IntExpected { actual, .. } if actual == "one two three four five");
             })
         }
+
+        #[rstest]
+        #[test_log::test]
+        #[serial(env)]
+        fn invalid_renotify_interval(env_var_prefix: String) {
+            let var_name = |name| format!("{env_var_prefix}{name}");
+
+            unsafe {
+                env::set_var(var_name(ENV_CONFIG_RENOTIFY_INTERVAL_MINUTES_SUFFIX), "a day");
+                env::set_var(var_name(ENV_CONFIG_SESSION_COOKIE_SUFFIX), TEST_AOC_SESSION);
+            }
+
+            let actual = get_env_config(&env_var_prefix);
+            assert_matches!(actual, Err(Error::Env { var_name: actual_var_name, source }) => {
+                assert_eq!(actual_var_name, var_name(ENV_CONFIG_RENOTIFY_INTERVAL_MINUTES_SUFFIX));
+                assert_matches!(source, EnvVarError::IntExpected { actual, .. } if actual == "a day");
+            })
+        }
+
+        #[rstest]
+        #[test_log::test]
+        #[serial(env)]
+        fn invalid_merged_leaderboard_ids(env_var_prefix: String) {
+            let var_name = |name| format!("{env_var_prefix}{name}");
+
+            unsafe {
+                env::set_var(var_name(ENV_CONFIG_MERGED_LEADERBOARD_IDS_SUFFIX), "23,forty-two");
+                env::set_var(var_name(ENV_CONFIG_SESSION_COOKIE_SUFFIX), TEST_AOC_SESSION);
+            }
+
+            let actual = get_env_config(&env_var_prefix);
+            assert_matches!(actual, Err(Error::Env { var_name: actual_var_name, source }) => {
+                assert_eq!(actual_var_name, var_name(ENV_CONFIG_MERGED_LEADERBOARD_IDS_SUFFIX));
+                assert_matches!(source, EnvVarError::IntExpected { actual, .. } if actual == "forty-two");
+            })
+        }
+
+        #[rstest]
+        #[test_log::test]
+        #[serial(env)]
+        fn invalid_teams(
+            env_var_prefix: String,
+            #[values("Dev", "Dev:1,two", ":1,2")] teams: &str,
+        ) {
+            let var_name = |name| format!("{env_var_prefix}{name}");
+
+            unsafe {
+                env::set_var(var_name(ENV_CONFIG_TEAMS_SUFFIX), teams);
+                env::set_var(var_name(ENV_CONFIG_SESSION_COOKIE_SUFFIX), TEST_AOC_SESSION);
+            }
+
+            let actual = get_env_config(&env_var_prefix);
+            assert_matches!(actual, Err(Error::Env { var_name: actual_var_name, source }) => {
+                assert_eq!(actual_var_name, var_name(ENV_CONFIG_TEAMS_SUFFIX));
+                assert_matches!(source, EnvVarError::InvalidFormat { actual, .. } if actual == teams);
+            })
+        }
+
+        #[rstest]
+        #[test_log::test]
+        #[serial(env)]
+        fn invalid_member_aliases(
+            env_var_prefix: String,
+            #[values("1", "one:Zaphod", "1:")] aliases: &str,
+        ) {
+            let var_name = |name| format!("{env_var_prefix}{name}");
+
+            unsafe {
+                env::set_var(var_name(ENV_CONFIG_MEMBER_ALIASES_SUFFIX), aliases);
+                env::set_var(var_name(ENV_CONFIG_SESSION_COOKIE_SUFFIX), TEST_AOC_SESSION);
+            }
+
+            let actual = get_env_config(&env_var_prefix);
+            assert_matches!(actual, Err(Error::Env { var_name: actual_var_name, source }) => {
+                assert_eq!(actual_var_name, var_name(ENV_CONFIG_MEMBER_ALIASES_SUFFIX));
+                assert_matches!(source, EnvVarError::InvalidFormat { actual, .. } if actual == aliases);
+            })
+        }
+
+        #[rstest]
+        #[test_log::test]
+        #[serial(env)]
+        fn invalid_pseudonymize_members(env_var_prefix: String) {
+            let var_name = |name| format!("{env_var_prefix}{name}");
+
+            unsafe {
+                env::set_var(var_name(ENV_CONFIG_PSEUDONYMIZE_MEMBERS_SUFFIX), "yes");
+                env::set_var(var_name(ENV_CONFIG_SESSION_COOKIE_SUFFIX), TEST_AOC_SESSION);
+            }
+
+            let actual = get_env_config(&env_var_prefix);
+            assert_matches!(actual, Err(Error::Env { var_name: actual_var_name, source }) => {
+                assert_eq!(actual_var_name, var_name(ENV_CONFIG_PSEUDONYMIZE_MEMBERS_SUFFIX));
+                assert_matches!(source, EnvVarError::InvalidFormat { actual, .. } if actual == "yes");
+            })
+        }
     }
 }
@@ -0,0 +1,362 @@
+mod config_file_format {
+    use aoc_leaderbot_lib::leaderbot::config::file::ConfigFileFormat;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::toml("config.toml", Some(ConfigFileFormat::Toml))]
+    #[case::yaml("config.yaml", Some(ConfigFileFormat::Yaml))]
+    #[case::yml("dir/config.YML", Some(ConfigFileFormat::Yaml))]
+    #[case::json("config.json", None)]
+    #[case::no_extension("config", None)]
+    #[test_log::test]
+    fn from_path(#[case] path: &str, #[case] expected: Option<ConfigFileFormat>) {
+        assert_eq!(ConfigFileFormat::from_path(path), expected);
+    }
+}
+
+mod config_file {
+    use std::env;
+
+    use aoc_leaderboard::aoc::LeaderboardCredentials;
+    use aoc_leaderbot_lib::Error;
+    use aoc_leaderbot_lib::error::{ConfigFileError, EnvVarError};
+    use aoc_leaderbot_lib::leaderbot::config::file::{ConfigFile, ConfigFileFormat};
+    use aoc_leaderbot_lib::leaderbot::event::EventWindow;
+    use aoc_leaderbot_lib::leaderbot::heartbeat::HeartbeatPolicy;
+    use aoc_leaderbot_lib::leaderbot::members::MemberSettings;
+    use aoc_leaderbot_lib::leaderbot::team::Team;
+    use aoc_leaderbot_lib::leaderbot::{MergedLeaderboard, RenotifyPolicy};
+    use assert_matches::assert_matches;
+    use chrono::TimeDelta;
+    use rstest::{fixture, rstest};
+    use serde::Deserialize;
+    use serial_test::serial;
+    use uuid::Uuid;
+
+    const TOML_CONFIG: &str = r#"
+[[leaderboards]]
+year = 2024
+leaderboard_id = 12345
+view_key = "${VIEW_KEY_VAR}"
+renotify_interval_minutes = 60
+renotify_after_failures = 3
+event_grace_days = 7
+heartbeat_max_silence_minutes = 120
+heartbeat_interval_minutes = 1440
+
+[[leaderboards.merged_leaderboards]]
+leaderboard_id = 67890
+session_cookie = "cookie"
+
+[[leaderboards.merged_leaderboards]]
+leaderboard_id = 13579
+
+[[leaderboards.teams]]
+name = "Dev"
+members = [1, 2]
+
+[leaderboards.members]
+aliases = { 4 = "Zaphod" }
+ignored = [5]
+opted_out = [6]
+pseudonymize = true
+
+[[leaderboards]]
+year = 2023
+leaderboard_id = 12345
+session_cookie = "$${not_a_var}"
+
+[reporters.test]
+name = "reporter"
+count = 42
+"#;
+
+    const YAML_CONFIG: &str = r#"
+leaderboards:
+  - year: 2024
+    leaderboard_id: 12345
+    view_key: "${VIEW_KEY_VAR}"
+    renotify_interval_minutes: 60
+    renotify_after_failures: 3
+    event_grace_days: 7
+    heartbeat_max_silence_minutes: 120
+    heartbeat_interval_minutes: 1440
+    merged_leaderboards:
+      - leaderboard_id: 67890
+        session_cookie: cookie
+      - leaderboard_id: 13579
+    teams:
+      - name: Dev
+        members: [1, 2]
+    members:
+      aliases:
+        "4": Zaphod
+      ignored: [5]
+      opted_out: [6]
+      pseudonymize: true
+  - year: 2023
+    leaderboard_id: 12345
+    session_cookie: "$${not_a_var}"
+reporters:
+  test:
+    name: reporter
+    count: 42
+"#;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct TestReporterSettings {
+        name: String,
+        count: u32,
+    }
+
+    #[fixture]
+    fn view_key_var() -> String {
+        let var_name = format!("AOC_LEADERBOT_TEST_VIEW_KEY_{}", Uuid::new_v4().simple());
+        unsafe {
+            env::set_var(&var_name, "secret");
+        }
+        var_name
+    }
+
+    fn assert_full_config(config: ConfigFile) {
+        assert_eq!(config.leaderboards.len(), 2);
+
+        let first = &config.leaderboards[0];
+        assert_eq!(first.year, Some(2024));
+        assert_eq!(first.leaderboard_id, 12345);
+        assert_eq!(first.credentials, LeaderboardCredentials::ViewKey("secret".into()));
+        assert_eq!(
+            first.renotify_policy,
+            RenotifyPolicy { interval: Some(TimeDelta::minutes(60)), failures: Some(3) }
+        );
+        assert_eq!(first.event_window, Some(EventWindow::new(TimeDelta::days(7))));
+        assert_eq!(
+            first.heartbeat_policy,
+            HeartbeatPolicy {
+                max_silence: Some(TimeDelta::hours(2)),
+                interval: Some(TimeDelta::days(1)),
+            }
+        );
+        assert_eq!(
+            first.merged_leaderboards,
+            [
+                MergedLeaderboard::new(67890)
+                    .with_credentials(LeaderboardCredentials::SessionCookie("cookie".into())),
+                MergedLeaderboard::new(13579),
+            ]
+        );
+        assert_eq!(first.teams, [Team::new("Dev", [1, 2])]);
+        assert_eq!(
+            first.member_settings,
+            MemberSettings {
+                aliases: [(4, "Zaphod".into())].into(),
+                ignored: [5].into(),
+                opted_out: [6].into(),
+                pseudonymize: true,
+            }
+        );
+
+        let second = &config.leaderboards[1];
+        assert_eq!(second.year, Some(2023));
+        assert_eq!(
+            second.credentials,
+            LeaderboardCredentials::SessionCookie("${not_a_var}".into())
+        );
+        assert_eq!(second.renotify_policy, RenotifyPolicy::never());
+        assert_eq!(second.heartbeat_policy, HeartbeatPolicy::default());
+        assert!(second.member_settings.is_empty());
+
+        assert_eq!(
+            config.reporter::<TestReporterSettings>("test").unwrap(),
+            Some(TestReporterSettings { name: "reporter".into(), count: 42 })
+        );
+        assert_eq!(config.reporter::<TestReporterSettings>("other").unwrap(), None);
+        assert_matches!(
+            config.reporter::<u32>("test"),
+            Err(Error::ConfigFile(ConfigFileError::Invalid { field, .. })) if field == "reporters.test"
+        );
+    }
+
+    #[rstest]
+    #[case::toml(TOML_CONFIG, ConfigFileFormat::Toml)]
+    #[case::yaml(YAML_CONFIG, ConfigFileFormat::Yaml)]
+    #[test_log::test]
+    #[serial(env)]
+    fn parse(view_key_var: String, #[case] content: &str, #[case] format: ConfigFileFormat) {
+        let content = content.replace("VIEW_KEY_VAR", &view_key_var);
+
+        assert_full_config(ConfigFile::parse(&content, format).unwrap());
+    }
+
+    #[rstest]
+    #[test_log::test]
+    #[serial(env)]
+    fn load(view_key_var: String) {
+        let path = env::temp_dir().join(format!("aoc_leaderbot_test_{}.toml", Uuid::new_v4()));
+        std::fs::write(&path, TOML_CONFIG.replace("VIEW_KEY_VAR", &view_key_var)).unwrap();
+
+        let config = ConfigFile::load(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_full_config(config.unwrap());
+    }
+
+    #[rstest]
+    #[case::toml(
+        "[[leaderboards]]\nleaderboard_id = 1\nview_key = \"${VAR}\"\n\
+         [[leaderboards.teams]]\nname = \"${VAR}\"\n\
+         [leaderboards.members]\naliases = { 4 = \"${VAR}\" }\n\
+         [reporters.test]\nname = \"${VAR}\"\ncount = 42\n",
+        ConfigFileFormat::Toml
+    )]
+    #[case::yaml(
+        "leaderboards:\n  - leaderboard_id: 1\n    view_key: ${VAR}\n\
+         \x20   teams:\n      - name: ${VAR}\n\
+         \x20   members:\n      aliases:\n        \"4\": ${VAR}\n\
+         reporters:\n  test:\n    name: ${VAR}\n    count: 42\n",
+        ConfigFileFormat::Yaml
+    )]
+    #[test_log::test]
+    #[serial(env)]
+    fn interpolates_parsed_values(#[case] content: &str, #[case] format: ConfigFileFormat) {
+        let var_name = format!("AOC_LEADERBOT_TEST_VALUE_{}", Uuid::new_v4().simple());
+        let value = "quote\" apostrophe' \\ newline\n# ${not_a_var}";
+        unsafe {
+            env::set_var(&var_name, value);
+        }
+        let content = content.replace("VAR", &var_name);
+
+        let config = ConfigFile::parse(&content, format).unwrap();
+        let leaderboard = &config.leaderboards[0];
+        assert_eq!(leaderboard.credentials, LeaderboardCredentials::ViewKey(value.into()));
+        assert_eq!(leaderboard.teams, [Team::new(value, [])]);
+        assert_eq!(leaderboard.member_settings.aliases, [(4, value.into())].into());
+        assert_eq!(
+            config.reporter::<TestReporterSettings>("test").unwrap(),
+            Some(TestReporterSettings { name: value.into(), count: 42 })
+        );
+    }
+
+    mod errors {
+        use super::*;
+
+        #[test_log::test]
+        fn unsupported_format() {
+            assert_matches!(
+                ConfigFile::load("config.json"),
+                Err(Error::ConfigFile(ConfigFileError::UnsupportedFormat { path })) => {
+                    assert_eq!(path.to_str(), Some("config.json"));
+                }
+            );
+        }
+
+        #[test_log::test]
+        fn io() {
+            let path =
+                env::temp_dir().join(format!("aoc_leaderbot_missing_{}.yaml", Uuid::new_v4()));
+
+            assert_matches!(
+                ConfigFile::load(&path),
+                Err(Error::ConfigFile(ConfigFileError::Io { path: actual_path, .. })) => {
+                    assert_eq!(actual_path, path);
+                }
+            );
+        }
+
+        #[rstest]
+        #[case::toml("[[leaderboards]]\nleaderboard_id = \"abc\"\n", ConfigFileFormat::Toml, 2, 18)]
+        #[case::yaml("leaderboards:\n  - leaderboard_id: abc\n", ConfigFileFormat::Yaml, 2, 21)]
+        #[case::toml_unknown_field(
+            "[[leaderboards]]\nleaderboard_id = 1\nview_kye = \"x\"\n",
+            ConfigFileFormat::Toml,
+            3,
+            1
+        )]
+        #[test_log::test]
+        fn syntax(
+            #[case] content: &str,
+            #[case] format: ConfigFileFormat,
+            #[case] expected_line: usize,
+            #[case] expected_column: usize,
+        ) {
+            assert_matches!(
+                ConfigFile::parse(content, format),
+                Err(Error::ConfigFile(ConfigFileError::Syntax { line, column, message })) => {
+                    assert_eq!(line, Some(expected_line));
+                    assert_eq!(column, Some(expected_column));
+                    assert!(!message.is_empty());
+                }
+            );
+        }
+
+        #[test_log::test]
+        fn unterminated_interpolation() {
+            assert_matches!(
+                ConfigFile::parse(
+                    "[[leaderboards]]\nleaderboard_id = 1\nview_key = \"${FOO\"\n",
+                    ConfigFileFormat::Toml
+                ),
+                Err(Error::ConfigFile(ConfigFileError::Invalid { field, .. })) => {
+                    assert_eq!(field, "leaderboards[0].view_key");
+                }
+            );
+        }
+
+        #[rstest]
+        #[test_log::test]
+        #[serial(env)]
+        fn missing_env_var() {
+            let var_name = format!("AOC_LEADERBOT_TEST_MISSING_{}", Uuid::new_v4().simple());
+            let content = format!(
+                "[[leaderboards]]\nleaderboard_id = 1\nview_key = \"a\"\n\
+                 [reporters.test]\nurls = [\"${{{var_name}}}\"]\n"
+            );
+
+            assert_matches!(
+                ConfigFile::parse(&content, ConfigFileFormat::Toml),
+                Err(Error::ConfigFile(ConfigFileError::Interpolation { field, var_name: actual_var_name, source })) => {
+                    assert_eq!(field, "reporters.test.urls[0]");
+                    assert_eq!(actual_var_name, var_name);
+                    assert_matches!(source, EnvVarError::NotPresent);
+                }
+            );
+        }
+
+        #[rstest]
+        #[case::no_leaderboards("", "leaderboards")]
+        #[case::no_credentials("[[leaderboards]]\nleaderboard_id = 1\n", "leaderboards[0]")]
+        #[case::both_credentials(
+            "[[leaderboards]]\nleaderboard_id = 1\nview_key = \"a\"\nsession_cookie = \"b\"\n",
+            "leaderboards[0]"
+        )]
+        #[case::merged_both_credentials(
+            "[[leaderboards]]\nleaderboard_id = 1\nview_key = \"a\"\n\
+             [[leaderboards.merged_leaderboards]]\nleaderboard_id = 2\nview_key = \"a\"\nsession_cookie = \"b\"\n",
+            "leaderboards[0].merged_leaderboards[0]"
+        )]
+        #[case::duplicate_leaderboard(
+            "[[leaderboards]]\nyear = 2024\nleaderboard_id = 1\nview_key = \"a\"\n\
+             [[leaderboards]]\nyear = 2024\nleaderboard_id = 1\nview_key = \"b\"\n",
+            "leaderboards[1].leaderboard_id"
+        )]
+        #[case::empty_team_name(
+            "[[leaderboards]]\nleaderboard_id = 1\nview_key = \"a\"\n\
+             [[leaderboards.teams]]\nname = \" \"\n",
+            "leaderboards[0].teams[0].name"
+        )]
+        #[case::invalid_alias_id(
+            "[[leaderboards]]\nleaderboard_id = 1\nview_key = \"a\"\n\
+             [leaderboards.members]\naliases = { abc = \"Zaphod\" }\n",
+            "leaderboards[0].members.aliases.abc"
+        )]
+        #[test_log::test]
+        fn invalid(#[case] content: &str, #[case] expected_field: &str) {
+            assert_matches!(
+                ConfigFile::parse(content, ConfigFileFormat::Toml),
+                Err(Error::ConfigFile(ConfigFileError::Invalid { field, .. })) => {
+                    assert_eq!(field, expected_field);
+                }
+            );
+        }
+    }
+}
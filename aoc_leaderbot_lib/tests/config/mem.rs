@@ -4,8 +4,10 @@ mod memory_config {
         TEST_AOC_SESSION, TEST_AOC_VIEW_KEY, TEST_LEADERBOARD_ID, TEST_YEAR,
         test_leaderboard_credentials,
     };
-    use aoc_leaderbot_lib::leaderbot::Config;
     use aoc_leaderbot_lib::leaderbot::config::mem::MemoryConfig;
+    use aoc_leaderbot_lib::leaderbot::members::MemberSettings;
+    use aoc_leaderbot_lib::leaderbot::team::Team;
+    use aoc_leaderbot_lib::leaderbot::{Config, MergedLeaderboard, RenotifyPolicy};
     use rstest::rstest;
 
     #[rstest]
@@ -16,14 +18,18 @@ mod memory_config {
         assert_eq!(actual.year(), TEST_YEAR);
         assert_eq!(actual.leaderboard_id(), TEST_LEADERBOARD_ID);
         assert_eq!(actual.credentials(), credentials);
+        assert_eq!(actual.renotify_policy(), RenotifyPolicy::never());
+        assert_eq!(actual.event_window(), None);
     }
 
     mod builder {
         use std::any::type_name;
 
         use aoc_leaderbot_lib::Error;
+        use aoc_leaderbot_lib::leaderbot::event::{EventWindow, current_event_year};
+        use aoc_leaderbot_lib::leaderbot::heartbeat::HeartbeatPolicy;
         use assert_matches::assert_matches;
-        use chrono::{Datelike, Local};
+        use chrono::{TimeDelta, Utc};
 
         use super::*;
 
@@ -32,16 +38,32 @@ mod memory_config {
         fn with_all_fields(
             #[from(test_leaderboard_credentials)] credentials: LeaderboardCredentials,
         ) {
+            let renotify_policy = RenotifyPolicy::every(TimeDelta::days(1));
+            let heartbeat_policy = HeartbeatPolicy::default().with_max_silence(TimeDelta::hours(2));
+            let member_settings =
+                MemberSettings { ignored: [TEST_LEADERBOARD_ID].into(), ..Default::default() };
             let actual = MemoryConfig::builder()
                 .year(TEST_YEAR)
                 .leaderboard_id(TEST_LEADERBOARD_ID)
                 .credentials(credentials.clone())
+                .renotify_policy(renotify_policy)
+                .event_window(EventWindow::default())
+                .heartbeat_policy(heartbeat_policy)
+                .merged_leaderboards(vec![MergedLeaderboard::new(42)])
+                .teams(vec![Team::new("Dev", [TEST_LEADERBOARD_ID])])
+                .member_settings(member_settings.clone())
                 .build()
                 .unwrap();
 
             assert_eq!(actual.year(), TEST_YEAR);
             assert_eq!(actual.leaderboard_id(), TEST_LEADERBOARD_ID);
             assert_eq!(actual.credentials(), credentials);
+            assert_eq!(actual.renotify_policy(), renotify_policy);
+            assert_eq!(actual.event_window(), Some(EventWindow::default()));
+            assert_eq!(actual.heartbeat_policy(), heartbeat_policy);
+            assert_eq!(actual.merged_leaderboards(), [MergedLeaderboard::new(42)]);
+            assert_eq!(actual.teams(), [Team::new("Dev", [TEST_LEADERBOARD_ID])]);
+            assert_eq!(actual.member_settings(), member_settings);
         }
 
         #[test_log::test]
@@ -87,7 +109,7 @@ mod memory_config {
                 .build()
                 .unwrap();
 
-            assert_eq!(actual.year(), Local::now().year());
+            assert_eq!(actual.year(), current_event_year(Utc::now()));
             assert_eq!(actual.leaderboard_id(), TEST_LEADERBOARD_ID);
             assert_eq!(actual.credentials(), credentials);
         }
@@ -1,4 +1,6 @@
 #[cfg(feature = "config-env")]
 mod env;
+#[cfg(feature = "config-file")]
+mod file;
 #[cfg(feature = "config-mem")]
 mod mem;
@@ -0,0 +1,57 @@
+#[cfg(unix)]
+mod command_credentials_provider {
+    use std::io;
+
+    use aoc_leaderboard::aoc::LeaderboardCredentials;
+    use aoc_leaderboard::test_helpers::{TEST_AOC_SESSION, TEST_AOC_VIEW_KEY};
+    use aoc_leaderbot_lib::leaderbot::CredentialsProvider;
+    use aoc_leaderbot_lib::leaderbot::credentials::command::CommandCredentialsProvider;
+    use assert_matches::assert_matches;
+
+    #[test_log::test(tokio::test)]
+    async fn session_cookie() {
+        let provider = CommandCredentialsProvider::session_cookie("echo", [TEST_AOC_SESSION]);
+        let actual = provider.get_credentials().await.unwrap();
+
+        assert_eq!(actual, LeaderboardCredentials::SessionCookie(TEST_AOC_SESSION.into()));
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn view_key() {
+        let provider = CommandCredentialsProvider::view_key("echo", [TEST_AOC_VIEW_KEY]);
+        let actual = provider.get_credentials().await.unwrap();
+
+        assert_eq!(actual, LeaderboardCredentials::ViewKey(TEST_AOC_VIEW_KEY.into()));
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn failing_command() {
+        let provider =
+            CommandCredentialsProvider::session_cookie("sh", ["-c", "echo sealed >&2; exit 3"]);
+        let actual = provider.get_credentials().await;
+
+        assert_matches!(actual, Err(err) => {
+            assert_eq!(err.kind(), io::ErrorKind::Other);
+            assert!(err.to_string().contains("sealed"));
+        });
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn empty_output() {
+        let provider = CommandCredentialsProvider::session_cookie("true", Vec::<&str>::new());
+        let actual = provider.get_credentials().await;
+
+        assert_matches!(actual, Err(err) if err.kind() == io::ErrorKind::InvalidData);
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn missing_program() {
+        let provider = CommandCredentialsProvider::session_cookie(
+            "aoc-leaderbot-no-such-program",
+            Vec::<&str>::new(),
+        );
+        let actual = provider.get_credentials().await;
+
+        assert_matches!(actual, Err(err) if err.kind() == io::ErrorKind::NotFound);
+    }
+}
@@ -0,0 +1,66 @@
+mod file_credentials_provider {
+    use std::{env, fs, io};
+
+    use aoc_leaderboard::aoc::LeaderboardCredentials;
+    use aoc_leaderboard::test_helpers::{TEST_AOC_SESSION, TEST_AOC_VIEW_KEY};
+    use aoc_leaderbot_lib::leaderbot::CredentialsProvider;
+    use aoc_leaderbot_lib::leaderbot::credentials::file::FileCredentialsProvider;
+    use assert_matches::assert_matches;
+    use rstest::{fixture, rstest};
+    use uuid::Uuid;
+
+    struct TempFile(std::path::PathBuf);
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    #[fixture]
+    fn temp_file() -> TempFile {
+        TempFile(env::temp_dir().join(format!("aoc_leaderbot_{}", Uuid::new_v4())))
+    }
+
+    #[rstest]
+    #[test_log::test(tokio::test)]
+    async fn session_cookie(temp_file: TempFile) {
+        fs::write(&temp_file.0, format!("{TEST_AOC_SESSION}\n")).unwrap();
+
+        let provider = FileCredentialsProvider::session_cookie(&temp_file.0);
+        let actual = provider.get_credentials().await.unwrap();
+
+        assert_eq!(actual, LeaderboardCredentials::SessionCookie(TEST_AOC_SESSION.into()));
+    }
+
+    #[rstest]
+    #[test_log::test(tokio::test)]
+    async fn view_key(temp_file: TempFile) {
+        fs::write(&temp_file.0, TEST_AOC_VIEW_KEY).unwrap();
+
+        let provider = FileCredentialsProvider::view_key(&temp_file.0);
+        let actual = provider.get_credentials().await.unwrap();
+
+        assert_eq!(actual, LeaderboardCredentials::ViewKey(TEST_AOC_VIEW_KEY.into()));
+    }
+
+    #[rstest]
+    #[test_log::test(tokio::test)]
+    async fn empty_file(temp_file: TempFile) {
+        fs::write(&temp_file.0, "\n").unwrap();
+
+        let provider = FileCredentialsProvider::session_cookie(&temp_file.0);
+        let actual = provider.get_credentials().await;
+
+        assert_matches!(actual, Err(err) if err.kind() == io::ErrorKind::InvalidData);
+    }
+
+    #[rstest]
+    #[test_log::test(tokio::test)]
+    async fn missing_file(temp_file: TempFile) {
+        let provider = FileCredentialsProvider::session_cookie(&temp_file.0);
+        let actual = provider.get_credentials().await;
+
+        assert_matches!(actual, Err(err) if err.kind() == io::ErrorKind::NotFound);
+    }
+}
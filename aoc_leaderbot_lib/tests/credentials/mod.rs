@@ -0,0 +1,62 @@
+#[cfg(feature = "credentials-command")]
+mod command;
+#[cfg(feature = "credentials-file")]
+mod file;
+
+mod provide_credentials {
+    use aoc_leaderboard::aoc::LeaderboardCredentials;
+    use aoc_leaderboard::test_helpers::test_leaderboard_credentials;
+    use aoc_leaderbot_lib::Error;
+    use aoc_leaderbot_lib::leaderbot::{CredentialsProvider, provide_credentials};
+    use assert_matches::assert_matches;
+    use rstest::rstest;
+
+    struct FailingProvider;
+
+    impl CredentialsProvider for FailingProvider {
+        type Err = std::io::Error;
+
+        async fn get_credentials(&self) -> Result<LeaderboardCredentials, Self::Err> {
+            Err(std::io::Error::other("vault is sealed"))
+        }
+    }
+
+    #[rstest]
+    #[test_log::test(tokio::test)]
+    async fn from_credentials(
+        #[from(test_leaderboard_credentials)] credentials: LeaderboardCredentials,
+    ) {
+        let actual = provide_credentials(&credentials).await.unwrap();
+        assert_eq!(actual, credentials);
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn with_error() {
+        let actual = provide_credentials(&FailingProvider).await;
+        assert_matches!(actual, Err(Error::Credentials(err)) => {
+            assert_eq!(err.to_string(), "vault is sealed");
+        });
+    }
+}
+
+mod credentials_from_secret {
+    use aoc_leaderboard::aoc::{LeaderboardCredentials, LeaderboardCredentialsKind};
+    use aoc_leaderbot_lib::leaderbot::credentials::credentials_from_secret;
+
+    #[test_log::test]
+    fn trims_secret() {
+        assert_eq!(
+            credentials_from_secret(LeaderboardCredentialsKind::SessionCookie, " cookie\n"),
+            Some(LeaderboardCredentials::SessionCookie("cookie".into()))
+        );
+        assert_eq!(
+            credentials_from_secret(LeaderboardCredentialsKind::ViewKey, "key"),
+            Some(LeaderboardCredentials::ViewKey("key".into()))
+        );
+    }
+
+    #[test_log::test]
+    fn empty_secret() {
+        assert_eq!(credentials_from_secret(LeaderboardCredentialsKind::ViewKey, " \n"), None);
+    }
+}
@@ -0,0 +1,246 @@
+mod daemon_schedule {
+    use aoc_leaderbot_lib::leaderbot::daemon::{DEFAULT_UNLOCK_DELAY, DaemonSchedule};
+    use aoc_leaderbot_lib::leaderbot::event::{EventWindow, MIN_INTERVAL};
+    use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+    use rstest::rstest;
+
+    fn utc(month: u32, day: u32, hour: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, month, day, hour, min, 0)
+            .unwrap()
+    }
+
+    #[test_log::test]
+    fn default() {
+        let schedule = DaemonSchedule::default();
+
+        assert_eq!(schedule.interval(), MIN_INTERVAL);
+        assert_eq!(schedule.unlock_delay(), Some(DEFAULT_UNLOCK_DELAY));
+    }
+
+    #[rstest]
+    #[case::less_than_min(TimeDelta::minutes(1), MIN_INTERVAL)]
+    #[case::min(MIN_INTERVAL, MIN_INTERVAL)]
+    #[case::more_than_min(TimeDelta::hours(1), TimeDelta::hours(1))]
+    #[test_log::test]
+    fn new(#[case] interval: TimeDelta, #[case] expected: TimeDelta) {
+        assert_eq!(DaemonSchedule::new(interval).interval(), expected);
+    }
+
+    #[test_log::test]
+    fn with_unlock_delay() {
+        let schedule = DaemonSchedule::default();
+
+        assert_eq!(schedule.with_unlock_delay(None).unlock_delay(), None);
+        assert_eq!(
+            schedule
+                .with_unlock_delay(Some(TimeDelta::minutes(5)))
+                .unlock_delay(),
+            Some(TimeDelta::minutes(5))
+        );
+        assert_eq!(
+            schedule
+                .with_unlock_delay(Some(TimeDelta::minutes(-5)))
+                .unlock_delay(),
+            Some(TimeDelta::zero())
+        );
+    }
+
+    #[rstest]
+    #[case::outside_event(Some(TimeDelta::minutes(10)), utc(11, 1, 12, 0), utc(11, 1, 13, 0))]
+    #[case::before_event(Some(TimeDelta::minutes(10)), utc(12, 1, 4, 30), utc(12, 1, 5, 10))]
+    #[case::before_unlock(Some(TimeDelta::minutes(10)), utc(12, 2, 4, 30), utc(12, 2, 5, 10))]
+    #[case::unlock_too_soon(Some(TimeDelta::minutes(10)), utc(12, 2, 5, 0), utc(12, 2, 5, 15))]
+    #[case::after_unlock(Some(TimeDelta::minutes(10)), utc(12, 2, 5, 10), utc(12, 2, 6, 10))]
+    #[case::after_event(Some(TimeDelta::minutes(10)), utc(12, 25, 5, 10), utc(12, 25, 6, 10))]
+    #[case::without_unlock_runs(None, utc(12, 2, 4, 30), utc(12, 2, 5, 30))]
+    #[test_log::test]
+    fn next_run(
+        #[case] unlock_delay: Option<TimeDelta>,
+        #[case] last_run: DateTime<Utc>,
+        #[case] expected: DateTime<Utc>,
+    ) {
+        let schedule = DaemonSchedule::new(TimeDelta::hours(1)).with_unlock_delay(unlock_delay);
+
+        assert_eq!(schedule.next_run(2024, last_run, None), expected);
+    }
+
+    #[rstest]
+    #[case::in_season(utc(12, 2, 4, 30), utc(12, 2, 5, 10))]
+    #[case::off_season(utc(11, 1, 12, 0), utc(12, 1, 5, 0))]
+    #[test_log::test]
+    fn next_run_with_event_window(
+        #[case] last_run: DateTime<Utc>,
+        #[case] expected: DateTime<Utc>,
+    ) {
+        let schedule = DaemonSchedule::new(TimeDelta::hours(1))
+            .with_unlock_delay(Some(TimeDelta::minutes(10)));
+        let event_window = EventWindow::new(TimeDelta::days(7));
+
+        assert_eq!(schedule.next_run(2024, last_run, Some(&event_window)), expected);
+    }
+}
+
+#[cfg(all(feature = "config-mem", feature = "storage-mem", feature = "reporter-recording"))]
+mod run_bot_forever {
+    use std::future::ready;
+    use std::time::Duration;
+
+    use aoc_leaderboard::aoc::Leaderboard;
+    use aoc_leaderboard::test_helpers::{
+        TEST_LEADERBOARD_ID, TEST_YEAR, mock_server_with_inaccessible_leaderboard,
+        mock_server_with_leaderboard, test_leaderboard, test_leaderboard_credentials,
+    };
+    use aoc_leaderboard::wiremock::MockServer;
+    use aoc_leaderbot_lib::ErrorKind;
+    use aoc_leaderbot_lib::leaderbot::Storage;
+    use aoc_leaderbot_lib::leaderbot::clock::TestClock;
+    use aoc_leaderbot_lib::leaderbot::config::mem::MemoryConfig;
+    use aoc_leaderbot_lib::leaderbot::daemon::{DaemonSchedule, run_bot_forever_from};
+    use aoc_leaderbot_lib::leaderbot::event::EventWindow;
+    use aoc_leaderbot_lib::leaderbot::reporter::recording::RecordingReporter;
+    use aoc_leaderbot_lib::leaderbot::storage::mem::MemoryStorage;
+    use assert_matches::assert_matches;
+    use chrono::{TimeZone, Utc};
+    use rstest::{fixture, rstest};
+
+    #[fixture]
+    fn config() -> MemoryConfig {
+        MemoryConfig::builder()
+            .year(TEST_YEAR)
+            .leaderboard_id(TEST_LEADERBOARD_ID)
+            .credentials(test_leaderboard_credentials::default())
+            .build()
+            .unwrap()
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn stops_on_shutdown(
+        config: MemoryConfig,
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        mock_server: MockServer,
+        #[from(test_leaderboard)] leaderboard: Leaderboard,
+    ) {
+        let mut storage = MemoryStorage::new();
+        let mut reporter = RecordingReporter::new();
+
+        let result = run_bot_forever_from(
+            Some(mock_server.uri()),
+            &config,
+            &mut storage,
+            &mut reporter,
+            &DaemonSchedule::default(),
+            false,
+            ready(()),
+        )
+        .await;
+        assert_matches!(result, Ok(()));
+
+        reporter.assert_first_run_reported(TEST_YEAR, TEST_LEADERBOARD_ID);
+        assert_eq!(reporter.call_count(), 1);
+
+        let (previous_leaderboard, _) = storage
+            .load_previous(TEST_YEAR, TEST_LEADERBOARD_ID)
+            .await
+            .unwrap();
+        assert_eq!(previous_leaderboard, Some(leaderboard));
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn stops_while_waiting(
+        config: MemoryConfig,
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        mock_server: MockServer,
+    ) {
+        let mut storage = MemoryStorage::new();
+        let mut reporter = RecordingReporter::new();
+
+        let result = run_bot_forever_from(
+            Some(mock_server.uri()),
+            &config,
+            &mut storage,
+            &mut reporter,
+            &DaemonSchedule::default(),
+            false,
+            tokio::time::sleep(Duration::from_millis(200)),
+        )
+        .await;
+        assert_matches!(result, Ok(()));
+        assert_eq!(reporter.call_count(), 1);
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn keeps_running_after_error(
+        config: MemoryConfig,
+        #[future]
+        #[from(mock_server_with_inaccessible_leaderboard)]
+        mock_server: MockServer,
+    ) {
+        let mut storage = MemoryStorage::new();
+        let mut reporter = RecordingReporter::new();
+
+        let result = run_bot_forever_from(
+            Some(mock_server.uri()),
+            &config,
+            &mut storage,
+            &mut reporter,
+            &DaemonSchedule::default(),
+            false,
+            ready(()),
+        )
+        .await;
+        assert_matches!(result, Ok(()));
+
+        reporter.assert_error_reported(
+            TEST_YEAR,
+            TEST_LEADERBOARD_ID,
+            ErrorKind::Leaderboard(aoc_leaderboard::ErrorKind::NoAccess),
+        );
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn skips_first_run_outside_event_window(
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        mock_server: MockServer,
+    ) {
+        let config = MemoryConfig::builder()
+            .year(TEST_YEAR)
+            .leaderboard_id(TEST_LEADERBOARD_ID)
+            .credentials(test_leaderboard_credentials::default())
+            .event_window(EventWindow::default())
+            .clock(TestClock::new(Utc.with_ymd_and_hms(TEST_YEAR, 7, 1, 12, 0, 0).unwrap()))
+            .build()
+            .unwrap();
+        let mut storage = MemoryStorage::new();
+        let mut reporter = RecordingReporter::new();
+
+        let result = run_bot_forever_from(
+            Some(mock_server.uri()),
+            &config,
+            &mut storage,
+            &mut reporter,
+            &DaemonSchedule::default(),
+            false,
+            ready(()),
+        )
+        .await;
+        assert_matches!(result, Ok(()));
+
+        reporter.assert_not_called();
+        assert_matches!(
+            storage.load_previous(TEST_YEAR, TEST_LEADERBOARD_ID).await,
+            Ok((None, None))
+        );
+        assert!(mock_server.received_requests().await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,364 @@
+mod run_diagnostics {
+    use std::time::Duration;
+
+    use aoc_leaderboard::aoc::Leaderboard;
+    use aoc_leaderboard::test_helpers::{TEST_LEADERBOARD_ID, TEST_YEAR, test_leaderboard};
+    use aoc_leaderbot_lib::leaderbot::BotOutput;
+    use aoc_leaderbot_lib::leaderbot::diagnostics::{
+        FetchDiagnostics, OperationDiagnostics, OperationOutcome, RunDiagnostics,
+    };
+    use rstest::rstest;
+    use serde_json::json;
+
+    fn operation(name: &str, outcome: OperationOutcome) -> OperationDiagnostics {
+        OperationDiagnostics { name: name.into(), duration_ms: 5, outcome }
+    }
+
+    #[test_log::test]
+    fn default() {
+        let diagnostics = RunDiagnostics::default();
+
+        assert!(diagnostics.fetches.is_empty());
+        assert!(diagnostics.storage_operations.is_empty());
+        assert!(diagnostics.reporter_calls.is_empty());
+        assert!(diagnostics.previous_error.is_none());
+        assert_eq!(diagnostics.fetch_latency(), Duration::ZERO);
+    }
+
+    #[test_log::test]
+    fn helpers() {
+        let diagnostics = RunDiagnostics {
+            fetches: vec![
+                FetchDiagnostics {
+                    leaderboard_id: 1,
+                    http_status: Some(200),
+                    latency_ms: 120,
+                    throttled: false,
+                },
+                FetchDiagnostics {
+                    leaderboard_id: 2,
+                    http_status: Some(200),
+                    latency_ms: 80,
+                    throttled: false,
+                },
+                FetchDiagnostics {
+                    leaderboard_id: 3,
+                    http_status: None,
+                    latency_ms: 50,
+                    throttled: true,
+                },
+            ],
+            storage_operations: vec![
+                operation("load_previous", OperationOutcome::Succeeded),
+                operation("save_success", OperationOutcome::Succeeded),
+            ],
+            reporter_calls: vec![operation(
+                "report_changes",
+                OperationOutcome::Failed { message: "oops".into() },
+            )],
+            previous_error: None,
+        };
+
+        assert_eq!(diagnostics.fetch_latency(), Duration::from_millis(200));
+        assert_eq!(diagnostics.storage_operation_names(), ["load_previous", "save_success"]);
+        assert_eq!(diagnostics.reporter_call_names(), ["report_changes"]);
+    }
+
+    #[rstest]
+    #[case::succeeded(OperationOutcome::Succeeded, json!({ "status": "succeeded" }))]
+    #[case::failed(
+        OperationOutcome::Failed { message: "oops".into() },
+        json!({ "status": "failed", "message": "oops" }),
+    )]
+    #[case::unchecked(OperationOutcome::Unchecked, json!({ "status": "unchecked" }))]
+    #[test_log::test]
+    fn outcome_serialization(
+        #[case] outcome: OperationOutcome,
+        #[case] expected: serde_json::Value,
+    ) {
+        assert_eq!(serde_json::to_value(&outcome).unwrap(), expected);
+        assert_eq!(serde_json::from_value::<OperationOutcome>(expected).unwrap(), outcome);
+    }
+
+    #[rstest]
+    #[test_log::test]
+    fn bot_output_without_diagnostics(#[from(test_leaderboard)] leaderboard: Leaderboard) {
+        let output = json!({
+            "year": TEST_YEAR,
+            "leaderboard_id": TEST_LEADERBOARD_ID,
+            "previous_leaderboard": null,
+            "leaderboard": leaderboard,
+            "changes": null,
+        });
+
+        let output: BotOutput = serde_json::from_value(output).unwrap();
+        assert_eq!(output.diagnostics, RunDiagnostics::default());
+    }
+}
+
+#[cfg(all(feature = "config-mem", feature = "storage-mem", feature = "reporter-recording"))]
+mod run_bot {
+    use aoc_leaderboard::aoc::Leaderboard;
+    use aoc_leaderboard::test_helpers::{
+        TEST_LEADERBOARD_ID, TEST_YEAR, mock_server_with_inaccessible_leaderboard,
+        mock_server_with_leaderboard, test_leaderboard, test_leaderboard_credentials,
+    };
+    use aoc_leaderboard::wiremock::MockServer;
+    use aoc_leaderbot_lib::ErrorKind;
+    use aoc_leaderbot_lib::error::StorageErrorKind;
+    use aoc_leaderbot_lib::leaderbot::clock::TestClock;
+    use aoc_leaderbot_lib::leaderbot::config::mem::MemoryConfig;
+    use aoc_leaderbot_lib::leaderbot::diagnostics::{OperationOutcome, PreviousErrorDiagnostics};
+    use aoc_leaderbot_lib::leaderbot::heartbeat::HeartbeatPolicy;
+    use aoc_leaderbot_lib::leaderbot::outbox::{
+        run_bot_outbox_diagnosed_from, run_bot_outbox_from,
+    };
+    use aoc_leaderbot_lib::leaderbot::reporter::recording::RecordingReporter;
+    use aoc_leaderbot_lib::leaderbot::storage::mem::MemoryStorage;
+    use aoc_leaderbot_lib::leaderbot::{ErrorState, Storage, run_bot_diagnosed_from, run_bot_from};
+    use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+    use rstest::{fixture, rstest};
+
+    const PREVIOUS_ERROR: ErrorKind = ErrorKind::Storage(StorageErrorKind::SaveSuccess);
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(TEST_YEAR, 12, 15, 12, 0, 0).unwrap()
+    }
+
+    #[fixture]
+    fn config() -> MemoryConfig {
+        MemoryConfig::builder()
+            .year(TEST_YEAR)
+            .leaderboard_id(TEST_LEADERBOARD_ID)
+            .credentials(test_leaderboard_credentials::default())
+            .clock(TestClock::new(now()))
+            .build()
+            .unwrap()
+    }
+
+    async fn storage_with_error(previous: &Leaderboard, notified: bool) -> MemoryStorage {
+        let first_seen = now() - TimeDelta::hours(1);
+        let error_state = ErrorState {
+            last_notified: Some(first_seen),
+            ..ErrorState::new(PREVIOUS_ERROR, first_seen)
+        };
+        let error_state = match notified {
+            true => error_state,
+            false => error_state.recur(PREVIOUS_ERROR, now() - TimeDelta::minutes(30)),
+        };
+
+        let mut storage = MemoryStorage::new();
+        storage
+            .save_success(TEST_YEAR, TEST_LEADERBOARD_ID, previous)
+            .await
+            .unwrap();
+        storage
+            .save_error_state(TEST_YEAR, TEST_LEADERBOARD_ID, &error_state)
+            .await
+            .unwrap();
+        storage
+    }
+
+    #[rstest]
+    #[case::notified(true, 1)]
+    #[case::suppressed(false, 2)]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn recovery(
+        config: MemoryConfig,
+        #[from(test_leaderboard)] leaderboard: Leaderboard,
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        mock_server: MockServer,
+        #[case] notified: bool,
+        #[case] expected_occurrences: u64,
+    ) {
+        let mut storage = storage_with_error(&leaderboard, notified).await;
+        let mut reporter = RecordingReporter::new();
+
+        let output =
+            run_bot_from(Some(mock_server.uri()), &config, &mut storage, &mut reporter, false)
+                .await
+                .unwrap()
+                .unwrap();
+
+        let diagnostics = output.diagnostics;
+        assert_eq!(
+            diagnostics.previous_error,
+            Some(PreviousErrorDiagnostics {
+                error_kind: PREVIOUS_ERROR,
+                occurrences: Some(expected_occurrences),
+                suppressed: Some(!notified),
+            })
+        );
+        assert_eq!(
+            diagnostics.storage_operation_names(),
+            ["load_previous", "load_error_state", "save_success"]
+        );
+        assert!(
+            diagnostics
+                .storage_operations
+                .iter()
+                .all(|operation| operation.outcome == OperationOutcome::Succeeded)
+        );
+        assert_eq!(diagnostics.reporter_call_names(), ["report_recovery"]);
+        assert_eq!(diagnostics.reporter_calls[0].outcome, OperationOutcome::Unchecked);
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn with_heartbeat(
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        mock_server: MockServer,
+    ) {
+        let config = MemoryConfig::builder()
+            .year(TEST_YEAR)
+            .leaderboard_id(TEST_LEADERBOARD_ID)
+            .credentials(test_leaderboard_credentials::default())
+            .heartbeat_policy(HeartbeatPolicy::default().with_max_silence(TimeDelta::hours(2)))
+            .clock(TestClock::new(now()))
+            .build()
+            .unwrap();
+        let mut storage = MemoryStorage::new();
+        let mut reporter = RecordingReporter::new();
+
+        let output =
+            run_bot_from(Some(mock_server.uri()), &config, &mut storage, &mut reporter, false)
+                .await
+                .unwrap()
+                .unwrap();
+
+        assert_eq!(
+            output.diagnostics.storage_operation_names(),
+            ["load_previous", "save_success", "load_heartbeat", "save_heartbeat"]
+        );
+        assert_eq!(output.diagnostics.reporter_call_names(), ["report_first_run"]);
+        assert!(output.diagnostics.previous_error.is_none());
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn outbox(
+        config: MemoryConfig,
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        mock_server: MockServer,
+    ) {
+        let mut storage = MemoryStorage::new();
+        let mut reporter = RecordingReporter::new();
+
+        let output = run_bot_outbox_from(
+            Some(mock_server.uri()),
+            &config,
+            &mut storage,
+            &mut reporter,
+            false,
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        let diagnostics = output.diagnostics;
+        assert_eq!(diagnostics.fetches.len(), 1);
+        assert_eq!(diagnostics.fetches[0].leaderboard_id, TEST_LEADERBOARD_ID);
+        assert_eq!(diagnostics.fetches[0].http_status, Some(200));
+        assert!(!diagnostics.fetches[0].throttled);
+        assert_eq!(
+            diagnostics.storage_operation_names(),
+            ["load_previous", "load_outbox", "save_success_with_outbox", "mark_delivered"]
+        );
+        assert_eq!(diagnostics.reporter_call_names(), ["report_first_run"]);
+        assert_eq!(diagnostics.reporter_calls[0].outcome, OperationOutcome::Succeeded);
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn error(
+        config: MemoryConfig,
+        #[future]
+        #[from(mock_server_with_inaccessible_leaderboard)]
+        mock_server: MockServer,
+    ) {
+        let mut storage = MemoryStorage::new();
+        let mut reporter = RecordingReporter::new();
+
+        let err = run_bot_diagnosed_from(
+            Some(mock_server.uri()),
+            &config,
+            &mut storage,
+            &mut reporter,
+            false,
+        )
+        .await
+        .unwrap_err();
+
+        assert!(err.error.is_leaderboard());
+        let diagnostics = err.diagnostics;
+        assert!(diagnostics.fetches.is_empty());
+        assert_eq!(diagnostics.storage_operation_names(), ["load_previous", "save_error_state"]);
+        assert_eq!(diagnostics.storage_operations[1].outcome, OperationOutcome::Succeeded);
+        assert_eq!(diagnostics.reporter_call_names(), ["report_error"]);
+        assert_eq!(diagnostics.reporter_calls[0].outcome, OperationOutcome::Unchecked);
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn error_dry_run(
+        config: MemoryConfig,
+        #[future]
+        #[from(mock_server_with_inaccessible_leaderboard)]
+        mock_server: MockServer,
+    ) {
+        let mut storage = MemoryStorage::new();
+        let mut reporter = RecordingReporter::new();
+
+        let err = run_bot_diagnosed_from(
+            Some(mock_server.uri()),
+            &config,
+            &mut storage,
+            &mut reporter,
+            true,
+        )
+        .await
+        .unwrap_err();
+
+        assert!(err.error.is_leaderboard());
+        assert_eq!(err.diagnostics.storage_operation_names(), ["load_previous"]);
+        assert!(err.diagnostics.reporter_calls.is_empty());
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn outbox_error(
+        config: MemoryConfig,
+        #[future]
+        #[from(mock_server_with_inaccessible_leaderboard)]
+        mock_server: MockServer,
+    ) {
+        let mut storage = MemoryStorage::new();
+        let mut reporter = RecordingReporter::new();
+
+        let err = run_bot_outbox_diagnosed_from(
+            Some(mock_server.uri()),
+            &config,
+            &mut storage,
+            &mut reporter,
+            false,
+        )
+        .await
+        .unwrap_err();
+
+        assert!(err.error.is_leaderboard());
+        assert_eq!(
+            err.diagnostics.storage_operation_names(),
+            ["load_previous", "load_outbox", "save_error_state"]
+        );
+        assert_eq!(err.diagnostics.reporter_call_names(), ["report_error"]);
+    }
+}
@@ -0,0 +1,436 @@
+mod digest_schedule {
+    use aoc_leaderbot_lib::leaderbot::digest::DigestSchedule;
+    use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+    use rstest::rstest;
+
+    fn time(hour: u32, min: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, min, 0).unwrap()
+    }
+
+    fn date_time(day: u32, hour: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 12, day)
+            .unwrap()
+            .and_time(time(hour, min))
+    }
+
+    #[test_log::test]
+    fn new() {
+        let schedule = DigestSchedule::new([time(18, 0), time(8, 0), time(18, 0)]);
+
+        assert_eq!(schedule.flush_times(), [time(8, 0), time(18, 0)]);
+    }
+
+    #[rstest]
+    #[case::before_flush_time(date_time(1, 7, 0), date_time(1, 7, 59), false)]
+    #[case::at_flush_time(date_time(1, 7, 0), date_time(1, 8, 0), true)]
+    #[case::after_flush_time(date_time(1, 7, 0), date_time(1, 8, 15), true)]
+    #[case::since_flush_time(date_time(1, 8, 0), date_time(1, 8, 15), false)]
+    #[case::between_flush_times(date_time(1, 8, 15), date_time(1, 17, 45), false)]
+    #[case::second_flush_time(date_time(1, 8, 15), date_time(1, 18, 15), true)]
+    #[case::overnight(date_time(1, 18, 15), date_time(2, 7, 45), false)]
+    #[case::next_morning(date_time(1, 18, 15), date_time(2, 8, 15), true)]
+    #[case::more_than_a_day(date_time(1, 8, 15), date_time(3, 7, 45), true)]
+    #[case::now_before_since(date_time(2, 7, 0), date_time(1, 9, 0), false)]
+    #[test_log::test]
+    fn is_flush_due(
+        #[case] since: NaiveDateTime,
+        #[case] now: NaiveDateTime,
+        #[case] expected: bool,
+    ) {
+        let schedule = DigestSchedule::new([time(8, 0), time(18, 0)]);
+
+        assert_eq!(schedule.is_flush_due(since, now), expected);
+    }
+
+    #[test_log::test]
+    fn is_flush_due_without_flush_times() {
+        let schedule = DigestSchedule::default();
+
+        assert!(!schedule.is_flush_due(date_time(1, 7, 0), date_time(3, 7, 0)));
+    }
+}
+
+#[cfg(all(feature = "config-mem", feature = "storage-mem", feature = "reporter-recording"))]
+mod run_bot_digest {
+    use aoc_leaderboard::aoc::Leaderboard;
+    use aoc_leaderboard::test_helpers::{
+        TEST_LEADERBOARD_ID, TEST_YEAR, mock_server_with_leaderboard, test_leaderboard,
+    };
+    use aoc_leaderboard::wiremock::MockServer;
+    use aoc_leaderbot_lib::ErrorKind;
+    use aoc_leaderbot_lib::error::ReporterErrorKind;
+    use aoc_leaderbot_lib::leaderbot::config::mem::MemoryConfig;
+    use aoc_leaderbot_lib::leaderbot::digest::{DigestOutput, DigestSchedule, run_bot_digest_from};
+    use aoc_leaderbot_lib::leaderbot::reporter::recording::RecordingReporter;
+    use aoc_leaderbot_lib::leaderbot::storage::mem::MemoryStorage;
+    use aoc_leaderbot_lib::leaderbot::{Changes, DigestStorage, PendingChanges, Storage};
+    use assert_matches::assert_matches;
+    use chrono::{NaiveTime, TimeDelta, Utc};
+    use rstest::{fixture, rstest};
+
+    #[fixture]
+    fn config() -> MemoryConfig {
+        MemoryConfig::builder()
+            .year(TEST_YEAR)
+            .leaderboard_id(TEST_LEADERBOARD_ID)
+            .credentials(aoc_leaderboard::test_helpers::test_leaderboard_credentials::default())
+            .build()
+            .unwrap()
+    }
+
+    #[fixture]
+    fn member_id(#[from(test_leaderboard)] leaderboard: Leaderboard) -> u64 {
+        *leaderboard.members.keys().min().unwrap()
+    }
+
+    #[fixture]
+    fn updated_leaderboard(
+        #[from(test_leaderboard)] mut leaderboard: Leaderboard,
+        member_id: u64,
+    ) -> Leaderboard {
+        leaderboard.members.get_mut(&member_id).unwrap().stars += 1;
+        leaderboard
+    }
+
+    fn always_due() -> DigestSchedule {
+        DigestSchedule::new([NaiveTime::MIN])
+    }
+
+    async fn storage_with(
+        previous: &Leaderboard,
+        pending_changes: Option<&PendingChanges>,
+    ) -> MemoryStorage {
+        let mut storage = MemoryStorage::new();
+        storage
+            .save_success(TEST_YEAR, TEST_LEADERBOARD_ID, previous)
+            .await
+            .unwrap();
+        if let Some(pending_changes) = pending_changes {
+            storage
+                .save_pending_changes(TEST_YEAR, TEST_LEADERBOARD_ID, pending_changes)
+                .await
+                .unwrap();
+        }
+        storage
+    }
+
+    async fn load_pending_changes(storage: &MemoryStorage) -> Option<PendingChanges> {
+        storage
+            .load_pending_changes(TEST_YEAR, TEST_LEADERBOARD_ID)
+            .await
+            .unwrap()
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn first_run(
+        config: MemoryConfig,
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        mock_server: MockServer,
+    ) {
+        let mut storage = MemoryStorage::new();
+        let mut reporter = RecordingReporter::new();
+
+        let result = run_bot_digest_from(
+            Some(mock_server.uri()),
+            &config,
+            &mut storage,
+            &mut reporter,
+            &always_due(),
+            false,
+        )
+        .await;
+        assert_matches!(
+            result,
+            Ok(Some(DigestOutput { reported_changes: None, pending_changes: None, .. }))
+        );
+
+        reporter.assert_first_run_reported(TEST_YEAR, TEST_LEADERBOARD_ID);
+        assert!(load_pending_changes(&storage).await.is_none());
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn accumulates_changes(
+        config: MemoryConfig,
+        #[from(test_leaderboard)] base: Leaderboard,
+        updated_leaderboard: Leaderboard,
+        member_id: u64,
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        #[with(updated_leaderboard.clone())]
+        mock_server: MockServer,
+    ) {
+        let mut storage = storage_with(&base, None).await;
+        let mut reporter = RecordingReporter::new();
+
+        let result = run_bot_digest_from(
+            Some(mock_server.uri()),
+            &config,
+            &mut storage,
+            &mut reporter,
+            &DigestSchedule::default(),
+            false,
+        )
+        .await;
+        let expected_changes = Changes::new([].into(), [member_id].into());
+        assert_matches!(result, Ok(Some(DigestOutput { bot_output, reported_changes: None, pending_changes: Some(pending_changes) })) => {
+            assert_eq!(bot_output.changes.as_ref(), Some(&expected_changes));
+            assert_eq!(pending_changes.previous_leaderboard, base);
+            assert_eq!(pending_changes.changes, expected_changes);
+        });
+
+        reporter.assert_not_called();
+        let pending_changes = load_pending_changes(&storage).await.unwrap();
+        assert_eq!(pending_changes.previous_leaderboard, base);
+        assert_eq!(pending_changes.changes, expected_changes);
+        let (previous, _) = storage
+            .load_previous(TEST_YEAR, TEST_LEADERBOARD_ID)
+            .await
+            .unwrap();
+        assert_eq!(previous, Some(updated_leaderboard));
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn merges_changes(
+        config: MemoryConfig,
+        #[from(test_leaderboard)] base: Leaderboard,
+        updated_leaderboard: Leaderboard,
+        member_id: u64,
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        #[with(updated_leaderboard.clone())]
+        mock_server: MockServer,
+    ) {
+        let _ = updated_leaderboard;
+
+        let since = Utc::now() - TimeDelta::minutes(15);
+        let existing =
+            PendingChanges::new(base.clone(), Changes::new([42].into(), [23].into()), since);
+        let mut storage = storage_with(&base, Some(&existing)).await;
+        let mut reporter = RecordingReporter::new();
+
+        run_bot_digest_from(
+            Some(mock_server.uri()),
+            &config,
+            &mut storage,
+            &mut reporter,
+            &DigestSchedule::default(),
+            false,
+        )
+        .await
+        .unwrap();
+
+        reporter.assert_not_called();
+        let pending_changes = load_pending_changes(&storage).await.unwrap();
+        assert_eq!(pending_changes.since, since);
+        assert_eq!(pending_changes.changes, Changes::new([42].into(), [23, member_id].into()));
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn flushes_changes(
+        config: MemoryConfig,
+        #[from(test_leaderboard)] base: Leaderboard,
+        updated_leaderboard: Leaderboard,
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        #[with(updated_leaderboard.clone())]
+        mock_server: MockServer,
+    ) {
+        let existing = PendingChanges::new(
+            base.clone(),
+            Changes::new([42].into(), [].into()),
+            Utc::now() - TimeDelta::days(2),
+        );
+        let mut storage = storage_with(&updated_leaderboard, Some(&existing)).await;
+        let mut reporter = RecordingReporter::new();
+
+        let result = run_bot_digest_from(
+            Some(mock_server.uri()),
+            &config,
+            &mut storage,
+            &mut reporter,
+            &always_due(),
+            false,
+        )
+        .await;
+        assert_matches!(result, Ok(Some(DigestOutput { bot_output, reported_changes: Some(reported_changes), pending_changes: None })) => {
+            assert!(bot_output.changes.is_none());
+            assert_eq!(reported_changes, existing.changes);
+        });
+
+        let reported =
+            reporter.assert_changes_reported(TEST_YEAR, TEST_LEADERBOARD_ID, &existing.changes);
+        assert_eq!(reported.previous_leaderboard, base);
+        assert_eq!(reported.leaderboard, updated_leaderboard);
+        assert!(load_pending_changes(&storage).await.is_none());
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn keeps_changes_if_flush_fails(
+        config: MemoryConfig,
+        #[from(test_leaderboard)] base: Leaderboard,
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        mock_server: MockServer,
+    ) {
+        let existing = PendingChanges::new(
+            base.clone(),
+            Changes::new([42].into(), [].into()),
+            Utc::now() - TimeDelta::days(2),
+        );
+        let mut storage = storage_with(&base, Some(&existing)).await;
+        let mut reporter = RecordingReporter::new().fail_report_changes(true);
+
+        let result = run_bot_digest_from(
+            Some(mock_server.uri()),
+            &config,
+            &mut storage,
+            &mut reporter,
+            &always_due(),
+            false,
+        )
+        .await;
+        assert_matches!(result, Err(err) if err.is_reporter());
+
+        assert_eq!(reporter.changes().count(), 1);
+        assert_eq!(
+            reporter
+                .errors()
+                .map(|error| error.error_kind)
+                .collect::<Vec<_>>(),
+            [ErrorKind::Reporter(ReporterErrorKind::ReportChanges)]
+        );
+        assert_eq!(load_pending_changes(&storage).await, Some(existing));
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn persists_new_changes_if_flush_fails(
+        config: MemoryConfig,
+        #[from(test_leaderboard)] base: Leaderboard,
+        updated_leaderboard: Leaderboard,
+        member_id: u64,
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        #[with(updated_leaderboard.clone())]
+        mock_server: MockServer,
+    ) {
+        let _ = updated_leaderboard;
+
+        let existing = PendingChanges::new(
+            base.clone(),
+            Changes::new([42].into(), [].into()),
+            Utc::now() - TimeDelta::days(2),
+        );
+        let mut storage = storage_with(&base, Some(&existing)).await;
+        let mut reporter = RecordingReporter::new().fail_report_changes(true);
+
+        let result = run_bot_digest_from(
+            Some(mock_server.uri()),
+            &config,
+            &mut storage,
+            &mut reporter,
+            &always_due(),
+            false,
+        )
+        .await;
+        assert_matches!(result, Err(err) if err.is_reporter());
+
+        let pending_changes = load_pending_changes(&storage).await.unwrap();
+        assert_eq!(pending_changes.since, existing.since);
+        assert_eq!(pending_changes.changes, Changes::new([42].into(), [member_id].into()));
+        let (previous, previous_error) = storage
+            .load_previous(TEST_YEAR, TEST_LEADERBOARD_ID)
+            .await
+            .unwrap();
+        assert_eq!(previous, Some(base));
+        assert_eq!(previous_error, Some(ErrorKind::Reporter(ReporterErrorKind::ReportChanges)));
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn does_not_repeat_errors(
+        config: MemoryConfig,
+        #[from(test_leaderboard)] base: Leaderboard,
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        mock_server: MockServer,
+    ) {
+        let existing = PendingChanges::new(
+            base.clone(),
+            Changes::new([42].into(), [].into()),
+            Utc::now() - TimeDelta::days(2),
+        );
+        let mut storage = storage_with(&base, Some(&existing)).await;
+        let mut reporter = RecordingReporter::new().fail_report_changes(true);
+
+        for _ in 0..2 {
+            let result = run_bot_digest_from(
+                Some(mock_server.uri()),
+                &config,
+                &mut storage,
+                &mut reporter,
+                &always_due(),
+                false,
+            )
+            .await;
+            assert_matches!(result, Err(err) if err.is_reporter());
+        }
+
+        assert_eq!(reporter.changes().count(), 2);
+        assert_eq!(reporter.errors().count(), 1);
+        let error_state = storage
+            .load_error_state(TEST_YEAR, TEST_LEADERBOARD_ID)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(error_state.occurrences, 2);
+        assert_eq!(load_pending_changes(&storage).await, Some(existing));
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn dry_run(
+        config: MemoryConfig,
+        #[from(test_leaderboard)] base: Leaderboard,
+        updated_leaderboard: Leaderboard,
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        #[with(updated_leaderboard.clone())]
+        mock_server: MockServer,
+    ) {
+        let _ = updated_leaderboard;
+
+        let mut storage = storage_with(&base, None).await;
+        let mut reporter = RecordingReporter::new();
+
+        let result = run_bot_digest_from(
+            Some(mock_server.uri()),
+            &config,
+            &mut storage,
+            &mut reporter,
+            &always_due(),
+            true,
+        )
+        .await;
+        assert_matches!(result, Ok(Some(DigestOutput { bot_output, reported_changes: None, pending_changes: None })) => {
+            assert!(bot_output.changes.is_some());
+        });
+
+        reporter.assert_not_called();
+        assert!(load_pending_changes(&storage).await.is_none());
+    }
+}
@@ -0,0 +1,230 @@
+#[cfg(all(feature = "storage-mem", feature = "reporter-recording"))]
+mod backend_registry {
+    use aoc_leaderboard::aoc::Leaderboard;
+    use aoc_leaderboard::test_helpers::{TEST_LEADERBOARD_ID, TEST_YEAR, test_leaderboard};
+    use aoc_leaderbot_lib::error::BackendKind;
+    use aoc_leaderbot_lib::leaderbot::Reporter;
+    use aoc_leaderbot_lib::leaderbot::dynamic::BackendRegistry;
+    use aoc_leaderbot_lib::leaderbot::reporter::recording::RecordingReporter;
+    use aoc_leaderbot_lib::leaderbot::storage::mem::MemoryStorage;
+    use aoc_leaderbot_lib::{Error, ErrorKind};
+    use assert_matches::assert_matches;
+    use rstest::rstest;
+    use serde_json::json;
+
+    fn registry() -> BackendRegistry {
+        BackendRegistry::new()
+            .with_storage("memory", |_| async { Ok::<_, anyhow::Error>(MemoryStorage::new()) })
+            .with_outbox_storage("memory-outbox", |_| async {
+                Ok::<_, anyhow::Error>(MemoryStorage::new())
+            })
+            .with_reporter("recording", |settings| async move {
+                let fail = settings["fail"].as_bool().unwrap_or_default();
+                Ok::<_, anyhow::Error>(RecordingReporter::new().fail_report_first_run(fail))
+            })
+            .with_reporter("broken", |_| async {
+                Err::<RecordingReporter, _>(anyhow::anyhow!("missing webhook URL"))
+            })
+    }
+
+    #[test_log::test]
+    fn new() {
+        let registry = BackendRegistry::new();
+
+        assert_eq!(registry.storage_names().count(), 0);
+        assert_eq!(registry.outbox_storage_names().count(), 0);
+        assert_eq!(registry.reporter_names().count(), 0);
+    }
+
+    #[test_log::test]
+    fn names() {
+        let registry = registry();
+
+        assert_eq!(registry.storage_names().collect::<Vec<_>>(), vec!["memory", "memory-outbox"]);
+        assert_eq!(registry.outbox_storage_names().collect::<Vec<_>>(), vec!["memory-outbox"]);
+        assert_eq!(registry.reporter_names().collect::<Vec<_>>(), vec!["broken", "recording"]);
+        assert_eq!(
+            format!("{registry:?}"),
+            r#"BackendRegistry { storages: ["memory", "memory-outbox"], outbox_storages: ["memory-outbox"], reporters: ["broken", "recording"] }"#
+        );
+    }
+
+    #[test_log::test]
+    fn register_replaces() {
+        let mut registry = registry();
+        registry
+            .register_storage("memory", |_| async { Ok::<_, anyhow::Error>(MemoryStorage::new()) });
+        registry.register_storage("memory-outbox", |_| async {
+            Ok::<_, anyhow::Error>(MemoryStorage::new())
+        });
+
+        assert_eq!(registry.storage_names().count(), 2);
+        assert_eq!(registry.outbox_storage_names().count(), 0);
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn build_storage() {
+        let registry = registry();
+
+        assert!(registry.build_storage("memory", json!({})).await.is_ok());
+        assert!(
+            registry
+                .build_storage("memory-outbox", json!({}))
+                .await
+                .is_ok()
+        );
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn build_outbox_storage() {
+        let registry = registry();
+
+        assert!(
+            registry
+                .build_outbox_storage("memory-outbox", json!({}))
+                .await
+                .is_ok()
+        );
+
+        let result = registry.build_outbox_storage("memory", json!({})).await;
+        assert_matches!(result, Err(Error::UnknownBackend { kind: BackendKind::Storage, name }) => {
+            assert_eq!(name, "memory");
+        });
+    }
+
+    #[rstest]
+    #[test_log::test(tokio::test)]
+    async fn build_reporter_with_settings(#[from(test_leaderboard)] leaderboard: Leaderboard) {
+        let registry = registry();
+
+        let mut reporter = registry
+            .build_reporter("recording", json!({ "fail": true }))
+            .await
+            .unwrap();
+        let result = reporter
+            .report_first_run(TEST_YEAR, TEST_LEADERBOARD_ID, None, &leaderboard)
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn unknown_backend() {
+        let registry = registry();
+
+        let result = registry.build_storage("dynamodb", json!({})).await;
+        assert_matches!(result, Err(Error::UnknownBackend { kind: BackendKind::Storage, name }) => {
+            assert_eq!(name, "dynamodb");
+        });
+
+        let error = registry
+            .build_reporter("slack", json!({}))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(error.to_string(), "unknown reporter backend: slack");
+        assert_eq!(ErrorKind::from(&error), ErrorKind::UnknownBackend);
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn factory_error() {
+        let registry = registry();
+
+        let error = registry
+            .build_reporter("broken", json!({}))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(
+            error.to_string(),
+            "failed to build reporter backend broken: missing webhook URL"
+        );
+        assert_eq!(ErrorKind::from(&error), ErrorKind::Backend);
+    }
+}
+
+#[cfg(all(feature = "storage-mem", feature = "storage-conformance"))]
+mod dyn_storage {
+    use aoc_leaderbot_lib::leaderbot::dynamic::DynStorage;
+    use aoc_leaderbot_lib::leaderbot::storage::conformance::{
+        check_error_state, check_heartbeat, check_storage,
+    };
+    use aoc_leaderbot_lib::leaderbot::storage::mem::MemoryStorage;
+
+    #[test_log::test(tokio::test)]
+    async fn conformance() {
+        let mut storage: Box<dyn DynStorage> = Box::new(MemoryStorage::new());
+
+        check_storage(&mut storage).await;
+        check_error_state(&mut storage).await;
+        check_heartbeat(&mut storage).await;
+    }
+}
+
+#[cfg(all(feature = "storage-mem", feature = "storage-conformance"))]
+mod dyn_outbox_storage {
+    use aoc_leaderbot_lib::leaderbot::dynamic::DynOutboxStorage;
+    use aoc_leaderbot_lib::leaderbot::storage::conformance::{
+        check_error_state, check_heartbeat, check_outbox_storage, check_storage,
+    };
+    use aoc_leaderbot_lib::leaderbot::storage::mem::MemoryStorage;
+
+    #[test_log::test(tokio::test)]
+    async fn conformance() {
+        let mut storage: Box<dyn DynOutboxStorage> = Box::new(MemoryStorage::new());
+
+        check_storage(&mut storage).await;
+        check_outbox_storage(&mut storage).await;
+        check_error_state(&mut storage).await;
+        check_heartbeat(&mut storage).await;
+    }
+}
+
+#[cfg(all(feature = "config-mem", feature = "storage-mem", feature = "reporter-recording"))]
+mod run_bot {
+    use aoc_leaderboard::aoc::Leaderboard;
+    use aoc_leaderboard::test_helpers::{
+        TEST_LEADERBOARD_ID, TEST_YEAR, mock_server_with_leaderboard, test_leaderboard,
+        test_leaderboard_credentials,
+    };
+    use aoc_leaderboard::wiremock::MockServer;
+    use aoc_leaderbot_lib::leaderbot::config::mem::MemoryConfig;
+    use aoc_leaderbot_lib::leaderbot::dynamic::{DynReporter, DynStorage};
+    use aoc_leaderbot_lib::leaderbot::reporter::recording::RecordingReporter;
+    use aoc_leaderbot_lib::leaderbot::storage::mem::MemoryStorage;
+    use aoc_leaderbot_lib::leaderbot::{Storage, run_bot_from};
+    use rstest::rstest;
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn with_trait_objects(
+        #[from(test_leaderboard)] leaderboard: Leaderboard,
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        mock_server: MockServer,
+    ) {
+        let config = MemoryConfig::builder()
+            .year(TEST_YEAR)
+            .leaderboard_id(TEST_LEADERBOARD_ID)
+            .credentials(test_leaderboard_credentials::default())
+            .build()
+            .unwrap();
+        let mut recording_reporter = RecordingReporter::new();
+
+        let mut storage: Box<dyn DynStorage> = Box::new(MemoryStorage::new());
+        {
+            let mut reporter: Box<dyn DynReporter + '_> = Box::new(&mut recording_reporter);
+            run_bot_from(Some(mock_server.uri()), &config, &mut storage, &mut reporter, false)
+                .await
+                .unwrap();
+        }
+
+        recording_reporter.assert_first_run_reported(TEST_YEAR, TEST_LEADERBOARD_ID);
+        let (previous_leaderboard, previous_error) =
+            Storage::load_previous(&storage, TEST_YEAR, TEST_LEADERBOARD_ID)
+                .await
+                .unwrap();
+        assert_eq!(previous_leaderboard, Some(leaderboard));
+        assert!(previous_error.is_none());
+    }
+}
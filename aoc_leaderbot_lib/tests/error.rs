@@ -3,7 +3,7 @@ use std::env;
 
 use anyhow::anyhow;
 use aoc_leaderbot_lib::Error;
-use aoc_leaderbot_lib::error::{EnvVarError, ReporterError, StorageError};
+use aoc_leaderbot_lib::error::{ConfigFileError, EnvVarError, ReporterError, StorageError};
 
 fn not_unicode_env_var_error() -> EnvVarError {
     EnvVarError::NotUnicode("foo".into())
@@ -102,6 +102,20 @@ mod error {
             assert!(!error.is_env_and(predicate));
         }
 
+        #[test]
+        fn is_config_file_and() {
+            let predicate = |config_file_err: &ConfigFileError| matches!(config_file_err, ConfigFileError::Invalid { field, .. } if field == "foo");
+
+            let error = Error::ConfigFile(ConfigFileError::Invalid {
+                field: "foo".into(),
+                message: "bar".into(),
+            });
+            assert!(error.is_config_file_and(predicate));
+
+            let error = Error::MissingField { target: "SomeType", field: "some_field" };
+            assert!(!error.is_config_file_and(predicate));
+        }
+
         #[test]
         fn is_leaderboard_and() {
             let predicate = |leaderboard_err: &aoc_leaderboard::Error| {
@@ -144,7 +158,9 @@ mod error {
 
 mod error_kind {
     use aoc_leaderbot_lib::ErrorKind;
-    use aoc_leaderbot_lib::error::{EnvVarErrorKind, ReporterErrorKind, StorageErrorKind};
+    use aoc_leaderbot_lib::error::{
+        ConfigFileErrorKind, EnvVarErrorKind, ReporterErrorKind, StorageErrorKind,
+    };
     use rstest::rstest;
 
     use super::*;
@@ -159,6 +175,13 @@ mod error_kind {
             assert!(!error_kind.is_env_of_kind(EnvVarErrorKind::NotUnicode));
         }
 
+        #[test]
+        fn is_config_file_of_kind() {
+            let error_kind = ErrorKind::ConfigFile(ConfigFileErrorKind::Syntax);
+            assert!(error_kind.is_config_file_of_kind(ConfigFileErrorKind::Syntax));
+            assert!(!error_kind.is_config_file_of_kind(ConfigFileErrorKind::Invalid));
+        }
+
         #[test]
         fn is_leaderboard_of_kind() {
             let error_kind = ErrorKind::Leaderboard(aoc_leaderboard::ErrorKind::NoAccess);
@@ -347,6 +370,31 @@ mod error_kind {
         }
     }
 
+    mod from_config_file_error_for_error_kind {
+        use super::*;
+
+        #[rstest]
+        #[case::syntax(
+            ConfigFileError::Syntax { line: Some(1), column: None, message: "bad".into() },
+            ErrorKind::ConfigFile(ConfigFileErrorKind::Syntax)
+        )]
+        #[case::interpolation(
+            ConfigFileError::Interpolation {
+                field: "leaderboards[0].view_key".into(),
+                var_name: "SOME_VAR".into(),
+                source: EnvVarError::NotPresent,
+            },
+            ErrorKind::ConfigFile(ConfigFileErrorKind::Interpolation)
+        )]
+        fn for_variant(#[case] config_file_error: ConfigFileError, #[case] error_kind: ErrorKind) {
+            let error_kind_from: ErrorKind = (&config_file_error).into();
+            assert_eq!(error_kind, error_kind_from);
+
+            let error_kind_from: ErrorKind = Error::ConfigFile(config_file_error).into();
+            assert_eq!(error_kind, error_kind_from);
+        }
+    }
+
     mod from_storage_error_kind_for_error_kind {
         use super::*;
 
@@ -705,6 +753,83 @@ mod storage_error {
             let error = StorageError::LoadPrevious(anyhow!("error"));
             assert!(!error.is_save_error_and(predicate));
         }
+
+        #[test]
+        fn is_load_pending_changes_and() {
+            let predicate = |anyhow_err: &anyhow::Error| !format!("{anyhow_err:?}").is_empty();
+
+            let error = StorageError::LoadPendingChanges(anyhow!("error"));
+            assert!(error.is_load_pending_changes_and(predicate));
+
+            let error = StorageError::LoadPrevious(anyhow!("error"));
+            assert!(!error.is_load_pending_changes_and(predicate));
+        }
+
+        #[test]
+        fn is_save_pending_changes_and() {
+            let predicate = |anyhow_err: &anyhow::Error| !format!("{anyhow_err:?}").is_empty();
+
+            let error = StorageError::SavePendingChanges(anyhow!("error"));
+            assert!(error.is_save_pending_changes_and(predicate));
+
+            let error = StorageError::LoadPrevious(anyhow!("error"));
+            assert!(!error.is_save_pending_changes_and(predicate));
+        }
+
+        #[test]
+        fn is_clear_pending_changes_and() {
+            let predicate = |anyhow_err: &anyhow::Error| !format!("{anyhow_err:?}").is_empty();
+
+            let error = StorageError::ClearPendingChanges(anyhow!("error"));
+            assert!(error.is_clear_pending_changes_and(predicate));
+
+            let error = StorageError::LoadPrevious(anyhow!("error"));
+            assert!(!error.is_clear_pending_changes_and(predicate));
+        }
+
+        #[test]
+        fn is_load_outbox_and() {
+            let predicate = |anyhow_err: &anyhow::Error| !format!("{anyhow_err:?}").is_empty();
+
+            let error = StorageError::LoadOutbox(anyhow!("error"));
+            assert!(error.is_load_outbox_and(predicate));
+
+            let error = StorageError::LoadPrevious(anyhow!("error"));
+            assert!(!error.is_load_outbox_and(predicate));
+        }
+
+        #[test]
+        fn is_mark_delivered_and() {
+            let predicate = |anyhow_err: &anyhow::Error| !format!("{anyhow_err:?}").is_empty();
+
+            let error = StorageError::MarkDelivered(anyhow!("error"));
+            assert!(error.is_mark_delivered_and(predicate));
+
+            let error = StorageError::LoadPrevious(anyhow!("error"));
+            assert!(!error.is_mark_delivered_and(predicate));
+        }
+
+        #[test]
+        fn is_load_heartbeat_and() {
+            let predicate = |anyhow_err: &anyhow::Error| !format!("{anyhow_err:?}").is_empty();
+
+            let error = StorageError::LoadHeartbeat(anyhow!("error"));
+            assert!(error.is_load_heartbeat_and(predicate));
+
+            let error = StorageError::LoadPrevious(anyhow!("error"));
+            assert!(!error.is_load_heartbeat_and(predicate));
+        }
+
+        #[test]
+        fn is_save_heartbeat_and() {
+            let predicate = |anyhow_err: &anyhow::Error| !format!("{anyhow_err:?}").is_empty();
+
+            let error = StorageError::SaveHeartbeat(anyhow!("error"));
+            assert!(error.is_save_heartbeat_and(predicate));
+
+            let error = StorageError::LoadPrevious(anyhow!("error"));
+            assert!(!error.is_save_heartbeat_and(predicate));
+        }
     }
 }
 
@@ -808,3 +933,114 @@ mod reporter_error {
         }
     }
 }
+
+mod config_file_error {
+    use super::*;
+
+    mod display {
+        use super::*;
+
+        #[rstest::rstest]
+        #[case::line_and_column(Some(3), Some(7), "line 3, column 7: bad")]
+        #[case::line_only(Some(3), None, "line 3: bad")]
+        #[case::no_location(None, None, "bad")]
+        fn syntax(
+            #[case] line: Option<usize>,
+            #[case] column: Option<usize>,
+            #[case] expected: &str,
+        ) {
+            let error = ConfigFileError::Syntax { line, column, message: "bad".into() };
+            assert_eq!(error.to_string(), expected);
+        }
+    }
+}
+
+mod credentials_error {
+    use aoc_leaderbot_lib::ErrorKind;
+
+    use super::*;
+
+    #[test]
+    fn display_and_kind() {
+        let error = Error::Credentials(anyhow!("no such file"));
+        assert_eq!(error.to_string(), "failed to fetch leaderboard credentials: no such file");
+        assert_eq!(ErrorKind::from(&error), ErrorKind::Credentials);
+    }
+}
+
+mod metrics_error {
+    use aoc_leaderbot_lib::ErrorKind;
+
+    use super::*;
+
+    #[test]
+    fn display_and_kind() {
+        let error = Error::Metrics(anyhow!("connection refused"));
+        assert_eq!(error.to_string(), "metrics error: connection refused");
+        assert_eq!(ErrorKind::from(&error), ErrorKind::Metrics);
+    }
+}
+
+mod duplicate_target_error {
+    use aoc_leaderbot_lib::ErrorKind;
+
+    use super::*;
+
+    #[test]
+    fn display_and_kind() {
+        let error = Error::DuplicateTarget { year: 2024, leaderboard_id: 12345 };
+        assert_eq!(error.to_string(), "leaderboard 12345 is already monitored for year 2024");
+        assert_eq!(ErrorKind::from(&error), ErrorKind::DuplicateTarget);
+    }
+}
+
+mod backend_error {
+    use std::error::Error as _;
+
+    use aoc_leaderbot_lib::ErrorKind;
+    use aoc_leaderbot_lib::error::{BackendKind, DynError};
+
+    use super::*;
+
+    #[test]
+    fn display_and_kind() {
+        let error = Error::UnknownBackend { kind: BackendKind::Storage, name: "foo".into() };
+        assert_eq!(error.to_string(), "unknown storage backend: foo");
+        assert_eq!(ErrorKind::from(&error), ErrorKind::UnknownBackend);
+
+        let error = Error::Backend {
+            kind: BackendKind::Reporter,
+            name: "bar".into(),
+            source: anyhow!("oops"),
+        };
+        assert_eq!(error.to_string(), "failed to build reporter backend bar: oops");
+        assert_eq!(ErrorKind::from(&error), ErrorKind::Backend);
+    }
+
+    #[test]
+    fn dyn_error_is_transparent() {
+        let error = DynError::from(anyhow!(not_unicode_env_var_error()).context("context"));
+        assert_eq!(error.to_string(), "context");
+        assert!(error.source().is_some());
+    }
+}
+
+mod run_error {
+    use std::error::Error as _;
+
+    use aoc_leaderbot_lib::error::RunError;
+    use aoc_leaderbot_lib::leaderbot::diagnostics::RunDiagnostics;
+
+    use super::*;
+
+    #[test]
+    fn display_source_and_into_error() {
+        let error =
+            RunError { error: load_previous_error(), diagnostics: RunDiagnostics::default() };
+        assert_eq!(error.to_string(), load_previous_error().to_string());
+        assert!(error.source().is_some());
+
+        let error: Error = error.into();
+        assert!(error.is_storage_and(|err| err.is_load_previous()));
+    }
+}
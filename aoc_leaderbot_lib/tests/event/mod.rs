@@ -0,0 +1,281 @@
+mod event_times {
+    use aoc_leaderbot_lib::leaderbot::event::{
+        current_event_year, event_end, event_start, puzzle_unlock_times,
+    };
+    use chrono::{DateTime, TimeZone, Utc};
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::before_2025(2024, 25)]
+    #[case::since_2025(2025, 12)]
+    #[test_log::test]
+    fn unlock_times(#[case] year: i32, #[case] expected_days: usize) {
+        let unlock_times: Vec<_> = puzzle_unlock_times(year).collect();
+
+        assert_eq!(unlock_times.len(), expected_days);
+        assert_eq!(unlock_times[0], Utc.with_ymd_and_hms(year, 12, 1, 5, 0, 0).unwrap());
+        assert!(unlock_times.is_sorted());
+    }
+
+    #[rstest]
+    #[case::before_2025(2024, Utc.with_ymd_and_hms(2024, 12, 26, 5, 0, 0).unwrap())]
+    #[case::since_2025(2025, Utc.with_ymd_and_hms(2025, 12, 13, 5, 0, 0).unwrap())]
+    #[test_log::test]
+    fn start_and_end(#[case] year: i32, #[case] expected_end: DateTime<Utc>) {
+        assert_eq!(event_start(year), Utc.with_ymd_and_hms(year, 12, 1, 5, 0, 0).unwrap());
+        assert_eq!(event_end(year), expected_end);
+    }
+
+    #[rstest]
+    #[case::january(Utc.with_ymd_and_hms(2025, 1, 15, 0, 0, 0).unwrap(), 2024)]
+    #[case::november(Utc.with_ymd_and_hms(2025, 11, 30, 23, 59, 0).unwrap(), 2024)]
+    #[case::before_first_unlock(Utc.with_ymd_and_hms(2025, 12, 1, 4, 59, 0).unwrap(), 2024)]
+    #[case::first_unlock(Utc.with_ymd_and_hms(2025, 12, 1, 5, 0, 0).unwrap(), 2025)]
+    #[case::december(Utc.with_ymd_and_hms(2025, 12, 31, 0, 0, 0).unwrap(), 2025)]
+    #[test_log::test]
+    fn current_year(#[case] now: DateTime<Utc>, #[case] expected: i32) {
+        assert_eq!(current_event_year(now), expected);
+    }
+}
+
+mod event_window {
+    use aoc_leaderbot_lib::leaderbot::event::{DEFAULT_GRACE_PERIOD, EventWindow, OffSeasonPolicy};
+    use chrono::{DateTime, NaiveTime, TimeDelta, TimeZone, Utc};
+    use rstest::rstest;
+
+    fn utc(year: i32, month: u32, day: u32, hour: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, hour, min, 0)
+            .unwrap()
+    }
+
+    fn daily() -> OffSeasonPolicy {
+        OffSeasonPolicy::Daily(NaiveTime::from_hms_opt(12, 0, 0).unwrap())
+    }
+
+    #[test_log::test]
+    fn default() {
+        let event_window = EventWindow::default();
+
+        assert_eq!(event_window.grace_period, DEFAULT_GRACE_PERIOD);
+        assert_eq!(event_window.off_season, OffSeasonPolicy::Skip);
+        assert_eq!(event_window.with_off_season(daily()).off_season, daily());
+    }
+
+    #[rstest]
+    #[case::before_event(utc(2024, 12, 1, 4, 0), false)]
+    #[case::during_event(utc(2024, 12, 15, 12, 0), true)]
+    #[case::during_grace_period(utc(2025, 1, 1, 12, 0), true)]
+    #[case::after_grace_period(utc(2025, 1, 2, 5, 0), false)]
+    #[case::summer(utc(2025, 7, 1, 12, 0), false)]
+    #[test_log::test]
+    fn is_in_season(#[case] now: DateTime<Utc>, #[case] expected: bool) {
+        let event_window = EventWindow::new(TimeDelta::days(7));
+
+        assert_eq!(event_window.is_in_season(now), expected);
+    }
+
+    #[rstest]
+    #[case::in_season_skip(OffSeasonPolicy::Skip, utc(2024, 12, 15, 3, 0), true)]
+    #[case::off_season_skip(OffSeasonPolicy::Skip, utc(2025, 7, 1, 12, 0), false)]
+    #[case::before_daily_run(daily(), utc(2025, 7, 1, 11, 59), false)]
+    #[case::at_daily_run(daily(), utc(2025, 7, 1, 12, 0), true)]
+    #[case::shortly_after_daily_run(daily(), utc(2025, 7, 1, 12, 14), true)]
+    #[case::long_after_daily_run(daily(), utc(2025, 7, 1, 12, 15), false)]
+    #[test_log::test]
+    fn is_run_due(
+        #[case] off_season: OffSeasonPolicy,
+        #[case] now: DateTime<Utc>,
+        #[case] expected: bool,
+    ) {
+        let event_window = EventWindow::new(TimeDelta::days(7)).with_off_season(off_season);
+
+        assert_eq!(event_window.is_run_due(now), expected);
+    }
+
+    #[rstest]
+    #[case::in_season(OffSeasonPolicy::Skip, utc(2024, 12, 15, 3, 0), utc(2024, 12, 15, 3, 0))]
+    #[case::skip(OffSeasonPolicy::Skip, utc(2025, 7, 1, 12, 0), utc(2025, 12, 1, 5, 0))]
+    #[case::before_event(OffSeasonPolicy::Skip, utc(2025, 12, 1, 4, 0), utc(2025, 12, 1, 5, 0))]
+    #[case::before_daily_run(daily(), utc(2025, 7, 1, 11, 0), utc(2025, 7, 1, 12, 0))]
+    #[case::after_daily_run(daily(), utc(2025, 7, 1, 13, 0), utc(2025, 7, 2, 12, 0))]
+    #[case::daily_after_event_start(daily(), utc(2025, 12, 1, 4, 0), utc(2025, 12, 1, 5, 0))]
+    #[test_log::test]
+    fn next_run_time(
+        #[case] off_season: OffSeasonPolicy,
+        #[case] time: DateTime<Utc>,
+        #[case] expected: DateTime<Utc>,
+    ) {
+        let event_window = EventWindow::new(TimeDelta::days(7)).with_off_season(off_season);
+
+        assert_eq!(event_window.next_run_time(time), expected);
+    }
+}
+
+#[cfg(all(feature = "config-mem", feature = "storage-mem", feature = "reporter-recording"))]
+mod run_bot {
+    use aoc_leaderboard::test_helpers::{
+        TEST_LEADERBOARD_ID, TEST_YEAR, mock_server_with_leaderboard, test_leaderboard_credentials,
+    };
+    use aoc_leaderboard::wiremock::MockServer;
+    use aoc_leaderbot_lib::leaderbot::clock::TestClock;
+    use aoc_leaderbot_lib::leaderbot::config::mem::MemoryConfig;
+    use aoc_leaderbot_lib::leaderbot::digest::{DigestSchedule, run_bot_digest_from};
+    use aoc_leaderbot_lib::leaderbot::event::EventWindow;
+    use aoc_leaderbot_lib::leaderbot::outbox::run_bot_outbox_from;
+    use aoc_leaderbot_lib::leaderbot::reporter::recording::RecordingReporter;
+    use aoc_leaderbot_lib::leaderbot::run_bot_from;
+    use aoc_leaderbot_lib::leaderbot::storage::mem::MemoryStorage;
+    use assert_matches::assert_matches;
+    use chrono::{TimeDelta, TimeZone, Utc};
+    use rstest::rstest;
+
+    fn config(event_window: Option<EventWindow>) -> MemoryConfig {
+        MemoryConfig::builder()
+            .year(TEST_YEAR)
+            .leaderboard_id(TEST_LEADERBOARD_ID)
+            .credentials(test_leaderboard_credentials::default())
+            .event_window(event_window)
+            .build()
+            .unwrap()
+    }
+
+    #[rstest]
+    #[case::without_event_window(None)]
+    #[case::always_in_season(Some(EventWindow::new(TimeDelta::days(400))))]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn runs(
+        #[case] event_window: Option<EventWindow>,
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        mock_server: MockServer,
+    ) {
+        let mut storage = MemoryStorage::new();
+        let mut reporter = RecordingReporter::new();
+
+        let result = run_bot_from(
+            Some(mock_server.uri()),
+            &config(event_window),
+            &mut storage,
+            &mut reporter,
+            false,
+        )
+        .await;
+        assert_matches!(result, Ok(Some(_)));
+
+        reporter.assert_first_run_reported(TEST_YEAR, TEST_LEADERBOARD_ID);
+        assert_eq!(storage.len(), 1);
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn skips_off_season(
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        mock_server: MockServer,
+    ) {
+        let mut storage = MemoryStorage::new();
+        let mut reporter = RecordingReporter::new();
+        let event_window = EventWindow::new(TimeDelta::days(-400));
+
+        let result = run_bot_from(
+            Some(mock_server.uri()),
+            &config(Some(event_window)),
+            &mut storage,
+            &mut reporter,
+            false,
+        )
+        .await;
+        assert_matches!(result, Ok(None));
+
+        assert!(!reporter.called());
+        assert!(storage.is_empty());
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn outbox_skips_off_season(
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        mock_server: MockServer,
+    ) {
+        let mut storage = MemoryStorage::new();
+        let mut reporter = RecordingReporter::new();
+        let event_window = EventWindow::new(TimeDelta::days(-400));
+
+        let result = run_bot_outbox_from(
+            Some(mock_server.uri()),
+            &config(Some(event_window)),
+            &mut storage,
+            &mut reporter,
+            false,
+        )
+        .await;
+        assert_matches!(result, Ok(None));
+
+        assert!(!reporter.called());
+        assert!(storage.is_empty());
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn digest_skips_off_season(
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        mock_server: MockServer,
+    ) {
+        let mut storage = MemoryStorage::new();
+        let mut reporter = RecordingReporter::new();
+        let event_window = EventWindow::new(TimeDelta::days(-400));
+
+        let result = run_bot_digest_from(
+            Some(mock_server.uri()),
+            &config(Some(event_window)),
+            &mut storage,
+            &mut reporter,
+            &DigestSchedule::default(),
+            false,
+        )
+        .await;
+        assert_matches!(result, Ok(None));
+
+        assert!(!reporter.called());
+        assert!(storage.is_empty());
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn follows_clock(
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        mock_server: MockServer,
+    ) {
+        let mut storage = MemoryStorage::new();
+        let mut reporter = RecordingReporter::new();
+        let clock = TestClock::new(Utc.with_ymd_and_hms(TEST_YEAR, 11, 30, 4, 0, 0).unwrap());
+        let config = MemoryConfig::builder()
+            .year(TEST_YEAR)
+            .leaderboard_id(TEST_LEADERBOARD_ID)
+            .credentials(test_leaderboard_credentials::default())
+            .event_window(EventWindow::new(TimeDelta::days(7)))
+            .clock(clock.clone())
+            .build()
+            .unwrap();
+
+        let result =
+            run_bot_from(Some(mock_server.uri()), &config, &mut storage, &mut reporter, false)
+                .await;
+        assert_matches!(result, Ok(None));
+
+        clock.advance(TimeDelta::days(1) + TimeDelta::hours(1));
+        let result =
+            run_bot_from(Some(mock_server.uri()), &config, &mut storage, &mut reporter, false)
+                .await;
+        assert_matches!(result, Ok(Some(_)));
+        reporter.assert_first_run_reported(TEST_YEAR, TEST_LEADERBOARD_ID);
+    }
+}
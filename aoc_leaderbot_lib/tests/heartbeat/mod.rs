@@ -0,0 +1,459 @@
+mod heartbeat_policy {
+    use aoc_leaderbot_lib::leaderbot::heartbeat::HeartbeatPolicy;
+    use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+    use rstest::rstest;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 12, 15, 12, 0, 0).unwrap()
+    }
+
+    #[test_log::test]
+    fn default() {
+        let policy = HeartbeatPolicy::default();
+
+        assert_eq!(policy.max_silence, None);
+        assert_eq!(policy.interval, None);
+        assert!(!policy.is_enabled());
+        assert!(!policy.is_heartbeat_due(None, now()));
+    }
+
+    #[rstest]
+    #[case::max_silence(HeartbeatPolicy::default().with_max_silence(TimeDelta::hours(2)))]
+    #[case::interval(HeartbeatPolicy::default().with_interval(TimeDelta::days(1)))]
+    #[test_log::test]
+    fn is_enabled(#[case] policy: HeartbeatPolicy) {
+        assert!(policy.is_enabled());
+    }
+
+    #[rstest]
+    #[case::never_reported(None, true)]
+    #[case::reported_recently(Some(now() - TimeDelta::hours(23)), false)]
+    #[case::reported_at_interval(Some(now() - TimeDelta::days(1)), true)]
+    #[case::reported_long_ago(Some(now() - TimeDelta::days(3)), true)]
+    #[test_log::test]
+    fn is_heartbeat_due(#[case] last_report: Option<DateTime<Utc>>, #[case] expected: bool) {
+        let policy = HeartbeatPolicy::default().with_interval(TimeDelta::days(1));
+
+        assert_eq!(policy.is_heartbeat_due(last_report, now()), expected);
+    }
+}
+
+#[cfg(all(feature = "config-mem", feature = "storage-mem", feature = "reporter-recording"))]
+mod check_heartbeat {
+    use aoc_leaderboard::test_helpers::{
+        TEST_LEADERBOARD_ID, TEST_YEAR, test_leaderboard_credentials,
+    };
+    use aoc_leaderbot_lib::leaderbot::Storage;
+    use aoc_leaderbot_lib::leaderbot::clock::TestClock;
+    use aoc_leaderbot_lib::leaderbot::config::mem::MemoryConfig;
+    use aoc_leaderbot_lib::leaderbot::event::{EventWindow, event_start};
+    use aoc_leaderbot_lib::leaderbot::heartbeat::{
+        HeartbeatPolicy, HeartbeatState, HeartbeatStatus, check_heartbeat,
+    };
+    use aoc_leaderbot_lib::leaderbot::reporter::recording::RecordingReporter;
+    use aoc_leaderbot_lib::leaderbot::storage::mem::MemoryStorage;
+    use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(TEST_YEAR, 12, 15, 12, 0, 0).unwrap()
+    }
+
+    fn config(policy: HeartbeatPolicy, clock: &TestClock) -> MemoryConfig {
+        MemoryConfig::builder()
+            .year(TEST_YEAR)
+            .leaderboard_id(TEST_LEADERBOARD_ID)
+            .credentials(test_leaderboard_credentials::default())
+            .event_window(EventWindow::new(TimeDelta::days(7)))
+            .heartbeat_policy(policy)
+            .clock(clock.clone())
+            .build()
+            .unwrap()
+    }
+
+    fn policy() -> HeartbeatPolicy {
+        HeartbeatPolicy::default().with_max_silence(TimeDelta::hours(2))
+    }
+
+    async fn storage_with_success(last_success: DateTime<Utc>) -> MemoryStorage {
+        let mut storage = MemoryStorage::new();
+        storage
+            .save_heartbeat(TEST_YEAR, TEST_LEADERBOARD_ID, &HeartbeatState::new(last_success))
+            .await
+            .unwrap();
+        storage
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn disabled() {
+        let clock = TestClock::new(now());
+        let mut storage = storage_with_success(now() - TimeDelta::days(2)).await;
+        let mut reporter = RecordingReporter::new();
+
+        let status = check_heartbeat(
+            &config(HeartbeatPolicy::default().with_interval(TimeDelta::days(1)), &clock),
+            &mut storage,
+            &mut reporter,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(status, HeartbeatStatus::Disabled);
+        reporter.assert_not_called();
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn off_season() {
+        let clock = TestClock::new(Utc.with_ymd_and_hms(TEST_YEAR, 7, 1, 12, 0, 0).unwrap());
+        let mut storage = storage_with_success(now() - TimeDelta::days(200)).await;
+        let mut reporter = RecordingReporter::new();
+
+        let status = check_heartbeat(&config(policy(), &clock), &mut storage, &mut reporter)
+            .await
+            .unwrap();
+
+        assert_eq!(status, HeartbeatStatus::OffSeason);
+        reporter.assert_not_called();
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn no_success_recorded() {
+        let clock = TestClock::new(event_start(TEST_YEAR) + TimeDelta::minutes(90));
+        let mut storage = MemoryStorage::new();
+        let mut reporter = RecordingReporter::new();
+
+        let status = check_heartbeat(&config(policy(), &clock), &mut storage, &mut reporter)
+            .await
+            .unwrap();
+
+        assert_eq!(status, HeartbeatStatus::NoSuccessRecorded);
+        reporter.assert_not_called();
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn alive() {
+        let clock = TestClock::new(now());
+        let last_success = now() - TimeDelta::minutes(90);
+        let mut storage = storage_with_success(last_success).await;
+        let mut reporter = RecordingReporter::new();
+
+        let status = check_heartbeat(&config(policy(), &clock), &mut storage, &mut reporter)
+            .await
+            .unwrap();
+
+        assert_eq!(status, HeartbeatStatus::Alive { last_success });
+        reporter.assert_not_called();
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn stale_is_reported_once() {
+        let clock = TestClock::new(now());
+        let last_success = now() - TimeDelta::hours(3);
+        let mut storage = storage_with_success(last_success).await;
+        let mut reporter = RecordingReporter::new();
+        let config = config(policy(), &clock);
+
+        let status = check_heartbeat(&config, &mut storage, &mut reporter)
+            .await
+            .unwrap();
+        assert_eq!(
+            status,
+            HeartbeatStatus::Stale {
+                last_success: Some(last_success),
+                silence: TimeDelta::hours(3)
+            }
+        );
+
+        let stales: Vec<_> = reporter.stales().collect();
+        assert_eq!(stales.len(), 1);
+        assert_eq!(stales[0].year, TEST_YEAR);
+        assert_eq!(stales[0].leaderboard_id, TEST_LEADERBOARD_ID);
+        assert_eq!(stales[0].silence, TimeDelta::hours(3));
+
+        let heartbeat = storage
+            .load_heartbeat(TEST_YEAR, TEST_LEADERBOARD_ID)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(heartbeat.last_success, Some(last_success));
+        assert_eq!(heartbeat.stale_reported, Some(now()));
+
+        clock.advance(TimeDelta::hours(1));
+        let status = check_heartbeat(&config, &mut storage, &mut reporter)
+            .await
+            .unwrap();
+        assert_eq!(
+            status,
+            HeartbeatStatus::Stale {
+                last_success: Some(last_success),
+                silence: TimeDelta::hours(4)
+            }
+        );
+        assert_eq!(reporter.call_count(), 1);
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn stale_since_event_start_after_year_rollover() {
+        let event_start = event_start(TEST_YEAR);
+        let clock = TestClock::new(event_start + TimeDelta::hours(1));
+        let mut storage = MemoryStorage::new();
+        storage
+            .save_heartbeat(
+                TEST_YEAR - 1,
+                TEST_LEADERBOARD_ID,
+                &HeartbeatState::new(Utc.with_ymd_and_hms(TEST_YEAR, 1, 5, 12, 0, 0).unwrap()),
+            )
+            .await
+            .unwrap();
+        let mut reporter = RecordingReporter::new();
+        let config = MemoryConfig::builder()
+            .leaderboard_id(TEST_LEADERBOARD_ID)
+            .credentials(test_leaderboard_credentials::default())
+            .event_window(EventWindow::new(TimeDelta::days(7)))
+            .heartbeat_policy(policy())
+            .clock(clock.clone())
+            .build()
+            .unwrap();
+
+        let status = check_heartbeat(&config, &mut storage, &mut reporter)
+            .await
+            .unwrap();
+        assert_eq!(status, HeartbeatStatus::NoSuccessRecorded);
+        reporter.assert_not_called();
+
+        clock.advance(TimeDelta::hours(2));
+        let status = check_heartbeat(&config, &mut storage, &mut reporter)
+            .await
+            .unwrap();
+        assert_eq!(
+            status,
+            HeartbeatStatus::Stale { last_success: None, silence: TimeDelta::hours(3) }
+        );
+
+        let stales: Vec<_> = reporter.stales().collect();
+        assert_eq!(stales.len(), 1);
+        assert_eq!(stales[0].year, TEST_YEAR);
+        assert_eq!(stales[0].silence, TimeDelta::hours(3));
+
+        clock.advance(TimeDelta::hours(1));
+        let status = check_heartbeat(&config, &mut storage, &mut reporter)
+            .await
+            .unwrap();
+        assert_eq!(
+            status,
+            HeartbeatStatus::Stale { last_success: None, silence: TimeDelta::hours(4) }
+        );
+        assert_eq!(reporter.call_count(), 1);
+    }
+}
+
+#[cfg(all(feature = "config-mem", feature = "storage-mem", feature = "reporter-recording"))]
+mod run_bot {
+    use aoc_leaderboard::aoc::Leaderboard;
+    use aoc_leaderboard::test_helpers::{
+        TEST_LEADERBOARD_ID, TEST_YEAR, mock_server_with_leaderboard, test_leaderboard,
+        test_leaderboard_credentials,
+    };
+    use aoc_leaderboard::wiremock::MockServer;
+    use aoc_leaderbot_lib::leaderbot::clock::TestClock;
+    use aoc_leaderbot_lib::leaderbot::config::mem::MemoryConfig;
+    use aoc_leaderbot_lib::leaderbot::digest::{DigestSchedule, run_bot_digest_from};
+    use aoc_leaderbot_lib::leaderbot::event::EventWindow;
+    use aoc_leaderbot_lib::leaderbot::heartbeat::{
+        HeartbeatPolicy, HeartbeatStatus, check_heartbeat,
+    };
+    use aoc_leaderbot_lib::leaderbot::reporter::recording::{RecordingReporter, ReportedCall};
+    use aoc_leaderbot_lib::leaderbot::storage::mem::MemoryStorage;
+    use aoc_leaderbot_lib::leaderbot::{Storage, run_bot_from};
+    use assert_matches::assert_matches;
+    use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+    use rstest::rstest;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(TEST_YEAR, 12, 15, 12, 0, 0).unwrap()
+    }
+
+    fn config(policy: HeartbeatPolicy, clock: &TestClock) -> MemoryConfig {
+        MemoryConfig::builder()
+            .year(TEST_YEAR)
+            .leaderboard_id(TEST_LEADERBOARD_ID)
+            .credentials(test_leaderboard_credentials::default())
+            .event_window(EventWindow::new(TimeDelta::days(7)))
+            .heartbeat_policy(policy)
+            .clock(clock.clone())
+            .build()
+            .unwrap()
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn records_success(
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        mock_server: MockServer,
+    ) {
+        let clock = TestClock::new(now());
+        let config =
+            config(HeartbeatPolicy::default().with_max_silence(TimeDelta::hours(2)), &clock);
+        let mut storage = MemoryStorage::new();
+        let mut reporter = RecordingReporter::new();
+
+        run_bot_from(Some(mock_server.uri()), &config, &mut storage, &mut reporter, false)
+            .await
+            .unwrap();
+
+        let heartbeat = storage
+            .load_heartbeat(TEST_YEAR, TEST_LEADERBOARD_ID)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(heartbeat.last_success, Some(now()));
+        assert_eq!(heartbeat.last_report, Some(now()));
+        assert_eq!(heartbeat.stale_reported, None);
+
+        clock.advance(TimeDelta::hours(1));
+        let status = check_heartbeat(&config, &mut storage, &mut reporter)
+            .await
+            .unwrap();
+        assert_eq!(status, HeartbeatStatus::Alive { last_success: now() });
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn does_not_record_without_policy(
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        mock_server: MockServer,
+    ) {
+        let clock = TestClock::new(now());
+        let config = config(HeartbeatPolicy::default(), &clock);
+        let mut storage = MemoryStorage::new();
+        let mut reporter = RecordingReporter::new();
+
+        run_bot_from(Some(mock_server.uri()), &config, &mut storage, &mut reporter, false)
+            .await
+            .unwrap();
+
+        let heartbeat = storage
+            .load_heartbeat(TEST_YEAR, TEST_LEADERBOARD_ID)
+            .await
+            .unwrap();
+        assert_matches!(heartbeat, None);
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn sends_heartbeat_when_due(
+        #[from(test_leaderboard)] leaderboard: Leaderboard,
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        mock_server: MockServer,
+    ) {
+        let clock = TestClock::new(now());
+        let config = config(HeartbeatPolicy::default().with_interval(TimeDelta::days(1)), &clock);
+        let mut storage = MemoryStorage::new();
+        let mut reporter = RecordingReporter::new();
+
+        // First run: reported as a first run, no heartbeat needed.
+        run_bot_from(Some(mock_server.uri()), &config, &mut storage, &mut reporter, false)
+            .await
+            .unwrap();
+        reporter.assert_first_run_reported(TEST_YEAR, TEST_LEADERBOARD_ID);
+        reporter.clear();
+
+        // No changes, and something was reported recently: no heartbeat.
+        clock.advance(TimeDelta::hours(12));
+        run_bot_from(Some(mock_server.uri()), &config, &mut storage, &mut reporter, false)
+            .await
+            .unwrap();
+        reporter.assert_not_called();
+
+        // No changes, and nothing was reported for a day: heartbeat.
+        clock.advance(TimeDelta::hours(12));
+        run_bot_from(Some(mock_server.uri()), &config, &mut storage, &mut reporter, false)
+            .await
+            .unwrap();
+        assert_matches!(reporter.calls(), [ReportedCall::Heartbeat(heartbeat)] => {
+            assert_eq!(heartbeat.year, TEST_YEAR);
+            assert_eq!(heartbeat.leaderboard_id, TEST_LEADERBOARD_ID);
+            assert_eq!(heartbeat.leaderboard, leaderboard);
+        });
+        reporter.clear();
+
+        // The heartbeat counts as a report.
+        clock.advance(TimeDelta::hours(12));
+        run_bot_from(Some(mock_server.uri()), &config, &mut storage, &mut reporter, false)
+            .await
+            .unwrap();
+        reporter.assert_not_called();
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn sends_heartbeat_while_accumulating_digest(
+        #[from(test_leaderboard)] leaderboard: Leaderboard,
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        mock_server: MockServer,
+    ) {
+        let clock = TestClock::new(now());
+        let config = config(HeartbeatPolicy::default().with_interval(TimeDelta::days(1)), &clock);
+        let mut previous_leaderboard = leaderboard.clone();
+        let departed_id = *previous_leaderboard.members.keys().max().unwrap();
+        previous_leaderboard.members.remove(&departed_id);
+        let mut storage = MemoryStorage::new();
+        storage
+            .save_success(TEST_YEAR, TEST_LEADERBOARD_ID, &previous_leaderboard)
+            .await
+            .unwrap();
+        let mut reporter = RecordingReporter::new();
+
+        // Changes are only accumulated, so nothing is reported and a heartbeat is due.
+        let output = run_bot_digest_from(
+            Some(mock_server.uri()),
+            &config,
+            &mut storage,
+            &mut reporter,
+            &DigestSchedule::default(),
+            false,
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert!(output.bot_output.changes.is_some());
+        assert!(output.pending_changes.is_some());
+        assert_matches!(reporter.calls(), [ReportedCall::Heartbeat(_)]);
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn dry_run_does_not_record(
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        mock_server: MockServer,
+    ) {
+        let clock = TestClock::new(now());
+        let config = config(
+            HeartbeatPolicy::default()
+                .with_max_silence(TimeDelta::hours(2))
+                .with_interval(TimeDelta::days(1)),
+            &clock,
+        );
+        let mut storage = MemoryStorage::new();
+        let mut reporter = RecordingReporter::new();
+
+        run_bot_from(Some(mock_server.uri()), &config, &mut storage, &mut reporter, true)
+            .await
+            .unwrap();
+
+        let heartbeat = storage
+            .load_heartbeat(TEST_YEAR, TEST_LEADERBOARD_ID)
+            .await
+            .unwrap();
+        assert_matches!(heartbeat, None);
+    }
+}
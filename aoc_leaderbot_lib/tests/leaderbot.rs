@@ -1,13 +1,32 @@
 #![allow(dead_code)]
 
+mod clock;
 mod config;
+mod credentials;
+#[cfg(feature = "daemon")]
+mod daemon;
+mod diagnostics;
+mod digest;
+#[cfg(feature = "dynamic")]
+mod dynamic;
+mod event;
+mod heartbeat;
+mod members;
+#[cfg(feature = "metrics")]
+mod metrics;
+#[cfg(feature = "multi-target")]
+mod multi_target;
+mod outbox;
+mod reporter;
 mod storage;
+mod team;
 
 mod leaderbot_config {
     use aoc_leaderboard::aoc::LeaderboardCredentials;
     use aoc_leaderboard::test_helpers::{TEST_LEADERBOARD_ID, test_leaderboard_credentials};
-    use aoc_leaderbot_lib::leaderbot::Config;
-    use chrono::{Datelike, Local};
+    use aoc_leaderbot_lib::leaderbot::event::current_event_year;
+    use aoc_leaderbot_lib::leaderbot::{Config, RenotifyPolicy};
+    use chrono::Utc;
 
     struct TestLeaderbotConfig;
 
@@ -25,9 +44,116 @@ mod leaderbot_config {
     fn default_year() {
         let config = TestLeaderbotConfig;
 
-        assert_eq!(config.year(), Local::now().year());
+        assert_eq!(config.year(), current_event_year(Utc::now()));
         assert_eq!(config.leaderboard_id(), TEST_LEADERBOARD_ID);
         assert_eq!(config.credentials(), test_leaderboard_credentials::default());
+        assert_eq!(config.renotify_policy(), RenotifyPolicy::never());
+        assert_eq!(config.event_window(), None);
+    }
+}
+
+mod leaderbot_error_state {
+    use aoc_leaderbot_lib::ErrorKind;
+    use aoc_leaderbot_lib::error::StorageErrorKind;
+    use aoc_leaderbot_lib::leaderbot::ErrorState;
+    use chrono::{DateTime, TimeDelta};
+
+    #[test_log::test]
+    fn recur() {
+        let first_seen = DateTime::from_timestamp(1_733_029_200, 0).unwrap();
+        let last_seen = first_seen + TimeDelta::hours(1);
+        let error_state = ErrorState {
+            last_message: Some("missing field".into()),
+            last_notified: Some(first_seen),
+            ..ErrorState::new(ErrorKind::MissingField, first_seen)
+        };
+
+        let actual =
+            error_state.recur(ErrorKind::Storage(StorageErrorKind::LoadPrevious), last_seen);
+        assert_eq!(
+            actual,
+            ErrorState {
+                error_kind: ErrorKind::Storage(StorageErrorKind::LoadPrevious),
+                first_seen,
+                last_seen,
+                occurrences: 2,
+                last_message: None,
+                last_notified: Some(first_seen),
+            }
+        );
+    }
+}
+
+mod leaderbot_renotify_policy {
+    use aoc_leaderbot_lib::ErrorKind;
+    use aoc_leaderbot_lib::leaderbot::{ErrorState, RenotifyPolicy};
+    use chrono::{DateTime, TimeDelta, Utc};
+    use rstest::rstest;
+
+    fn first_seen() -> DateTime<Utc> {
+        DateTime::from_timestamp(1_733_029_200, 0).unwrap()
+    }
+
+    fn error_state(occurrences: u64, last_notified_hours: Option<i64>) -> ErrorState {
+        ErrorState {
+            occurrences,
+            last_notified: last_notified_hours.map(|hours| first_seen() + TimeDelta::hours(hours)),
+            ..ErrorState::new(ErrorKind::MissingField, first_seen())
+        }
+    }
+
+    #[rstest]
+    #[case::never(RenotifyPolicy::never(), error_state(100, Some(0)), 1000, false)]
+    #[case::interval_not_elapsed(
+        RenotifyPolicy::every(TimeDelta::hours(24)),
+        error_state(5, Some(0)),
+        23,
+        false
+    )]
+    #[case::interval_elapsed(
+        RenotifyPolicy::every(TimeDelta::hours(24)),
+        error_state(5, Some(0)),
+        24,
+        true
+    )]
+    #[case::interval_since_last_notified(
+        RenotifyPolicy::every(TimeDelta::hours(24)),
+        error_state(5, Some(12)),
+        30,
+        false
+    )]
+    #[case::interval_without_notification(
+        RenotifyPolicy::every(TimeDelta::hours(24)),
+        error_state(5, None),
+        25,
+        true
+    )]
+    #[case::failures_not_reached(
+        RenotifyPolicy::after_failures(3),
+        error_state(3, Some(0)),
+        0,
+        false
+    )]
+    #[case::failures_reached(RenotifyPolicy::after_failures(3), error_state(4, Some(0)), 0, true)]
+    #[case::failures_reached_again(
+        RenotifyPolicy::after_failures(3),
+        error_state(7, Some(0)),
+        0,
+        true
+    )]
+    #[case::first_failure(RenotifyPolicy::after_failures(1), error_state(1, None), 0, false)]
+    #[case::zero_failures(RenotifyPolicy::after_failures(0), error_state(4, Some(0)), 0, false)]
+    #[case::both(RenotifyPolicy { interval: Some(TimeDelta::hours(24)), failures: Some(3) }, error_state(4, Some(0)), 1, true)]
+    #[test_log::test]
+    fn is_due(
+        #[case] policy: RenotifyPolicy,
+        #[case] error_state: ErrorState,
+        #[case] now_hours: i64,
+        #[case] expected: bool,
+    ) {
+        let now = first_seen() + TimeDelta::hours(now_hours);
+
+        assert_eq!(policy.is_due(&error_state, now), expected);
     }
 }
 
@@ -87,4 +213,136 @@ mod leaderbot_changes {
         let changes = Changes::if_needed(HashSet::new(), HashSet::new());
         assert!(changes.is_none());
     }
+
+    mod merge {
+        use rstest::rstest;
+
+        use super::*;
+
+        #[rstest]
+        #[case::disjoint(Changes::new([1].into(), [2].into()), Changes::new([3].into(), [4].into()), Changes::new([1, 3].into(), [2, 4].into()))]
+        #[case::overlapping(Changes::new([1].into(), [2].into()), Changes::new([1].into(), [2, 4].into()), Changes::new([1].into(), [2, 4].into()))]
+        #[case::new_member_with_new_stars(Changes::new([1].into(), [2].into()), Changes::new([].into(), [1].into()), Changes::new([1].into(), [2].into()))]
+        #[case::member_with_new_stars_then_new(Changes::new([].into(), [1].into()), Changes::new([1].into(), [].into()), Changes::new([1].into(), [].into()))]
+        #[test_log::test]
+        fn with(#[case] mut changes: Changes, #[case] other: Changes, #[case] expected: Changes) {
+            changes.merge(&other);
+            assert_eq!(changes, expected);
+        }
+    }
+}
+
+mod leaderbot_merged_changes {
+    use aoc_leaderboard::aoc::Leaderboard;
+    use aoc_leaderboard::test_helpers::{TEST_LEADERBOARD_ID, TEST_YEAR, test_leaderboard};
+    use aoc_leaderbot_lib::leaderbot::{Changes, LeaderboardChanges, MergedChanges};
+    use rstest::rstest;
+
+    const OTHER_LEADERBOARD_ID: u64 = TEST_LEADERBOARD_ID + 1;
+
+    fn leaderboard_changes(
+        year: i32,
+        leaderboard_id: u64,
+        leaderboard: &Leaderboard,
+        members: &[u64],
+        changes: Changes,
+    ) -> LeaderboardChanges {
+        let mut leaderboard = leaderboard.clone();
+        leaderboard.members.retain(|id, _| members.contains(id));
+
+        LeaderboardChanges {
+            year,
+            leaderboard_id,
+            view_key: None,
+            previous_leaderboard: leaderboard.clone(),
+            leaderboard,
+            changes,
+        }
+    }
+
+    #[rstest]
+    #[test_log::test]
+    fn deduplicates_members(#[from(test_leaderboard)] leaderboard: Leaderboard) {
+        let merged = MergedChanges::merge([
+            leaderboard_changes(
+                TEST_YEAR,
+                TEST_LEADERBOARD_ID,
+                &leaderboard,
+                &[12345, 12346, 12347],
+                Changes::new([12345].into(), [12346].into()),
+            ),
+            leaderboard_changes(
+                TEST_YEAR,
+                OTHER_LEADERBOARD_ID,
+                &leaderboard,
+                &[12345, 12346, 12348],
+                Changes::new([12345, 12348].into(), [12346].into()),
+            ),
+        ]);
+
+        assert_eq!(merged.len(), 1);
+        let merged = &merged[0];
+        assert_eq!(merged.year, TEST_YEAR);
+        assert_eq!(merged.leaderboards.len(), 2);
+        assert_eq!(merged.leaderboards[0].changes, Changes::new([12345].into(), [12346].into()));
+        assert_eq!(merged.leaderboards[1].changes, Changes::new([12348].into(), [].into()));
+        assert_eq!(merged.leaderboards_of(12345), [TEST_LEADERBOARD_ID, OTHER_LEADERBOARD_ID]);
+        assert_eq!(merged.leaderboards_of(12346), [TEST_LEADERBOARD_ID, OTHER_LEADERBOARD_ID]);
+        assert_eq!(merged.leaderboards_of(12348), [OTHER_LEADERBOARD_ID]);
+        assert!(merged.leaderboards_of(12347).is_empty());
+    }
+
+    #[rstest]
+    #[test_log::test]
+    fn drops_leaderboards_without_changes(#[from(test_leaderboard)] leaderboard: Leaderboard) {
+        let merged = MergedChanges::merge([
+            leaderboard_changes(
+                TEST_YEAR,
+                TEST_LEADERBOARD_ID,
+                &leaderboard,
+                &[12345, 12346],
+                Changes::new([12345].into(), [12346].into()),
+            ),
+            leaderboard_changes(
+                TEST_YEAR,
+                OTHER_LEADERBOARD_ID,
+                &leaderboard,
+                &[12345, 12346],
+                Changes::new([].into(), [12345, 12346].into()),
+            ),
+        ]);
+
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].leaderboards.len(), 1);
+        assert_eq!(merged[0].leaderboards[0].leaderboard_id, TEST_LEADERBOARD_ID);
+    }
+
+    #[rstest]
+    #[test_log::test]
+    fn groups_by_year(#[from(test_leaderboard)] leaderboard: Leaderboard) {
+        let merged = MergedChanges::merge([
+            leaderboard_changes(
+                TEST_YEAR,
+                TEST_LEADERBOARD_ID,
+                &leaderboard,
+                &[12345],
+                Changes::new([12345].into(), [].into()),
+            ),
+            leaderboard_changes(
+                TEST_YEAR - 1,
+                TEST_LEADERBOARD_ID,
+                &leaderboard,
+                &[12345],
+                Changes::new([12345].into(), [].into()),
+            ),
+        ]);
+
+        assert_eq!(merged.iter().map(|m| m.year).collect::<Vec<_>>(), [TEST_YEAR - 1, TEST_YEAR]);
+        assert!(merged.iter().all(|m| m.leaderboards.len() == 1));
+    }
+
+    #[test_log::test]
+    fn empty() {
+        assert!(MergedChanges::merge([]).is_empty());
+    }
 }
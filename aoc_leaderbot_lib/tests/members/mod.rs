@@ -0,0 +1,107 @@
+mod member_settings {
+    use aoc_leaderboard::aoc::Leaderboard;
+    use aoc_leaderboard::test_helpers::test_leaderboard;
+    use aoc_leaderbot_lib::leaderbot::members::{MemberSettings, pseudonym};
+    use rstest::rstest;
+
+    #[rstest]
+    #[test_log::test]
+    fn is_empty() {
+        assert!(MemberSettings::default().is_empty());
+        assert!(!MemberSettings { ignored: [1].into(), ..Default::default() }.is_empty());
+        assert!(!MemberSettings { pseudonymize: true, ..Default::default() }.is_empty());
+    }
+
+    mod apply {
+        use super::*;
+
+        #[rstest]
+        #[test_log::test]
+        fn empty(#[from(test_leaderboard)] leaderboard: Leaderboard) {
+            assert_eq!(MemberSettings::default().applied(leaderboard.clone()), leaderboard);
+        }
+
+        #[rstest]
+        #[test_log::test]
+        fn aliases(#[from(test_leaderboard)] leaderboard: Leaderboard) {
+            let settings = MemberSettings {
+                aliases: [(12345, "Zaphod".into()), (99999, "Nobody".into())].into(),
+                ..Default::default()
+            };
+
+            let actual = settings.applied(leaderboard.clone());
+
+            assert_eq!(actual.members[&12345].name.as_deref(), Some("Zaphod"));
+            assert_eq!(actual.members.len(), leaderboard.members.len());
+            assert_eq!(actual.members[&12345].stars, leaderboard.members[&12345].stars);
+        }
+
+        #[rstest]
+        #[test_log::test]
+        fn ignored(#[from(test_leaderboard)] leaderboard: Leaderboard) {
+            let settings = MemberSettings { ignored: [12345].into(), ..Default::default() };
+
+            let actual = settings.applied(leaderboard.clone());
+
+            assert!(!actual.members.contains_key(&12345));
+            assert_eq!(actual.members.len(), leaderboard.members.len() - 1);
+        }
+
+        #[rstest]
+        #[test_log::test]
+        fn opted_out(#[from(test_leaderboard)] leaderboard: Leaderboard) {
+            let settings = MemberSettings {
+                aliases: [(12345, "Zaphod".into())].into(),
+                opted_out: [12345].into(),
+                ..Default::default()
+            };
+
+            let actual = settings.applied(leaderboard.clone());
+
+            assert_eq!(actual.members[&12345].name, None);
+            assert_eq!(actual.members[&12345].local_score, leaderboard.members[&12345].local_score);
+        }
+
+        #[rstest]
+        #[test_log::test]
+        fn pseudonymize(#[from(test_leaderboard)] leaderboard: Leaderboard) {
+            let settings = MemberSettings {
+                aliases: [(12345, "Zaphod".into())].into(),
+                pseudonymize: true,
+                ..Default::default()
+            };
+
+            let actual = settings.applied(leaderboard.clone());
+
+            assert_eq!(actual.members.len(), leaderboard.members.len());
+            for (id, member) in &actual.members {
+                assert_eq!(member.name, Some(pseudonym(*id)));
+                assert_eq!(member.stars, leaderboard.members[id].stars);
+                assert_eq!(member.local_score, leaderboard.members[id].local_score);
+            }
+        }
+    }
+}
+
+mod pseudonym {
+    use aoc_leaderbot_lib::leaderbot::members::pseudonym;
+
+    #[test_log::test]
+    fn is_stable() {
+        assert_eq!(pseudonym(12345), pseudonym(12345));
+    }
+
+    #[test_log::test]
+    fn is_adjective_animal() {
+        let actual = pseudonym(12345);
+
+        let (adjective, animal) = actual.split_once('-').unwrap();
+        assert!(!adjective.is_empty());
+        assert!(!animal.is_empty());
+    }
+
+    #[test_log::test]
+    fn differs_between_members() {
+        assert_ne!(pseudonym(1), pseudonym(2));
+    }
+}
@@ -0,0 +1,274 @@
+mod bot_metrics {
+    use anyhow::anyhow;
+    use aoc_leaderboard::aoc::{Leaderboard, LeaderboardMember};
+    use aoc_leaderboard::test_helpers::{TEST_LEADERBOARD_ID, TEST_YEAR, test_leaderboard};
+    use aoc_leaderbot_lib::Error;
+    use aoc_leaderbot_lib::error::ReporterError;
+    use aoc_leaderbot_lib::leaderbot::BotOutput;
+    use aoc_leaderbot_lib::leaderbot::diagnostics::{FetchDiagnostics, RunDiagnostics};
+    use aoc_leaderbot_lib::leaderbot::metrics::BotMetrics;
+    use assert_matches::assert_matches;
+    use prometheus::Registry;
+    use rstest::rstest;
+
+    fn output(leaderboard: Leaderboard) -> aoc_leaderbot_lib::Result<Option<BotOutput>> {
+        Ok(Some(BotOutput {
+            year: TEST_YEAR,
+            leaderboard_id: TEST_LEADERBOARD_ID,
+            previous_leaderboard: None,
+            leaderboard,
+            changes: None,
+            diagnostics: RunDiagnostics {
+                fetches: vec![FetchDiagnostics {
+                    leaderboard_id: TEST_LEADERBOARD_ID,
+                    http_status: Some(200),
+                    latency_ms: 250,
+                    throttled: false,
+                }],
+                ..RunDiagnostics::default()
+            },
+            deferred: false,
+        }))
+    }
+
+    fn member_labels(member: &LeaderboardMember) -> String {
+        let name = member
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("(anonymous user #{})", member.id));
+        format!(
+            "{{leaderboard_id=\"{TEST_LEADERBOARD_ID}\",member=\"{name}\",member_id=\"{}\",year=\"{TEST_YEAR}\"}}",
+            member.id
+        )
+    }
+
+    #[rstest]
+    #[test_log::test]
+    fn observe_success(#[from(test_leaderboard)] leaderboard: Leaderboard) {
+        let metrics = BotMetrics::new().unwrap();
+
+        metrics.observe(TEST_YEAR, TEST_LEADERBOARD_ID, &output(leaderboard.clone()));
+
+        let encoded = metrics.encode().unwrap();
+        assert!(encoded.contains(&format!(
+            "aoc_leaderbot_runs_total{{leaderboard_id=\"{TEST_LEADERBOARD_ID}\",outcome=\"success\",year=\"{TEST_YEAR}\"}} 1"
+        )));
+        assert!(encoded.contains(&format!(
+            "aoc_leaderbot_fetch_latency_seconds_sum{{leaderboard_id=\"{TEST_LEADERBOARD_ID}\",year=\"{TEST_YEAR}\"}} 0.25"
+        )));
+        for member in leaderboard.members.values() {
+            let labels = member_labels(member);
+            assert!(
+                encoded.contains(&format!("aoc_leaderbot_member_stars{labels} {}", member.stars))
+            );
+            assert!(encoded.contains(&format!(
+                "aoc_leaderbot_member_local_score{labels} {}",
+                member.local_score
+            )));
+        }
+    }
+
+    #[rstest]
+    #[test_log::test]
+    fn observe_deferred(#[from(test_leaderboard)] leaderboard: Leaderboard) {
+        let metrics = BotMetrics::new().unwrap();
+        let result = output(leaderboard)
+            .map(|output| output.map(|output| BotOutput { deferred: true, ..output }));
+
+        metrics.observe(TEST_YEAR, TEST_LEADERBOARD_ID, &result);
+
+        let encoded = metrics.encode().unwrap();
+        assert!(encoded.contains(&format!(
+            "aoc_leaderbot_runs_total{{leaderboard_id=\"{TEST_LEADERBOARD_ID}\",outcome=\"deferred\",year=\"{TEST_YEAR}\"}} 1"
+        )));
+        assert!(!encoded.contains("outcome=\"success\""));
+    }
+
+    #[test_log::test]
+    fn observe_skipped() {
+        let metrics = BotMetrics::new().unwrap();
+
+        metrics.observe(TEST_YEAR, TEST_LEADERBOARD_ID, &Ok(None));
+
+        let encoded = metrics.encode().unwrap();
+        assert!(!encoded.contains("aoc_leaderbot_runs_total"));
+    }
+
+    #[rstest]
+    #[test_log::test]
+    fn observe_removes_departed_members(#[from(test_leaderboard)] leaderboard: Leaderboard) {
+        let metrics = BotMetrics::new().unwrap();
+        metrics.observe(TEST_YEAR, TEST_LEADERBOARD_ID, &output(leaderboard.clone()));
+
+        let mut updated = leaderboard.clone();
+        let departed_id = *updated.members.keys().min().unwrap();
+        let departed = updated.members.remove(&departed_id).unwrap();
+        metrics.observe(TEST_YEAR, TEST_LEADERBOARD_ID, &output(updated.clone()));
+
+        let encoded = metrics.encode().unwrap();
+        assert!(!encoded.contains(&member_labels(&departed)));
+        for member in updated.members.values() {
+            assert!(encoded.contains(&format!(
+                "aoc_leaderbot_member_stars{} {}",
+                member_labels(member),
+                member.stars
+            )));
+        }
+    }
+
+    #[rstest]
+    #[case::fetch(
+        Error::Leaderboard(aoc_leaderboard::Error::NoAccess),
+        "aoc_leaderbot_fetch_errors_total",
+        "NoAccess"
+    )]
+    #[case::reporter(
+        Error::Reporter(ReporterError::ReportChanges(anyhow!("oops"))),
+        "aoc_leaderbot_reporter_failures_total",
+        "ReportChanges"
+    )]
+    #[test_log::test]
+    fn observe_error(#[case] error: Error, #[case] metric: &str, #[case] kind: &str) {
+        let metrics = BotMetrics::new().unwrap();
+
+        metrics.observe(TEST_YEAR, TEST_LEADERBOARD_ID, &Err(error));
+
+        let encoded = metrics.encode().unwrap();
+        assert!(encoded.contains(&format!(
+            "aoc_leaderbot_runs_total{{leaderboard_id=\"{TEST_LEADERBOARD_ID}\",outcome=\"error\",year=\"{TEST_YEAR}\"}} 1"
+        )));
+        assert!(encoded.contains(&format!(
+            "{metric}{{kind=\"{kind}\",leaderboard_id=\"{TEST_LEADERBOARD_ID}\",year=\"{TEST_YEAR}\"}} 1"
+        )));
+    }
+
+    #[test_log::test]
+    fn with_registry() {
+        let registry = Registry::new();
+
+        let metrics = BotMetrics::with_registry(registry.clone()).unwrap();
+        metrics.observe(
+            TEST_YEAR,
+            TEST_LEADERBOARD_ID,
+            &Err(Error::Leaderboard(aoc_leaderboard::Error::NoAccess)),
+        );
+        assert!(!registry.gather().is_empty());
+
+        let duplicate = BotMetrics::with_registry(registry);
+        assert_matches!(duplicate, Err(Error::Metrics(_)));
+    }
+
+    mod serve {
+        use aoc_leaderbot_lib::leaderbot::metrics::{METRICS_PATH, METRICS_REQUEST_TIMEOUT};
+        use reqwest::StatusCode;
+        use tokio::io::AsyncReadExt;
+        use tokio::net::{TcpListener, TcpStream};
+        use tokio::time::Instant;
+
+        use super::*;
+
+        #[test_log::test(tokio::test)]
+        async fn metrics_and_not_found() {
+            let metrics = BotMetrics::new().unwrap();
+            metrics.observe(
+                TEST_YEAR,
+                TEST_LEADERBOARD_ID,
+                &Err(Error::Leaderboard(aoc_leaderboard::Error::NoAccess)),
+            );
+
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let base = format!("http://{}", listener.local_addr().unwrap());
+
+            // The server stops once the client is done.
+            let client = async move {
+                let http_client = reqwest::Client::new();
+
+                let response = http_client
+                    .get(format!("{base}{METRICS_PATH}"))
+                    .send()
+                    .await
+                    .unwrap();
+                assert_eq!(response.status(), StatusCode::OK);
+                let body = response.text().await.unwrap();
+                assert!(body.contains("aoc_leaderbot_runs_total"));
+
+                let response = http_client.get(format!("{base}/foo")).send().await.unwrap();
+                assert_eq!(response.status(), StatusCode::NOT_FOUND);
+            };
+
+            let result = metrics.serve(listener, client).await;
+            assert_matches!(result, Ok(()));
+        }
+
+        #[test_log::test(tokio::test(start_paused = true))]
+        async fn drops_requests_after_timeout() {
+            let metrics = BotMetrics::new().unwrap();
+
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let address = listener.local_addr().unwrap();
+
+            // The server closes the connection of a client that never sends its request.
+            let client = async move {
+                let mut stream = TcpStream::connect(address).await.unwrap();
+                let started = Instant::now();
+
+                let mut buffer = [0; 16];
+                let read = stream.read(&mut buffer).await.unwrap();
+                assert_eq!(read, 0);
+                assert!(started.elapsed() >= METRICS_REQUEST_TIMEOUT);
+            };
+
+            let result = metrics.serve(listener, client).await;
+            assert_matches!(result, Ok(()));
+        }
+    }
+
+    mod push {
+        use aoc_leaderboard::wiremock::matchers::{body_string_contains, method, path};
+        use aoc_leaderboard::wiremock::{Mock, MockServer, ResponseTemplate};
+
+        use super::*;
+
+        const JOB: &str = "aoc_leaderbot";
+
+        #[test_log::test(tokio::test)]
+        async fn success() {
+            let mock_server = MockServer::start().await;
+            Mock::given(method("PUT"))
+                .and(path(format!("/metrics/job/{JOB}")))
+                .and(body_string_contains("aoc_leaderbot_runs_total"))
+                .respond_with(ResponseTemplate::new(200))
+                .expect(1)
+                .mount(&mock_server)
+                .await;
+
+            let metrics = BotMetrics::new().unwrap();
+            metrics.observe(
+                TEST_YEAR,
+                TEST_LEADERBOARD_ID,
+                &Err(Error::Leaderboard(aoc_leaderboard::Error::NoAccess)),
+            );
+
+            let result = metrics
+                .push(&reqwest::Client::new(), mock_server.uri(), JOB)
+                .await;
+            assert_matches!(result, Ok(()));
+        }
+
+        #[test_log::test(tokio::test)]
+        async fn error() {
+            let mock_server = MockServer::start().await;
+            Mock::given(method("PUT"))
+                .respond_with(ResponseTemplate::new(500))
+                .mount(&mock_server)
+                .await;
+
+            let metrics = BotMetrics::new().unwrap();
+
+            let result = metrics
+                .push(&reqwest::Client::new(), mock_server.uri(), JOB)
+                .await;
+            assert_matches!(result, Err(Error::Metrics(_)));
+        }
+    }
+}
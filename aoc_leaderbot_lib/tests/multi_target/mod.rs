@@ -0,0 +1,247 @@
+mod multi_target_runner {
+    use aoc_leaderbot_lib::leaderbot::event::MIN_INTERVAL;
+    use aoc_leaderbot_lib::leaderbot::multi_target::{DEFAULT_MAX_CONCURRENCY, MultiTargetRunner};
+    use chrono::TimeDelta;
+    use rstest::rstest;
+
+    type Runner = MultiTargetRunner<(), ()>;
+
+    #[test_log::test]
+    fn default() {
+        let runner = Runner::new();
+
+        assert!(runner.targets().is_empty());
+        assert_eq!(runner.max_concurrency(), DEFAULT_MAX_CONCURRENCY);
+        assert_eq!(runner.min_fetch_interval(), MIN_INTERVAL);
+        assert_eq!(runner.last_fetch(2024, 12345), None);
+    }
+
+    #[rstest]
+    #[case::zero(0, 1)]
+    #[case::one(1, 1)]
+    #[case::more(8, 8)]
+    #[test_log::test]
+    fn with_max_concurrency(#[case] max_concurrency: usize, #[case] expected: usize) {
+        let runner = Runner::new().with_max_concurrency(max_concurrency);
+
+        assert_eq!(runner.max_concurrency(), expected);
+    }
+
+    #[rstest]
+    #[case::less_than_min(TimeDelta::minutes(1), MIN_INTERVAL)]
+    #[case::more_than_min(TimeDelta::hours(1), TimeDelta::hours(1))]
+    #[test_log::test]
+    fn with_min_fetch_interval(#[case] interval: TimeDelta, #[case] expected: TimeDelta) {
+        let runner = Runner::new().with_min_fetch_interval(interval);
+
+        assert_eq!(runner.min_fetch_interval(), expected);
+    }
+}
+
+#[cfg(all(feature = "config-mem", feature = "storage-mem", feature = "reporter-recording"))]
+mod run {
+    use aoc_leaderboard::test_helpers::{
+        TEST_LEADERBOARD_ID, TEST_YEAR, mock_server_with_leaderboard, test_leaderboard_credentials,
+    };
+    use aoc_leaderboard::wiremock::MockServer;
+    use aoc_leaderbot_lib::Error;
+    use aoc_leaderbot_lib::leaderbot::clock::{Clock, TestClock};
+    use aoc_leaderbot_lib::leaderbot::config::mem::MemoryConfig;
+    use aoc_leaderbot_lib::leaderbot::event::MIN_INTERVAL;
+    use aoc_leaderbot_lib::leaderbot::multi_target::{MultiTargetRunner, TargetOutput};
+    use aoc_leaderbot_lib::leaderbot::reporter::recording::RecordingReporter;
+    use aoc_leaderbot_lib::leaderbot::storage::mem::MemoryStorage;
+    use assert_matches::assert_matches;
+    use chrono::Utc;
+    use rstest::rstest;
+
+    const OTHER_LEADERBOARD_ID: u64 = TEST_LEADERBOARD_ID + 1;
+
+    fn config(leaderboard_id: u64) -> MemoryConfig {
+        MemoryConfig::builder()
+            .year(TEST_YEAR)
+            .leaderboard_id(leaderboard_id)
+            .credentials(test_leaderboard_credentials::default())
+            .build()
+            .unwrap()
+    }
+
+    fn runner() -> MultiTargetRunner<MemoryConfig, RecordingReporter> {
+        MultiTargetRunner::new()
+            .with_max_concurrency(2)
+            .with_target(config(TEST_LEADERBOARD_ID), RecordingReporter::new())
+            .unwrap()
+            .with_target(config(OTHER_LEADERBOARD_ID), RecordingReporter::new())
+            .unwrap()
+    }
+
+    #[test_log::test]
+    fn with_target() {
+        let runner = runner();
+
+        assert_eq!(runner.targets().len(), 2);
+    }
+
+    #[test_log::test]
+    fn rejects_duplicate_targets() {
+        let mut runner = runner();
+
+        let result = runner.add_target(config(TEST_LEADERBOARD_ID), RecordingReporter::new());
+        assert_matches!(
+            result,
+            Err(Error::DuplicateTarget { year: TEST_YEAR, leaderboard_id: TEST_LEADERBOARD_ID })
+        );
+        assert_eq!(runner.targets().len(), 2);
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn isolates_errors(
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        mock_server: MockServer,
+    ) {
+        let mut runner = runner();
+        let mut storage = MemoryStorage::new();
+
+        let outputs = runner
+            .run_from(Some(mock_server.uri()), &mut storage, false)
+            .await
+            .unwrap();
+        assert_eq!(outputs.len(), 2);
+        assert_matches!(
+            &outputs[0],
+            TargetOutput {
+                year: TEST_YEAR,
+                leaderboard_id: TEST_LEADERBOARD_ID,
+                result: Ok(Some(output)),
+                diagnostics,
+            } if *diagnostics == output.diagnostics
+        );
+        assert_matches!(
+            &outputs[1],
+            TargetOutput {
+                year: TEST_YEAR,
+                leaderboard_id: OTHER_LEADERBOARD_ID,
+                result: Err(_),
+                ..
+            }
+        );
+        assert_eq!(
+            outputs[1].diagnostics.storage_operation_names(),
+            ["load_previous", "save_error_state"]
+        );
+        assert_eq!(outputs[1].diagnostics.reporter_call_names(), ["report_error"]);
+
+        let targets = runner.targets();
+        targets[0]
+            .reporter
+            .assert_first_run_reported(TEST_YEAR, TEST_LEADERBOARD_ID);
+        assert_eq!(targets[1].reporter.errors().count(), 1);
+        assert!(runner.last_fetch(TEST_YEAR, TEST_LEADERBOARD_ID).is_some());
+        assert!(runner.last_fetch(TEST_YEAR, OTHER_LEADERBOARD_ID).is_some());
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn rejects_targets_made_duplicate(
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        mock_server: MockServer,
+    ) {
+        let mut runner = runner();
+        runner.targets_mut()[1].config.leaderboard_id = TEST_LEADERBOARD_ID;
+        let mut storage = MemoryStorage::new();
+
+        let outputs = runner
+            .run_from(Some(mock_server.uri()), &mut storage, false)
+            .await
+            .unwrap();
+        assert_matches!(&outputs[0].result, Ok(Some(_)));
+        assert_matches!(
+            &outputs[1].result,
+            Err(Error::DuplicateTarget { year: TEST_YEAR, leaderboard_id: TEST_LEADERBOARD_ID })
+        );
+        assert!(!runner.targets()[1].reporter.called());
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn throttles_fetches(
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        mock_server: MockServer,
+    ) {
+        let mut runner = runner();
+        let mut storage = MemoryStorage::new();
+
+        runner
+            .run_from(Some(mock_server.uri()), &mut storage, false)
+            .await
+            .unwrap();
+        for target in runner.targets_mut() {
+            target.reporter.clear();
+        }
+
+        let outputs = runner
+            .run_from(Some(mock_server.uri()), &mut storage, false)
+            .await
+            .unwrap();
+        assert!(
+            outputs
+                .iter()
+                .all(|output| matches!(output.result, Ok(None)))
+        );
+        assert!(outputs.iter().all(|output| {
+            output.diagnostics.fetches.len() == 1
+                && output.diagnostics.fetches[0].throttled
+                && output.diagnostics.fetches[0].http_status.is_none()
+                && output.diagnostics.storage_operations.is_empty()
+        }));
+        assert!(
+            runner
+                .targets()
+                .iter()
+                .all(|target| !target.reporter.called())
+        );
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn throttles_fetches_using_config_clock(
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        mock_server: MockServer,
+    ) {
+        let clock = TestClock::new(Utc::now());
+        let config = MemoryConfig::builder()
+            .year(TEST_YEAR)
+            .leaderboard_id(TEST_LEADERBOARD_ID)
+            .credentials(test_leaderboard_credentials::default())
+            .clock(clock.clone())
+            .build()
+            .unwrap();
+        let mut runner = MultiTargetRunner::new()
+            .with_target(config, RecordingReporter::new())
+            .unwrap();
+        let mut storage = MemoryStorage::new();
+
+        runner
+            .run_from(Some(mock_server.uri()), &mut storage, false)
+            .await
+            .unwrap();
+        assert_eq!(runner.last_fetch(TEST_YEAR, TEST_LEADERBOARD_ID), Some(clock.now()));
+
+        clock.advance(MIN_INTERVAL);
+        let outputs = runner
+            .run_from(Some(mock_server.uri()), &mut storage, false)
+            .await
+            .unwrap();
+        assert_matches!(&outputs[0].result, Ok(Some(_)));
+        assert_eq!(runner.last_fetch(TEST_YEAR, TEST_LEADERBOARD_ID), Some(clock.now()));
+    }
+}
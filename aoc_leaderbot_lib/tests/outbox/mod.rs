@@ -0,0 +1,336 @@
+#[cfg(all(feature = "config-mem", feature = "storage-mem", feature = "reporter-recording"))]
+mod run_bot_outbox {
+    use aoc_leaderboard::aoc::Leaderboard;
+    use aoc_leaderboard::test_helpers::{
+        TEST_LEADERBOARD_ID, TEST_YEAR, mock_server_with_leaderboard, test_leaderboard,
+        test_leaderboard_credentials,
+    };
+    use aoc_leaderboard::wiremock::MockServer;
+    use aoc_leaderbot_lib::ErrorKind;
+    use aoc_leaderbot_lib::error::ReporterErrorKind;
+    use aoc_leaderbot_lib::leaderbot::config::mem::MemoryConfig;
+    use aoc_leaderbot_lib::leaderbot::members::MemberSettings;
+    use aoc_leaderbot_lib::leaderbot::outbox::run_bot_outbox_from;
+    use aoc_leaderbot_lib::leaderbot::reporter::recording::RecordingReporter;
+    use aoc_leaderbot_lib::leaderbot::storage::mem::MemoryStorage;
+    use aoc_leaderbot_lib::leaderbot::{Changes, OutboxReport, OutboxStorage, Storage};
+    use assert_matches::assert_matches;
+    use rstest::{fixture, rstest};
+
+    #[fixture]
+    fn config() -> MemoryConfig {
+        MemoryConfig::builder()
+            .year(TEST_YEAR)
+            .leaderboard_id(TEST_LEADERBOARD_ID)
+            .credentials(test_leaderboard_credentials::default())
+            .build()
+            .unwrap()
+    }
+
+    #[fixture]
+    fn member_id(#[from(test_leaderboard)] leaderboard: Leaderboard) -> u64 {
+        *leaderboard.members.keys().min().unwrap()
+    }
+
+    #[fixture]
+    fn updated_leaderboard(
+        #[from(test_leaderboard)] mut leaderboard: Leaderboard,
+        member_id: u64,
+    ) -> Leaderboard {
+        leaderboard.members.get_mut(&member_id).unwrap().stars += 1;
+        leaderboard
+    }
+
+    async fn storage_with(previous: &Leaderboard) -> MemoryStorage {
+        let mut storage = MemoryStorage::new();
+        storage
+            .save_success(TEST_YEAR, TEST_LEADERBOARD_ID, previous)
+            .await
+            .unwrap();
+        storage
+    }
+
+    async fn load_previous(storage: &MemoryStorage) -> (Option<Leaderboard>, Option<ErrorKind>) {
+        storage
+            .load_previous(TEST_YEAR, TEST_LEADERBOARD_ID)
+            .await
+            .unwrap()
+    }
+
+    async fn load_outbox(storage: &MemoryStorage) -> Option<OutboxReport> {
+        storage
+            .load_outbox(TEST_YEAR, TEST_LEADERBOARD_ID)
+            .await
+            .unwrap()
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn first_run(
+        config: MemoryConfig,
+        #[from(test_leaderboard)] leaderboard: Leaderboard,
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        mock_server: MockServer,
+    ) {
+        let mut storage = MemoryStorage::new();
+        let mut reporter = RecordingReporter::new();
+
+        let result = run_bot_outbox_from(
+            Some(mock_server.uri()),
+            &config,
+            &mut storage,
+            &mut reporter,
+            false,
+        )
+        .await;
+        assert_matches!(result, Ok(Some(output)) => {
+            assert!(output.previous_leaderboard.is_none());
+            assert!(output.changes.is_none());
+        });
+
+        reporter.assert_first_run_reported(TEST_YEAR, TEST_LEADERBOARD_ID);
+        assert_eq!(load_previous(&storage).await, (Some(leaderboard), None));
+        assert!(load_outbox(&storage).await.is_none());
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn with_changes(
+        config: MemoryConfig,
+        #[from(test_leaderboard)] base: Leaderboard,
+        updated_leaderboard: Leaderboard,
+        member_id: u64,
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        #[with(updated_leaderboard.clone())]
+        mock_server: MockServer,
+    ) {
+        let mut storage = storage_with(&base).await;
+        let mut reporter = RecordingReporter::new();
+
+        run_bot_outbox_from(Some(mock_server.uri()), &config, &mut storage, &mut reporter, false)
+            .await
+            .unwrap();
+
+        let expected_changes = Changes::new([].into(), [member_id].into());
+        let reported =
+            reporter.assert_changes_reported(TEST_YEAR, TEST_LEADERBOARD_ID, &expected_changes);
+        assert_eq!(reported.previous_leaderboard, base);
+        assert_eq!(reported.leaderboard, updated_leaderboard);
+        assert_eq!(load_previous(&storage).await, (Some(updated_leaderboard), None));
+        assert!(load_outbox(&storage).await.is_none());
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn with_ignored_member(
+        mut config: MemoryConfig,
+        #[from(test_leaderboard)] base: Leaderboard,
+        updated_leaderboard: Leaderboard,
+        member_id: u64,
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        #[with(updated_leaderboard.clone())]
+        mock_server: MockServer,
+    ) {
+        config.member_settings =
+            MemberSettings { ignored: [member_id].into(), ..Default::default() };
+        let mut storage = storage_with(&base).await;
+        let mut reporter = RecordingReporter::new();
+
+        let output = run_bot_outbox_from(
+            Some(mock_server.uri()),
+            &config,
+            &mut storage,
+            &mut reporter,
+            false,
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        assert!(output.changes.is_none());
+        assert!(!output.leaderboard.members.contains_key(&member_id));
+        reporter.assert_not_called();
+        assert_eq!(
+            load_previous(&storage).await,
+            (Some(config.member_settings.applied(updated_leaderboard)), None)
+        );
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn without_changes(
+        config: MemoryConfig,
+        #[from(test_leaderboard)] base: Leaderboard,
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        mock_server: MockServer,
+    ) {
+        let mut storage = storage_with(&base).await;
+        let mut reporter = RecordingReporter::new();
+
+        run_bot_outbox_from(Some(mock_server.uri()), &config, &mut storage, &mut reporter, false)
+            .await
+            .unwrap();
+
+        reporter.assert_not_called();
+        assert_eq!(load_previous(&storage).await, (Some(base), None));
+        assert!(load_outbox(&storage).await.is_none());
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn retries_undelivered_report(
+        config: MemoryConfig,
+        #[from(test_leaderboard)] base: Leaderboard,
+        updated_leaderboard: Leaderboard,
+        member_id: u64,
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        #[with(updated_leaderboard.clone())]
+        mock_server: MockServer,
+    ) {
+        let mut storage = storage_with(&base).await;
+        let expected_changes = Changes::new([].into(), [member_id].into());
+
+        let mut failing_reporter = RecordingReporter::new().fail_report_changes(true);
+        let result = run_bot_outbox_from(
+            Some(mock_server.uri()),
+            &config,
+            &mut storage,
+            &mut failing_reporter,
+            false,
+        )
+        .await;
+        assert_matches!(result, Err(err) if err.is_reporter());
+        assert_eq!(failing_reporter.changes().count(), 1);
+        assert_eq!(
+            failing_reporter
+                .errors()
+                .map(|error| error.error_kind)
+                .collect::<Vec<_>>(),
+            [ErrorKind::Reporter(ReporterErrorKind::ReportChanges)]
+        );
+        assert_eq!(
+            load_previous(&storage).await,
+            (
+                Some(updated_leaderboard.clone()),
+                Some(ErrorKind::Reporter(ReporterErrorKind::ReportChanges))
+            )
+        );
+        assert_eq!(
+            load_outbox(&storage).await,
+            Some(OutboxReport::Changes {
+                previous_leaderboard: base.clone(),
+                changes: expected_changes.clone()
+            })
+        );
+
+        let mut reporter = RecordingReporter::new();
+        let result = run_bot_outbox_from(
+            Some(mock_server.uri()),
+            &config,
+            &mut storage,
+            &mut reporter,
+            false,
+        )
+        .await;
+        assert_matches!(result, Ok(Some(output)) => {
+            assert!(output.changes.is_none());
+        });
+
+        // Delivery failed during the first run, so the bot reports recovering from that error.
+        assert_eq!(reporter.call_count(), 2);
+        let reported = reporter.changes().next().unwrap();
+        assert_eq!(reported.changes, expected_changes);
+        assert_eq!(reported.previous_leaderboard, base);
+        assert_eq!(reported.leaderboard, updated_leaderboard);
+        assert_eq!(
+            reporter
+                .recoveries()
+                .map(|recovery| recovery.error_kind)
+                .collect::<Vec<_>>(),
+            vec![ErrorKind::Reporter(ReporterErrorKind::ReportChanges)]
+        );
+        assert_eq!(load_previous(&storage).await, (Some(updated_leaderboard), None));
+        assert!(load_outbox(&storage).await.is_none());
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn dry_run(
+        config: MemoryConfig,
+        #[from(test_leaderboard)] base: Leaderboard,
+        updated_leaderboard: Leaderboard,
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        #[with(updated_leaderboard.clone())]
+        mock_server: MockServer,
+    ) {
+        let _ = updated_leaderboard;
+
+        let mut storage = MemoryStorage::new();
+        storage
+            .save_success_with_outbox(
+                TEST_YEAR,
+                TEST_LEADERBOARD_ID,
+                &base,
+                &OutboxReport::FirstRun,
+            )
+            .await
+            .unwrap();
+        let mut reporter = RecordingReporter::new();
+
+        let result = run_bot_outbox_from(
+            Some(mock_server.uri()),
+            &config,
+            &mut storage,
+            &mut reporter,
+            true,
+        )
+        .await;
+        assert_matches!(result, Ok(Some(output)) => {
+            assert!(output.changes.is_some());
+        });
+
+        reporter.assert_not_called();
+        assert_eq!(load_previous(&storage).await, (Some(base), None));
+        assert_eq!(load_outbox(&storage).await, Some(OutboxReport::FirstRun));
+    }
+
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn reports_recovery(
+        config: MemoryConfig,
+        #[from(test_leaderboard)] base: Leaderboard,
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        mock_server: MockServer,
+    ) {
+        let error_kind = ErrorKind::Reporter(ReporterErrorKind::ReportChanges);
+        let mut storage = storage_with(&base).await;
+        storage
+            .save_error(TEST_YEAR, TEST_LEADERBOARD_ID, error_kind)
+            .await
+            .unwrap();
+        let mut reporter = RecordingReporter::new();
+
+        run_bot_outbox_from(Some(mock_server.uri()), &config, &mut storage, &mut reporter, false)
+            .await
+            .unwrap();
+
+        let reported =
+            reporter.assert_recovery_reported(TEST_YEAR, TEST_LEADERBOARD_ID, error_kind);
+        assert!(reported.outage_duration.is_some());
+        assert_eq!(load_previous(&storage).await, (Some(base), None));
+    }
+}
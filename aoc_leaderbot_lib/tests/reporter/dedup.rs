@@ -0,0 +1,191 @@
+mod dedup_reporter {
+    use aoc_leaderboard::aoc::Leaderboard;
+    use aoc_leaderboard::test_helpers::{TEST_LEADERBOARD_ID, TEST_YEAR, test_leaderboard};
+    use aoc_leaderbot_lib::error::StorageError;
+    use aoc_leaderbot_lib::leaderbot::clock::TestClock;
+    use aoc_leaderbot_lib::leaderbot::reporter::dedup::ChangesCollector;
+    use aoc_leaderbot_lib::leaderbot::reporter::middleware::ReporterExt;
+    use aoc_leaderbot_lib::leaderbot::reporter::recording::RecordingReporter;
+    use aoc_leaderbot_lib::leaderbot::{Changes, Reporter};
+    use aoc_leaderbot_lib::{Error, ErrorKind};
+    use chrono::{Local, NaiveTime, TimeDelta, TimeZone, Utc};
+    use rstest::rstest;
+
+    const OTHER_LEADERBOARD_ID: u64 = TEST_LEADERBOARD_ID + 1;
+
+    #[rstest]
+    #[test_log::test(tokio::test)]
+    async fn collects_changes(#[from(test_leaderboard)] leaderboard: Leaderboard) {
+        let collector = ChangesCollector::new();
+        let mut first = collector.reporter(RecordingReporter::new());
+        let mut second = collector.reporter(RecordingReporter::new());
+
+        first
+            .report_changes(
+                TEST_YEAR,
+                TEST_LEADERBOARD_ID,
+                None,
+                &leaderboard,
+                &leaderboard,
+                &Changes::new([12345].into(), [12346].into()),
+            )
+            .await
+            .unwrap();
+        second
+            .report_changes(
+                TEST_YEAR,
+                OTHER_LEADERBOARD_ID,
+                None,
+                &leaderboard,
+                &leaderboard,
+                &Changes::new([12345, 12347].into(), [].into()),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(collector.len(), 2);
+        assert!(!first.inner().called());
+        assert!(!second.inner().called());
+
+        let mut destination = RecordingReporter::new();
+        collector.flush(&mut destination).await.unwrap();
+
+        assert!(collector.is_empty());
+        let reported: Vec<_> = destination
+            .changes()
+            .map(|reported| (reported.leaderboard_id, reported.changes.clone()))
+            .collect();
+        assert_eq!(
+            reported,
+            [
+                (TEST_LEADERBOARD_ID, Changes::new([12345].into(), [12346].into())),
+                (OTHER_LEADERBOARD_ID, Changes::new([12347].into(), [].into())),
+            ]
+        );
+    }
+
+    #[rstest]
+    #[test_log::test(tokio::test)]
+    async fn forwards_other_calls(#[from(test_leaderboard)] leaderboard: Leaderboard) {
+        let collector = ChangesCollector::new();
+        let mut reporter = collector.reporter(RecordingReporter::new());
+        let error = Error::Storage(StorageError::LoadPrevious(anyhow::anyhow!("oops")));
+
+        reporter
+            .report_first_run(TEST_YEAR, TEST_LEADERBOARD_ID, None, &leaderboard)
+            .await
+            .unwrap();
+        reporter
+            .report_error(TEST_YEAR, TEST_LEADERBOARD_ID, None, &error)
+            .await;
+        reporter
+            .report_recovery(TEST_YEAR, TEST_LEADERBOARD_ID, None, ErrorKind::from(&error), None)
+            .await;
+        reporter
+            .report_stale(TEST_YEAR, TEST_LEADERBOARD_ID, None, TimeDelta::hours(3))
+            .await;
+        reporter
+            .report_heartbeat(TEST_YEAR, TEST_LEADERBOARD_ID, None, &leaderboard)
+            .await;
+
+        assert!(collector.is_empty());
+        let inner = reporter.into_inner();
+        assert_eq!(inner.call_count(), 5);
+        assert_eq!(inner.stales().count(), 1);
+        assert_eq!(inner.heartbeats().count(), 1);
+        assert_eq!(inner.first_runs().count(), 1);
+        assert_eq!(inner.errors().count(), 1);
+        assert_eq!(inner.recoveries().count(), 1);
+    }
+
+    #[rstest]
+    #[test_log::test(tokio::test)]
+    async fn keeps_changes_if_flush_fails(#[from(test_leaderboard)] leaderboard: Leaderboard) {
+        let collector = ChangesCollector::new();
+        let mut reporter = collector.reporter(RecordingReporter::new());
+        for (year, leaderboard_id) in
+            [(TEST_YEAR, TEST_LEADERBOARD_ID), (TEST_YEAR + 1, OTHER_LEADERBOARD_ID)]
+        {
+            reporter
+                .report_changes(
+                    year,
+                    leaderboard_id,
+                    None,
+                    &leaderboard,
+                    &leaderboard,
+                    &Changes::new([12345].into(), [].into()),
+                )
+                .await
+                .unwrap();
+        }
+
+        let mut destination = RecordingReporter::new().fail_report_changes(true);
+        assert!(collector.flush(&mut destination).await.is_err());
+
+        assert_eq!(collector.len(), 2);
+        assert_eq!(destination.changes().count(), 1);
+
+        let mut destination = RecordingReporter::new();
+        collector.flush(&mut destination).await.unwrap();
+
+        assert!(collector.is_empty());
+        let reported: Vec<_> = destination
+            .changes()
+            .map(|reported| (reported.year, reported.leaderboard_id))
+            .collect();
+        assert_eq!(
+            reported,
+            [(TEST_YEAR, TEST_LEADERBOARD_ID), (TEST_YEAR + 1, OTHER_LEADERBOARD_ID)]
+        );
+    }
+
+    #[rstest]
+    #[test_log::test(tokio::test)]
+    async fn keeps_changes_if_reports_are_deferred(
+        #[from(test_leaderboard)] leaderboard: Leaderboard,
+    ) {
+        let collector = ChangesCollector::new();
+        let mut reporter = collector.reporter(RecordingReporter::new());
+        reporter
+            .report_changes(
+                TEST_YEAR,
+                TEST_LEADERBOARD_ID,
+                None,
+                &leaderboard,
+                &leaderboard,
+                &Changes::new([12345].into(), [].into()),
+            )
+            .await
+            .unwrap();
+
+        let clock = TestClock::new(
+            Local
+                .with_ymd_and_hms(2024, 12, 1, 23, 0, 0)
+                .unwrap()
+                .with_timezone(&Utc),
+        );
+        let mut destination = RecordingReporter::new()
+            .quiet_hours(NaiveTime::from_hms_opt(22, 0, 0).unwrap(), NaiveTime::MIN)
+            .with_clock(clock.clone());
+        collector.flush(&mut destination).await.unwrap();
+
+        assert_eq!(collector.len(), 1);
+        destination.inner().assert_not_called();
+
+        clock.advance(TimeDelta::hours(1));
+        collector.flush(&mut destination).await.unwrap();
+
+        assert!(collector.is_empty());
+        assert_eq!(destination.inner().changes().count(), 1);
+    }
+
+    #[test_log::test(tokio::test)]
+    async fn flush_without_changes() {
+        let collector = ChangesCollector::new();
+        let mut destination = RecordingReporter::new();
+
+        collector.flush(&mut destination).await.unwrap();
+
+        destination.assert_not_called();
+    }
+}
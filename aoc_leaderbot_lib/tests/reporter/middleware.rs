@@ -0,0 +1,473 @@
+use std::collections::HashMap;
+use std::convert::Infallible;
+
+use aoc_leaderboard::aoc::Leaderboard;
+use aoc_leaderboard::test_helpers::{TEST_LEADERBOARD_ID, TEST_YEAR, test_leaderboard};
+use aoc_leaderbot_lib::Error;
+use aoc_leaderbot_lib::error::StorageError;
+use aoc_leaderbot_lib::leaderbot::reporter::recording::RecordingReporter;
+use aoc_leaderbot_lib::leaderbot::{Changes, LeaderboardChanges, MergedChanges, Reporter};
+
+async fn report_all<R>(reporter: &mut R, leaderboard: &Leaderboard, changes: &Changes)
+where
+    R: Reporter,
+{
+    let _ = reporter
+        .report_changes(TEST_YEAR, TEST_LEADERBOARD_ID, None, leaderboard, leaderboard, changes)
+        .await;
+    let _ = reporter
+        .report_first_run(TEST_YEAR, TEST_LEADERBOARD_ID, None, leaderboard)
+        .await;
+    reporter
+        .report_error(TEST_YEAR, TEST_LEADERBOARD_ID, None, &load_previous_error())
+        .await;
+    reporter
+        .report_recovery(
+            TEST_YEAR,
+            TEST_LEADERBOARD_ID,
+            None,
+            (&load_previous_error()).into(),
+            Some(chrono::TimeDelta::hours(1)),
+        )
+        .await;
+    reporter
+        .report_stale(TEST_YEAR, TEST_LEADERBOARD_ID, None, chrono::TimeDelta::hours(3))
+        .await;
+    reporter
+        .report_heartbeat(TEST_YEAR, TEST_LEADERBOARD_ID, None, leaderboard)
+        .await;
+}
+
+fn load_previous_error() -> Error {
+    Error::Storage(StorageError::LoadPrevious(anyhow::anyhow!("oops")))
+}
+
+fn merged_changes(leaderboard: &Leaderboard, changes: &[(u64, Changes)]) -> MergedChanges {
+    MergedChanges::merge(
+        changes
+            .iter()
+            .map(|(leaderboard_id, changes)| LeaderboardChanges {
+                year: TEST_YEAR,
+                leaderboard_id: *leaderboard_id,
+                view_key: None,
+                previous_leaderboard: leaderboard.clone(),
+                leaderboard: leaderboard.clone(),
+                changes: changes.clone(),
+            }),
+    )
+    .remove(0)
+}
+
+// Reporter that only supports reporting merged changes, to make sure they are forwarded as-is.
+#[derive(Debug, Default)]
+struct MergedChangesReporter {
+    merged_changes: Vec<MergedChanges>,
+}
+
+impl Reporter for MergedChangesReporter {
+    type Err = Infallible;
+
+    async fn report_changes(
+        &mut self,
+        _year: i32,
+        _leaderboard_id: u64,
+        _view_key: Option<&str>,
+        _previous_leaderboard: &Leaderboard,
+        _leaderboard: &Leaderboard,
+        _changes: &Changes,
+    ) -> Result<(), Self::Err> {
+        panic!("changes should be reported as merged changes");
+    }
+
+    async fn report_merged_changes(
+        &mut self,
+        merged_changes: &MergedChanges,
+    ) -> Result<(), Self::Err> {
+        self.merged_changes.push(merged_changes.clone());
+        Ok(())
+    }
+}
+
+mod member_filter_reporter {
+    use aoc_leaderbot_lib::leaderbot::reporter::middleware::{MemberFilterReporter, ReporterExt};
+    use rstest::rstest;
+
+    use super::*;
+
+    #[test_log::test]
+    fn new() {
+        let reporter = MemberFilterReporter::new(RecordingReporter::new(), [42, 23]);
+
+        assert_eq!(*reporter.ignored_members(), [42, 23].into());
+        assert!(!reporter.inner().called());
+        assert!(!reporter.into_inner().called());
+    }
+
+    #[rstest]
+    #[case::nothing_ignored(Changes::new([1, 2].into(), [3].into()), Some(Changes::new([1, 2].into(), [3].into())))]
+    #[case::some_ignored(Changes::new([1, 42].into(), [23, 3].into()), Some(Changes::new([1].into(), [3].into())))]
+    #[case::all_ignored(Changes::new([42].into(), [23].into()), None)]
+    #[test_log::test(tokio::test)]
+    async fn report_changes(
+        #[from(test_leaderboard)] leaderboard: Leaderboard,
+        #[case] changes: Changes,
+        #[case] expected_changes: Option<Changes>,
+    ) {
+        let mut reporter = RecordingReporter::new().filter_members([42, 23]);
+
+        reporter
+            .report_changes(
+                TEST_YEAR,
+                TEST_LEADERBOARD_ID,
+                None,
+                &leaderboard,
+                &leaderboard,
+                &changes,
+            )
+            .await
+            .unwrap();
+
+        let inner = reporter.into_inner();
+        match expected_changes {
+            Some(expected_changes) => {
+                inner.assert_changes_reported(TEST_YEAR, TEST_LEADERBOARD_ID, &expected_changes);
+            },
+            None => inner.assert_not_called(),
+        }
+    }
+
+    #[rstest]
+    #[test_log::test(tokio::test)]
+    async fn forwards_first_run_and_errors(#[from(test_leaderboard)] leaderboard: Leaderboard) {
+        let mut reporter = RecordingReporter::new().filter_members([42]);
+
+        report_all(&mut reporter, &leaderboard, &Changes::new([42].into(), [].into())).await;
+
+        let inner = reporter.into_inner();
+        assert_eq!(inner.changes().count(), 0);
+        assert_eq!(inner.first_runs().count(), 1);
+        assert_eq!(inner.errors().count(), 1);
+        assert_eq!(inner.recoveries().count(), 1);
+        assert_eq!(inner.stales().count(), 1);
+        assert_eq!(inner.heartbeats().count(), 1);
+    }
+
+    #[rstest]
+    #[test_log::test(tokio::test)]
+    async fn report_merged_changes(#[from(test_leaderboard)] leaderboard: Leaderboard) {
+        let mut reporter = MemberFilterReporter::new(MergedChangesReporter::default(), [42, 23]);
+        let mut member_leaderboards = HashMap::new();
+        member_leaderboards.insert(1, vec![TEST_LEADERBOARD_ID]);
+        member_leaderboards.insert(42, vec![TEST_LEADERBOARD_ID]);
+        member_leaderboards.insert(23, vec![TEST_LEADERBOARD_ID + 1]);
+        let mut some_ignored = merged_changes(
+            &leaderboard,
+            &[
+                (TEST_LEADERBOARD_ID, Changes::new([1, 42].into(), [].into())),
+                (TEST_LEADERBOARD_ID + 1, Changes::new([].into(), [23].into())),
+            ],
+        );
+        some_ignored.member_leaderboards = member_leaderboards;
+        let all_ignored = merged_changes(
+            &leaderboard,
+            &[(TEST_LEADERBOARD_ID, Changes::new([42].into(), [23].into()))],
+        );
+
+        reporter.report_merged_changes(&some_ignored).await.unwrap();
+        reporter.report_merged_changes(&all_ignored).await.unwrap();
+
+        let merged_changes = reporter.into_inner().merged_changes;
+        assert_eq!(merged_changes.len(), 1);
+        assert_eq!(merged_changes[0].leaderboards.len(), 1);
+        assert_eq!(merged_changes[0].leaderboards[0].leaderboard_id, TEST_LEADERBOARD_ID);
+        assert_eq!(merged_changes[0].leaderboards[0].changes, Changes::new([1].into(), [].into()));
+        assert_eq!(merged_changes[0].member_leaderboards, [(1, vec![TEST_LEADERBOARD_ID])].into());
+    }
+}
+
+mod quiet_hours_reporter {
+    #[cfg(all(feature = "config-mem", feature = "storage-mem"))]
+    use aoc_leaderboard::test_helpers::{
+        mock_server_with_leaderboard, test_leaderboard_credentials,
+    };
+    #[cfg(all(feature = "config-mem", feature = "storage-mem"))]
+    use aoc_leaderboard::wiremock::MockServer;
+    #[cfg(all(feature = "config-mem", feature = "storage-mem"))]
+    use aoc_leaderbot_lib::leaderbot::Storage;
+    use aoc_leaderbot_lib::leaderbot::clock::TestClock;
+    #[cfg(all(feature = "config-mem", feature = "storage-mem"))]
+    use aoc_leaderbot_lib::leaderbot::config::mem::MemoryConfig;
+    use aoc_leaderbot_lib::leaderbot::reporter::middleware::{QuietHoursReporter, ReporterExt};
+    #[cfg(all(feature = "config-mem", feature = "storage-mem"))]
+    use aoc_leaderbot_lib::leaderbot::run_bot_from;
+    #[cfg(all(feature = "config-mem", feature = "storage-mem"))]
+    use aoc_leaderbot_lib::leaderbot::storage::mem::MemoryStorage;
+    #[cfg(all(feature = "config-mem", feature = "storage-mem"))]
+    use assert_matches::assert_matches;
+    use chrono::{Local, NaiveTime, TimeDelta, TimeZone, Utc};
+    use rstest::rstest;
+
+    use super::*;
+
+    fn time(hour: u32, min: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, min, 0).unwrap()
+    }
+
+    #[test_log::test]
+    fn new() {
+        let reporter = QuietHoursReporter::new(RecordingReporter::new(), time(22, 0), time(7, 0));
+
+        assert_eq!(reporter.start(), time(22, 0));
+        assert_eq!(reporter.end(), time(7, 0));
+        assert!(!reporter.inner().called());
+    }
+
+    #[rstest]
+    #[case::before(time(9, 0), time(17, 0), time(8, 59), false)]
+    #[case::at_start(time(9, 0), time(17, 0), time(9, 0), true)]
+    #[case::during(time(9, 0), time(17, 0), time(12, 0), true)]
+    #[case::at_end(time(9, 0), time(17, 0), time(17, 0), false)]
+    #[case::wrapping_before_midnight(time(22, 0), time(7, 0), time(23, 0), true)]
+    #[case::wrapping_after_midnight(time(22, 0), time(7, 0), time(3, 0), true)]
+    #[case::wrapping_outside(time(22, 0), time(7, 0), time(12, 0), false)]
+    #[case::empty(time(9, 0), time(9, 0), time(9, 0), false)]
+    #[test_log::test]
+    fn is_quiet_at(
+        #[case] start: NaiveTime,
+        #[case] end: NaiveTime,
+        #[case] time: NaiveTime,
+        #[case] expected: bool,
+    ) {
+        let reporter = RecordingReporter::new().quiet_hours(start, end);
+
+        assert_eq!(reporter.is_quiet_at(time), expected);
+    }
+
+    #[rstest]
+    #[case::loud(false, false, 6)]
+    #[case::quiet(true, false, 3)]
+    #[case::quiet_with_suppressed_errors(true, true, 0)]
+    #[test_log::test(tokio::test)]
+    async fn report(
+        #[from(test_leaderboard)] leaderboard: Leaderboard,
+        #[case] quiet: bool,
+        #[case] suppress_errors: bool,
+        #[case] expected_calls: usize,
+    ) {
+        let now = Local::now().time();
+        let (start, end) = match quiet {
+            true => (now - TimeDelta::hours(1), now + TimeDelta::hours(1)),
+            false => (now + TimeDelta::hours(1), now + TimeDelta::hours(2)),
+        };
+        let mut reporter = RecordingReporter::new()
+            .quiet_hours(start, end)
+            .suppress_errors(suppress_errors);
+
+        report_all(&mut reporter, &leaderboard, &Changes::new([42].into(), [].into())).await;
+
+        assert_eq!(reporter.into_inner().call_count(), expected_calls);
+    }
+
+    #[rstest]
+    #[test_log::test(tokio::test)]
+    async fn with_clock(#[from(test_leaderboard)] leaderboard: Leaderboard) {
+        let start = Local.with_ymd_and_hms(2024, 12, 1, 22, 0, 0).unwrap();
+        let clock = TestClock::new(start.with_timezone(&Utc));
+        let mut reporter = RecordingReporter::new()
+            .quiet_hours(time(22, 0), time(7, 0))
+            .suppress_errors(true)
+            .with_clock(clock.clone());
+
+        report_all(&mut reporter, &leaderboard, &Changes::new([42].into(), [].into())).await;
+        assert_eq!(reporter.inner().call_count(), 0);
+
+        clock.advance(TimeDelta::hours(9));
+        report_all(&mut reporter, &leaderboard, &Changes::new([42].into(), [].into())).await;
+        assert_eq!(reporter.into_inner().call_count(), 6);
+    }
+
+    #[rstest]
+    #[test_log::test(tokio::test)]
+    async fn report_merged_changes(#[from(test_leaderboard)] leaderboard: Leaderboard) {
+        let start = Local.with_ymd_and_hms(2024, 12, 1, 12, 0, 0).unwrap();
+        let clock = TestClock::new(start.with_timezone(&Utc));
+        let mut reporter = MergedChangesReporter::default()
+            .quiet_hours(time(22, 0), time(7, 0))
+            .with_clock(clock);
+        let merged_changes = merged_changes(
+            &leaderboard,
+            &[(TEST_LEADERBOARD_ID, Changes::new([42].into(), [].into()))],
+        );
+
+        reporter
+            .report_merged_changes(&merged_changes)
+            .await
+            .unwrap();
+
+        assert_eq!(reporter.into_inner().merged_changes, [merged_changes]);
+    }
+
+    #[rstest]
+    #[test_log::test(tokio::test)]
+    async fn defers_reports(#[from(test_leaderboard)] leaderboard: Leaderboard) {
+        let start = Local.with_ymd_and_hms(2024, 12, 1, 23, 0, 0).unwrap();
+        let clock = TestClock::new(start.with_timezone(&Utc));
+        let mut reporter = RecordingReporter::new()
+            .quiet_hours(time(22, 0), time(7, 0))
+            .with_clock(clock.clone());
+        let changes = Changes::new([42].into(), [].into());
+
+        assert!(reporter.defers_reports());
+        reporter
+            .report_changes(
+                TEST_YEAR,
+                TEST_LEADERBOARD_ID,
+                None,
+                &leaderboard,
+                &leaderboard,
+                &changes,
+            )
+            .await
+            .unwrap();
+        reporter
+            .report_first_run(TEST_YEAR, TEST_LEADERBOARD_ID, None, &leaderboard)
+            .await
+            .unwrap();
+        reporter
+            .report_merged_changes(&merged_changes(&leaderboard, &[(TEST_LEADERBOARD_ID, changes)]))
+            .await
+            .unwrap();
+        reporter.inner().assert_not_called();
+
+        clock.advance(TimeDelta::hours(9));
+        assert!(!reporter.defers_reports());
+    }
+
+    #[cfg(all(feature = "config-mem", feature = "storage-mem"))]
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn reports_deferred_first_run_after_quiet_hours(
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        mock_server: MockServer,
+    ) {
+        let start = Local.with_ymd_and_hms(2024, 12, 1, 23, 0, 0).unwrap();
+        let clock = TestClock::new(start.with_timezone(&Utc));
+        let config = MemoryConfig::builder()
+            .year(TEST_YEAR)
+            .leaderboard_id(TEST_LEADERBOARD_ID)
+            .credentials(test_leaderboard_credentials::default())
+            .clock(clock.clone())
+            .build()
+            .unwrap();
+        let mut storage = MemoryStorage::new();
+        let mut reporter = RecordingReporter::new()
+            .quiet_hours(time(22, 0), time(7, 0))
+            .with_clock(clock.clone());
+
+        let result =
+            run_bot_from(Some(mock_server.uri()), &config, &mut storage, &mut reporter, false)
+                .await;
+        assert_matches!(result, Ok(Some(output)) => {
+            assert!(output.deferred);
+            assert!(output.previous_leaderboard.is_none());
+        });
+        assert_matches!(
+            storage.load_previous(TEST_YEAR, TEST_LEADERBOARD_ID).await,
+            Ok((None, None))
+        );
+        assert!(!reporter.inner().called());
+
+        clock.advance(TimeDelta::hours(9));
+        let result =
+            run_bot_from(Some(mock_server.uri()), &config, &mut storage, &mut reporter, false)
+                .await;
+        assert_matches!(result, Ok(Some(output)) => {
+            assert!(!output.deferred);
+        });
+        let inner = reporter.into_inner();
+        inner.assert_first_run_reported(TEST_YEAR, TEST_LEADERBOARD_ID);
+        assert_eq!(inner.call_count(), 1);
+    }
+
+    #[cfg(all(feature = "config-mem", feature = "storage-mem"))]
+    #[rstest]
+    #[awt]
+    #[test_log::test(tokio::test)]
+    async fn reports_recovery_after_quiet_hours(
+        #[future]
+        #[from(mock_server_with_leaderboard)]
+        mock_server: MockServer,
+    ) {
+        let start = Local.with_ymd_and_hms(2024, 12, 1, 23, 0, 0).unwrap();
+        let clock = TestClock::new(start.with_timezone(&Utc));
+        let config = MemoryConfig::builder()
+            .year(TEST_YEAR)
+            .leaderboard_id(TEST_LEADERBOARD_ID)
+            .credentials(test_leaderboard_credentials::default())
+            .clock(clock.clone())
+            .build()
+            .unwrap();
+        let mut storage = MemoryStorage::new();
+        storage
+            .save_error(TEST_YEAR, TEST_LEADERBOARD_ID, (&load_previous_error()).into())
+            .await
+            .unwrap();
+        let mut reporter = RecordingReporter::new()
+            .quiet_hours(time(22, 0), time(7, 0))
+            .with_clock(clock.clone());
+
+        let result =
+            run_bot_from(Some(mock_server.uri()), &config, &mut storage, &mut reporter, false)
+                .await;
+        assert_matches!(result, Ok(Some(output)) => {
+            assert!(output.deferred);
+        });
+        assert!(!reporter.inner().called());
+
+        clock.advance(TimeDelta::hours(9));
+        let result =
+            run_bot_from(Some(mock_server.uri()), &config, &mut storage, &mut reporter, false)
+                .await;
+        assert_matches!(result, Ok(Some(_)));
+        let inner = reporter.into_inner();
+        assert_eq!(inner.first_runs().count(), 1);
+        assert_eq!(inner.recoveries().count(), 1);
+        assert_eq!(inner.call_count(), 2);
+    }
+}
+
+mod error_router_reporter {
+    use aoc_leaderbot_lib::leaderbot::reporter::middleware::{ErrorRouterReporter, ReporterExt};
+    use rstest::rstest;
+
+    use super::*;
+
+    #[test_log::test]
+    fn new() {
+        let reporter = ErrorRouterReporter::new(RecordingReporter::new(), RecordingReporter::new());
+
+        assert!(!reporter.changes_reporter().called());
+        assert!(!reporter.error_reporter().called());
+    }
+
+    #[rstest]
+    #[test_log::test(tokio::test)]
+    async fn routes_calls(#[from(test_leaderboard)] leaderboard: Leaderboard) {
+        let mut reporter = RecordingReporter::new().route_errors_to(RecordingReporter::new());
+
+        report_all(&mut reporter, &leaderboard, &Changes::new([42].into(), [].into())).await;
+
+        let (changes_reporter, error_reporter) = reporter.into_inner();
+        assert_eq!(changes_reporter.changes().count(), 1);
+        assert_eq!(changes_reporter.first_runs().count(), 1);
+        assert_eq!(changes_reporter.errors().count(), 0);
+        assert_eq!(changes_reporter.recoveries().count(), 0);
+        assert_eq!(changes_reporter.heartbeats().count(), 1);
+        assert_eq!(error_reporter.call_count(), 3);
+        assert_eq!(error_reporter.errors().count(), 1);
+        assert_eq!(error_reporter.recoveries().count(), 1);
+        assert_eq!(error_reporter.stales().count(), 1);
+    }
+}
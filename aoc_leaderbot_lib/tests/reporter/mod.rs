@@ -0,0 +1,8 @@
+#[cfg(all(feature = "reporter-dedup", feature = "reporter-recording"))]
+mod dedup;
+#[cfg(all(feature = "reporter-middleware", feature = "reporter-recording"))]
+mod middleware;
+#[cfg(all(feature = "reporter-multi", feature = "reporter-recording"))]
+mod multi;
+#[cfg(feature = "reporter-recording")]
+mod recording;
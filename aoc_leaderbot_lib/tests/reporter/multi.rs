@@ -0,0 +1,200 @@
+mod multi_reporter {
+    use aoc_leaderboard::aoc::Leaderboard;
+    use aoc_leaderboard::test_helpers::{TEST_LEADERBOARD_ID, TEST_YEAR, test_leaderboard};
+    use aoc_leaderbot_lib::error::{ReporterError, StorageError};
+    use aoc_leaderbot_lib::leaderbot::clock::TestClock;
+    use aoc_leaderbot_lib::leaderbot::reporter::middleware::ReporterExt;
+    use aoc_leaderbot_lib::leaderbot::reporter::multi::{FailurePolicy, MultiReporter};
+    use aoc_leaderbot_lib::leaderbot::reporter::recording::RecordingReporter;
+    use aoc_leaderbot_lib::leaderbot::{Changes, Reporter};
+    use aoc_leaderbot_lib::{Error, ErrorKind};
+    use assert_matches::assert_matches;
+    use chrono::{Local, NaiveTime, TimeDelta, TimeZone, Utc};
+    use rstest::rstest;
+
+    fn changes() -> Changes {
+        Changes::new([42].into(), [23].into())
+    }
+
+    async fn report_changes(
+        reporter: &mut MultiReporter<'_>,
+        leaderboard: &Leaderboard,
+    ) -> Result<(), ReporterError> {
+        reporter
+            .report_changes(
+                TEST_YEAR,
+                TEST_LEADERBOARD_ID,
+                None,
+                leaderboard,
+                leaderboard,
+                &changes(),
+            )
+            .await
+    }
+
+    #[test_log::test]
+    fn new() {
+        let reporter = MultiReporter::new();
+
+        assert!(reporter.is_empty());
+        assert_eq!(reporter.len(), 0);
+        assert_eq!(reporter.failure_policy(), FailurePolicy::FailIfAny);
+    }
+
+    #[test_log::test]
+    fn with_reporters() {
+        let mut reporter = MultiReporter::new()
+            .with_failure_policy(FailurePolicy::BestEffort)
+            .with_reporter("first", RecordingReporter::new());
+        reporter.add_reporter("second", RecordingReporter::new());
+
+        assert_eq!(reporter.len(), 2);
+        assert_eq!(reporter.reporter_names().collect::<Vec<_>>(), vec!["first", "second"]);
+        assert_eq!(reporter.failure_policy(), FailurePolicy::BestEffort);
+        assert_eq!(
+            format!("{reporter:?}"),
+            r#"MultiReporter { reporters: ["first", "second"], failure_policy: BestEffort }"#
+        );
+    }
+
+    #[test_log::test]
+    fn defers_reports_if_any_child_does() {
+        let time = |hour| NaiveTime::from_hms_opt(hour, 0, 0).unwrap();
+        let clock = TestClock::new(
+            Local
+                .with_ymd_and_hms(2024, 12, 1, 23, 0, 0)
+                .unwrap()
+                .with_timezone(&Utc),
+        );
+        let quiet_hours = |start, end| {
+            RecordingReporter::new()
+                .quiet_hours(start, end)
+                .with_clock(clock.clone())
+        };
+
+        let reporter = MultiReporter::new()
+            .with_reporter("first", RecordingReporter::new())
+            .with_reporter("second", quiet_hours(time(9), time(17)));
+        assert!(!reporter.defers_reports());
+
+        let reporter = reporter.with_reporter("third", quiet_hours(time(22), time(7)));
+        assert!(reporter.defers_reports());
+    }
+
+    #[rstest]
+    #[test_log::test(tokio::test)]
+    async fn forwards_all_calls(#[from(test_leaderboard)] leaderboard: Leaderboard) {
+        let mut first = RecordingReporter::new();
+        let mut second = RecordingReporter::new();
+        let error = Error::Storage(StorageError::LoadPrevious(anyhow::anyhow!("oops")));
+
+        {
+            let mut reporter = MultiReporter::new()
+                .with_reporter("first", &mut first)
+                .with_reporter("second", &mut second);
+
+            report_changes(&mut reporter, &leaderboard).await.unwrap();
+            reporter
+                .report_first_run(TEST_YEAR, TEST_LEADERBOARD_ID, None, &leaderboard)
+                .await
+                .unwrap();
+            reporter
+                .report_error(TEST_YEAR, TEST_LEADERBOARD_ID, None, &error)
+                .await;
+            reporter
+                .report_recovery(
+                    TEST_YEAR,
+                    TEST_LEADERBOARD_ID,
+                    None,
+                    ErrorKind::from(&error),
+                    None,
+                )
+                .await;
+            reporter
+                .report_stale(TEST_YEAR, TEST_LEADERBOARD_ID, None, TimeDelta::hours(3))
+                .await;
+            reporter
+                .report_heartbeat(TEST_YEAR, TEST_LEADERBOARD_ID, None, &leaderboard)
+                .await;
+        }
+
+        for child in [first, second] {
+            assert_eq!(child.call_count(), 6);
+            assert_eq!(child.changes().next().unwrap().changes, changes());
+            assert_eq!(child.first_runs().next().unwrap().leaderboard, leaderboard);
+            assert_eq!(child.errors().next().unwrap().error_kind, ErrorKind::from(&error));
+            assert_eq!(child.recoveries().next().unwrap().error_kind, ErrorKind::from(&error));
+            assert_eq!(child.stales().next().unwrap().silence, TimeDelta::hours(3));
+            assert_eq!(child.heartbeats().next().unwrap().leaderboard, leaderboard);
+        }
+    }
+
+    mod failure_policy {
+        use super::*;
+
+        fn reporter<'a>(failure_policy: FailurePolicy, failing: &[bool]) -> MultiReporter<'a> {
+            failing.iter().enumerate().fold(
+                MultiReporter::new().with_failure_policy(failure_policy),
+                |reporter, (i, &fail)| {
+                    reporter.with_reporter(
+                        format!("child {i}"),
+                        RecordingReporter::new()
+                            .fail_report_changes(fail)
+                            .fail_report_first_run(fail),
+                    )
+                },
+            )
+        }
+
+        #[rstest]
+        #[case::fail_if_any_none_failing(FailurePolicy::FailIfAny, &[false, false], &[])]
+        #[case::fail_if_any_some_failing(FailurePolicy::FailIfAny, &[true, false], &["child 0"])]
+        #[case::fail_if_any_all_failing(FailurePolicy::FailIfAny, &[true, true], &["child 0", "child 1"])]
+        #[case::fail_if_all_none_failing(FailurePolicy::FailIfAll, &[false, false], &[])]
+        #[case::fail_if_all_some_failing(FailurePolicy::FailIfAll, &[false, true], &[])]
+        #[case::fail_if_all_all_failing(FailurePolicy::FailIfAll, &[true, true], &["child 0", "child 1"])]
+        #[case::best_effort_all_failing(FailurePolicy::BestEffort, &[true, true], &[])]
+        #[case::no_children(FailurePolicy::FailIfAll, &[], &[])]
+        #[test_log::test(tokio::test)]
+        async fn report_changes_and_first_run(
+            #[from(test_leaderboard)] leaderboard: Leaderboard,
+            #[case] failure_policy: FailurePolicy,
+            #[case] failing: &[bool],
+            #[case] expected_failures: &[&str],
+        ) {
+            let mut reporter = reporter(failure_policy, failing);
+
+            let changes_result = report_changes(&mut reporter, &leaderboard).await;
+            let first_run_result = reporter
+                .report_first_run(TEST_YEAR, TEST_LEADERBOARD_ID, None, &leaderboard)
+                .await;
+
+            for result in [changes_result, first_run_result] {
+                match expected_failures {
+                    [] => assert_matches!(result, Ok(())),
+                    expected_failures => {
+                        assert_matches!(result, Err(ReporterError::Children(failures)) => {
+                            let names: Vec<_> =
+                                failures.iter().map(|failure| failure.name.as_str()).collect();
+                            assert_eq!(names, expected_failures);
+                        });
+                    },
+                }
+            }
+        }
+
+        #[rstest]
+        #[test_log::test(tokio::test)]
+        async fn error_message(#[from(test_leaderboard)] leaderboard: Leaderboard) {
+            let mut reporter = reporter(FailurePolicy::FailIfAny, &[true, false]);
+
+            let result = report_changes(&mut reporter, &leaderboard).await;
+            assert_matches!(result, Err(err) => {
+                assert_eq!(
+                    err.to_string(),
+                    "1 child reporter(s) failed: reporter child 0 failed: failed to report changes to leaderboard: recording reporter failure"
+                );
+            });
+        }
+    }
+}
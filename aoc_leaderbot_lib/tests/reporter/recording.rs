@@ -0,0 +1,279 @@
+mod recording_reporter {
+    use aoc_leaderboard::aoc::Leaderboard;
+    use aoc_leaderboard::test_helpers::{TEST_LEADERBOARD_ID, TEST_YEAR, test_leaderboard};
+    use aoc_leaderbot_lib::error::{ReporterError, StorageError};
+    use aoc_leaderbot_lib::leaderbot::reporter::recording::{
+        RecordingReporter, ReportedCall, ReportedError, ReportedFirstRun, ReportedHeartbeat,
+        ReportedRecovery, ReportedStale,
+    };
+    use aoc_leaderbot_lib::leaderbot::{Changes, Reporter};
+    use aoc_leaderbot_lib::{Error, ErrorKind};
+    use assert_matches::assert_matches;
+    use chrono::TimeDelta;
+    use rstest::rstest;
+
+    fn load_previous_error() -> Error {
+        Error::Storage(StorageError::LoadPrevious(anyhow::anyhow!("oops")))
+    }
+
+    #[test_log::test]
+    fn new() {
+        let reporter = RecordingReporter::new();
+
+        assert!(!reporter.called());
+        assert_eq!(reporter.call_count(), 0);
+        reporter.assert_not_called();
+    }
+
+    #[rstest]
+    #[test_log::test(tokio::test)]
+    async fn records_calls_in_order(#[from(test_leaderboard)] leaderboard: Leaderboard) {
+        let mut reporter = RecordingReporter::new();
+        let changes = Changes::new([42].into(), [23].into());
+
+        reporter
+            .report_first_run(TEST_YEAR, TEST_LEADERBOARD_ID, Some("view_key"), &leaderboard)
+            .await
+            .unwrap();
+        reporter
+            .report_changes(
+                TEST_YEAR,
+                TEST_LEADERBOARD_ID,
+                None,
+                &leaderboard,
+                &leaderboard,
+                &changes,
+            )
+            .await
+            .unwrap();
+        reporter
+            .report_error(TEST_YEAR, TEST_LEADERBOARD_ID, None, &load_previous_error())
+            .await;
+        reporter
+            .report_recovery(
+                TEST_YEAR,
+                TEST_LEADERBOARD_ID,
+                None,
+                ErrorKind::from(&load_previous_error()),
+                Some(TimeDelta::minutes(42)),
+            )
+            .await;
+        reporter
+            .report_stale(TEST_YEAR, TEST_LEADERBOARD_ID, None, TimeDelta::hours(3))
+            .await;
+        reporter
+            .report_heartbeat(TEST_YEAR, TEST_LEADERBOARD_ID, Some("view_key"), &leaderboard)
+            .await;
+
+        assert_eq!(reporter.call_count(), 6);
+        assert_matches!(
+            reporter.calls(),
+            [
+                ReportedCall::FirstRun(_),
+                ReportedCall::Changes(_),
+                ReportedCall::Error(_),
+                ReportedCall::Recovery(_),
+                ReportedCall::Stale(_),
+                ReportedCall::Heartbeat(_),
+            ]
+        );
+        assert_eq!(
+            reporter.first_runs().collect::<Vec<_>>(),
+            vec![&ReportedFirstRun {
+                year: TEST_YEAR,
+                leaderboard_id: TEST_LEADERBOARD_ID,
+                view_key: Some("view_key".into()),
+                leaderboard: leaderboard.clone(),
+            }]
+        );
+        assert_eq!(
+            reporter
+                .changes()
+                .map(|reported| &reported.changes)
+                .collect::<Vec<_>>(),
+            vec![&changes]
+        );
+        assert_eq!(
+            reporter.errors().collect::<Vec<_>>(),
+            vec![&ReportedError {
+                year: TEST_YEAR,
+                leaderboard_id: TEST_LEADERBOARD_ID,
+                view_key: None,
+                error_kind: ErrorKind::from(&load_previous_error()),
+                message: "failed to load previous leaderboard data: oops".into(),
+            }]
+        );
+        assert_eq!(
+            reporter.recoveries().collect::<Vec<_>>(),
+            vec![&ReportedRecovery {
+                year: TEST_YEAR,
+                leaderboard_id: TEST_LEADERBOARD_ID,
+                view_key: None,
+                error_kind: ErrorKind::from(&load_previous_error()),
+                outage_duration: Some(TimeDelta::minutes(42)),
+            }]
+        );
+        assert_eq!(
+            reporter.stales().collect::<Vec<_>>(),
+            vec![&ReportedStale {
+                year: TEST_YEAR,
+                leaderboard_id: TEST_LEADERBOARD_ID,
+                view_key: None,
+                silence: TimeDelta::hours(3),
+            }]
+        );
+        assert_eq!(
+            reporter.heartbeats().collect::<Vec<_>>(),
+            vec![&ReportedHeartbeat {
+                year: TEST_YEAR,
+                leaderboard_id: TEST_LEADERBOARD_ID,
+                view_key: Some("view_key".into()),
+                leaderboard: leaderboard.clone(),
+            }]
+        );
+
+        reporter.clear();
+        reporter.assert_not_called();
+    }
+
+    mod assertions {
+        use super::*;
+
+        #[rstest]
+        #[test_log::test(tokio::test)]
+        async fn changes_reported(#[from(test_leaderboard)] leaderboard: Leaderboard) {
+            let mut reporter = RecordingReporter::new();
+            let changes = Changes::new([42].into(), [].into());
+
+            reporter
+                .report_changes(
+                    TEST_YEAR,
+                    TEST_LEADERBOARD_ID,
+                    None,
+                    &leaderboard,
+                    &leaderboard,
+                    &changes,
+                )
+                .await
+                .unwrap();
+
+            let reported =
+                reporter.assert_changes_reported(TEST_YEAR, TEST_LEADERBOARD_ID, &changes);
+            assert_eq!(reported.leaderboard, leaderboard);
+        }
+
+        #[rstest]
+        #[test_log::test(tokio::test)]
+        async fn first_run_reported(#[from(test_leaderboard)] leaderboard: Leaderboard) {
+            let mut reporter = RecordingReporter::new();
+
+            reporter
+                .report_first_run(TEST_YEAR, TEST_LEADERBOARD_ID, None, &leaderboard)
+                .await
+                .unwrap();
+
+            let reported = reporter.assert_first_run_reported(TEST_YEAR, TEST_LEADERBOARD_ID);
+            assert_eq!(reported.leaderboard, leaderboard);
+        }
+
+        #[test_log::test(tokio::test)]
+        async fn error_reported() {
+            let mut reporter = RecordingReporter::new();
+
+            reporter
+                .report_error(TEST_YEAR, TEST_LEADERBOARD_ID, None, &load_previous_error())
+                .await;
+
+            reporter.assert_error_reported(
+                TEST_YEAR,
+                TEST_LEADERBOARD_ID,
+                ErrorKind::from(&load_previous_error()),
+            );
+        }
+
+        #[test_log::test(tokio::test)]
+        async fn recovery_reported() {
+            let mut reporter = RecordingReporter::new();
+
+            reporter
+                .report_recovery(
+                    TEST_YEAR,
+                    TEST_LEADERBOARD_ID,
+                    None,
+                    ErrorKind::from(&load_previous_error()),
+                    None,
+                )
+                .await;
+
+            let reported = reporter.assert_recovery_reported(
+                TEST_YEAR,
+                TEST_LEADERBOARD_ID,
+                ErrorKind::from(&load_previous_error()),
+            );
+            assert!(reported.outage_duration.is_none());
+        }
+
+        #[test_log::test(tokio::test)]
+        #[should_panic(expected = "expected a single call to report_first_run")]
+        async fn wrong_call() {
+            let mut reporter = RecordingReporter::new();
+
+            reporter
+                .report_error(TEST_YEAR, TEST_LEADERBOARD_ID, None, &load_previous_error())
+                .await;
+
+            reporter.assert_first_run_reported(TEST_YEAR, TEST_LEADERBOARD_ID);
+        }
+
+        #[test_log::test(tokio::test)]
+        #[should_panic(expected = "unexpected error reported")]
+        async fn wrong_leaderboard() {
+            let mut reporter = RecordingReporter::new();
+
+            reporter
+                .report_error(TEST_YEAR, TEST_LEADERBOARD_ID, None, &load_previous_error())
+                .await;
+
+            reporter.assert_error_reported(
+                TEST_YEAR - 1,
+                TEST_LEADERBOARD_ID,
+                ErrorKind::from(&load_previous_error()),
+            );
+        }
+    }
+
+    mod failures {
+        use super::*;
+
+        #[rstest]
+        #[test_log::test(tokio::test)]
+        async fn report_changes(#[from(test_leaderboard)] leaderboard: Leaderboard) {
+            let mut reporter = RecordingReporter::new().fail_report_changes(true);
+
+            let result = reporter
+                .report_changes(
+                    TEST_YEAR,
+                    TEST_LEADERBOARD_ID,
+                    None,
+                    &leaderboard,
+                    &leaderboard,
+                    &Changes::default(),
+                )
+                .await;
+            assert_matches!(result, Err(Error::Reporter(ReporterError::ReportChanges(_))));
+            assert_eq!(reporter.changes().count(), 1);
+        }
+
+        #[rstest]
+        #[test_log::test(tokio::test)]
+        async fn report_first_run(#[from(test_leaderboard)] leaderboard: Leaderboard) {
+            let mut reporter = RecordingReporter::new().fail_report_first_run(true);
+
+            let result = reporter
+                .report_first_run(TEST_YEAR, TEST_LEADERBOARD_ID, None, &leaderboard)
+                .await;
+            assert_matches!(result, Err(Error::Reporter(ReporterError::ReportFirstRun(_))));
+            assert_eq!(reporter.first_runs().count(), 1);
+        }
+    }
+}
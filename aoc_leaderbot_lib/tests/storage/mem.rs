@@ -1,10 +1,8 @@
 mod memory_storage {
     use aoc_leaderboard::aoc::Leaderboard;
     use aoc_leaderboard::test_helpers::{TEST_LEADERBOARD_ID, TEST_YEAR, test_leaderboard};
-    use aoc_leaderbot_lib::ErrorKind;
     use aoc_leaderbot_lib::leaderbot::Storage;
     use aoc_leaderbot_lib::leaderbot::storage::mem::MemoryStorage;
-    use assert_matches::assert_matches;
     use rstest::rstest;
 
     mod new {
@@ -56,103 +54,43 @@ mod memory_storage {
         }
     }
 
-    mod storage_impl {
+    #[cfg(feature = "storage-conformance")]
+    mod conformance {
+        use aoc_leaderbot_lib::leaderbot::storage::conformance::{
+            check_digest_storage, check_error_state, check_heartbeat, check_outbox_storage,
+            check_storage, check_storage_admin,
+        };
+
         use super::*;
 
-        #[rstest]
         #[test_log::test(tokio::test)]
-        async fn load_save(
-            #[from(test_leaderboard)] leaderboard: Leaderboard,
-            #[from(test_leaderboard)] expected: Leaderboard,
-        ) {
-            let mut storage = MemoryStorage::new();
-
-            let (previous_leaderboard, previous_error) = storage
-                .load_previous(TEST_YEAR, TEST_LEADERBOARD_ID)
-                .await
-                .unwrap();
-            assert!(previous_leaderboard.is_none());
-            assert!(previous_error.is_none());
-
-            storage
-                .save_success(TEST_YEAR, TEST_LEADERBOARD_ID, &leaderboard)
-                .await
-                .unwrap();
-
-            let (previous_leaderboard, previous_error) = storage
-                .load_previous(TEST_YEAR, TEST_LEADERBOARD_ID)
-                .await
-                .unwrap();
-            assert_eq!(previous_leaderboard, Some(expected));
-            assert!(previous_error.is_none());
-
-            let (previous_leaderboard, previous_error) = storage
-                .load_previous(TEST_YEAR - 1, TEST_LEADERBOARD_ID)
-                .await
-                .unwrap();
-            assert!(previous_leaderboard.is_none());
-            assert!(previous_error.is_none());
+        async fn storage() {
+            check_storage(&mut MemoryStorage::new()).await;
         }
 
-        #[rstest]
         #[test_log::test(tokio::test)]
-        async fn save_success_and_error(
-            #[from(test_leaderboard)] leaderboard: Leaderboard,
-            #[from(test_leaderboard)] expected: Leaderboard,
-        ) {
-            let mut storage = MemoryStorage::new();
-
-            let (previous_leaderboard, previous_error) = storage
-                .load_previous(TEST_YEAR, TEST_LEADERBOARD_ID)
-                .await
-                .unwrap();
-            assert!(previous_leaderboard.is_none());
-            assert!(previous_error.is_none());
-
-            let error_kind = ErrorKind::Leaderboard(aoc_leaderboard::ErrorKind::NoAccess);
-            storage
-                .save_error(TEST_YEAR, TEST_LEADERBOARD_ID, error_kind)
-                .await
-                .unwrap();
-
-            let (previous_leaderboard, previous_error) = storage
-                .load_previous(TEST_YEAR, TEST_LEADERBOARD_ID)
-                .await
-                .unwrap();
-            assert!(previous_leaderboard.is_none());
-            assert_matches!(previous_error, Some(previous_err) => {
-                assert_eq!(error_kind, previous_err);
-            });
+        async fn storage_admin() {
+            check_storage_admin(&mut MemoryStorage::new()).await;
+        }
 
-            storage
-                .save_success(TEST_YEAR, TEST_LEADERBOARD_ID, &leaderboard)
-                .await
-                .unwrap();
+        #[test_log::test(tokio::test)]
+        async fn digest_storage() {
+            check_digest_storage(&mut MemoryStorage::new()).await;
+        }
 
-            let (previous_leaderboard, previous_error) = storage
-                .load_previous(TEST_YEAR, TEST_LEADERBOARD_ID)
-                .await
-                .unwrap();
-            assert_matches!(previous_leaderboard, Some(previous_leaderboard) => {
-                assert_eq!(previous_leaderboard, expected);
-            });
-            assert!(previous_error.is_none());
+        #[test_log::test(tokio::test)]
+        async fn outbox_storage() {
+            check_outbox_storage(&mut MemoryStorage::new()).await;
+        }
 
-            storage
-                .save_error(TEST_YEAR, TEST_LEADERBOARD_ID, error_kind)
-                .await
-                .unwrap();
+        #[test_log::test(tokio::test)]
+        async fn error_state() {
+            check_error_state(&mut MemoryStorage::new()).await;
+        }
 
-            let (previous_leaderboard, previous_error) = storage
-                .load_previous(TEST_YEAR, TEST_LEADERBOARD_ID)
-                .await
-                .unwrap();
-            assert_matches!(previous_leaderboard, Some(previous_leaderboard) => {
-                assert_eq!(previous_leaderboard, expected);
-            });
-            assert_matches!(previous_error, Some(previous_err) => {
-                assert_eq!(error_kind, previous_err);
-            });
+        #[test_log::test(tokio::test)]
+        async fn heartbeat() {
+            check_heartbeat(&mut MemoryStorage::new()).await;
         }
     }
 }
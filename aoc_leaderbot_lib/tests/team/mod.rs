@@ -0,0 +1,58 @@
+mod team_standings {
+    use aoc_leaderboard::aoc::Leaderboard;
+    use aoc_leaderboard::test_helpers::test_leaderboard;
+    use aoc_leaderbot_lib::leaderbot::team::{Team, TeamStanding, team_standings};
+    use rstest::rstest;
+
+    #[rstest]
+    #[test_log::test]
+    fn computes_aggregates(#[from(test_leaderboard)] mut leaderboard: Leaderboard) {
+        for (id, stars, local_score) in [(12345, 50, 100), (12346, 10, 40), (12347, 30, 61)] {
+            let member = leaderboard.members.get_mut(&id).unwrap();
+            member.stars = stars;
+            member.local_score = local_score;
+        }
+        let teams = [
+            Team::new("Dev", [12345, 12346, 99999]),
+            Team::new("Ops", [12347]),
+            Team::new("Empty", []),
+        ];
+
+        let standings = team_standings(&leaderboard, &teams);
+
+        assert_eq!(
+            standings,
+            [
+                TeamStanding {
+                    name: "Dev".into(),
+                    members: 2,
+                    total_stars: 60,
+                    total_local_score: 140,
+                    members_finished: 1,
+                },
+                TeamStanding {
+                    name: "Ops".into(),
+                    members: 1,
+                    total_stars: 30,
+                    total_local_score: 61,
+                    members_finished: 0,
+                },
+                TeamStanding {
+                    name: "Empty".into(),
+                    members: 0,
+                    total_stars: 0,
+                    total_local_score: 0,
+                    members_finished: 0,
+                },
+            ]
+        );
+        assert_eq!(standings[0].average_local_score(), 70.0);
+        assert_eq!(standings[2].average_local_score(), 0.0);
+    }
+
+    #[rstest]
+    #[test_log::test]
+    fn without_teams(#[from(test_leaderboard)] leaderboard: Leaderboard) {
+        assert!(team_standings(&leaderboard, &[]).is_empty());
+    }
+}
@@ -10,17 +10,21 @@ use std::env;
 use std::fmt::Debug;
 
 use aoc_leaderboard::aoc::{Leaderboard, LeaderboardMember};
-use aoc_leaderbot_lib::leaderbot::{Changes, Reporter};
+use aoc_leaderbot_lib::leaderbot::team::{Team, TeamStanding, team_standings};
+use aoc_leaderbot_lib::leaderbot::{Changes, LeaderboardChanges, MergedChanges, Reporter};
+use chrono::TimeDelta;
 use derive_builder::Builder;
 use gratte::{Display, EnumProperty, EnumString};
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
-use tracing::{error, trace};
+use tracing::{error, info, trace};
 use veil::Redact;
 
 use crate::error::{WebhookError, WebhookMessageError};
 use crate::leaderbot::reporter::slack::USER_AGENT;
-use crate::leaderbot::reporter::slack::webhook::detail::SlackWebhookReporterStringExt;
+use crate::leaderbot::reporter::slack::webhook::detail::{
+    SlackWebhookReporterStringExt, duration_text,
+};
 use crate::slack::webhook::WebhookMessage;
 
 /// Environment variable from which the Slack webhook URL will be
@@ -105,6 +109,37 @@ impl LeaderboardSortOrder {
         value_text.right_pad(12, '\u{2007}')
     }
 
+    /// Compares two [`TeamStanding`]s using this sort order.
+    ///
+    /// If the teams are [`Equal`](Ordering::Equal) according to the chosen sort value,
+    /// they will then be compared using the other possible sort value, then finally by
+    /// [`name`](TeamStanding::name) for a stable sort.
+    pub fn cmp_teams(&self, lhs: &TeamStanding, rhs: &TeamStanding) -> Ordering {
+        let ordering = match *self {
+            Self::Stars => rhs
+                .total_stars
+                .cmp(&lhs.total_stars)
+                .then_with(|| rhs.total_local_score.cmp(&lhs.total_local_score)),
+            Self::Score => rhs
+                .total_local_score
+                .cmp(&lhs.total_local_score)
+                .then_with(|| rhs.total_stars.cmp(&lhs.total_stars)),
+        };
+
+        ordering.then_with(|| lhs.name.cmp(&rhs.name))
+    }
+
+    /// Returns a string representation of the value that would be used
+    /// to sort the given [`TeamStanding`] according to this sort order.
+    pub fn team_value_text(&self, standing: &TeamStanding) -> String {
+        let value_text = match *self {
+            Self::Stars => standing.total_stars.to_string(),
+            Self::Score => standing.total_local_score.to_string(),
+        };
+
+        value_text.right_pad(12, '\u{2007}')
+    }
+
     /// Returns the header text to display in a message when this sort order is used.
     pub fn header_text(&self) -> String {
         self.get_str("header").unwrap().right_pad(12, '\u{2007}')
@@ -171,6 +206,13 @@ pub struct SlackWebhookReporter {
     #[builder(default = "Self::default_sort_order()?")]
     pub sort_order: LeaderboardSortOrder,
 
+    /// Teams of leaderboard members. Used when [reporting changes](Reporter::report_changes)
+    /// to add a team standings section after the leaderboard members.
+    ///
+    /// If not specified, no team standings are reported.
+    #[builder(default, setter(into))]
+    pub teams: Vec<Team>,
+
     #[builder(private, default = "Self::default_http_client()?")]
     http_client: reqwest::Client,
 }
@@ -205,13 +247,99 @@ impl SlackWebhookReporter {
         };
 
         format!(
-            "{}{}\n{}",
+            "{}{}\n{}{}",
             first_run_prefix,
             self.header_row_text(leaderboard.year, leaderboard_id, view_key),
-            member_rows.join("\n")
+            member_rows.join("\n"),
+            self.team_standings_text(leaderboard),
         )
     }
 
+    fn team_standings_text(&self, leaderboard: &Leaderboard) -> String {
+        if self.teams.is_empty() {
+            return "".into();
+        }
+
+        let mut team_rows = team_standings(leaderboard, &self.teams)
+            .into_iter()
+            .sorted_by(|lhs, rhs| self.sort_order.cmp_teams(lhs, rhs))
+            .map(|standing| {
+                format!(
+                    "{}{} _({} members, {} finished, average score {:.1})_",
+                    self.sort_order.team_value_text(&standing),
+                    standing.name,
+                    standing.members,
+                    standing.members_finished,
+                    standing.average_local_score(),
+                )
+            });
+
+        format!("\n\n*{}Team standings*\n{}", self.sort_order.header_text(), team_rows.join("\n"))
+    }
+
+    fn merged_message_text(&self, merged_changes: &MergedChanges) -> String {
+        merged_changes
+            .leaderboards
+            .iter()
+            .map(|lc| {
+                let mut member_rows = lc
+                    .leaderboard
+                    .members
+                    .values()
+                    .sorted_by(|lhs, rhs| self.sort_order.cmp_members(lhs, rhs))
+                    .map(|member| {
+                        let row_text = self.member_row_text(member, Some(&lc.changes));
+                        self.add_other_leaderboards_text(row_text, member, lc, merged_changes)
+                    });
+
+                format!(
+                    "{}\n{}",
+                    self.header_row_text(lc.year, lc.leaderboard_id, lc.view_key.as_deref()),
+                    member_rows.join("\n")
+                )
+            })
+            .join("\n\n")
+    }
+
+    fn add_other_leaderboards_text(
+        &self,
+        row_text: String,
+        member: &LeaderboardMember,
+        leaderboard_changes: &LeaderboardChanges,
+        merged_changes: &MergedChanges,
+    ) -> String {
+        let changes = &leaderboard_changes.changes;
+        if !changes.new_members.contains(&member.id)
+            && !changes.members_with_new_stars.contains(&member.id)
+        {
+            return row_text;
+        }
+
+        let other_links = merged_changes
+            .leaderboards_of(member.id)
+            .iter()
+            .filter(|id| **id != leaderboard_changes.leaderboard_id)
+            .map(|id| {
+                let view_key = merged_changes
+                    .leaderboards
+                    .iter()
+                    .find(|lc| lc.leaderboard_id == *id)
+                    .and_then(|lc| lc.view_key.as_deref());
+                self.leaderboard_link(
+                    merged_changes.year,
+                    *id,
+                    view_key,
+                    &format!("leaderboard {id}"),
+                )
+            })
+            .join(", ");
+
+        match other_links.is_empty() {
+            true => row_text,
+            false => format!("{row_text} _(also in {other_links})_"),
+        }
+    }
+
     fn member_row_text(&self, member: &LeaderboardMember, changes: Option<&Changes>) -> String {
         let row_text = format!(
             "{}{}",
@@ -276,6 +404,52 @@ impl SlackWebhookReporter {
         )
     }
 
+    fn recovery_message_text(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        outage_duration: Option<TimeDelta>,
+    ) -> String {
+        let outage_suffix = outage_duration
+            .map(|duration| format!(" (outage lasted {})", duration_text(duration)))
+            .unwrap_or_default();
+
+        format!(
+            "{} has recovered and is once again watching this {}{outage_suffix}.",
+            self.username,
+            self.leaderboard_link(year, leaderboard_id, view_key, "leaderboard")
+        )
+    }
+
+    fn stale_message_text(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        silence: TimeDelta,
+    ) -> String {
+        format!(
+            "{} has not been able to check this {} for {}.",
+            self.username,
+            self.leaderboard_link(year, leaderboard_id, view_key, "leaderboard"),
+            duration_text(silence)
+        )
+    }
+
+    fn heartbeat_message_text(
+        &self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+    ) -> String {
+        format!(
+            "{} is still watching this {}; no changes since the last report.",
+            self.username,
+            self.leaderboard_link(year, leaderboard_id, view_key, "leaderboard")
+        )
+    }
+
     #[cfg_attr(not(coverage), tracing::instrument(skip_all, err))]
     async fn send_message<M>(
         &self,
@@ -295,10 +469,11 @@ impl SlackWebhookReporter {
             .expect("webhook message should have valid fields");
         trace!(?message);
 
-        let response = self
-            .http_client
-            .post(&self.webhook_url)
-            .json(&message)
+        let request = self.http_client.post(&self.webhook_url).json(&message);
+        #[cfg(feature = "opentelemetry")]
+        let request = aoc_leaderboard::telemetry::inject_trace_context(request);
+
+        let response = request
             .send()
             .await
             .and_then(reqwest::Response::error_for_status);
@@ -395,6 +570,24 @@ impl Reporter for SlackWebhookReporter {
         .map_err(|err| WebhookError::ReportChanges(err).into())
     }
 
+    #[cfg_attr(not(coverage), tracing::instrument(skip_all, err))]
+    async fn report_merged_changes(
+        &mut self,
+        merged_changes: &MergedChanges,
+    ) -> Result<(), Self::Err> {
+        let Some(first) = merged_changes.leaderboards.first() else {
+            return Ok(());
+        };
+
+        self.send_message(
+            merged_changes.year,
+            first.leaderboard_id,
+            self.merged_message_text(merged_changes),
+        )
+        .await
+        .map_err(|err| WebhookError::ReportChanges(err).into())
+    }
+
     #[cfg_attr(not(coverage), tracing::instrument(skip(self, leaderboard), err))]
     async fn report_first_run(
         &mut self,
@@ -435,4 +628,79 @@ impl Reporter for SlackWebhookReporter {
             );
         }
     }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self)))]
+    async fn report_recovery(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        error_kind: aoc_leaderbot_lib::ErrorKind,
+        outage_duration: Option<TimeDelta>,
+    ) {
+        info!(
+            "aoc_leaderbot recovered from error for leaderboard {leaderboard_id} and year {year}: {error_kind:?}"
+        );
+
+        let response = self
+            .send_message(
+                year,
+                leaderboard_id,
+                self.recovery_message_text(year, leaderboard_id, view_key, outage_duration),
+            )
+            .await;
+        if let Err(err) = response {
+            error!(
+                "error trying to report recovery to Slack webhook for leaderboard {leaderboard_id} and year {year}: {err}"
+            );
+        }
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self)))]
+    async fn report_stale(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        silence: TimeDelta,
+    ) {
+        error!(
+            "aoc_leaderbot has not succeeded for leaderboard {leaderboard_id} and year {year} in {silence}"
+        );
+
+        let response = self
+            .send_message(
+                year,
+                leaderboard_id,
+                self.stale_message_text(year, leaderboard_id, view_key, silence),
+            )
+            .await;
+        if let Err(err) = response {
+            error!(
+                "error trying to report stale bot to Slack webhook for leaderboard {leaderboard_id} and year {year}: {err}"
+            );
+        }
+    }
+
+    #[cfg_attr(not(coverage), tracing::instrument(skip(self, _leaderboard)))]
+    async fn report_heartbeat(
+        &mut self,
+        year: i32,
+        leaderboard_id: u64,
+        view_key: Option<&str>,
+        _leaderboard: &Leaderboard,
+    ) {
+        let response = self
+            .send_message(
+                year,
+                leaderboard_id,
+                self.heartbeat_message_text(year, leaderboard_id, view_key),
+            )
+            .await;
+        if let Err(err) = response {
+            error!(
+                "error trying to send heartbeat to Slack webhook for leaderboard {leaderboard_id} and year {year}: {err}"
+            );
+        }
+    }
 }
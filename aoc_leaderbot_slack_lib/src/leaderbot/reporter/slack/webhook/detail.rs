@@ -1,4 +1,5 @@
-use itertools::repeat_n;
+use chrono::TimeDelta;
+use itertools::{Itertools, repeat_n};
 
 pub trait SlackWebhookReporterStringExt {
     fn right_pad(self, width: usize, with: char) -> String;
@@ -20,3 +21,25 @@ where
         }
     }
 }
+
+pub fn duration_text(duration: TimeDelta) -> String {
+    let parts = [
+        (duration.num_days(), "day"),
+        (duration.num_hours() % 24, "hour"),
+        (duration.num_minutes() % 60, "minute"),
+    ];
+
+    let text = parts
+        .into_iter()
+        .filter(|(value, _)| *value > 0)
+        .map(|(value, unit)| match value {
+            1 => format!("{value} {unit}"),
+            _ => format!("{value} {unit}s"),
+        })
+        .join(", ");
+
+    match text.is_empty() {
+        true => "less than a minute".into(),
+        false => text,
+    }
+}
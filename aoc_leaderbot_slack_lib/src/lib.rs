@@ -23,6 +23,9 @@
 //! reporter instance would be via the [`builder`].  Many properties will also default to reading
 //! their values from environment variables (see each property's documentation for details).
 //!
+//! If the `opentelemetry` feature is enabled, requests sent to the Slack webhook include the
+//! trace context of the current span, like requests sent to the Advent of Code website.
+//!
 //! [`aoc_leaderbot`]: https://github.com/clechasseur/aoc_leaderbot
 //! [Advent of Code]: https://adventofcode.com/
 //! [`SlackWebhookReporter`]: leaderbot::reporter::slack::webhook::SlackWebhookReporter
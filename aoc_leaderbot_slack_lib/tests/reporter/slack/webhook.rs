@@ -190,6 +190,42 @@ mod leaderboard_sort_order {
         }
     }
 
+    mod cmp_teams {
+        use std::cmp::Ordering;
+
+        use aoc_leaderbot_lib::leaderbot::team::TeamStanding;
+
+        use super::*;
+
+        fn standing(name: &str, total_stars: u32, total_local_score: u64) -> TeamStanding {
+            TeamStanding {
+                name: name.into(),
+                members: 2,
+                total_stars,
+                total_local_score,
+                members_finished: 0,
+            }
+        }
+
+        #[test]
+        fn stars() {
+            let lhs = standing("Vogons", 10, 100);
+            let rhs = standing("Dolphins", 20, 50);
+
+            assert_eq!(LeaderboardSortOrder::Stars.cmp_teams(&lhs, &rhs), Ordering::Greater);
+            assert_eq!(LeaderboardSortOrder::Score.cmp_teams(&lhs, &rhs), Ordering::Less);
+        }
+
+        #[test]
+        fn same_values() {
+            let lhs = standing("Dolphins", 10, 100);
+            let rhs = standing("Vogons", 10, 100);
+
+            assert_eq!(LeaderboardSortOrder::Stars.cmp_teams(&lhs, &rhs), Ordering::Less);
+            assert_eq!(LeaderboardSortOrder::Score.cmp_teams(&lhs, &lhs), Ordering::Equal);
+        }
+    }
+
     mod header_text {
         use super::*;
 
@@ -562,6 +598,71 @@ mod slack_webhook_reporter {
                 }
             }
 
+            mod team_standings {
+                use aoc_leaderboard::wiremock::matchers::body_string_contains;
+                use aoc_leaderbot_lib::leaderbot::team::Team;
+
+                use super::*;
+
+                #[rstest]
+                #[tokio::test]
+                #[serial(slack_webhook_reporter_env)]
+                async fn reported(
+                    owner: LeaderboardMember,
+                    progressing_member: LeaderboardMember,
+                    #[with(42, 100)] new_member: LeaderboardMember,
+                ) {
+                    unsafe {
+                        set_reporter_env_vars(None::<&OsStr>, None::<&OsStr>, None::<&OsStr>);
+                    }
+
+                    let mock_server = MockServer::start().await;
+                    Mock::given(method(Method::POST))
+                        .and(path(WEBHOOK_PATH))
+                        .and(body_string_contains("Team standings*"))
+                        .and(body_string_contains(
+                            "Heart of Gold _(2 members, 0 finished, average score 50.0)_",
+                        ))
+                        .respond_with(ResponseTemplate::new(200))
+                        .expect(1)
+                        .mount(&mock_server)
+                        .await;
+
+                    let mut reporter = reporter(&mock_server, None);
+                    reporter.teams = vec![
+                        Team::new("Heart of Gold", [PROGRESSING_MEMBER_ID, NEW_MEMBER_ID]),
+                        Team::new("Earth", [OWNER_ID]),
+                    ];
+
+                    let previous_leaderboard = Leaderboard {
+                        year: TEST_YEAR,
+                        owner_id: owner.id,
+                        day1_ts: 0,
+                        members: [(owner.id, owner), (progressing_member.id, progressing_member)]
+                            .into(),
+                    };
+                    let mut leaderboard = previous_leaderboard.clone();
+                    leaderboard.members.insert(new_member.id, new_member);
+
+                    let changes = Changes {
+                        new_members: [NEW_MEMBER_ID].into(),
+                        members_with_new_stars: Default::default(),
+                    };
+
+                    let result = reporter
+                        .report_changes(
+                            TEST_YEAR,
+                            TEST_LEADERBOARD_ID,
+                            None,
+                            &previous_leaderboard,
+                            &leaderboard,
+                            &changes,
+                        )
+                        .await;
+                    assert!(result.is_ok());
+                }
+            }
+
             mod errors {
                 use super::*;
 
@@ -784,5 +885,315 @@ mod slack_webhook_reporter {
                 )));
             }
         }
+
+        mod report_recovery {
+            use aoc_leaderboard::wiremock::matchers::body_string_contains;
+            use aoc_leaderbot_lib::ErrorKind;
+            use chrono::TimeDelta;
+
+            use super::*;
+
+            #[rstest]
+            #[case::no_duration(None, "is once again watching this")]
+            #[case::less_than_a_minute(
+                Some(TimeDelta::seconds(42)),
+                "(outage lasted less than a minute)"
+            )]
+            #[case::minutes(Some(TimeDelta::minutes(5)), "(outage lasted 5 minutes)")]
+            #[case::singular(Some(TimeDelta::hours(25) + TimeDelta::minutes(1)), "(outage lasted 1 day, 1 hour, 1 minute)")]
+            #[case::plural(Some(TimeDelta::days(2) + TimeDelta::hours(3)), "(outage lasted 2 days, 3 hours)")]
+            #[tokio::test]
+            #[serial(slack_webhook_reporter_env)]
+            async fn working(
+                #[case] outage_duration: Option<TimeDelta>,
+                #[case] expected_text: &str,
+            ) {
+                unsafe {
+                    set_reporter_env_vars(None::<&OsStr>, None::<&OsStr>, None::<&OsStr>);
+                }
+
+                let mock_server = MockServer::start().await;
+                Mock::given(method(Method::POST))
+                    .and(path(WEBHOOK_PATH))
+                    .and(body_string_contains(expected_text))
+                    .respond_with(ResponseTemplate::new(200))
+                    .expect(1)
+                    .mount(&mock_server)
+                    .await;
+
+                let mut reporter = reporter(&mock_server, None);
+
+                reporter
+                    .report_recovery(
+                        TEST_YEAR,
+                        TEST_LEADERBOARD_ID,
+                        None,
+                        ErrorKind::Leaderboard(aoc_leaderboard::ErrorKind::NoAccess),
+                        outage_duration,
+                    )
+                    .await;
+            }
+
+            #[rstest]
+            #[awt]
+            #[tokio::test]
+            #[traced_test]
+            #[serial(slack_webhook_reporter_env)]
+            async fn offline(
+                #[future]
+                #[from(working_mock_server)]
+                mock_server: MockServer,
+            ) {
+                unsafe {
+                    set_reporter_env_vars(None::<&OsStr>, None::<&OsStr>, None::<&OsStr>);
+                }
+
+                let mut reporter = offline_reporter(&mock_server);
+
+                reporter
+                    .report_recovery(
+                        TEST_YEAR,
+                        TEST_LEADERBOARD_ID,
+                        None,
+                        ErrorKind::Leaderboard(aoc_leaderboard::ErrorKind::NoAccess),
+                        Some(TimeDelta::hours(1)),
+                    )
+                    .await;
+
+                assert!(logs_contain(&format!(
+                    "error trying to report recovery to Slack webhook for leaderboard {TEST_LEADERBOARD_ID} and year {TEST_YEAR}"
+                )));
+            }
+        }
+
+        mod report_stale {
+            use aoc_leaderboard::wiremock::matchers::body_string_contains;
+            use chrono::TimeDelta;
+
+            use super::*;
+
+            #[rstest]
+            #[tokio::test]
+            #[serial(slack_webhook_reporter_env)]
+            async fn working() {
+                unsafe {
+                    set_reporter_env_vars(None::<&OsStr>, None::<&OsStr>, None::<&OsStr>);
+                }
+
+                let mock_server = MockServer::start().await;
+                Mock::given(method(Method::POST))
+                    .and(path(WEBHOOK_PATH))
+                    .and(body_string_contains("has not been able to check this"))
+                    .and(body_string_contains("for 3 hours"))
+                    .respond_with(ResponseTemplate::new(200))
+                    .expect(1)
+                    .mount(&mock_server)
+                    .await;
+
+                let mut reporter = reporter(&mock_server, None);
+
+                reporter
+                    .report_stale(TEST_YEAR, TEST_LEADERBOARD_ID, None, TimeDelta::hours(3))
+                    .await;
+            }
+
+            #[rstest]
+            #[awt]
+            #[tokio::test]
+            #[traced_test]
+            #[serial(slack_webhook_reporter_env)]
+            async fn offline(
+                #[future]
+                #[from(working_mock_server)]
+                mock_server: MockServer,
+            ) {
+                unsafe {
+                    set_reporter_env_vars(None::<&OsStr>, None::<&OsStr>, None::<&OsStr>);
+                }
+
+                let mut reporter = offline_reporter(&mock_server);
+
+                reporter
+                    .report_stale(TEST_YEAR, TEST_LEADERBOARD_ID, None, TimeDelta::hours(3))
+                    .await;
+
+                assert!(logs_contain(&format!(
+                    "error trying to report stale bot to Slack webhook for leaderboard {TEST_LEADERBOARD_ID} and year {TEST_YEAR}"
+                )));
+            }
+        }
+
+        mod report_heartbeat {
+            use aoc_leaderboard::wiremock::matchers::body_string_contains;
+
+            use super::*;
+
+            fn leaderboard(owner: LeaderboardMember) -> Leaderboard {
+                Leaderboard {
+                    year: TEST_YEAR,
+                    owner_id: owner.id,
+                    day1_ts: 0,
+                    members: [(owner.id, owner)].into(),
+                }
+            }
+
+            #[rstest]
+            #[tokio::test]
+            #[serial(slack_webhook_reporter_env)]
+            async fn working(owner: LeaderboardMember) {
+                unsafe {
+                    set_reporter_env_vars(None::<&OsStr>, None::<&OsStr>, None::<&OsStr>);
+                }
+
+                let mock_server = MockServer::start().await;
+                Mock::given(method(Method::POST))
+                    .and(path(WEBHOOK_PATH))
+                    .and(body_string_contains("is still watching this"))
+                    .respond_with(ResponseTemplate::new(200))
+                    .expect(1)
+                    .mount(&mock_server)
+                    .await;
+
+                let mut reporter = reporter(&mock_server, None);
+
+                reporter
+                    .report_heartbeat(TEST_YEAR, TEST_LEADERBOARD_ID, None, &leaderboard(owner))
+                    .await;
+            }
+
+            #[rstest]
+            #[awt]
+            #[tokio::test]
+            #[traced_test]
+            #[serial(slack_webhook_reporter_env)]
+            async fn offline(
+                owner: LeaderboardMember,
+                #[future]
+                #[from(working_mock_server)]
+                mock_server: MockServer,
+            ) {
+                unsafe {
+                    set_reporter_env_vars(None::<&OsStr>, None::<&OsStr>, None::<&OsStr>);
+                }
+
+                let mut reporter = offline_reporter(&mock_server);
+
+                reporter
+                    .report_heartbeat(TEST_YEAR, TEST_LEADERBOARD_ID, None, &leaderboard(owner))
+                    .await;
+
+                assert!(logs_contain(&format!(
+                    "error trying to send heartbeat to Slack webhook for leaderboard {TEST_LEADERBOARD_ID} and year {TEST_YEAR}"
+                )));
+            }
+        }
+
+        mod report_merged_changes {
+            use aoc_leaderboard::wiremock::matchers::body_string_contains;
+            use aoc_leaderbot_lib::leaderbot::{LeaderboardChanges, MergedChanges};
+
+            use super::*;
+
+            const OTHER_LEADERBOARD_ID: u64 = TEST_LEADERBOARD_ID + 1;
+
+            fn merged_changes(
+                owner: LeaderboardMember,
+                progressing_member: LeaderboardMember,
+            ) -> MergedChanges {
+                let leaderboard_changes = |leaderboard_id, owner: &LeaderboardMember| {
+                    let previous_leaderboard = Leaderboard {
+                        year: TEST_YEAR,
+                        owner_id: owner.id,
+                        day1_ts: 0,
+                        members: [
+                            (owner.id, owner.clone()),
+                            (progressing_member.id, progressing_member.clone()),
+                        ]
+                        .into(),
+                    };
+                    let mut leaderboard = previous_leaderboard.clone();
+                    leaderboard
+                        .members
+                        .get_mut(&PROGRESSING_MEMBER_ID)
+                        .unwrap()
+                        .stars += 1;
+
+                    LeaderboardChanges {
+                        year: TEST_YEAR,
+                        leaderboard_id,
+                        view_key: None,
+                        previous_leaderboard,
+                        leaderboard,
+                        changes: Changes {
+                            new_members: Default::default(),
+                            members_with_new_stars: [PROGRESSING_MEMBER_ID].into(),
+                        },
+                    }
+                };
+
+                MergedChanges::merge([
+                    leaderboard_changes(TEST_LEADERBOARD_ID, &owner),
+                    leaderboard_changes(OTHER_LEADERBOARD_ID, &owner),
+                ])
+                .pop()
+                .unwrap()
+            }
+
+            #[rstest]
+            #[tokio::test]
+            #[serial(slack_webhook_reporter_env)]
+            async fn working(owner: LeaderboardMember, progressing_member: LeaderboardMember) {
+                unsafe {
+                    set_reporter_env_vars(None::<&OsStr>, None::<&OsStr>, None::<&OsStr>);
+                }
+
+                let mock_server = MockServer::start().await;
+                Mock::given(method(Method::POST))
+                    .and(path(WEBHOOK_PATH))
+                    .and(body_string_contains(format!(
+                        "_(also in <https://adventofcode.com/{TEST_YEAR}/leaderboard/private/view/{OTHER_LEADERBOARD_ID}?order=stars|leaderboard {OTHER_LEADERBOARD_ID}>)_"
+                    )))
+                    .respond_with(ResponseTemplate::new(200))
+                    .expect(1)
+                    .mount(&mock_server)
+                    .await;
+
+                let mut reporter = reporter(&mock_server, None);
+
+                let result = reporter
+                    .report_merged_changes(&merged_changes(owner, progressing_member))
+                    .await;
+                assert!(result.is_ok());
+            }
+
+            #[rstest]
+            #[awt]
+            #[tokio::test]
+            #[serial(slack_webhook_reporter_env)]
+            async fn not_found(
+                #[future]
+                #[from(working_mock_server)]
+                mock_server: MockServer,
+                owner: LeaderboardMember,
+                progressing_member: LeaderboardMember,
+            ) {
+                unsafe {
+                    set_reporter_env_vars(None::<&OsStr>, None::<&OsStr>, None::<&OsStr>);
+                }
+
+                let mut reporter = offline_reporter(&mock_server);
+
+                let result = reporter
+                    .report_merged_changes(&merged_changes(owner, progressing_member))
+                    .await;
+                assert_matches!(
+                    result,
+                    Err(Error::Webhook(WebhookError::ReportChanges(WebhookMessageError {
+                        leaderboard_id: TEST_LEADERBOARD_ID,
+                        ..
+                    })))
+                );
+            }
+        }
     }
 }